@@ -0,0 +1,15 @@
+#!/bin/sh
+
+set -o errexit
+
+# The csfle feature links against libmongocrypt, so install it from MongoDB's package repository.
+curl -sS https://www.mongodb.org/static/pgp/libmongocrypt.asc | sudo apt-key add -
+echo "deb https://libmongocrypt.s3.amazonaws.com/apt/ubuntu bionic/libmongocrypt/1.1 universe" \
+    | sudo tee /etc/apt/sources.list.d/libmongocrypt.list
+sudo apt-get update
+sudo apt-get install -y libmongocrypt-dev
+
+. ~/.cargo/env
+cargo build --features csfle -p mongodb
+cargo clippy --all-targets --features csfle -p mongodb -- -D warnings
+cargo test --doc --features csfle -p mongodb client_encryption
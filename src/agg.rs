@@ -0,0 +1,191 @@
+//! Contains a builder for aggregation pipelines.
+
+use std::convert::TryFrom;
+
+use crate::bson::{doc, Bson, Document};
+
+/// An aggregation pipeline, built up one stage at a time. A `Pipeline` can be passed directly to
+/// [`Collection::aggregate`](../struct.Collection.html#method.aggregate) or
+/// [`Database::aggregate`](../struct.Database.html#method.aggregate), and produces exactly the same
+/// stages as the equivalent hand-written `Vec<Document>`.
+///
+/// ```rust
+/// # use mongodb::{agg::Pipeline, bson::doc};
+/// let pipeline = Pipeline::new()
+///     .match_stage(doc! { "status": "A" })
+///     .group("$cust_id", doc! { "total": { "$sum": "$amount" } })
+///     .sort(doc! { "total": -1 })
+///     .limit(10);
+///
+/// let stages: Vec<_> = pipeline.into();
+/// assert_eq!(
+///     stages,
+///     vec![
+///         doc! { "$match": { "status": "A" } },
+///         doc! { "$group": { "_id": "$cust_id", "total": { "$sum": "$amount" } } },
+///         doc! { "$sort": { "total": -1 } },
+///         doc! { "$limit": 10 },
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pipeline {
+    stages: Vec<Document>,
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an arbitrary stage to the pipeline, e.g. one that this builder does not have a
+    /// dedicated method for.
+    pub fn stage(mut self, stage: Document) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// Appends a `$match` stage that filters the documents using the given query.
+    pub fn match_stage(self, filter: Document) -> Self {
+        self.stage(doc! { "$match": filter })
+    }
+
+    /// Appends a `$group` stage that groups the documents by `id` and computes the given
+    /// accumulator fields for each group, e.g. `doc! { "total": { "$sum": "$amount" } }`.
+    pub fn group(self, id: impl Into<Bson>, accumulators: Document) -> Self {
+        let mut group = doc! { "_id": id.into() };
+        for (field, accumulator) in accumulators {
+            group.insert(field, accumulator);
+        }
+        self.stage(doc! { "$group": group })
+    }
+
+    /// Appends a `$sort` stage that sorts the documents by the given fields.
+    pub fn sort(self, sort: Document) -> Self {
+        self.stage(doc! { "$sort": sort })
+    }
+
+    /// Appends a `$limit` stage that passes at most `limit` documents to the next stage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is negative.
+    pub fn limit(self, limit: i64) -> Self {
+        assert!(limit >= 0, "$limit must be non-negative, got {}", limit);
+
+        // Small limits are encoded the same way as the equivalent `doc!` literal would be.
+        let limit = match i32::try_from(limit) {
+            Ok(limit) => Bson::Int32(limit),
+            Err(_) => Bson::Int64(limit),
+        };
+        self.stage(doc! { "$limit": limit })
+    }
+
+    /// Appends a `$lookup` stage that joins each document with the documents in the collection
+    /// `from` whose `foreign_field` is equal to the document's `local_field`, storing them in an
+    /// array in the field `as_field`.
+    pub fn lookup(
+        self,
+        from: impl Into<String>,
+        local_field: impl Into<String>,
+        foreign_field: impl Into<String>,
+        as_field: impl Into<String>,
+    ) -> Self {
+        self.stage(doc! {
+            "$lookup": {
+                "from": from.into(),
+                "localField": local_field.into(),
+                "foreignField": foreign_field.into(),
+                "as": as_field.into(),
+            }
+        })
+    }
+
+    /// Appends an `$unwind` stage that outputs one document per element of the array in the given
+    /// field. The field can be given either as a field path (e.g. `"$items"`) or as a plain field
+    /// name (e.g. `"items"`).
+    pub fn unwind(self, field: impl AsRef<str>) -> Self {
+        let field = field.as_ref();
+        let path = if field.starts_with('$') {
+            field.to_string()
+        } else {
+            format!("${}", field)
+        };
+        self.stage(doc! { "$unwind": path })
+    }
+
+    /// Gets the stages appended to the pipeline so far.
+    pub fn stages(&self) -> &[Document] {
+        &self.stages
+    }
+}
+
+impl From<Pipeline> for Vec<Document> {
+    fn from(pipeline: Pipeline) -> Self {
+        pipeline.stages
+    }
+}
+
+impl IntoIterator for Pipeline {
+    type Item = Document;
+    type IntoIter = std::vec::IntoIter<Document>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.stages.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Pipeline;
+    use crate::bson::doc;
+
+    #[test]
+    fn matches_hand_written_pipeline() {
+        let pipeline = Pipeline::new()
+            .match_stage(doc! { "x": { "$gt": 1 } })
+            .lookup("orders", "_id", "customer_id", "orders")
+            .unwind("orders")
+            .unwind("$tags")
+            .group("$x", doc! { "count": { "$sum": 1 } })
+            .sort(doc! { "count": -1 })
+            .limit(5)
+            .limit(i64::from(i32::MAX) + 1);
+
+        let expected = vec![
+            doc! { "$match": { "x": { "$gt": 1 } } },
+            doc! {
+                "$lookup": {
+                    "from": "orders",
+                    "localField": "_id",
+                    "foreignField": "customer_id",
+                    "as": "orders",
+                }
+            },
+            doc! { "$unwind": "$orders" },
+            doc! { "$unwind": "$tags" },
+            doc! { "$group": { "_id": "$x", "count": { "$sum": 1 } } },
+            doc! { "$sort": { "count": -1 } },
+            doc! { "$limit": 5 },
+            doc! { "$limit": i64::from(i32::MAX) + 1 },
+        ];
+
+        let mut actual_bytes = Vec::new();
+        let mut expected_bytes = Vec::new();
+        for (actual, expected) in pipeline.stages().iter().zip(expected.iter()) {
+            actual.to_writer(&mut actual_bytes).unwrap();
+            expected.to_writer(&mut expected_bytes).unwrap();
+        }
+        assert_eq!(actual_bytes, expected_bytes);
+
+        let stages: Vec<_> = pipeline.into();
+        assert_eq!(stages, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_limit() {
+        Pipeline::new().limit(-1);
+    }
+}
@@ -0,0 +1,155 @@
+//! Contains the types related to a `ChangeStream` event.
+use serde::{Deserialize, Serialize};
+
+use crate::bson::{Bson, Document, Timestamp};
+
+/// An opaque token used for resuming an interrupted
+/// [`ChangeStream`](../struct.ChangeStream.html).
+///
+/// See the documentation
+/// [here](https://docs.mongodb.com/manual/changeStreams/#change-stream-resume-token) for more
+/// information on resume tokens.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ResumeToken(pub(crate) Bson);
+
+/// A `ChangeStreamEvent` represents a
+/// [change event](https://docs.mongodb.com/manual/reference/change-events/) in the associated
+/// change stream.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangeStreamEvent<T> {
+    /// An opaque token for use when resuming an interrupted `ChangeStream`.
+    ///
+    /// Also see the documentation on [resuming a change
+    /// stream](https://docs.mongodb.com/manual/changeStreams/#resume-a-change-stream).
+    #[serde(rename = "_id")]
+    pub id: ResumeToken,
+
+    /// Describes the type of operation represented in this change notification.
+    pub operation_type: OperationType,
+
+    /// Identifies the collection or database on which the event occurred.
+    pub ns: Option<ChangeStreamEventNamespace>,
+
+    /// A `Document` that contains the `_id` of the document created or modified by the `insert`,
+    /// `replace`, `delete`, `update` operations (i.e. CRUD operations). For sharded collections,
+    /// also displays the full shard key for the document. The `_id` field is not repeated if it is
+    /// already a part of the shard key.
+    pub document_key: Option<Document>,
+
+    /// A description of the fields that were updated or removed by the update operation.
+    /// Only specified if `operation_type` is `OperationType::Update`.
+    pub update_description: Option<UpdateDescription>,
+
+    /// The cluster time at which the change occurred.
+    pub cluster_time: Option<Timestamp>,
+
+    /// The `Document` created or modified by the `insert`, `replace`, `delete`, `update`
+    /// operations (i.e. CRUD operations).
+    ///
+    /// For `insert` and `replace` operations, this represents the new document created by the
+    /// operation. For `delete` operations, this field is omitted as the document no longer
+    /// exists. For `update` operations, this field only appears if you configured the change
+    /// stream with `full_document` set to `UpdateLookup`. This field then represents the most
+    /// current majority-committed version of the document modified by the update operation.
+    pub full_document: Option<T>,
+}
+
+/// Describes which fields have been updated or removed from a document.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UpdateDescription {
+    /// A `Document` containing key:value pairs of names of the fields that were changed, and the
+    /// new value for those fields.
+    pub updated_fields: Document,
+
+    /// An array of field names that were removed from the `Document`.
+    pub removed_fields: Vec<String>,
+}
+
+/// The operation type represented in a given change notification.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum OperationType {
+    /// See [insert-event](https://docs.mongodb.com/manual/reference/change-events/#insert-event)
+    Insert,
+
+    /// See [update-event](https://docs.mongodb.com/manual/reference/change-events/#update-event)
+    Update,
+
+    /// See [replace-event](https://docs.mongodb.com/manual/reference/change-events/#replace-event)
+    Replace,
+
+    /// See [delete-event](https://docs.mongodb.com/manual/reference/change-events/#delete-event)
+    Delete,
+
+    /// See [drop-event](https://docs.mongodb.com/manual/reference/change-events/#drop-event)
+    Drop,
+
+    /// See [rename-event](https://docs.mongodb.com/manual/reference/change-events/#rename-event)
+    Rename,
+
+    /// See [dropdatabase-event](https://docs.mongodb.com/manual/reference/change-events/#dropdatabase-event)
+    DropDatabase,
+
+    /// See [invalidate-event](https://docs.mongodb.com/manual/reference/change-events/#invalidate-event)
+    Invalidate,
+
+    /// An operation type that is not recognized by this version of the driver.
+    #[serde(other)]
+    Other,
+}
+
+/// Identifies the collection or database on which an event occurred.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct ChangeStreamEventNamespace {
+    /// The name of the database in which the change occurred.
+    pub db: String,
+
+    /// The name of the collection in which the change occurred. This is not present for
+    /// database-level events such as `dropDatabase`.
+    pub coll: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChangeStreamEvent, ChangeStreamEventNamespace, OperationType};
+    use crate::bson::{doc, from_document, Document};
+
+    #[test]
+    fn deserialize_event() {
+        let event: ChangeStreamEvent<Document> = from_document(doc! {
+            "_id": { "_data": "token" },
+            "operationType": "insert",
+            "ns": { "db": "db", "coll": "coll" },
+            "documentKey": { "_id": 1 },
+            "fullDocument": { "_id": 1, "x": 1 },
+        })
+        .unwrap();
+
+        assert_eq!(event.operation_type, OperationType::Insert);
+        assert_eq!(
+            event.ns,
+            Some(ChangeStreamEventNamespace {
+                db: "db".to_string(),
+                coll: Some("coll".to_string()),
+            })
+        );
+        assert_eq!(event.full_document, Some(doc! { "_id": 1, "x": 1 }));
+
+        let event: ChangeStreamEvent<Document> = from_document(doc! {
+            "_id": { "_data": "token" },
+            "operationType": "someFutureOperation",
+            "ns": { "db": "db" },
+        })
+        .unwrap();
+
+        assert_eq!(event.operation_type, OperationType::Other);
+        assert_eq!(event.ns.and_then(|ns| ns.coll), None);
+    }
+}
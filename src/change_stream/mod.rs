@@ -0,0 +1,373 @@
+//! Contains the functionality for change streams.
+pub mod event;
+pub(crate) mod options;
+
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use derivative::Derivative;
+use futures::{
+    future::{BoxFuture, FutureExt},
+    Stream,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    bson::{doc, from_bson, from_document, Bson, Document, Timestamp},
+    change_stream::{
+        event::{ChangeStreamEvent, ResumeToken},
+        options::ChangeStreamOptions,
+    },
+    error::{ErrorKind, Result},
+    operation::{Aggregate, AggregateTarget},
+    Client,
+    Cursor,
+    Namespace,
+};
+
+/// A callback registered with [`ChangeStream::on_resume_token`].
+type ResumeTokenCallback = Box<dyn FnMut(&ResumeToken) + Send + Sync>;
+
+/// The aggregation run to resume a change stream, which yields the new cursor and the max wire
+/// version of the server it was created on.
+type PendingResume = BoxFuture<'static, Result<(Cursor<Document>, Option<i32>)>>;
+
+/// A `ChangeStream` streams the ongoing changes of its associated collection, database or
+/// deployment. `ChangeStream` instances should be created with the `watch` method of the relevant
+/// target, such as [`Client::watch`](../struct.Client.html#method.watch).
+///
+/// A `ChangeStream` can be iterated like any other `Stream`; each item is a
+/// [`ChangeStreamEvent`](event/struct.ChangeStreamEvent.html) describing a single change:
+///
+/// ```rust
+/// # use futures::stream::StreamExt;
+/// # use mongodb::{bson::doc, error::Result, Client};
+/// #
+/// # async fn func() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com").await?;
+/// let mut change_stream = client.watch(None, None).await?;
+/// while let Some(event) = change_stream.next().await {
+///     let event = event?;
+///     println!("operation performed: {:?} on {:?}", event.operation_type, event.ns);
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
+/// See the documentation [here](https://docs.mongodb.com/manual/changeStreams) for more
+/// details. Also see the documentation on [usage recommendations](https://docs.mongodb.com/manual/administration/change-streams-production-recommendations/).
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ChangeStream<T = ChangeStreamEvent<Document>>
+where
+    T: DeserializeOwned + Unpin,
+{
+    /// The cursor to iterate over the underlying events.
+    cursor: Cursor<Document>,
+
+    /// The information needed to re-run the aggregation that started the stream when resuming it.
+    client: Client,
+    target: ChangeStreamTarget,
+    pipeline: Vec<Document>,
+    options: Option<ChangeStreamOptions>,
+
+    /// The token to resume the stream after, i.e. the resume token of the most recently returned
+    /// event or the `postBatchResumeToken` of the most recent batch once it's been fully iterated.
+    resume_token: Option<ResumeToken>,
+
+    /// Whether any events have been returned from the stream.
+    returned_event: bool,
+
+    /// The `operationTime` of the aggregation that started the stream, which is used to resume it
+    /// if no resume token has been cached yet and no starting point was specified.
+    initial_operation_time: Option<Timestamp>,
+
+    /// The callback to run with the resume token of each event before the event is returned.
+    #[derivative(Debug = "ignore")]
+    resume_token_callback: Option<ResumeTokenCallback>,
+
+    /// The max wire version of the server that the current cursor was created on, which
+    /// determines which errors the stream resumes after.
+    max_wire_version: Option<i32>,
+
+    /// The aggregation that is currently being run to resume the stream after an error, if any.
+    #[derivative(Debug = "ignore")]
+    pending_resume: Option<PendingResume>,
+
+    /// Whether the stream has been resumed since it last returned an event. The stream is only
+    /// resumed once per error, so a resumable error that occurs again before an event arrives is
+    /// returned instead.
+    resume_attempted: bool,
+
+    _phantom: PhantomData<T>,
+}
+
+/// The target of a change stream.
+#[derive(Clone, Debug)]
+pub(crate) enum ChangeStreamTarget {
+    /// All changes in the deployment, i.e. the `$changeStream` stage is run with
+    /// `allChangesForCluster: true` against the `admin` database.
+    Cluster,
+
+    /// The changes to all of the non-system collections in the database.
+    Database(String),
+
+    /// The changes to a single collection.
+    Collection(Namespace),
+}
+
+impl ChangeStreamTarget {
+    fn aggregate_target(&self) -> AggregateTarget {
+        match self {
+            Self::Cluster => AggregateTarget::Database("admin".to_string()),
+            Self::Database(ref name) => AggregateTarget::Database(name.clone()),
+            Self::Collection(ref ns) => AggregateTarget::Collection(ns.clone()),
+        }
+    }
+}
+
+impl<T> ChangeStream<T>
+where
+    T: DeserializeOwned + Unpin,
+{
+    /// Starts a new change stream by running an aggregation with a `$changeStream` stage
+    /// prepended to the provided `pipeline`.
+    pub(crate) async fn execute(
+        client: &Client,
+        target: ChangeStreamTarget,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: Option<ChangeStreamOptions>,
+    ) -> Result<Self> {
+        let pipeline: Vec<_> = pipeline.into_iter().collect();
+        let (cursor, operation_time, max_wire_version) = start_cursor(
+            client.clone(),
+            target.clone(),
+            pipeline.clone(),
+            options.clone(),
+        )
+        .await?;
+
+        // The operation time is only needed to resume the stream if no other starting point was
+        // given, and servers before 4.0 don't support starting a stream at an operation time.
+        let has_starting_point = match options {
+            Some(ref options) => {
+                options.resume_after.is_some()
+                    || options.start_after.is_some()
+                    || options.start_at_operation_time.is_some()
+            }
+            None => false,
+        };
+        let initial_operation_time = match (operation_time, max_wire_version) {
+            (Some(time), Some(version)) if !has_starting_point && version >= 7 => Some(time),
+            _ => None,
+        };
+
+        let mut change_stream = Self {
+            cursor,
+            client: client.clone(),
+            target,
+            pipeline,
+            options,
+            resume_token: None,
+            returned_event: false,
+            initial_operation_time,
+            resume_token_callback: None,
+            max_wire_version,
+            pending_resume: None,
+            resume_attempted: false,
+            _phantom: Default::default(),
+        };
+        change_stream.cache_post_batch_resume_token();
+        Ok(change_stream)
+    }
+
+    /// Returns the cached resume token that can be used to resume after the most recently returned
+    /// change. Once all of the changes in a batch have been returned, this is the
+    /// `postBatchResumeToken` of the batch if the server reported one, which may be later than the
+    /// resume token of the last change.
+    ///
+    /// See the documentation
+    /// [here](https://docs.mongodb.com/manual/changeStreams/#change-stream-resume-token) for more
+    /// information on change stream resume tokens.
+    pub fn resume_token(&self) -> Option<&ResumeToken> {
+        self.resume_token.as_ref()
+    }
+
+    /// Registers a callback that is run with the resume token of each change before the change is
+    /// returned from the stream. This can be used to persist the resume token, e.g. to resume the
+    /// change stream with
+    /// [`ChangeStreamOptions::resume_after`](../options/struct.ChangeStreamOptions.html#structfield.resume_after)
+    /// after the process is restarted, without the risk of losing the token of a change that has
+    /// already been returned.
+    pub fn on_resume_token(
+        mut self,
+        callback: impl FnMut(&ResumeToken) + Send + Sync + 'static,
+    ) -> Self {
+        self.resume_token_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Records the resume token of the event and deserializes it into `T`.
+    fn handle_event(&mut self, event: Document) -> Result<T> {
+        let id = match event.get("_id") {
+            Some(id) => id.clone(),
+            None => {
+                return Err(ErrorKind::ResponseError {
+                    message: "change stream event is missing a resume token (the \"_id\" field). \
+                              Resume tokens must not be projected out of change stream events"
+                        .to_string(),
+                }
+                .into())
+            }
+        };
+
+        let resume_token: ResumeToken = from_bson(id)?;
+        if let Some(ref mut callback) = self.resume_token_callback {
+            callback(&resume_token);
+        }
+        self.resume_token = Some(resume_token);
+        self.returned_event = true;
+        self.resume_attempted = false;
+        self.cache_post_batch_resume_token();
+        from_document(event).map_err(Into::into)
+    }
+
+    /// Caches the `postBatchResumeToken` of the current batch if all of its events have been
+    /// returned, since it's a later point to resume from than the last event's resume token.
+    fn cache_post_batch_resume_token(&mut self) {
+        if let Some(token) = self.cursor.post_batch_resume_token() {
+            self.resume_token = Some(ResumeToken(Bson::Document(token.clone())));
+        }
+    }
+
+    /// Starts re-running the aggregation that started the stream so that it resumes from the
+    /// cached resume token. If no resume token has been cached yet, the stream is resumed from the
+    /// operation time it was started at, or otherwise from the same point it was originally
+    /// started from.
+    fn resume(&mut self) {
+        let mut options = self.options.clone().unwrap_or_default();
+        if let Some(ref resume_token) = self.resume_token {
+            // A stream started with `start_after` keeps using it until it has returned an event,
+            // since `resume_after` can't resume after an invalidate event.
+            if options.start_after.is_some() && !self.returned_event {
+                options.start_after = Some(resume_token.clone());
+                options.resume_after = None;
+            } else {
+                options.resume_after = Some(resume_token.clone());
+                options.start_after = None;
+            }
+            options.start_at_operation_time = None;
+        } else if let Some(time) = options
+            .start_at_operation_time
+            .or(self.initial_operation_time)
+        {
+            options.start_at_operation_time = Some(time);
+            options.resume_after = None;
+            options.start_after = None;
+        }
+
+        let client = self.client.clone();
+        let target = self.target.clone();
+        let pipeline = self.pipeline.clone();
+        self.resume_attempted = true;
+        self.pending_resume = Some(
+            async move {
+                let (cursor, _, max_wire_version) =
+                    start_cursor(client, target, pipeline, Some(options)).await?;
+                Ok((cursor, max_wire_version))
+            }
+            .boxed(),
+        );
+    }
+}
+
+/// Runs an aggregation with a `$changeStream` stage prepended to the provided `pipeline`,
+/// returning a cursor over the resulting events along with the `operationTime` of the aggregation,
+/// if the server reported one, and the max wire version of the server it was run on.
+async fn start_cursor(
+    client: Client,
+    target: ChangeStreamTarget,
+    pipeline: Vec<Document>,
+    options: Option<ChangeStreamOptions>,
+) -> Result<(Cursor<Document>, Option<Timestamp>, Option<i32>)> {
+    let mut stage = match options {
+        Some(ref options) => crate::bson::to_document(options)?,
+        None => Document::new(),
+    };
+
+    if let ChangeStreamTarget::Cluster = target {
+        stage.insert("allChangesForCluster", true);
+    }
+
+    let pipeline: Vec<_> = std::iter::once(doc! { "$changeStream": stage })
+        .chain(pipeline)
+        .collect();
+
+    let aggregate = Aggregate::new(
+        target.aggregate_target(),
+        pipeline,
+        options.as_ref().map(ChangeStreamOptions::aggregate_options),
+    );
+
+    // Change streams are meant to stay open indefinitely, so a time limit only applies to the
+    // initial aggregation and to each getMore individually.
+    let (spec, session) = client.execute_cursor_operation(aggregate).await?;
+    let spec = spec.with_timeout_deadline(None);
+    let operation_time = spec.operation_time;
+    let max_wire_version = client.server_max_wire_version(&spec.info.address).await;
+    Ok((
+        Cursor::new(client, spec, session),
+        operation_time,
+        max_wire_version,
+    ))
+}
+
+impl<T> Stream for ChangeStream<T>
+where
+    T: DeserializeOwned + Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(ref mut pending_resume) = self.pending_resume {
+                let result = match pending_resume.as_mut().poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+                self.pending_resume = None;
+
+                match result {
+                    Ok((cursor, max_wire_version)) => {
+                        self.cursor = cursor;
+                        self.max_wire_version = max_wire_version;
+                        self.cache_post_batch_resume_token();
+                    }
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                }
+            }
+
+            match Pin::new(&mut self.cursor).poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => return Poll::Ready(Some(self.handle_event(event))),
+                // Resumable errors are only returned if resuming the stream fails or the error
+                // occurs again before the resumed stream returns an event.
+                Poll::Ready(Some(Err(e)))
+                    if !self.resume_attempted && e.is_resumable(self.max_wire_version) =>
+                {
+                    self.resume()
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => {
+                    // The cursor may have received an empty batch before waiting for the next one.
+                    self.cache_post_batch_resume_token();
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Contains options for change streams.
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    bson::Timestamp,
+    change_stream::event::ResumeToken,
+    concern::ReadConcern,
+    options::{AggregateOptions, Collation},
+    selection_criteria::SelectionCriteria,
+};
+
+/// These are the valid options that can be passed to the `watch` method for creating a
+/// [`ChangeStream`](../change_stream/struct.ChangeStream.html).
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ChangeStreamOptions {
+    /// Configures how the
+    /// [`ChangeStreamEvent::full_document`](../change_stream/event/struct.ChangeStreamEvent.html#structfield.full_document)
+    /// field will be populated. By default, the field will be empty for updates.
+    #[builder(default)]
+    pub full_document: Option<FullDocumentType>,
+
+    /// Specifies the logical starting point for the new change stream: the stream will return the
+    /// changes that occurred after the one identified by the given resume token. Note that a change
+    /// stream cannot be resumed with `resume_after` after an invalidate event; `start_after` must
+    /// be used instead.
+    #[builder(default)]
+    pub resume_after: Option<ResumeToken>,
+
+    /// Takes a resume token and starts a new change stream returning the first notification after
+    /// the token. Unlike `resume_after`, this can be used to start a new change stream after an
+    /// invalidate event.
+    ///
+    /// This option is only supported on server versions 4.2 and above.
+    #[builder(default)]
+    pub start_after: Option<ResumeToken>,
+
+    /// The change stream will only return the changes that occurred at or after the given
+    /// operation time. This is ignored when resuming the stream once a resume token has been
+    /// received.
+    ///
+    /// This option is only supported on server versions 4.0 and above.
+    #[builder(default)]
+    pub start_at_operation_time: Option<Timestamp>,
+
+    /// The maximum amount of time for the server to wait on new documents to satisfy a change
+    /// stream query.
+    #[builder(default)]
+    #[serde(skip)]
+    pub max_await_time: Option<Duration>,
+
+    /// The number of documents to return per batch.
+    #[builder(default)]
+    #[serde(skip)]
+    pub batch_size: Option<u32>,
+
+    /// Specifies a collation.
+    #[builder(default)]
+    #[serde(skip)]
+    pub collation: Option<Collation>,
+
+    /// The read concern to use for the operation.
+    ///
+    /// If none is specified, the read concern defined on the object executing this operation will
+    /// be used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub read_concern: Option<ReadConcern>,
+
+    /// The criteria used to select a server for this operation.
+    ///
+    /// If none is specified, the selection criteria defined on the object executing this operation
+    /// will be used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub selection_criteria: Option<SelectionCriteria>,
+}
+
+impl ChangeStreamOptions {
+    /// Gets the options that should be used for the `aggregate` command that starts the change
+    /// stream.
+    pub(crate) fn aggregate_options(&self) -> AggregateOptions {
+        AggregateOptions::builder()
+            .batch_size(self.batch_size)
+            .collation(self.collation.clone())
+            .max_await_time(self.max_await_time)
+            .read_concern(self.read_concern.clone())
+            .selection_criteria(self.selection_criteria.clone())
+            .build()
+    }
+}
+
+/// Describes the modes for configuring the
+/// [`ChangeStreamEvent::full_document`](../change_stream/event/struct.ChangeStreamEvent.html#structfield.full_document)
+/// field.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum FullDocumentType {
+    /// The field will be populated for inserts and replaces, but not for updates.
+    Default,
+
+    /// The field will be populated with a copy of the entire document that was updated from some
+    /// time after the change occurred when an update event occurs.
+    UpdateLookup,
+}
@@ -368,7 +368,13 @@ impl Credential {
         doc
     }
 
+    /// The database to authenticate against: the explicitly configured source if there is one,
+    /// otherwise the default source for the mechanism.
     pub(crate) fn resolved_source(&self) -> &str {
+        if let Some(ref source) = self.source {
+            return source;
+        }
+
         self.mechanism
             .as_ref()
             .map(|m| m.default_source(None))
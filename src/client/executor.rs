@@ -1,18 +1,38 @@
-use super::{Client, ClientSession};
-
-use std::{collections::HashSet, sync::Arc};
+use super::{session::TransactionState, Client, ClientSession};
+
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
+use futures::Future;
 use lazy_static::lazy_static;
 use time::PreciseTime;
+use tokio::sync::SemaphorePermit;
 
 use crate::{
-    bson::Document,
-    cmap::Connection,
-    error::{Error, ErrorKind, Result},
-    event::command::{CommandFailedEvent, CommandStartedEvent, CommandSucceededEvent},
-    operation::{Operation, Retryability},
+    bson::{Bson, Document},
+    cmap::{Command, Connection, PinnedConnection},
+    concern::ReadConcernLevel,
+    cursor::CursorSpecification,
+    error::{Error, ErrorKind, Result, RETRYABLE_WRITE_ERROR, TRANSIENT_TRANSACTION_ERROR},
+    event::command::{
+        CommandEvent,
+        CommandFailedEvent,
+        CommandStartedEvent,
+        CommandSucceededEvent,
+        OperationEvent,
+        SlowCommandEvent,
+    },
+    operation::{AbortTransaction, CommitTransaction, Operation, Retryability},
     options::SelectionCriteria,
-    sdam::{Server, SessionSupportStatus},
+    sdam::{Server, ServerType, SessionSupportStatus},
+    RUNTIME,
 };
 
 lazy_static! {
@@ -45,22 +65,75 @@ impl Client {
             }
             .into());
         }
-        let mut implicit_session = self.start_implicit_session(&op).await?;
-        self.execute_operation_with_retry(op, implicit_session.as_mut())
+        check_snapshot_read_without_session(&op)?;
+        let deadline = self.operation_deadline(&op, None);
+        let mut implicit_session =
+            with_deadline(deadline, self.start_implicit_session(&op)).await??;
+        self.execute_operation_with_retry(op, implicit_session.as_mut(), deadline, None)
             .await
     }
 
-    /// Execute the given operation, returning the implicit session created for it if one was.
+    /// Execute the given operation, returning the implicit session created for it if one was. The
+    /// operation's time limit, if any, also bounds the lifetime of the resulting cursor. When
+    /// connected to a load balancer, a cursor that isn't exhausted by its first batch is pinned to
+    /// the connection it was created on.
     ///
     /// Server selection be will performed using the criteria specified on the operation, if any.
-    pub(crate) async fn execute_cursor_operation<T: Operation>(
+    pub(crate) async fn execute_cursor_operation<T: Operation<O = CursorSpecification>>(
+        &self,
+        op: T,
+    ) -> Result<(CursorSpecification, Option<ClientSession>)> {
+        check_snapshot_read_without_session(&op)?;
+        let deadline = self.operation_deadline(&op, None);
+        let mut implicit_session =
+            with_deadline(deadline, self.start_implicit_session(&op)).await??;
+        let spec = self
+            .execute_cursor_operation_with_deadline(op, implicit_session.as_mut(), deadline)
+            .await?;
+        Ok((spec, implicit_session))
+    }
+
+    /// Execute the given operation with the given session, bounding the lifetime of the resulting
+    /// cursor in the same way as `execute_cursor_operation`.
+    pub(crate) async fn execute_cursor_operation_with_session<
+        T: Operation<O = CursorSpecification>,
+    >(
         &self,
         op: T,
-    ) -> Result<(T::O, Option<ClientSession>)> {
-        let mut implicit_session = self.start_implicit_session(&op).await?;
-        self.execute_operation_with_retry(op, implicit_session.as_mut())
+        session: &mut ClientSession,
+    ) -> Result<CursorSpecification> {
+        self.check_session_client(session)?;
+        let deadline = self.operation_deadline(&op, Some(session));
+        self.execute_cursor_operation_with_deadline(op, Some(session), deadline)
             .await
-            .map(|result| (result, implicit_session))
+    }
+
+    /// Does the work shared by `execute_cursor_operation` and
+    /// `execute_cursor_operation_with_session`.
+    async fn execute_cursor_operation_with_deadline<T: Operation<O = CursorSpecification>>(
+        &self,
+        op: T,
+        session: Option<&mut ClientSession>,
+        deadline: Option<Instant>,
+    ) -> Result<CursorSpecification> {
+        let mut connection = None;
+        let keep_connection = if self.inner.options.load_balanced == Some(true) {
+            Some(&mut connection)
+        } else {
+            None
+        };
+        let mut spec = self
+            .execute_operation_with_retry(op, session, deadline, keep_connection)
+            .await?
+            .with_timeout_deadline(deadline);
+
+        if let Some(conn) = connection {
+            if spec.id() != 0 {
+                spec = spec.with_pinned_connection(PinnedConnection::new(conn));
+            }
+        }
+
+        Ok(spec)
     }
 
     /// Execute the given operation with the given session.
@@ -70,19 +143,190 @@ impl Client {
         op: T,
         session: &mut ClientSession,
     ) -> Result<T::O> {
-        self.execute_operation_with_retry(op, Some(session)).await
+        self.check_session_client(session)?;
+        let deadline = self.operation_deadline(&op, Some(session));
+        self.execute_operation_with_retry(op, Some(session), deadline, None)
+            .await
+    }
+
+    /// Returns an error if `session` was started by a different client.
+    fn check_session_client(&self, session: &ClientSession) -> Result<()> {
+        if !Arc::ptr_eq(&session.client().inner, &self.inner) {
+            return Err(ErrorKind::ArgumentError {
+                message: "the session was not started by the client executing the operation"
+                    .to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Execute the given operation with the given session if there is one, and the same as
+    /// `execute_operation` otherwise.
+    pub(crate) async fn execute_operation_with_optional_session<T: Operation>(
+        &self,
+        op: T,
+        session: Option<&mut ClientSession>,
+    ) -> Result<T::O> {
+        match session {
+            Some(session) => self.execute_operation_with_session(op, session).await,
+            None => self.execute_operation(op).await,
+        }
+    }
+
+    /// Gets the time by which the given operation must complete, if it has a time limit. When the
+    /// operation is executed with a session that has a deadline, the earlier of the two applies.
+    fn operation_deadline<T: Operation>(
+        &self,
+        op: &T,
+        session: Option<&ClientSession>,
+    ) -> Option<Instant> {
+        let op_deadline = op.timeout_deadline().or_else(|| {
+            op.timeout()
+                .or(self.inner.options.timeout)
+                .map(|timeout| Instant::now() + timeout)
+        });
+
+        match (op_deadline, session.and_then(ClientSession::deadline)) {
+            (Some(op_deadline), Some(session_deadline)) => Some(op_deadline.min(session_deadline)),
+            (op_deadline, session_deadline) => op_deadline.or(session_deadline),
+        }
     }
 
     /// Selects a server and executes the given operation on it, optionally using a provided
-    /// session. Retries the operation upon failure if retryability is supported.
+    /// session. Retries the operation upon failure if retryability is supported. Errors are
+    /// recorded in the counters reported by `Client::metrics`, and an `OperationEvent` is emitted
+    /// if the `emit_operation_events` option is set.
+    ///
+    /// If a deadline is given, the whole execution, including server selection, connection
+    /// checkout and any retry, is abandoned with a timeout error once it passes.
+    ///
+    /// If `keep_connection` is given, the connection the operation succeeded on is moved into it
+    /// rather than being checked back into its pool.
     async fn execute_operation_with_retry<T: Operation>(
         &self,
         op: T,
         mut session: Option<&mut ClientSession>,
+        deadline: Option<Instant>,
+        keep_connection: Option<&mut Option<Connection>>,
     ) -> Result<T::O> {
-        let server = self.select_server(op.selection_criteria()).await?;
+        // The operations executed after a transaction was committed or aborted aren't part of it.
+        let in_transaction = match session {
+            Some(ref mut session) => {
+                if !ends_transaction::<T>() {
+                    session.clear_finished_transaction();
+                }
+                session.in_transaction()
+            }
+            None => false,
+        };
 
-        let mut conn = match server.checkout_connection().await {
+        let start = Instant::now();
+        let mut timings = OperationTimings::default();
+        let result = with_deadline(
+            deadline,
+            self.select_server_and_execute(op, session, &mut timings, keep_connection),
+        )
+        .await
+        .and_then(|result| result)
+        .map_err(|error| {
+            // The transaction can be retried from the start if one of its operations couldn't
+            // reach the server.
+            let is_transient = error.is_network_error()
+                || matches!(error.kind.as_ref(), ErrorKind::ServerSelectionError { .. });
+            if in_transaction && is_transient {
+                error.with_label(TRANSIENT_TRANSACTION_ERROR)
+            } else {
+                error
+            }
+        });
+        if let Err(ref error) = result {
+            self.inner.counters.record_error(error);
+        }
+
+        if self.inner.options.emit_operation_events == Some(true) {
+            self.emit_command_event(|| {
+                let operation_event = OperationEvent {
+                    operation_name: T::NAME.to_string(),
+                    duration: start.elapsed(),
+                    server_selection_duration: timings.server_selection,
+                    connection_checkout_duration: timings.connection_checkout,
+                    command_duration: timings.command,
+                    retried: timings.retried,
+                    succeeded: result.is_ok(),
+                };
+
+                CommandEvent::Operation(operation_event)
+            })
+            .await;
+        }
+
+        result
+    }
+
+    /// Does the work of `execute_operation_with_retry`, recording the time spent in each phase in
+    /// `timings`.
+    async fn select_server_and_execute<T: Operation>(
+        &self,
+        op: T,
+        mut session: Option<&mut ClientSession>,
+        timings: &mut OperationTimings,
+        keep_connection: Option<&mut Option<Connection>>,
+    ) -> Result<T::O> {
+        let _in_flight = self.start_operation().await?;
+
+        // The operations in a transaction are executed on the server the transaction is on, and
+        // aren't retried.
+        let in_transaction = session
+            .as_ref()
+            .map(|session| session.in_transaction())
+            .unwrap_or(false);
+        let transaction_criteria = match session {
+            Some(ref session) if session.transaction_state() != TransactionState::None => {
+                Some(session.transaction_selection_criteria())
+            }
+            _ => None,
+        };
+        let criteria = transaction_criteria
+            .as_ref()
+            .or_else(|| op.selection_criteria());
+
+        // The comment is chosen once per operation, so every attempt at it is tagged the same.
+        let comment = self
+            .inner
+            .options
+            .comment_provider
+            .as_ref()
+            .and_then(|provider| provider());
+
+        if let Some(pinned_connection) = op.pinned_connection() {
+            let start = Instant::now();
+            let mut conn = pinned_connection.lock().await;
+            timings.connection_checkout += start.elapsed();
+
+            let start = Instant::now();
+            let result = self
+                .execute_operation_on_connection(
+                    &op,
+                    &mut conn,
+                    &mut session,
+                    None,
+                    comment.as_ref(),
+                )
+                .await;
+            timings.command += start.elapsed();
+            return result;
+        }
+
+        let start = Instant::now();
+        let server = self.select_server(criteria, None).await;
+        timings.server_selection += start.elapsed();
+        let server = server?;
+
+        let start = Instant::now();
+        let conn = server.checkout_connection().await;
+        timings.connection_checkout += start.elapsed();
+        let mut conn = match conn {
             Ok(conn) => conn,
             Err(err) => {
                 self.inner
@@ -93,20 +337,34 @@ impl Client {
             }
         };
 
-        let retryability = self.get_retryability(&conn, &op).await?;
+        let retryability = self.get_retryability(&conn, &op, in_transaction).await?;
 
         let txn_number = match session {
+            Some(ref session) if session.transaction_state() != TransactionState::None => {
+                Some(session.txn_number())
+            }
             Some(ref mut session) if retryability == Retryability::Write => {
                 Some(session.get_and_increment_txn_number())
             }
             _ => None,
         };
 
-        let first_error = match self
-            .execute_operation_on_connection(&op, &mut conn, &mut session, txn_number)
-            .await
-        {
+        let start = Instant::now();
+        let result = self
+            .execute_operation_on_connection(
+                &op,
+                &mut conn,
+                &mut session,
+                txn_number,
+                comment.as_ref(),
+            )
+            .await;
+        timings.command += start.elapsed();
+        let first_error = match result {
             Ok(result) => {
+                if let Some(keep_connection) = keep_connection {
+                    *keep_connection = Some(conn);
+                }
                 return Ok(result);
             }
             Err(err) => {
@@ -117,13 +375,20 @@ impl Client {
 
                 // Retryable writes are only supported by storage engines with document-level
                 // locking, so users need to disable retryable writes if using mmapv1.
-                if let ErrorKind::CommandError(ref err) = err.kind.as_ref() {
-                    if err.code == 20 && err.message.starts_with("Transaction numbers") {
-                        let mut err = err.clone();
-                        err.message = "This MongoDB deployment does not support retryable writes. \
-                                       Please add retryWrites=false to your connection string."
+                if let ErrorKind::CommandError(ref command_error) = err.kind.as_ref() {
+                    if command_error.code == 20
+                        && command_error.message.starts_with("Transaction numbers")
+                    {
+                        let mut command_error = command_error.clone();
+                        command_error.message = "This MongoDB deployment does not support \
+                                                 retryable writes. Please add retryWrites=false \
+                                                 to your connection string."
                             .to_string();
-                        return Err(ErrorKind::CommandError(err).into());
+                        let error = Error::from(ErrorKind::CommandError(command_error));
+                        return Err(match err.error_response() {
+                            Some(response) => error.with_response(response.clone()),
+                            None => error,
+                        });
                     }
                 }
 
@@ -135,7 +400,6 @@ impl Client {
                     _ => err,
                 };
 
-                // TODO RUST-90: Do not retry read if session is in a transaction
                 if retryability == Retryability::Read && err.is_read_retryable()
                     || retryability == Retryability::Write && err.is_write_retryable()
                 {
@@ -146,14 +410,22 @@ impl Client {
             }
         };
 
-        let server = match self.select_server(op.selection_criteria()).await {
+        // Prefer a different server for the retry in case the first one is the cause of the error,
+        // e.g. a mongos that is shutting down.
+        let start = Instant::now();
+        let server = self.select_server(criteria, Some(conn.address())).await;
+        timings.server_selection += start.elapsed();
+        let server = match server {
             Ok(server) => server,
             Err(_) => {
                 return Err(first_error);
             }
         };
 
-        let mut conn = match server.checkout_connection().await {
+        let start = Instant::now();
+        let conn = server.checkout_connection().await;
+        timings.connection_checkout += start.elapsed();
+        let mut conn = match conn {
             Ok(conn) => conn,
             Err(err) => {
                 self.inner
@@ -164,16 +436,31 @@ impl Client {
             }
         };
 
-        let retryability = self.get_retryability(&conn, &op).await?;
+        let retryability = self.get_retryability(&conn, &op, in_transaction).await?;
         if retryability == Retryability::None {
             return Err(first_error);
         }
-
-        match self
-            .execute_operation_on_connection(&op, &mut conn, &mut session, txn_number)
-            .await
-        {
-            Ok(result) => Ok(result),
+        self.inner.counters.record_retry();
+        timings.retried = true;
+
+        let start = Instant::now();
+        let result = self
+            .execute_operation_on_connection(
+                &op,
+                &mut conn,
+                &mut session,
+                txn_number,
+                comment.as_ref(),
+            )
+            .await;
+        timings.command += start.elapsed();
+        match result {
+            Ok(result) => {
+                if let Some(keep_connection) = keep_connection {
+                    *keep_connection = Some(conn);
+                }
+                Ok(result)
+            }
             Err(err) => {
                 self.inner
                     .topology
@@ -194,24 +481,64 @@ impl Client {
         }
     }
 
-    /// Executes an operation on a given connection, optionally using a provided session.
+    /// Executes an operation on a given connection, optionally using a provided session. The
+    /// `comment` from the client's `comment_provider`, if any, is attached to the command if the
+    /// server accepts one on it.
     async fn execute_operation_on_connection<T: Operation>(
         &self,
         op: &T,
         connection: &mut Connection,
         session: &mut Option<&mut ClientSession>,
         txn_number: Option<u64>,
+        comment: Option<&Bson>,
     ) -> Result<T::O> {
         if let Some(wc) = op.write_concern() {
             wc.validate()?;
         }
 
-        let mut cmd = op.build(connection.stream_description()?)?;
+        let stream_description = connection.stream_description()?;
+        let max_wire_version = stream_description.max_wire_version.unwrap_or(0);
+        let is_mongos = stream_description.initial_server_type == ServerType::Mongos;
+        let transaction_state = session
+            .as_ref()
+            .map(|session| session.transaction_state())
+            .unwrap_or(TransactionState::None);
+        let in_transaction = transaction_state != TransactionState::None;
+
+        if transaction_state == TransactionState::Starting
+            && (max_wire_version < 7 || is_mongos && max_wire_version < 8)
+        {
+            return Err(ErrorKind::Transaction {
+                message: "transactions require a replica set running MongoDB 4.0 or newer, or a \
+                          sharded cluster running MongoDB 4.2 or newer"
+                    .to_string(),
+            }
+            .into());
+        }
+
+        let mut cmd = op.build(stream_description)?;
+        let criteria = if in_transaction {
+            None
+        } else {
+            op.selection_criteria()
+        };
         self.inner
             .topology
-            .update_command_with_read_pref(connection.address(), &mut cmd, op.selection_criteria())
+            .update_command_with_read_pref(connection.address(), &mut cmd, criteria)
             .await;
 
+        if let Some(comment) = comment {
+            if cmd.accepts_comment(max_wire_version) {
+                cmd.set_default_comment(comment.clone());
+            }
+        }
+
+        // The connection declares the API version on every command it sends, but it's set here
+        // too so that command events show what's sent.
+        if let Some(ref server_api) = self.inner.options.server_api {
+            cmd.set_server_api(server_api);
+        }
+
         match session {
             Some(ref mut session) if op.supports_sessions() && op.is_acknowledged() => {
                 cmd.set_session(session);
@@ -236,6 +563,66 @@ impl Client {
             _ => {}
         }
 
+        // The commands in a transaction only carry its read concern, on the command that starts it,
+        // and its write concern, on the command that commits or aborts it.
+        if in_transaction {
+            cmd.body.remove("readConcern");
+            if !ends_transaction::<T>() {
+                cmd.body.remove("writeConcern");
+            }
+            cmd.set_autocommit();
+        }
+
+        // Snapshot reads in a session all read from the snapshot of the first one, so they can
+        // only be performed in a session the user started.
+        let is_snapshot_read = cmd.is_snapshot_read();
+        if is_snapshot_read {
+            match session {
+                Some(ref session) if !session.is_implicit() => {
+                    if let Some(snapshot_time) = session.snapshot_time() {
+                        cmd.set_snapshot_time(snapshot_time);
+                    }
+                }
+                _ => return Err(snapshot_read_without_session_error()),
+            }
+        }
+
+        // Reads in a causally consistent session wait for the server to observe the latest
+        // operation in the session. This doesn't apply to snapshot reads, which read from a
+        // fixed point in time instead.
+        if let Some(ref session) = session {
+            if let Some(operation_time) = session.operation_time() {
+                if session.is_causally_consistent()
+                    && op.supports_read_concern()
+                    && !is_snapshot_read
+                    && !in_transaction
+                {
+                    cmd.set_after_cluster_time(operation_time);
+                }
+            }
+        }
+
+        if transaction_state == TransactionState::Starting {
+            if let Some(ref mut session) = session {
+                let read_concern = session
+                    .transaction_options()
+                    .and_then(|options| options.read_concern.as_ref());
+                cmd.set_start_transaction(read_concern)?;
+                if session.is_causally_consistent() {
+                    if let Some(operation_time) = session.operation_time() {
+                        cmd.set_after_cluster_time(operation_time);
+                    }
+                }
+
+                let mongos = if is_mongos {
+                    Some(connection.address().clone())
+                } else {
+                    None
+                };
+                session.mark_transaction_in_progress(mongos);
+            }
+        }
+
         let session_cluster_time = session.as_ref().and_then(|session| session.cluster_time());
         let client_cluster_time = self.inner.topology.cluster_time().await;
         let max_cluster_time = std::cmp::max(session_cluster_time, client_cluster_time.as_ref());
@@ -245,14 +632,21 @@ impl Client {
 
         let connection_info = connection.info();
         let request_id = crate::cmap::conn::next_request_id();
+        self.inner.counters.record_command(&cmd.name);
 
-        self.emit_command_event(|handler| {
-            let should_redact = REDACTED_COMMANDS.contains(cmd.name.to_lowercase().as_str());
+        let should_redact =
+            self.inner.options.command_event_redaction != Some(false) && is_sensitive(&cmd);
 
+        // The command is serialized before the started event is emitted so that the event can
+        // report the length of the message it's sent in.
+        let message = connection.serialize_command(cmd.clone(), request_id).await;
+        let wire_bytes = message.as_ref().ok().map(Vec::len);
+
+        self.emit_command_event(|| {
             let command_body = if should_redact {
                 Document::new()
             } else {
-                cmd.body.clone()
+                self.reportable_command_body(&cmd).into_owned()
             };
             let command_started_event = CommandStartedEvent {
                 command: command_body,
@@ -260,14 +654,38 @@ impl Client {
                 command_name: cmd.name.clone(),
                 request_id,
                 connection: connection_info.clone(),
+                wire_bytes,
             };
 
-            handler.handle_command_started_event(command_started_event);
-        });
+            CommandEvent::Started(command_started_event)
+        })
+        .await;
+
+        #[cfg(feature = "tracing")]
+        let span = {
+            let body = if should_redact {
+                None
+            } else {
+                Some(self.reportable_command_body(&cmd))
+            };
+            crate::trace::command_span(
+                &cmd.name,
+                request_id,
+                &connection_info.address,
+                body.as_deref(),
+            )
+        };
 
         let start_time = PreciseTime::now();
 
-        let response_result = match connection.send_command(cmd.clone(), request_id).await {
+        let send = async {
+            let message = message?;
+            connection.send_serialized_command(&message).await
+        };
+        #[cfg(feature = "tracing")]
+        let send = tracing::Instrument::instrument(send, span.clone());
+
+        let response_result = match send.await {
             Ok(response) => {
                 if let Some(cluster_time) = response.cluster_time() {
                     self.inner.topology.advance_cluster_time(cluster_time).await;
@@ -275,6 +693,16 @@ impl Client {
                         session.advance_cluster_time(cluster_time)
                     }
                 }
+                if let Some(ref mut session) = session {
+                    if let Some(operation_time) = response.operation_time() {
+                        session.advance_operation_time(operation_time);
+                    }
+                    if let Some(snapshot_time) = response.snapshot_time() {
+                        if is_snapshot_read {
+                            session.pin_snapshot_time(snapshot_time);
+                        }
+                    }
+                }
                 response.validate().map(|_| response)
             }
             err => err,
@@ -283,9 +711,35 @@ impl Client {
         let end_time = PreciseTime::now();
         let duration = start_time.to(end_time).to_std()?;
 
+        #[cfg(feature = "tracing")]
+        crate::trace::command_finished(&span, duration, &response_result);
+
+        if let Some(threshold) = self.inner.options.slow_operation_threshold {
+            if duration > threshold {
+                self.emit_command_event(|| {
+                    let slow_command_event = SlowCommandEvent {
+                        duration,
+                        command_name: cmd.name.clone(),
+                        db: cmd.target_db.clone(),
+                        collection: cmd
+                            .body
+                            .get(&cmd.name)
+                            .and_then(Bson::as_str)
+                            .map(String::from),
+                        succeeded: response_result.is_ok(),
+                        request_id,
+                        connection: connection_info.clone(),
+                    };
+
+                    CommandEvent::Slow(slow_command_event)
+                })
+                .await;
+            }
+        }
+
         match response_result {
             Err(error) => {
-                self.emit_command_event(|handler| {
+                self.emit_command_event(|| {
                     let command_failed_event = CommandFailedEvent {
                         duration,
                         command_name: cmd.name,
@@ -294,8 +748,9 @@ impl Client {
                         connection: connection_info,
                     };
 
-                    handler.handle_command_failed_event(command_failed_event);
-                });
+                    CommandEvent::Failed(command_failed_event)
+                })
+                .await;
 
                 if let Some(session) = session {
                     if error.is_network_error() {
@@ -306,9 +761,7 @@ impl Client {
                 op.handle_error(error)
             }
             Ok(response) => {
-                self.emit_command_event(|handler| {
-                    let should_redact =
-                        REDACTED_COMMANDS.contains(cmd.name.to_lowercase().as_str());
+                self.emit_command_event(|| {
                     let reply = if should_redact {
                         Document::new()
                     } else {
@@ -321,15 +774,46 @@ impl Client {
                         command_name: cmd.name.clone(),
                         request_id,
                         connection: connection_info,
+                        wire_bytes: response.wire_bytes(),
                     };
-                    handler.handle_command_succeeded_event(command_succeeded_event);
-                });
+                    CommandEvent::Succeeded(command_succeeded_event)
+                })
+                .await;
+
+                // Write errors are reported in replies with "ok: 1", so a copy of such replies is
+                // kept to attach to the error the operation returns for them.
+                let error_response = if response.raw_response.contains_key("writeErrors")
+                    || response.raw_response.contains_key("writeConcernError")
+                {
+                    Some(response.raw_response.clone())
+                } else {
+                    None
+                };
 
                 op.handle_response(response)
+                    .map_err(|error| match error_response {
+                        Some(response) if error.is_server_error() => error.with_response(response),
+                        _ => error,
+                    })
             }
         }
     }
 
+    /// The fields of `cmd` that may be reported in command events and traces: all of them, unless
+    /// `command_event_field_allowlist` restricts them.
+    fn reportable_command_body<'a>(&self, cmd: &'a Command) -> Cow<'a, Document> {
+        match self.inner.options.command_event_field_allowlist {
+            Some(ref allowlist) => Cow::Owned(
+                cmd.body
+                    .iter()
+                    .filter(|(key, _)| **key == cmd.name || allowlist.contains(key))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            ),
+            None => Cow::Borrowed(&cmd.body),
+        }
+    }
+
     /// Start an implicit session if the operation and write concern are compatible with sessions.
     async fn start_implicit_session<T: Operation>(&self, op: &T) -> Result<Option<ClientSession>> {
         match self.get_session_support_status().await? {
@@ -343,10 +827,66 @@ impl Client {
         }
     }
 
+    /// Starts a causally consistent implicit session for a sequence of operations that depend on
+    /// each other, if the deployment supports sessions.
+    pub(crate) async fn start_causally_consistent_implicit_session(
+        &self,
+    ) -> Result<Option<ClientSession>> {
+        match self.get_session_support_status().await? {
+            SessionSupportStatus::Supported {
+                logical_session_timeout,
+            } => {
+                let mut session = self
+                    .start_implicit_session_with_timeout(logical_session_timeout)
+                    .await;
+                session.make_causally_consistent();
+                Ok(Some(session))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Registers a new operation as in progress, waiting for or failing due to
+    /// `max_concurrent_operations` as needed. The operation is considered to be in progress until
+    /// the returned guard is dropped. Fails if the `Client` has been shut down.
+    async fn start_operation(&self) -> Result<InFlightOperation<'_>> {
+        if !self.inner.topology.is_alive() {
+            return Err(ErrorKind::ClientShutdown.into());
+        }
+
+        let permit = match self.inner.operation_limiter {
+            Some(ref limiter)
+                if self.inner.options.fail_fast_on_max_concurrent_operations == Some(true) =>
+            {
+                let permit = limiter.try_acquire().map_err(|_| {
+                    ErrorKind::MaxConcurrentOperationsExceeded {
+                        max_concurrent_operations: self
+                            .inner
+                            .options
+                            .max_concurrent_operations
+                            .unwrap_or_default(),
+                    }
+                })?;
+                Some(permit)
+            }
+            Some(ref limiter) => Some(limiter.acquire().await),
+            None => None,
+        };
+
+        self.inner
+            .in_flight_operations
+            .fetch_add(1, Ordering::SeqCst);
+
+        Ok(InFlightOperation {
+            count: &self.inner.in_flight_operations,
+            _permit: permit,
+        })
+    }
+
     /// Gets whether the topology supports sessions, and if so, returns the topology's logical
     /// session timeout. If it has yet to be determined if the topology supports sessions, this
     /// method will perform a server selection that will force that determination to be made.
-    async fn get_session_support_status(&self) -> Result<SessionSupportStatus> {
+    pub(super) async fn get_session_support_status(&self) -> Result<SessionSupportStatus> {
         let initial_status = self.inner.topology.session_support_status().await;
 
         // Need to guarantee that we're connected to at least one server that can determine if
@@ -356,7 +896,7 @@ impl Client {
                 let criteria = SelectionCriteria::Predicate(Arc::new(move |server_info| {
                     server_info.server_type().is_data_bearing()
                 }));
-                let _: Arc<Server> = self.select_server(Some(&criteria)).await?;
+                let _: Arc<Server> = self.select_server(Some(&criteria), None).await?;
                 Ok(self.inner.topology.session_support_status().await)
             }
             _ => Ok(initial_status),
@@ -368,8 +908,10 @@ impl Client {
         &self,
         conn: &Connection,
         op: &T,
+        in_transaction: bool,
     ) -> Result<Retryability> {
         match op.retryability() {
+            _ if in_transaction => Ok(Retryability::None),
             Retryability::Read if self.inner.options.retry_reads != Some(false) => {
                 Ok(Retryability::Read)
             }
@@ -384,6 +926,12 @@ impl Client {
     }
 }
 
+/// Whether the operation commits or aborts a transaction. These are the only commands in a
+/// transaction that are retried and sent with a write concern.
+fn ends_transaction<T: Operation>() -> bool {
+    T::NAME == CommitTransaction::NAME || T::NAME == AbortTransaction::NAME
+}
+
 /// Returns an Error with a "RetryableWriteError" label added if necessary. On a pre-4.4
 /// connection, a label should be added to any write-retryable error. On a 4.4+ connection, a
 /// label should only be added to network errors. Regardless of server version, a label should
@@ -391,8 +939,78 @@ impl Client {
 async fn get_error_with_retryable_write_label(conn: &Connection, err: Error) -> Result<Error> {
     if let Some(max_wire_version) = conn.stream_description()?.max_wire_version {
         if err.should_add_retryable_write_label(max_wire_version) {
-            return Ok(err.with_label("RetryableWriteError"));
+            return Ok(err.with_label(RETRYABLE_WRITE_ERROR));
         }
     }
     Ok(err)
 }
+
+/// Returns an error if `op` reads with a "snapshot" read concern, which can only be used in an
+/// explicit session. This is checked before a server is selected for operations that would run in
+/// an implicit session, so that they fail immediately.
+fn check_snapshot_read_without_session<T: Operation>(op: &T) -> Result<()> {
+    match op.read_concern() {
+        Some(read_concern) if read_concern.level == ReadConcernLevel::Snapshot => {
+            Err(snapshot_read_without_session_error())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn snapshot_read_without_session_error() -> Error {
+    ErrorKind::ArgumentError {
+        message: "a \"snapshot\" read concern can only be used in an explicit session, e.g. one \
+                  started with Client::start_session"
+            .to_string(),
+    }
+    .into()
+}
+
+/// Runs the given future to completion, returning a timeout error instead if `deadline` passes
+/// first. Dropping the future when the deadline passes is safe, as a connection that was waiting
+/// for a reply is closed rather than returned to its pool.
+async fn with_deadline<F: Future>(deadline: Option<Instant>, future: F) -> Result<F::Output> {
+    let deadline = match deadline {
+        Some(deadline) => deadline,
+        None => return Ok(future.await),
+    };
+
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining == Duration::from_secs(0) {
+        return Err(Error::timeout());
+    }
+    RUNTIME
+        .timeout(remaining, future)
+        .await
+        .map_err(|_| Error::timeout())
+}
+
+/// Whether the events for the given command must be redacted because it may contain credentials.
+fn is_sensitive(cmd: &Command) -> bool {
+    let name = cmd.name.to_lowercase();
+    REDACTED_COMMANDS.contains(name.as_str())
+        || ((name == "hello" || name == "ismaster")
+            && cmd.body.contains_key("speculativeAuthenticate"))
+}
+
+/// Tracks an operation that is in progress, releasing its slot when dropped.
+struct InFlightOperation<'a> {
+    count: &'a AtomicUsize,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+impl Drop for InFlightOperation<'_> {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The time spent in each phase of executing an operation, accumulated across the initial attempt
+/// and any retry.
+#[derive(Debug, Default)]
+struct OperationTimings {
+    server_selection: Duration,
+    connection_checkout: Duration,
+    command: Duration,
+    retried: bool,
+}
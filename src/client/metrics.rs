@@ -0,0 +1,276 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{error::Error, options::StreamAddress};
+
+/// A snapshot of the counters a [`Client`](struct.Client.html) maintains about the operations it
+/// executes and the connections it manages. This can be obtained via
+/// [`Client::metrics`](struct.Client.html#method.metrics).
+///
+/// All counters other than the pool sizes are totals since the `Client` was created. The snapshot
+/// is not updated as the `Client` continues to be used; to get newer values, a new snapshot must be
+/// requested.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ClientMetrics {
+    /// The number of commands sent to the server, keyed by command name. Retried commands are
+    /// counted once per attempt.
+    pub commands: HashMap<String, u64>,
+
+    /// The number of operations that returned an error, keyed by the name of the
+    /// [`ErrorKind`](error/enum.ErrorKind.html) variant, e.g. `"CommandError"`.
+    pub errors: HashMap<String, u64>,
+
+    /// The number of times an operation was retried after a retryable error.
+    pub retries: u64,
+
+    /// Metrics for the connection pool of each server currently in the topology, keyed by the
+    /// address of the server.
+    pub pools: HashMap<StreamAddress, PoolMetrics>,
+}
+
+/// A snapshot of the counters maintained by the connection pool of a single server.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct PoolMetrics {
+    /// The number of connections currently managed by the pool, including connections which are
+    /// checked out or still being established.
+    pub connections: u32,
+
+    /// The total number of connections the pool has created.
+    pub connections_created: u64,
+
+    /// The total number of connections the pool has closed, including connections that failed to
+    /// be established.
+    pub connections_closed: u64,
+
+    /// The number of connections currently idle in the pool and available to be checked out.
+    pub available_connections: u32,
+
+    /// The number of connections currently being established.
+    pub pending_connections: u32,
+}
+
+/// The names of the commands that the driver runs itself, sorted so that they can be binary
+/// searched. Each of them is counted without taking a lock.
+const COMMAND_NAMES: &[&str] = &[
+    "abortTransaction",
+    "aggregate",
+    "authenticate",
+    "buildInfo",
+    "collStats",
+    "commitTransaction",
+    "count",
+    "create",
+    "createIndexes",
+    "delete",
+    "distinct",
+    "drop",
+    "dropDatabase",
+    "dropIndexes",
+    "endSessions",
+    "explain",
+    "find",
+    "findAndModify",
+    "getDefaultRWConcern",
+    "getMore",
+    "getnonce",
+    "hello",
+    "insert",
+    "isMaster",
+    "killCursors",
+    "listCollections",
+    "listDatabases",
+    "listIndexes",
+    "ping",
+    "saslContinue",
+    "saslStart",
+    "update",
+    "validate",
+];
+
+/// The counters updated as a `Client` executes operations.
+#[derive(Debug)]
+pub(crate) struct ClientCounters {
+    /// The number of times each of the commands in `COMMAND_NAMES` was sent, in the same order.
+    commands: Box<[AtomicU64]>,
+
+    /// The number of times any other command, e.g. one run through `Database::run_command`, was
+    /// sent.
+    other_commands: Mutex<HashMap<String, u64>>,
+
+    errors: Mutex<HashMap<&'static str, u64>>,
+    retries: AtomicU64,
+}
+
+impl Default for ClientCounters {
+    fn default() -> Self {
+        Self {
+            commands: COMMAND_NAMES.iter().map(|_| AtomicU64::new(0)).collect(),
+            other_commands: Default::default(),
+            errors: Default::default(),
+            retries: Default::default(),
+        }
+    }
+}
+
+impl ClientCounters {
+    pub(crate) fn record_command(&self, name: &str) {
+        match COMMAND_NAMES.binary_search(&name) {
+            Ok(index) => {
+                self.commands[index].fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {
+                *self
+                    .other_commands
+                    .lock()
+                    .unwrap()
+                    .entry(name.to_string())
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    pub(crate) fn record_error(&self, error: &Error) {
+        *self
+            .errors
+            .lock()
+            .unwrap()
+            .entry(error.kind.name())
+            .or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Builds a snapshot of these counters along with the given pool metrics.
+    pub(crate) fn snapshot(&self, pools: HashMap<StreamAddress, PoolMetrics>) -> ClientMetrics {
+        let errors = self
+            .errors
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(kind, count)| (kind.to_string(), *count))
+            .collect();
+
+        let mut commands = self.other_commands.lock().unwrap().clone();
+        for (name, count) in COMMAND_NAMES.iter().zip(self.commands.iter()) {
+            let count = count.load(Ordering::Relaxed);
+            if count > 0 {
+                commands.insert(name.to_string(), count);
+            }
+        }
+
+        ClientMetrics {
+            commands,
+            errors,
+            retries: self.retries.load(Ordering::Relaxed),
+            pools,
+        }
+    }
+}
+
+/// The counters updated by a connection pool's worker as connections are created and closed.
+#[derive(Debug, Default)]
+pub(crate) struct PoolCounters {
+    connections: AtomicU32,
+    connections_created: AtomicU64,
+    connections_closed: AtomicU64,
+    available_connections: AtomicU32,
+    pending_connections: AtomicU32,
+}
+
+impl PoolCounters {
+    pub(crate) fn record_connection_created(&self, total_connection_count: u32) {
+        self.connections_created.fetch_add(1, Ordering::Relaxed);
+        self.connections
+            .store(total_connection_count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_connection_closed(&self, total_connection_count: u32) {
+        self.connections_closed.fetch_add(1, Ordering::Relaxed);
+        self.connections
+            .store(total_connection_count, Ordering::Relaxed);
+    }
+
+    /// Records the number of connections that are available and being established, which the
+    /// worker does after each task it handles.
+    pub(crate) fn record_pool_state(&self, available_connections: u32, pending_connections: u32) {
+        self.available_connections
+            .store(available_connections, Ordering::Relaxed);
+        self.pending_connections
+            .store(pending_connections, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> PoolMetrics {
+        PoolMetrics {
+            connections: self.connections.load(Ordering::Relaxed),
+            connections_created: self.connections_created.load(Ordering::Relaxed),
+            connections_closed: self.connections_closed.load(Ordering::Relaxed),
+            available_connections: self.available_connections.load(Ordering::Relaxed),
+            pending_connections: self.pending_connections.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClientCounters, PoolCounters, COMMAND_NAMES};
+    use crate::{error::ErrorKind, options::StreamAddress};
+
+    #[test]
+    fn command_names_sorted() {
+        let mut sorted = COMMAND_NAMES.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, COMMAND_NAMES);
+    }
+
+    #[test]
+    fn snapshot() {
+        let counters = ClientCounters::default();
+        counters.record_command("find");
+        counters.record_command("find");
+        counters.record_command("insert");
+        counters.record_command("serverStatus");
+        counters.record_error(
+            &ErrorKind::ArgumentError {
+                message: "bad".to_string(),
+            }
+            .into(),
+        );
+        counters.record_retry();
+
+        let pool_counters = PoolCounters::default();
+        pool_counters.record_connection_created(1);
+        pool_counters.record_connection_created(2);
+        pool_counters.record_connection_closed(1);
+        pool_counters.record_pool_state(0, 1);
+
+        let address = StreamAddress::default();
+        let pools = vec![(address.clone(), pool_counters.snapshot())]
+            .into_iter()
+            .collect();
+        let metrics = counters.snapshot(pools);
+
+        assert_eq!(metrics.commands.get("find"), Some(&2));
+        assert_eq!(metrics.commands.get("insert"), Some(&1));
+        assert_eq!(metrics.commands.get("serverStatus"), Some(&1));
+        assert_eq!(metrics.commands.len(), 3);
+        assert_eq!(metrics.errors.get("ArgumentError"), Some(&1));
+        assert_eq!(metrics.retries, 1);
+
+        let pool = &metrics.pools[&address];
+        assert_eq!(pool.connections, 1);
+        assert_eq!(pool.connections_created, 2);
+        assert_eq!(pool.connections_closed, 1);
+        assert_eq!(pool.available_connections, 0);
+        assert_eq!(pool.pending_connections, 1);
+    }
+}
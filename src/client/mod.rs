@@ -1,36 +1,69 @@
 pub mod auth;
 mod executor;
+pub(crate) mod metrics;
 pub mod options;
 mod session;
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use derivative::Derivative;
 use time::PreciseTime;
+use tokio::sync::Semaphore;
 
-#[cfg(test)]
-use crate::options::StreamAddress;
 use crate::{
-    bson::{Bson, Document},
+    bson::{doc, Bson, Document},
+    change_stream::{
+        event::ChangeStreamEvent,
+        options::ChangeStreamOptions,
+        ChangeStream,
+        ChangeStreamTarget,
+    },
+    cmap::PinnedConnection,
     concern::{ReadConcern, WriteConcern},
+    cursor::CursorInformation,
     db::Database,
     error::{ErrorKind, Result},
-    event::command::CommandEventHandler,
-    operation::ListDatabases,
+    event::command::{CommandEvent, CommandEventQueue},
+    operation::{GetDefaultRwConcern, ListDatabases, RunCommand},
     options::{
         ClientOptions,
         DatabaseOptions,
         ListDatabasesOptions,
         ReadPreference,
         SelectionCriteria,
+        SessionOptions,
+        StreamAddress,
     },
-    sdam::{Server, SessionSupportStatus, Topology},
+    results::DefaultRwConcern,
+    sdam::{public::TopologyDescription, Server, SessionSupportStatus, Topology},
+    Namespace,
+    RUNTIME,
 };
-pub(crate) use session::{ClientSession, ClusterTime, SESSIONS_UNSUPPORTED_COMMANDS};
+pub use session::{ClientSession, ClusterTime};
+pub(crate) use session::SESSIONS_UNSUPPORTED_COMMANDS;
+use metrics::{ClientCounters, ClientMetrics};
 use session::{ServerSession, ServerSessionPool};
 
 const DEFAULT_SERVER_SELECTION_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// How often `Client::shutdown` checks whether the operations in progress have finished.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long `Client::shutdown` spends killing cursors and ending sessions when it isn't given a
+/// timeout, so that an unreachable server doesn't hold it up for the server selection timeout.
+const SHUTDOWN_CLEANUP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The maximum number of sessions that can be ended by a single `endSessions` command.
+const MAX_END_SESSIONS_BATCH_SIZE: usize = 10_000;
+
 /// This is the main entry point for the API. A `Client` is used to connect to a MongoDB cluster.
 /// By default, it will monitor the topology of the cluster, keeping track of any changes, such
 /// as servers being added or removed.
@@ -40,7 +73,7 @@ const DEFAULT_SERVER_SELECTION_TIMEOUT: Duration = Duration::from_secs(30);
 ///
 /// ```rust
 /// # #[cfg(not(feature = "sync"))]
-/// # use mongodb::{Client, error::Result};
+/// # use mongodb::{bson::Document, Client, error::Result};
 /// # #[cfg(feature = "async-std-runtime")]
 /// # use async_std::task;
 /// # #[cfg(feature = "tokio-runtime")]
@@ -54,7 +87,7 @@ const DEFAULT_SERVER_SELECTION_TIMEOUT: Duration = Duration::from_secs(30);
 ///     let client_ref = client.clone();
 ///
 ///     task::spawn(async move {
-///         let collection = client_ref.database("items").collection(&format!("coll{}", i));
+///         let collection = client_ref.database("items").collection::<Document>(&format!("coll{}", i));
 ///
 ///         // Do something with the collection
 ///     });
@@ -69,6 +102,16 @@ const DEFAULT_SERVER_SELECTION_TIMEOUT: Duration = Duration::from_secs(30);
 /// driver does not set ``tcp_keepalive_intvl``. See the
 /// [MongoDB Diagnostics FAQ keepalive section](https://docs.mongodb.com/manual/faq/diagnostics/#does-tcp-keepalive-time-affect-mongodb-deployments)
 /// for instructions on setting these values at the system level.
+///
+/// ## Cancellation
+/// An operation can be cancelled at any point by dropping the future returned for it, e.g. when it
+/// loses a `select!` against a cancellation signal or a timeout. Doing so never leaves a
+/// connection in an indeterminate state: if the operation was waiting for a reply when it was
+/// cancelled, its connection is closed rather than returned to the pool, and any other
+/// connection it had checked out is returned to the pool as usual. Dropping a
+/// [`Cursor`](struct.Cursor.html) that has not been exhausted kills it on the server in the
+/// background. Note that the server may still complete an operation that had already been sent
+/// to it when it was cancelled.
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: Arc<ClientInner>,
@@ -80,6 +123,29 @@ struct ClientInner {
     topology: Topology,
     options: ClientOptions,
     session_pool: ServerSessionPool,
+
+    /// Bounds the number of operations in progress if `max_concurrent_operations` is set.
+    operation_limiter: Option<Semaphore>,
+
+    /// The number of operations currently in progress.
+    in_flight_operations: AtomicUsize,
+
+    /// The counters reported by `Client::metrics`.
+    counters: ClientCounters,
+
+    /// The cursors that are still open on the server, keyed by their server's address and their
+    /// id, so that they can be killed by `Client::shutdown`.
+    open_cursors: Mutex<HashMap<(StreamAddress, i64), OpenCursor>>,
+
+    /// The queue of events for the `async_command_event_handler`, if one is configured.
+    command_event_queue: Option<CommandEventQueue>,
+}
+
+/// A cursor that is still open on the server.
+#[derive(Debug)]
+struct OpenCursor {
+    ns: Namespace,
+    pinned_connection: Option<PinnedConnection>,
 }
 
 impl Drop for ClientInner {
@@ -104,18 +170,67 @@ impl Client {
     pub fn with_options(options: ClientOptions) -> Result<Self> {
         options.validate()?;
 
+        // Load the TLS files up front so that a missing or malformed file is reported here rather
+        // than when the first connection is established.
+        if let Some(tls_options) = options.tls_options() {
+            tls_options.into_rustls_config()?;
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        let topology_options = options.clone();
+        #[cfg(feature = "tracing")]
+        let topology_options = {
+            let mut topology_options = options.clone();
+            topology_options.sdam_event_handler = Some(crate::trace::sdam_event_handler(
+                topology_options.sdam_event_handler.take(),
+            ));
+            topology_options
+        };
+
+        let command_event_queue = options.async_command_event_handler.clone().map(|handler| {
+            CommandEventQueue::start(
+                handler,
+                options.async_command_event_backpressure.unwrap_or_default(),
+            )
+        });
+
         let inner = Arc::new(ClientInner {
-            topology: Topology::new(options.clone())?,
+            topology: Topology::new(topology_options)?,
             session_pool: ServerSessionPool::new(),
+            operation_limiter: options.max_concurrent_operations.map(Semaphore::new),
+            in_flight_operations: AtomicUsize::new(0),
+            counters: Default::default(),
+            open_cursors: Default::default(),
+            command_event_queue,
             options,
         });
 
         Ok(Self { inner })
     }
 
-    pub(crate) fn emit_command_event(&self, emit: impl FnOnce(&Arc<dyn CommandEventHandler>)) {
-        if let Some(ref handler) = self.inner.options.command_event_handler {
-            emit(handler);
+    /// Emits the event created by `generate` to the configured command event handlers. The event
+    /// is only created if there's a handler to emit it to.
+    pub(crate) async fn emit_command_event(&self, generate: impl FnOnce() -> CommandEvent) {
+        let handler = self.inner.options.command_event_handler.as_ref();
+        let queue = self.inner.command_event_queue.as_ref();
+
+        match (handler, queue) {
+            (Some(handler), Some(queue)) => {
+                let event = generate();
+                event.clone().emit(handler.as_ref());
+                queue.push(event).await;
+            }
+            (Some(handler), None) => generate().emit(handler.as_ref()),
+            (None, Some(queue)) => queue.push(generate()).await,
+            (None, None) => {}
+        }
+    }
+
+    /// Adds an `_id` generated by the `id_generator` option to `doc` if one is configured and the
+    /// document doesn't already have an `_id`.
+    pub(crate) fn add_generated_id(&self, doc: &mut Document) {
+        if let Some(ref generator) = self.inner.options.id_generator {
+            doc.entry("_id".to_string()).or_insert_with(|| generator());
         }
     }
 
@@ -189,6 +304,224 @@ impl Client {
         }
     }
 
+    /// Gets the cluster-wide default read and write concerns that the server applies to operations
+    /// that don't specify their own, using the `getDefaultRWConcern` command. Note that this
+    /// command is only supported in MongoDB 4.4 or greater.
+    pub async fn server_default_rw_concern(&self) -> Result<DefaultRwConcern> {
+        self.execute_operation(GetDefaultRwConcern).await
+    }
+
+    /// Starts a new [`ChangeStream`](change_stream/struct.ChangeStream.html) that receives events
+    /// for all changes in the cluster. The stream does not observe changes from system collections
+    /// or the "config", "local" or "admin" databases. Note that this method (`watch` on a cluster)
+    /// is only supported in MongoDB 4.0 or greater.
+    ///
+    /// A single cluster-wide change stream can be used to consume the changes on every namespace
+    /// by inspecting the [`ns`](change_stream/event/struct.ChangeStreamEvent.html#structfield.ns)
+    /// field of each event. To only receive events for particular databases or collections, the
+    /// `pipeline` can filter on that field, e.g. `doc! { "$match": { "ns.db": "inventory" } }`.
+    ///
+    /// See the documentation [here](https://docs.mongodb.com/manual/changeStreams/) on change
+    /// streams.
+    ///
+    /// Change streams require either a "majority" read concern or no read
+    /// concern. Anything else will cause a server error.
+    ///
+    /// Note that using a `$project` stage to remove the `_id` field of the events will cause an
+    /// error, since the driver requires it to track the stream's resume token.
+    pub async fn watch(
+        &self,
+        pipeline: impl Into<Option<Vec<Document>>>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream<ChangeStreamEvent<Document>>> {
+        let mut options = options.into();
+        resolve_options!(self, options, [read_concern, selection_criteria]);
+
+        ChangeStream::execute(
+            self,
+            ChangeStreamTarget::Cluster,
+            pipeline.into().unwrap_or_default(),
+            options,
+        )
+        .await
+    }
+
+    /// Starts a new [`ClientSession`](struct.ClientSession.html) that operations can be executed
+    /// with, e.g. using
+    /// [`Collection::find_one_with_session`](struct.Collection.html#method.find_one_with_session).
+    ///
+    /// Sessions are only supported by replica sets and sharded clusters; an error is returned if
+    /// the deployment doesn't support them.
+    pub async fn start_session(
+        &self,
+        options: impl Into<Option<SessionOptions>>,
+    ) -> Result<ClientSession> {
+        match self.get_session_support_status().await? {
+            SessionSupportStatus::Supported {
+                logical_session_timeout,
+            } => Ok(ClientSession::new_explicit(
+                self.inner
+                    .session_pool
+                    .check_out(logical_session_timeout)
+                    .await,
+                self.clone(),
+                options.into(),
+            )),
+            _ => Err(ErrorKind::SessionsNotSupported.into()),
+        }
+    }
+
+    /// Gets a snapshot of the driver's current view of the topology of the cluster, including the
+    /// topology type and information about each known server.
+    ///
+    /// This does not perform any I/O; it only reports the state most recently observed by the
+    /// driver's monitoring tasks.
+    pub async fn topology_description(&self) -> TopologyDescription {
+        TopologyDescription::new(self.inner.topology.description().await)
+    }
+
+    /// Gets the highest cluster time that this `Client` has seen in a server response, which it
+    /// sends along with the commands it runs. This is `None` if it hasn't seen one yet, e.g.
+    /// because it's connected to a standalone server.
+    pub async fn cluster_time(&self) -> Option<ClusterTime> {
+        self.inner.topology.cluster_time().await
+    }
+
+    /// Advances the cluster time that this `Client` sends along with its commands to
+    /// `cluster_time`, e.g. one obtained from [`cluster_time`](#method.cluster_time) on another
+    /// `Client`. The cluster time only ever moves forward, so this has no effect if `cluster_time`
+    /// is not greater than the current one.
+    pub async fn advance_cluster_time(&self, cluster_time: &ClusterTime) {
+        self.inner.topology.advance_cluster_time(cluster_time).await
+    }
+
+    /// Gets the number of operations currently in progress on this `Client`. Operations that are
+    /// waiting for another operation to finish due to `max_concurrent_operations` are not included.
+    pub fn in_flight_operations(&self) -> usize {
+        self.inner.in_flight_operations.load(Ordering::SeqCst)
+    }
+
+    /// Gets a snapshot of the counters this `Client` maintains about the commands it has sent, the
+    /// errors and retries it has encountered, and the connection pools of the servers currently
+    /// in the topology.
+    ///
+    /// This does not perform any I/O.
+    pub async fn metrics(&self) -> ClientMetrics {
+        let pools = self.inner.topology.pool_metrics().await;
+        self.inner.counters.snapshot(pools)
+    }
+
+    /// Shuts the `Client` down, releasing the resources it holds on the server and locally.
+    ///
+    /// This first waits for the operations in progress to finish, and so for the connections they
+    /// checked out to be returned to their pools. The cursors the driver still has open on the
+    /// server are then killed and the server sessions in the session pool are ended. If `timeout`
+    /// is given, these steps together take at most that long; otherwise, killing the cursors and
+    /// ending the sessions is given up on after a second. Finally, the monitoring of the servers
+    /// is stopped and the connection pools are closed, emitting a `PoolClearedEvent` and a
+    /// `PoolClosedEvent` for each.
+    ///
+    /// Once this returns, any operation attempted with a clone of this `Client`, including the
+    /// ones held by its `Database`s, `Collection`s and `Cursor`s, fails with
+    /// [`ErrorKind::ClientShutdown`](error/enum.ErrorKind.html#variant.ClientShutdown).
+    pub async fn shutdown(self, timeout: impl Into<Option<Duration>>) {
+        let deadline = timeout.into().map(|timeout| Instant::now() + timeout);
+        while self.in_flight_operations() > 0
+            && deadline
+                .map(|deadline| Instant::now() < deadline)
+                .unwrap_or(true)
+        {
+            RUNTIME.delay_for(SHUTDOWN_POLL_INTERVAL).await;
+        }
+
+        // Errors are ignored, and the cleanup is bounded, since the shutdown shouldn't be held up
+        // by the server being unreachable; the server eventually times out cursors and sessions
+        // by itself.
+        let open_cursors: Vec<_> = self.inner.open_cursors.lock().unwrap().drain().collect();
+        let session_ids = self.inner.session_pool.drain().await;
+        let cleanup = async {
+            for ((address, id), cursor) in open_cursors {
+                let _: Result<_> = self.kill_open_cursor(address, id, cursor).await;
+            }
+            for batch in session_ids.chunks(MAX_END_SESSIONS_BATCH_SIZE) {
+                let _: Result<_> = self.end_sessions(batch).await;
+            }
+        };
+        let cleanup_timeout = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => SHUTDOWN_CLEANUP_TIMEOUT,
+        };
+        let _: Result<_> = RUNTIME.timeout(cleanup_timeout, cleanup).await;
+
+        self.inner.topology.shutdown().await;
+    }
+
+    async fn kill_open_cursor(
+        &self,
+        address: StreamAddress,
+        id: i64,
+        cursor: OpenCursor,
+    ) -> Result<()> {
+        let op = RunCommand::new(
+            cursor.ns.db,
+            doc! {
+                "killCursors": cursor.ns.coll,
+                "cursors": [id],
+            },
+            Some(SelectionCriteria::from_address(address)),
+        )?
+        .with_pinned_connection(cursor.pinned_connection);
+        self.execute_operation(op).await?;
+        Ok(())
+    }
+
+    async fn end_sessions(&self, session_ids: &[Document]) -> Result<()> {
+        let op = RunCommand::new(
+            "admin".to_string(),
+            doc! { "endSessions": session_ids },
+            Some(SelectionCriteria::ReadPreference(
+                ReadPreference::PrimaryPreferred {
+                    options: Default::default(),
+                },
+            )),
+        )?;
+        self.execute_operation(op).await?;
+        Ok(())
+    }
+
+    /// Tracks the cursor described by `info` as open on the server until `untrack_cursor` is
+    /// called for it.
+    pub(crate) fn track_cursor(&self, info: &CursorInformation) {
+        let cursor = OpenCursor {
+            ns: info.ns.clone(),
+            pinned_connection: info.pinned_connection.clone(),
+        };
+        self.inner
+            .open_cursors
+            .lock()
+            .unwrap()
+            .insert((info.address.clone(), info.id), cursor);
+    }
+
+    /// Gets the max wire version of the server at `address`, if it's known.
+    pub(crate) async fn server_max_wire_version(&self, address: &StreamAddress) -> Option<i32> {
+        self.inner
+            .topology
+            .description()
+            .await
+            .get_server_description(address)
+            .and_then(|description| description.max_wire_version().ok().flatten())
+    }
+
+    /// Stops tracking the cursor described by `info`, e.g. because it was exhausted or killed.
+    pub(crate) fn untrack_cursor(&self, info: &CursorInformation) {
+        self.inner
+            .open_cursors
+            .lock()
+            .unwrap()
+            .remove(&(info.address.clone(), info.id));
+    }
+
     /// Check in a server session to the server session pool.
     /// If the session is expired or dirty, or the topology no longer supports sessions, the session
     /// will be discarded.
@@ -240,16 +573,57 @@ impl Client {
         &self,
         criteria: Option<&SelectionCriteria>,
     ) -> Result<StreamAddress> {
-        let server = self.select_server(criteria).await?;
+        let server = self.select_server(criteria, None).await?;
         Ok(server.address.clone())
     }
 
     /// Select a server using the provided criteria. If none is provided, a primary read preference
-    /// will be used instead.
-    async fn select_server(&self, criteria: Option<&SelectionCriteria>) -> Result<Arc<Server>> {
+    /// will be used instead. The `deprioritized` server is only selected if no other server is
+    /// suitable. If server selection times out, it is re-attempted as specified by the
+    /// `reconnect_policy` option.
+    async fn select_server(
+        &self,
+        criteria: Option<&SelectionCriteria>,
+        deprioritized: Option<&StreamAddress>,
+    ) -> Result<Arc<Server>> {
         let criteria =
             criteria.unwrap_or(&SelectionCriteria::ReadPreference(ReadPreference::Primary));
 
+        let mut attempt = 0;
+        loop {
+            if let Some(server) = self
+                .select_server_before_timeout(criteria, deprioritized)
+                .await?
+            {
+                return Ok(server);
+            }
+
+            match self.inner.options.reconnect_policy {
+                Some(ref policy) if attempt < policy.max_attempts() => {
+                    RUNTIME.delay_for(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                _ => {
+                    return Err(ErrorKind::ServerSelectionError {
+                        message: self
+                            .inner
+                            .topology
+                            .server_selection_timeout_error_message(&criteria)
+                            .await,
+                    }
+                    .into());
+                }
+            }
+        }
+    }
+
+    /// Attempts to select a server until `server_selection_timeout` elapses, returning `None` if
+    /// none was selected in time.
+    async fn select_server_before_timeout(
+        &self,
+        criteria: &SelectionCriteria,
+        deprioritized: Option<&StreamAddress>,
+    ) -> Result<Option<Arc<Server>>> {
         let start_time = PreciseTime::now();
         let timeout = time::Duration::from_std(
             self.inner
@@ -262,11 +636,19 @@ impl Client {
             let selected_server = self
                 .inner
                 .topology
-                .attempt_to_select_server(criteria)
+                .attempt_to_select_server(criteria, deprioritized)
                 .await?;
 
             if let Some(server) = selected_server {
-                return Ok(server);
+                return Ok(Some(server));
+            }
+
+            if self.inner.options.fail_fast_on_no_primary == Some(true)
+                && criteria.is_read_pref_primary()
+            {
+                if let Some(message) = self.inner.topology.no_primary_error_message().await {
+                    return Err(ErrorKind::ServerSelectionError { message }.into());
+                }
             }
 
             let mut topology_change_subscriber =
@@ -281,14 +663,7 @@ impl Client {
                 .await;
 
             if !change_occurred {
-                return Err(ErrorKind::ServerSelectionError {
-                    message: self
-                        .inner
-                        .topology
-                        .server_selection_timeout_error_message(&criteria)
-                        .await,
-                }
-                .into());
+                return Ok(None);
             }
         }
     }
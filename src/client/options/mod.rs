@@ -8,8 +8,11 @@ use std::{
     hash::{Hash, Hasher},
     io::{BufReader, Seek, SeekFrom},
     str::FromStr,
-    sync::Arc,
-    time::Duration,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
 };
 
 use derivative::Derivative;
@@ -23,17 +26,24 @@ use rustls::{
     TLSError,
 };
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use strsim::jaro_winkler;
 pub use trust_dns_resolver::config::ResolverConfig;
+
+pub use crate::compression::Compressor;
 use typed_builder::TypedBuilder;
 use webpki_roots::TLS_SERVER_ROOTS;
 
 use crate::{
-    bson::{Bson, Document},
+    bson::{oid::ObjectId, Bson, Document},
     client::auth::{AuthMechanism, Credential},
     concern::{Acknowledgment, ReadConcern, WriteConcern},
-    error::{ErrorKind, Result},
-    event::{cmap::CmapEventHandler, command::CommandEventHandler},
+    error::{Error, ErrorKind, Result},
+    event::{
+        cmap::CmapEventHandler,
+        command::{CommandEventHandler, CommandEventHandlerAsync, EventBackpressure},
+        sdam::SdamEventHandler,
+    },
     options::ReadConcernLevel,
     sdam::MIN_HEARTBEAT_FREQUENCY,
     selection_criteria::{ReadPreference, SelectionCriteria, TagSet},
@@ -42,6 +52,15 @@ use crate::{
 
 const DEFAULT_PORT: u16 = 27017;
 
+const DEFAULT_RECONNECT_MAX_ATTEMPTS: u32 = 3;
+
+const DEFAULT_RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+const DEFAULT_RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// The maximum length in bytes of an application name accepted by the server in the handshake.
+const MAX_APP_NAME_LENGTH: usize = 128;
+
 const URI_OPTIONS: &[&str] = &[
     "appname",
     "authmechanism",
@@ -52,7 +71,9 @@ const URI_OPTIONS: &[&str] = &[
     "directconnection",
     "heartbeatfrequencyms",
     "journal",
+    "loadbalanced",
     "localthresholdms",
+    "maxconnecting",
     "maxidletimems",
     "maxstalenessseconds",
     "maxpoolsize",
@@ -65,10 +86,12 @@ const URI_OPTIONS: &[&str] = &[
     "retryreads",
     "serverselectiontimeoutms",
     "sockettimeoutms",
+    "timeoutms",
     "tls",
     "ssl",
     "tlsinsecure",
     "tlsallowinvalidcertificates",
+    "tlsallowinvalidhostnames",
     "tlscafile",
     "tlscertificatekeyfile",
     "w",
@@ -77,6 +100,37 @@ const URI_OPTIONS: &[&str] = &[
     "zlibcompressionlevel",
 ];
 
+/// The placeholder substituted for secrets in connection strings built by
+/// `ClientOptions::to_connection_string`.
+const REDACTED: &str = "*****";
+
+/// The characters percent-encoded in usernames written to a connection string, i.e. the reserved
+/// characters listed below along with '%' itself.
+const USERINFO_ENCODE_SET: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b':')
+    .add(b'/')
+    .add(b'?')
+    .add(b'#')
+    .add(b'[')
+    .add(b']')
+    .add(b'@')
+    .add(b'%');
+
+/// The characters which are percent-encoded in option values produced by
+/// `ClientOptions::to_connection_string`, i.e. those which would otherwise be mistaken for the
+/// delimiters of the options section or of structured values such as `readPreferenceTags`.
+const OPTION_VALUE_ENCODE_SET: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'/')
+    .add(b',')
+    .add(b':')
+    .add(b'=')
+    .add(b'?');
+
 lazy_static! {
     /// Reserved characters as defined by [Section 2.2 of RFC-3986](https://tools.ietf.org/html/rfc3986#section-2.2).
     /// Usernames / passwords that contain these characters must instead include the URL encoded version of them when included
@@ -212,12 +266,37 @@ pub struct ClientOptions {
 
     /// The application name that the Client will send to the server as part of the handshake. This
     /// can be used in combination with the server logs to determine which Client is connected to a
-    /// server.
+    /// server. It must be no longer than 128 bytes.
     #[builder(default)]
     pub app_name: Option<String>,
 
+    /// The compressors that the Client should offer to the server to compress the messages sent
+    /// between them, in order of preference. The first one that the server also supports will be
+    /// used; if there is none, messages will not be compressed.
+    ///
+    /// When parsing a connection string, the compressors listed in the `compressors` option that
+    /// the driver does not support are ignored. zstd and snappy are only supported if the
+    /// `zstd-compression` and `snappy-compression` features are enabled, respectively.
+    ///
+    /// The default is that messages are not compressed.
     #[builder(default)]
-    pub(crate) compressors: Option<Vec<String>>,
+    #[serde(skip)]
+    pub compressors: Option<Vec<Compressor>>,
+
+    /// The minimum size in bytes that a message sent to the server must have for it to be
+    /// compressed, which avoids the overhead of compressing small requests such as most queries.
+    /// This has no effect unless `compressors` is also specified.
+    ///
+    /// This only applies to messages sent by the Client. Replies are compressed by the server,
+    /// which only compresses replies to messages that were themselves compressed; whenever the
+    /// server sends a compressed reply, it is decompressed transparently. This means that a large
+    /// reply to a request below the threshold, such as the results of a `find`, will not be
+    /// compressed either. To have such replies compressed, the threshold must be lowered enough
+    /// for the corresponding requests to be compressed too.
+    ///
+    /// The default is that all messages are compressed once a compressor has been negotiated.
+    #[builder(default)]
+    pub compression_threshold: Option<usize>,
 
     /// The handler that should process all Connection Monitoring and Pooling events. See the
     /// CmapEventHandler type documentation for more details.
@@ -226,6 +305,33 @@ pub struct ClientOptions {
     #[serde(skip)]
     pub cmap_event_handler: Option<Arc<dyn CmapEventHandler>>,
 
+    /// The top-level fields of commands that should be included in the `command` document of
+    /// each [`CommandStartedEvent`](../event/command/struct.CommandStartedEvent.html). The field
+    /// holding the command name, e.g. `find`, is always included; all other fields not in the
+    /// list, such as `filter` or `updates`, are removed before the event is emitted. This can be
+    /// used to keep potentially sensitive values out of the events without losing track of which
+    /// commands are run.
+    ///
+    /// The field names are matched exactly. The command actually sent to the server is not
+    /// affected, and neither are the replies included in `CommandSucceededEvent`s.
+    ///
+    /// The default is to include every field of the command.
+    #[builder(default)]
+    pub command_event_field_allowlist: Option<Vec<String>>,
+
+    /// Whether the `command` and `reply` documents of command events for security-sensitive
+    /// commands should be replaced with empty documents, as required by the command monitoring
+    /// specification. The sensitive commands are the authentication and user management commands,
+    /// such as `saslStart` and `createUser`, as well as `hello` and `isMaster` commands that
+    /// include a `speculativeAuthenticate` field.
+    ///
+    /// This should only be disabled in tests that need to inspect the raw payloads, as the
+    /// unredacted events can contain credentials.
+    ///
+    /// The default value is true.
+    #[builder(default)]
+    pub command_event_redaction: Option<bool>,
+
     /// The handler that should process all command-related events. See the CommandEventHandler
     /// type documentation for more details.
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
@@ -233,6 +339,44 @@ pub struct ClientOptions {
     #[serde(skip)]
     pub command_event_handler: Option<Arc<dyn CommandEventHandler>>,
 
+    /// A handler that should process all command-related events asynchronously, in addition to
+    /// the `command_event_handler`. See the CommandEventHandlerAsync type documentation for more
+    /// details.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub async_command_event_handler: Option<Arc<dyn CommandEventHandlerAsync>>,
+
+    /// What to do with the events for the `async_command_event_handler` when it falls behind.
+    ///
+    /// The default is to queue up to 1000 events and drop any that don't fit.
+    #[builder(default)]
+    #[serde(skip)]
+    pub async_command_event_backpressure: Option<EventBackpressure>,
+
+    /// The handler that should process all Server Discovery and Monitoring events. See the
+    /// SdamEventHandler type documentation for more details.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub sdam_event_handler: Option<Arc<dyn SdamEventHandler>>,
+
+    /// A function that is called once at the start of each operation to get a value to attach to
+    /// its command as the `comment` field. A retry of the operation is sent with the same value.
+    /// If the function returns `None`, or the command already has a `comment` specified, no
+    /// comment will be attached.
+    ///
+    /// This can be used, for example, to tag every operation with a request or correlation id
+    /// stored in a task-local variable, so that operations can be traced end-to-end via the
+    /// server logs, the profiler, and `currentOp` output.
+    ///
+    /// Servers older than 4.4 only accept a `comment` on `find` and `aggregate` commands, so the
+    /// comment is left off of every other command, e.g. `getMore`, sent to them.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub comment_provider: Option<Arc<dyn Fn() -> Option<Bson> + Send + Sync>>,
+
     /// The connect timeout passed to each underlying TcpStream when attemtping to connect to the
     /// server.
     ///
@@ -247,6 +391,12 @@ pub struct ClientOptions {
     /// Specifies whether the Client should directly connect to a single host rather than
     /// autodiscover all servers in the cluster.
     ///
+    /// When enabled, exactly one host must be specified, and no other members of the deployment
+    /// are discovered or connected to. Operations are sent to that host whatever its type, and
+    /// reads are sent with a `primaryPreferred` read preference when no other non-primary one is
+    /// specified, so they succeed against a secondary. If `repl_set_name` is also set, the host
+    /// is only used if it's a member of that replica set.
+    ///
     /// The default value is false.
     #[builder(default)]
     pub direct_connection: Option<bool>,
@@ -256,13 +406,68 @@ pub struct ClientOptions {
     #[builder(default)]
     pub driver_info: Option<DriverInfo>,
 
+    /// Whether the Client should emit an
+    /// [`OperationEvent`](../event/command/struct.OperationEvent.html) to the
+    /// `command_event_handler` for each operation it executes, breaking down how long the
+    /// operation spent selecting a server, checking out a connection and executing the command.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub emit_operation_events: Option<bool>,
+
+    /// Whether server selection for an operation that requires a primary (e.g. a write, or a read
+    /// with a primary read preference) should fail immediately rather than waiting for the
+    /// `server_selection_timeout` when the client is connected to a replica set that is known to
+    /// currently have no primary, such as during an election.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub fail_fast_on_no_primary: Option<bool>,
+
+    /// Whether an operation should fail immediately with a `MaxConcurrentOperationsExceeded`
+    /// error rather than waiting for another operation to finish when `max_concurrent_operations`
+    /// operations are already in progress.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub fail_fast_on_max_concurrent_operations: Option<bool>,
+
+    /// The maximum amount of time establishing a new connection may take as a whole: connecting
+    /// the socket, the TLS handshake, the initial handshake with the server and authentication.
+    /// If establishing a connection takes longer, the attempt is abandoned with a timeout error,
+    /// and the server is marked as unknown so that subsequent operations select another suitable
+    /// server if there is one.
+    ///
+    /// Unlike `connect_timeout`, which only bounds connecting the socket, this also bounds
+    /// servers that accept connections but are slow to respond to the handshake or to
+    /// authenticate. It does not apply to the operations executed on connections once they are
+    /// established.
+    ///
+    /// The default is that only the individual steps are bounded, e.g. by `connect_timeout`.
+    #[builder(default)]
+    pub handshake_timeout: Option<Duration>,
+
     /// The amount of time each monitoring thread should wait between sending an isMaster command
-    /// to its respective server.
+    /// to its respective server. A server is re-checked once right away after a check of it fails,
+    /// and whenever an operation on it fails with a network or "not master" error; a server that
+    /// stays down is checked at this frequency.
     ///
-    /// The default value is 10 seconds.
+    /// The default value is 10 seconds, and the minimum is 500 milliseconds.
     #[builder(default)]
     pub heartbeat_freq: Option<Duration>,
 
+    /// A function the driver calls to generate the `_id` of each document inserted with
+    /// `insert_one` or `insert_many` that does not already have one. If it is not set, a new
+    /// random `ObjectId` is generated for each such document.
+    ///
+    /// This is mostly useful in tests, where
+    /// [`sequential_object_ids`](fn.sequential_object_ids.html) can be used to make the ids
+    /// generated by each run the same.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub id_generator: Option<Arc<dyn Fn() -> Bson + Send + Sync>>,
+
     /// When running a read operation with a ReadPreference that allows selecting secondaries,
     /// `local_threshold` is used to determine how much longer the average round trip time between
     /// the driver and server is allowed compared to the least round trip time of all the suitable
@@ -277,13 +482,44 @@ pub struct ClientOptions {
     #[builder(default)]
     pub local_threshold: Option<Duration>,
 
+    /// Whether the driver is connecting to a load balancer fronting a set of mongos servers rather
+    /// than to the deployment directly. When enabled, exactly one host must be specified, no
+    /// server monitoring is performed, and that host is always considered selectable.
+    ///
+    /// This cannot be combined with `repl_set_name` or with `direct_connection` set to true.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub load_balanced: Option<bool>,
+
     /// The amount of time that a connection can remain idle in a connection pool before being
     /// closed. A value of zero indicates that connections should not be closed due to being idle.
+    /// Idle connections are closed in the background, but never so many that the pool would drop
+    /// below `min_pool_size`.
     ///
     /// By default, connections will not be closed due to being idle.
     #[builder(default)]
     pub max_idle_time: Option<Duration>,
 
+    /// The maximum number of connections that a server's connection pool may be establishing at a
+    /// given time. If an operation needs a new connection while `max_connecting` connections are
+    /// already being established, it will wait for one of them to become ready, or for another
+    /// connection to be checked back in, rather than starting another handshake. This also bounds
+    /// how quickly connections are created in the background to reach `min_pool_size`.
+    ///
+    /// The default value is 2.
+    #[builder(default)]
+    pub max_connecting: Option<u32>,
+
+    /// The maximum number of operations that the Client should allow to be in progress at a given
+    /// time, across all servers. If an operation is attempted while `max_concurrent_operations`
+    /// operations are in progress, it will wait until one of them finishes, or fail immediately if
+    /// `fail_fast_on_max_concurrent_operations` is true. This must be greater than zero.
+    ///
+    /// The default is that the number of concurrent operations is not limited.
+    #[builder(default)]
+    pub max_concurrent_operations: Option<usize>,
+
     /// The maximum amount of connections that the Client should allow to be created in a
     /// connection pool for a given server. If an operation is attempted on a server while
     /// `max_pool_size` connections are checked out, the operation will block until an in-progress
@@ -306,6 +542,16 @@ pub struct ClientOptions {
     #[builder(default)]
     pub read_concern: Option<ReadConcern>,
 
+    /// How many more times the Client should attempt server selection after failing to select a
+    /// server within `server_selection_timeout`, and how long it should wait between attempts.
+    /// The monitors keep checking the servers throughout, so this allows operations to wait out
+    /// e.g. a rolling restart of the whole cluster while still bounding how long they can take.
+    ///
+    /// The default is to return an error as soon as server selection times out.
+    #[builder(default)]
+    #[serde(skip)]
+    pub reconnect_policy: Option<ReconnectPolicy>,
+
     /// The name of the replica set that the Client should connect to.
     #[builder(default)]
     pub repl_set_name: Option<String>,
@@ -316,7 +562,11 @@ pub struct ClientOptions {
     #[builder(default)]
     pub retry_reads: Option<bool>,
 
-    /// Whether or not the client should retry a write operation if the operation fails.
+    /// Whether or not the client should retry a write operation if the operation fails. Writes
+    /// that modify at most one document (e.g. `insert_one`, `update_one`, `delete_one` and the
+    /// `find_one_and_*` methods) and `insert_many` are retried once after a retryable error, on a
+    /// different server if one is available. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information.
     ///
     /// The default value is true.
     #[builder(default)]
@@ -327,6 +577,19 @@ pub struct ClientOptions {
     #[builder(default)]
     pub selection_criteria: Option<SelectionCriteria>,
 
+    /// The Stable API version to declare on every command the Client sends, for MongoDB 5.0+.
+    /// Declaring a version guarantees that the behavior of the commands in that version does not
+    /// change when the server is upgraded. See the documentation
+    /// [here](https://docs.mongodb.com/manual/reference/stable-api/) for more information.
+    ///
+    /// This can't be specified in a connection string, but can be set on the `ClientOptions`
+    /// returned by [`ClientOptions::parse`](#method.parse).
+    ///
+    /// By default, no API version is declared.
+    #[builder(default)]
+    #[serde(skip)]
+    pub server_api: Option<ServerApi>,
+
     /// The amount of time the Client should attempt to select a server for an operation before
     /// timing outs
     ///
@@ -334,9 +597,42 @@ pub struct ClientOptions {
     #[builder(default)]
     pub server_selection_timeout: Option<Duration>,
 
+    /// The duration above which a command is considered slow. When a command takes longer than
+    /// this to complete, a [`SlowCommandEvent`](../event/command/struct.SlowCommandEvent.html)
+    /// will be emitted to the `command_event_handler`.
+    ///
+    /// By default, no slow command events are emitted.
+    #[builder(default)]
+    pub slow_operation_threshold: Option<Duration>,
+
     #[builder(default)]
     pub(crate) socket_timeout: Option<Duration>,
 
+    /// The minimum amount of time to wait between the SRV lookups that are periodically performed
+    /// to discover hosts being added to or removed from a sharded cluster when the Client was
+    /// created from a `mongodb+srv` connection string. The lookups otherwise follow the TTL of the
+    /// SRV records. Polling is never done for other connection strings or for load-balanced
+    /// topologies.
+    ///
+    /// The default value is 60 seconds.
+    #[builder(default)]
+    pub srv_min_heartbeat_frequency: Option<Duration>,
+
+    /// The time limit for each operation performed by the Client, covering server selection,
+    /// checking out a connection, sending the command and reading its reply, as well as any retry.
+    /// If the limit passes, the operation returns a
+    /// [`Timeout`](../error/enum.ErrorKind.html#variant.Timeout) error labeled with
+    /// [`TIMEOUT_ERROR`](../error/constant.TIMEOUT_ERROR.html). The limit also bounds the lifetime
+    /// of the cursors returned by operations, so their getMores fail the same way once it has
+    /// passed since the operation was started. Change streams are the exception: only their
+    /// initial aggregation and each individual getMore are bounded.
+    ///
+    /// The options of some operations have a `timeout` field that overrides this value.
+    ///
+    /// By default, operations have no time limit.
+    #[builder(default)]
+    pub timeout: Option<Duration>,
+
     /// The TLS configuration for the Client to use in its connections with the server.
     ///
     /// By default, TLS is disabled.
@@ -346,6 +642,9 @@ pub struct ClientOptions {
     /// The amount of time a thread should block while waiting to check out a connection before
     /// returning an error. Note that if there are fewer than `max_pool_size` connections checked
     /// out or if a connection is available in the pool, checking out a connection will not block.
+    /// Threads waiting for a connection are served in the order they started waiting. The error
+    /// returned on timeout is an `ErrorKind::WaitQueueTimeoutError` that includes the state of the
+    /// pool.
     ///
     /// By default, threads will wait indefinitely for a connection to become available.
     #[builder(default)]
@@ -356,9 +655,6 @@ pub struct ClientOptions {
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
 
-    #[builder(default)]
-    pub(crate) zlib_compression: Option<i32>,
-
     #[builder(default)]
     pub(crate) original_srv_hostname: Option<String>,
 
@@ -374,6 +670,11 @@ pub struct ClientOptions {
     #[serde(skip)]
     pub(crate) resolver_config: Option<ResolverConfig>,
 
+    /// The maximum amount of time to wait for each SRV or TXT lookup to complete.
+    #[builder(default)]
+    #[serde(skip)]
+    pub(crate) srv_lookup_timeout: Option<Duration>,
+
     /// Used by tests to override MIN_HEARTBEAT_FREQUENCY.
     #[builder(default)]
     pub(crate) heartbeat_freq_test: Option<Duration>,
@@ -392,6 +693,20 @@ impl Default for ClientOptions {
     }
 }
 
+/// Creates an id generator for
+/// [`ClientOptions::id_generator`](struct.ClientOptions.html#structfield.id_generator) that
+/// produces `ObjectId`s counting up from `seed`, so that the generated ids are the same every time
+/// a program runs. The generated ids do not contain a meaningful timestamp and are only unique
+/// among the ids produced by the same generator, so this should only be used for testing.
+pub fn sequential_object_ids(seed: u64) -> Arc<dyn Fn() -> Bson + Send + Sync> {
+    let next = AtomicU64::new(seed);
+    Arc::new(move || {
+        let mut bytes = [0; 12];
+        bytes[4..].copy_from_slice(&next.fetch_add(1, Ordering::SeqCst).to_be_bytes());
+        Bson::ObjectId(ObjectId::with_bytes(bytes))
+    })
+}
+
 #[derive(Debug, Default, PartialEq)]
 struct ClientOptionsParser {
     pub hosts: Vec<StreamAddress>,
@@ -399,6 +714,7 @@ struct ClientOptionsParser {
     pub app_name: Option<String>,
     pub tls: Option<Tls>,
     pub heartbeat_freq: Option<Duration>,
+    pub load_balanced: Option<bool>,
     pub local_threshold: Option<Duration>,
     pub read_concern: Option<ReadConcern>,
     pub selection_criteria: Option<SelectionCriteria>,
@@ -407,6 +723,7 @@ struct ClientOptionsParser {
     pub server_selection_timeout: Option<Duration>,
     pub max_pool_size: Option<u32>,
     pub min_pool_size: Option<u32>,
+    pub max_connecting: Option<u32>,
     pub max_idle_time: Option<Duration>,
     pub wait_queue_timeout: Option<Duration>,
     pub compressors: Option<Vec<String>>,
@@ -414,6 +731,7 @@ struct ClientOptionsParser {
     pub retry_reads: Option<bool>,
     pub retry_writes: Option<bool>,
     pub socket_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
     pub zlib_compression: Option<i32>,
     pub direct_connection: Option<bool>,
     pub credential: Option<Credential>,
@@ -459,6 +777,14 @@ pub struct TlsOptions {
     #[builder(default)]
     pub allow_invalid_certificates: Option<bool>,
 
+    /// Whether or not the [`Client`](../struct.Client.html) should accept a server certificate
+    /// that is valid but issued for a different hostname than the one being connected to. Like
+    /// `allow_invalid_certificates`, this should only be used for testing.
+    ///
+    /// The default value is to error when the hostname of the certificate doesn't match.
+    #[builder(default)]
+    pub allow_invalid_hostnames: Option<bool>,
+
     /// The path to the CA file that the [`Client`](../struct.Client.html) should use for TLS. If
     /// none is specified, then the driver will use the Mozilla root certificates from the
     /// `webpki-roots` crate.
@@ -466,11 +792,22 @@ pub struct TlsOptions {
     pub ca_file_path: Option<String>,
 
     /// The path to the certificate file that the [`Client`](../struct.Client.html) should present
-    /// to the server to verify its identify. If none is specified, then the
-    /// [`Client`](../struct.Client.html) will not attempt to verify its identity to the
+    /// to the server to verify its identify. The file must contain the PEM-encoded certificate
+    /// chain followed by the PEM-encoded RSA or PKCS #8 private key. If none is specified, then
+    /// the [`Client`](../struct.Client.html) will not attempt to verify its identity to the
     /// server.
     #[builder(default)]
     pub cert_key_file_path: Option<String>,
+
+    /// The SHA-256 fingerprints of the certificates that the server is allowed to present. If
+    /// specified, the connection will only be accepted if the server's leaf certificate matches
+    /// one of the fingerprints, and no other verification of the certificate (including of its
+    /// hostname and its chain of trust) will be performed. This is useful for deployments using
+    /// self-signed certificates.
+    ///
+    /// This has no effect if `allow_invalid_certificates` is true.
+    #[builder(default)]
+    pub pinned_cert_sha256: Option<Vec<[u8; 32]>>,
 }
 
 struct NoCertVerifier {}
@@ -487,7 +824,94 @@ impl ServerCertVerifier for NoCertVerifier {
     }
 }
 
+/// The signature algorithms accepted in the certificate chains presented by servers, which are the
+/// ones rustls accepts by default.
+static SUPPORTED_SIG_ALGS: &[&webpki::SignatureAlgorithm] = &[
+    &webpki::ECDSA_P256_SHA256,
+    &webpki::ECDSA_P256_SHA384,
+    &webpki::ECDSA_P384_SHA256,
+    &webpki::ECDSA_P384_SHA384,
+    &webpki::RSA_PSS_2048_8192_SHA256_LEGACY_KEY,
+    &webpki::RSA_PSS_2048_8192_SHA384_LEGACY_KEY,
+    &webpki::RSA_PSS_2048_8192_SHA512_LEGACY_KEY,
+    &webpki::RSA_PKCS1_2048_8192_SHA256,
+    &webpki::RSA_PKCS1_2048_8192_SHA384,
+    &webpki::RSA_PKCS1_2048_8192_SHA512,
+    &webpki::RSA_PKCS1_3072_8192_SHA384,
+];
+
+/// Verifies the chain of trust of server certificates without checking that they were issued for
+/// the hostname being connected to.
+struct NoHostnameVerifier {}
+
+impl ServerCertVerifier for NoHostnameVerifier {
+    fn verify_server_cert(
+        &self,
+        roots: &RootCertStore,
+        presented_certs: &[Certificate],
+        _: webpki::DNSNameRef,
+        _: &[u8],
+    ) -> std::result::Result<ServerCertVerified, TLSError> {
+        let (leaf, chain) = presented_certs
+            .split_first()
+            .ok_or(TLSError::NoCertificatesPresented)?;
+        let leaf = webpki::EndEntityCert::from(&leaf.0).map_err(TLSError::WebPKIError)?;
+        let chain: Vec<&[u8]> = chain.iter().map(|cert| cert.0.as_ref()).collect();
+        let trust_roots: Vec<_> = roots
+            .roots
+            .iter()
+            .map(|root| root.to_trust_anchor())
+            .collect();
+        let now = webpki::Time::try_from(SystemTime::now())
+            .map_err(|_| TLSError::FailedToGetCurrentTime)?;
+
+        leaf.verify_is_valid_tls_server_cert(
+            SUPPORTED_SIG_ALGS,
+            &webpki::TLSServerTrustAnchors(&trust_roots),
+            &chain,
+            now,
+        )
+        .map_err(TLSError::WebPKIError)?;
+
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// Accepts only server certificates whose SHA-256 fingerprint is one of `fingerprints`.
+struct PinnedCertVerifier {
+    fingerprints: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _: &RootCertStore,
+        presented_certs: &[Certificate],
+        _: webpki::DNSNameRef,
+        _: &[u8],
+    ) -> std::result::Result<ServerCertVerified, TLSError> {
+        let leaf = presented_certs
+            .first()
+            .ok_or(TLSError::NoCertificatesPresented)?;
+        let fingerprint = Sha256::digest(&leaf.0);
+
+        if self
+            .fingerprints
+            .iter()
+            .any(|pinned| pinned[..] == fingerprint[..])
+        {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(TLSError::General(
+                "server certificate does not match any pinned fingerprint".to_string(),
+            ))
+        }
+    }
+}
+
 impl TlsOptions {
+    /// Builds the rustls configuration for these options, loading the CA file and the client
+    /// certificate and key file if they're specified.
     pub fn into_rustls_config(self) -> Result<rustls::ClientConfig> {
         let mut config = rustls::ClientConfig::new();
 
@@ -495,16 +919,26 @@ impl TlsOptions {
             config
                 .dangerous()
                 .set_certificate_verifier(Arc::new(NoCertVerifier {}));
+        } else if let Some(fingerprints) = self.pinned_cert_sha256 {
+            config
+                .dangerous()
+                .set_certificate_verifier(Arc::new(PinnedCertVerifier { fingerprints }));
+        } else if let Some(true) = self.allow_invalid_hostnames {
+            config
+                .dangerous()
+                .set_certificate_verifier(Arc::new(NoHostnameVerifier {}));
         }
 
         let mut store = RootCertStore::empty();
         if let Some(path) = self.ca_file_path {
-            store
-                .add_pem_file(&mut BufReader::new(File::open(&path)?))
-                .map_err(|_| ErrorKind::ParseError {
+            let parsed = store.add_pem_file(&mut open_pem_file(&path)?);
+            if !matches!(parsed, Ok((valid, _)) if valid > 0) {
+                return Err(ErrorKind::ParseError {
                     data_type: "PEM-encoded root certificate".to_string(),
                     file_path: path,
-                })?;
+                }
+                .into());
+            }
         } else {
             store.add_server_trust_anchors(&TLS_SERVER_ROOTS);
         }
@@ -512,10 +946,10 @@ impl TlsOptions {
         config.root_store = store;
 
         if let Some(path) = self.cert_key_file_path {
-            let mut file = BufReader::new(File::open(&path)?);
+            let mut file = open_pem_file(&path)?;
             let certs = match pemfile::certs(&mut file) {
-                Ok(certs) => certs,
-                Err(()) => {
+                Ok(certs) if !certs.is_empty() => certs,
+                _ => {
                     return Err(ErrorKind::ParseError {
                         data_type: "PEM-encoded client certificate".to_string(),
                         file_path: path,
@@ -525,25 +959,41 @@ impl TlsOptions {
             };
 
             file.seek(SeekFrom::Start(0))?;
-            let key = match pemfile::rsa_private_keys(&mut file) {
-                Ok(key) => key,
-                Err(()) => {
+            let mut keys = pemfile::rsa_private_keys(&mut file).unwrap_or_default();
+            if keys.is_empty() {
+                file.seek(SeekFrom::Start(0))?;
+                keys = pemfile::pkcs8_private_keys(&mut file).unwrap_or_default();
+            }
+
+            let key = match keys.into_iter().next() {
+                Some(key) => key,
+                None => {
                     return Err(ErrorKind::ParseError {
-                        data_type: "PEM-encoded RSA key".to_string(),
+                        data_type: "PEM-encoded private key".to_string(),
                         file_path: path,
                     }
                     .into())
                 }
             };
 
-            // TODO: Get rid of unwrap.
-            config.set_single_client_cert(certs, key.into_iter().next().unwrap())?;
+            config.set_single_client_cert(certs, key)?;
         }
 
         Ok(config)
     }
 }
 
+/// Opens a PEM file specified in the TLS options, naming it in the error if it can't be opened.
+fn open_pem_file(path: &str) -> Result<BufReader<File>> {
+    match File::open(path) {
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(e) => Err(ErrorKind::ArgumentError {
+            message: format!("unable to open the TLS file {}: {}", path, e),
+        }
+        .into()),
+    }
+}
+
 /// Extra information to append to the driver version in the metadata of the handshake with the
 /// server. This should be used by libraries wrapping the driver, e.g. ODMs.
 #[derive(Clone, Debug, Deserialize, TypedBuilder, PartialEq)]
@@ -561,37 +1011,201 @@ pub struct DriverInfo {
     pub platform: Option<String>,
 }
 
+/// The Stable API version and behavior a [`Client`](../struct.Client.html) declares to the server.
+/// See [`ClientOptions::server_api`](struct.ClientOptions.html#structfield.server_api).
+///
+/// ```rust
+/// # use mongodb::options::{ClientOptions, ServerApi, ServerApiVersion};
+/// #
+/// let server_api = ServerApi::builder()
+///     .version(ServerApiVersion::V1)
+///     .strict(true)
+///     .build();
+/// let options = ClientOptions::builder().server_api(server_api).build();
+/// ```
+#[derive(Clone, Debug, TypedBuilder, PartialEq)]
+#[non_exhaustive]
+pub struct ServerApi {
+    /// The declared API version.
+    pub version: ServerApiVersion,
+
+    /// Whether the server should return an error for commands and options that are not part of
+    /// the declared API version. The `isMaster` commands the driver uses to handshake and monitor
+    /// connections are exempt from this.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub strict: Option<bool>,
+
+    /// Whether the server should return an error for commands and options that are deprecated in
+    /// the declared API version.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub deprecation_errors: Option<bool>,
+}
+
+/// The versions of the Stable API that can be declared with a [`ServerApi`](struct.ServerApi.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ServerApiVersion {
+    /// Version 1 of the Stable API, introduced in MongoDB 5.0.
+    V1,
+}
+
+impl ServerApiVersion {
+    /// Gets the value sent to the server as the `apiVersion` of commands.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ServerApiVersion::V1 => "1",
+        }
+    }
+}
+
+/// Specifies the options to a [`Client::start_session`](../struct.Client.html#method.start_session)
+/// operation.
+#[derive(Clone, Debug, Default, TypedBuilder, PartialEq)]
+#[non_exhaustive]
+pub struct SessionOptions {
+    /// Whether the operations in the session should be causally consistent with each other, i.e.
+    /// whether each read in the session should observe the results of the writes and reads that
+    /// preceded it in the session, even if they were executed on a different member of the
+    /// replica set.
+    ///
+    /// The default value is true.
+    #[builder(default)]
+    pub causal_consistency: Option<bool>,
+
+    /// The options used for the transactions started in the session when
+    /// [`ClientSession::start_transaction`](../struct.ClientSession.html#method.start_transaction)
+    /// isn't given any. Options that aren't set in either place are inherited from the `Client`.
+    #[builder(default)]
+    pub default_transaction_options: Option<TransactionOptions>,
+}
+
+/// Specifies the options to a
+/// [`ClientSession::start_transaction`](../struct.ClientSession.html#method.start_transaction)
+/// operation. The operations in a transaction are always executed on the primary.
+#[derive(Clone, Debug, Default, TypedBuilder, PartialEq)]
+#[non_exhaustive]
+pub struct TransactionOptions {
+    /// The read concern that the reads in the transaction use.
+    #[builder(default)]
+    pub read_concern: Option<ReadConcern>,
+
+    /// The write concern that the transaction is committed with. It must be acknowledged.
+    #[builder(default)]
+    pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow a single `commitTransaction` command to run on the
+    /// server.
+    #[builder(default)]
+    pub max_commit_time: Option<Duration>,
+}
+
+/// Specifies how many times and how often a [`Client`](../struct.Client.html) re-attempts server
+/// selection after it times out. See
+/// [`ClientOptions::reconnect_policy`](struct.ClientOptions.html#structfield.reconnect_policy).
+#[derive(Clone, Debug, Deserialize, TypedBuilder, PartialEq)]
+#[non_exhaustive]
+pub struct ReconnectPolicy {
+    /// The maximum number of server selection attempts to make after the first one times out.
+    ///
+    /// The default value is 3.
+    #[builder(default)]
+    pub max_attempts: Option<u32>,
+
+    /// How long to wait before the first re-attempt. The wait is doubled after each subsequent
+    /// attempt, up to `max_backoff`.
+    ///
+    /// The default value is 500 ms.
+    #[builder(default)]
+    pub initial_backoff: Option<Duration>,
+
+    /// The maximum amount of time to wait between attempts.
+    ///
+    /// The default value is 10 seconds.
+    #[builder(default)]
+    pub max_backoff: Option<Duration>,
+}
+
+impl ReconnectPolicy {
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts.unwrap_or(DEFAULT_RECONNECT_MAX_ATTEMPTS)
+    }
+
+    /// Gets how long to wait before the given re-attempt, numbered from 0.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let initial = self
+            .initial_backoff
+            .unwrap_or(DEFAULT_RECONNECT_INITIAL_BACKOFF);
+        let max = self.max_backoff.unwrap_or(DEFAULT_RECONNECT_MAX_BACKOFF);
+
+        initial
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(max, |backoff| backoff.min(max))
+    }
+}
+
 impl From<ClientOptionsParser> for ClientOptions {
     fn from(parser: ClientOptionsParser) -> Self {
+        let zlib_level = parser.zlib_compression;
+        let compressors = parser.compressors.map(|names| {
+            names
+                .iter()
+                .filter_map(|name| Compressor::from_name(name, zlib_level))
+                .collect()
+        });
+
         Self {
             hosts: parser.hosts,
             app_name: parser.app_name,
             tls: parser.tls,
             heartbeat_freq: parser.heartbeat_freq,
+            load_balanced: parser.load_balanced,
             local_threshold: parser.local_threshold,
             read_concern: parser.read_concern,
+            reconnect_policy: None,
             selection_criteria: parser.selection_criteria,
             repl_set_name: parser.repl_set_name,
             write_concern: parser.write_concern,
             max_pool_size: parser.max_pool_size,
             min_pool_size: parser.min_pool_size,
+            max_connecting: parser.max_connecting,
             max_idle_time: parser.max_idle_time,
             wait_queue_timeout: parser.wait_queue_timeout,
+            server_api: None,
             server_selection_timeout: parser.server_selection_timeout,
-            compressors: parser.compressors,
+            compressors,
+            compression_threshold: None,
             connect_timeout: parser.connect_timeout,
             retry_reads: parser.retry_reads,
             retry_writes: parser.retry_writes,
+            slow_operation_threshold: None,
             socket_timeout: parser.socket_timeout,
-            zlib_compression: parser.zlib_compression,
+            srv_min_heartbeat_frequency: None,
+            timeout: parser.timeout,
             direct_connection: parser.direct_connection,
             driver_info: None,
+            emit_operation_events: None,
+            fail_fast_on_no_primary: None,
+            fail_fast_on_max_concurrent_operations: None,
+            handshake_timeout: None,
+            max_concurrent_operations: None,
             credential: parser.credential,
             cmap_event_handler: None,
+            command_event_field_allowlist: None,
+            command_event_redaction: None,
             command_event_handler: None,
+            async_command_event_handler: None,
+            async_command_event_backpressure: None,
+            sdam_event_handler: None,
+            comment_provider: None,
+            id_generator: None,
             original_srv_hostname: None,
             original_uri: Some(parser.original_uri),
             resolver_config: None,
+            srv_lookup_timeout: None,
             heartbeat_freq_test: None,
         }
     }
@@ -622,15 +1236,17 @@ impl ClientOptions {
     ///   * `authSource`: maps to the `source` field of the `credential` field
     ///   * `authMechanismProperties`: maps to the `mechanism_properties` field of the `credential`
     ///     field
-    ///   * `compressors`: not yet implemented
+    ///   * `compressors`: maps to the `compressors` field
     ///   * `connectTimeoutMS`: maps to the `connect_timeout` field
     ///   * `direct`: maps to the `direct` field
-    ///   * `heartbeatFrequencyMS`: maps to the `heartbeat_frequency` field
+    ///   * `heartbeatFrequencyMS`: maps to the `heartbeat_freq` field
     ///   * `journal`: maps to the `journal` field of the `write_concern` field
+    ///   * `loadBalanced`: maps to the `load_balanced` field
     ///   * `localThresholdMS`: maps to the `local_threshold` field
+    ///   * `maxConnecting`: maps to the `max_connecting` field
     ///   * `maxIdleTimeMS`: maps to the `max_idle_time` field
     ///   * `maxStalenessSeconds`: maps to the `max_staleness` field of the `selection_criteria`
-    ///     field
+    ///     field; a value of -1 specifies no max staleness
     ///   * `maxPoolSize`: maps to the `max_pool_size` field
     ///   * `minPoolSize`: maps to the `min_pool_size` field
     ///   * `readConcernLevel`: maps to the `read_concern` field
@@ -640,23 +1256,28 @@ impl ClientOptions {
     ///     that this option can appear more than once; each instance will be mapped to a separate
     ///     tag set
     ///   * `replicaSet`: maps to the `repl_set_name` field
-    ///   * `retryWrites`: not yet implemented
+    ///   * `retryWrites`: maps to the `retry_writes` field
     ///   * `retryReads`: maps to the `retry_reads` field
     ///   * `serverSelectionTimeoutMS`: maps to the `server_selection_timeout` field
     ///   * `socketTimeoutMS`: maps to the `socket_timeout` field
+    ///   * `timeoutMS`: maps to the `timeout` field
     ///   * `ssl`: an alias of the `tls` option
     ///   * `tls`: maps to the TLS variant of the `tls` field`.
-    ///   * `tlsInsecure`: relaxes the TLS constraints on connections being made; currently is just
-    ///     an alias of `tlsAllowInvalidCertificates`, but more behavior may be added to this option
-    ///     in the future
-    ///   * `tlsAllowInvalidCertificates`: maps to the `allow_invalidCertificates` field of the
+    ///   * `tlsInsecure`: relaxes the TLS constraints on connections being made; setting it to
+    ///     true sets both the `allow_invalid_certificates` and the `allow_invalid_hostnames` fields
+    ///     of the `tls` field, so it can't be combined with `tlsAllowInvalidCertificates` or
+    ///     `tlsAllowInvalidHostnames`
+    ///   * `tlsAllowInvalidCertificates`: maps to the `allow_invalid_certificates` field of the
     ///     `tls` field
+    ///   * `tlsAllowInvalidHostnames`: maps to the `allow_invalid_hostnames` field of the `tls`
+    ///     field
     ///   * `tlsCAFile`: maps to the `ca_file_path` field of the `tls` field
     ///   * `tlsCertificateKeyFile`: maps to the `cert_key_file_path` field of the `tls` field
     ///   * `w`: maps to the `w` field of the `write_concern` field
     ///   * `waitQueueTimeoutMS`: maps to the `wait_queue_timeout` field
     ///   * `wTimeoutMS`: maps to the `w_timeout` field of the `write_concern` field
-    ///   * `zlibCompressionLevel`: not yet implemented
+    ///   * `zlibCompressionLevel`: the compression level to use with the "zlib" compressor, from
+    ///     -1 (the default level) to 9 (best compression)
     ///
     /// Note: if the `sync` feature is enabled, then this method will be replaced with [the sync
     /// version](#method.parse-1).
@@ -703,20 +1324,71 @@ impl ClientOptions {
         crate::RUNTIME.block_on(Self::parse_uri(uri, Some(resolver_config)))
     }
 
+    /// Parses a MongoDB connection string into a `ClientOptions` struct.
+    /// If the string is malformed or one of the options has an invalid value, an error will be
+    /// returned.
+    ///
+    /// In the case that "mongodb+srv" is used, SRV and TXT record lookups will be done using the
+    /// provided `ResolverConfig` (or the system's resolver configuration if none is provided), and
+    /// each lookup will fail with an error if it does not complete within `lookup_timeout`. This
+    /// timeout also applies to the lookups done when polling for changes to the SRV records of a
+    /// sharded cluster.
+    ///
+    /// See the docstring on `ClientOptions::parse` for information on how the various URI options
+    /// map to fields on `ClientOptions`.
+    ///
+    /// Note: if the `sync` feature is enabled, then this method will be replaced with [the sync
+    /// version](#method.parse_with_srv_lookup_timeout-1).
+    #[cfg(not(feature = "sync"))]
+    pub async fn parse_with_srv_lookup_timeout(
+        uri: &str,
+        resolver_config: impl Into<Option<ResolverConfig>>,
+        lookup_timeout: Duration,
+    ) -> Result<Self> {
+        Self::parse_uri_with_lookup_timeout(uri, resolver_config.into(), Some(lookup_timeout)).await
+    }
+
+    /// This method will be present if the `sync` feature is enabled. It's otherwise identical to
+    /// [the async version](#method.parse_with_srv_lookup_timeout)
+    #[cfg(any(feature = "sync", docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+    pub fn parse_with_srv_lookup_timeout(
+        uri: &str,
+        resolver_config: impl Into<Option<ResolverConfig>>,
+        lookup_timeout: Duration,
+    ) -> Result<Self> {
+        crate::RUNTIME.block_on(Self::parse_uri_with_lookup_timeout(
+            uri,
+            resolver_config.into(),
+            Some(lookup_timeout),
+        ))
+    }
+
     /// Populate this `ClientOptions` from the given URI, optionally using the resolver config for
     /// DNS lookups.
     pub(crate) async fn parse_uri(
         uri: &str,
         resolver_config: Option<ResolverConfig>,
+    ) -> Result<Self> {
+        Self::parse_uri_with_lookup_timeout(uri, resolver_config, None).await
+    }
+
+    /// Populate this `ClientOptions` from the given URI, optionally using the resolver config and
+    /// timeout for DNS lookups.
+    async fn parse_uri_with_lookup_timeout(
+        uri: &str,
+        resolver_config: Option<ResolverConfig>,
+        srv_lookup_timeout: Option<Duration>,
     ) -> Result<Self> {
         let parser = ClientOptionsParser::parse(uri)?;
         let srv = parser.srv;
         let auth_source_present = parser.auth_source.is_some();
         let mut options: Self = parser.into();
         options.resolver_config = resolver_config.clone();
+        options.srv_lookup_timeout = srv_lookup_timeout;
 
         if srv {
-            let mut resolver = SrvResolver::new(resolver_config).await?;
+            let mut resolver = SrvResolver::new(resolver_config, srv_lookup_timeout).await?;
             let mut config = resolver
                 .resolve_client_options(&options.hosts[0].hostname)
                 .await?;
@@ -765,6 +1437,250 @@ impl ClientOptions {
         Ok(options)
     }
 
+    /// Reconstructs a MongoDB connection string from these options, e.g. for logging how a
+    /// `Client` is configured after defaults and programmatic overrides have been applied.
+    ///
+    /// The hosts are the resolved seed list (i.e. the results of the SRV lookup for "mongodb+srv"
+    /// URIs), so the returned string always uses the "mongodb" scheme. The password of the
+    /// credential, if any, is replaced with `*****`, as is the `AWS_SESSION_TOKEN` mechanism
+    /// property. Options which cannot be expressed in a connection string, such as event handlers,
+    /// are omitted.
+    pub fn to_connection_string(&self) -> String {
+        let mut uri = String::from("mongodb://");
+
+        if let Some(ref credential) = self.credential {
+            if let Some(ref username) = credential.username {
+                uri.push_str(&percent_encode(username));
+                if credential.password.is_some() {
+                    uri.push(':');
+                    uri.push_str(REDACTED);
+                }
+                uri.push('@');
+            }
+        }
+
+        let hosts: Vec<String> = self.hosts.iter().map(ToString::to_string).collect();
+        uri.push_str(&hosts.join(","));
+        uri.push('/');
+
+        let mut options: Vec<(&str, String)> = Vec::new();
+
+        if let Some(ref app_name) = self.app_name {
+            options.push(("appName", percent_encode_option_value(app_name)));
+        }
+
+        if let Some(ref credential) = self.credential {
+            if let Some(ref mechanism) = credential.mechanism {
+                options.push(("authMechanism", mechanism.as_str().to_string()));
+            }
+
+            if let Some(ref properties) = credential.mechanism_properties {
+                let properties: Vec<String> = properties
+                    .iter()
+                    .map(|(key, value)| match (key.as_str(), value) {
+                        ("AWS_SESSION_TOKEN", _) => format!("{}:{}", key, REDACTED),
+                        (_, Bson::String(s)) => format!(
+                            "{}:{}",
+                            percent_encode_option_value(key),
+                            percent_encode_option_value(s)
+                        ),
+                        (_, other) => format!(
+                            "{}:{}",
+                            percent_encode_option_value(key),
+                            percent_encode_option_value(&other.to_string())
+                        ),
+                    })
+                    .collect();
+                options.push(("authMechanismProperties", properties.join(",")));
+            }
+
+            options.push((
+                "authSource",
+                percent_encode_option_value(credential.resolved_source()),
+            ));
+        }
+
+        if let Some(ref compressors) = self.compressors {
+            let names: Vec<&str> = compressors.iter().map(Compressor::name).collect();
+            options.push(("compressors", names.join(",")));
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            options.push(("connectTimeoutMS", timeout.as_millis().to_string()));
+        }
+
+        if let Some(direct_connection) = self.direct_connection {
+            options.push(("directConnection", direct_connection.to_string()));
+        }
+
+        if let Some(freq) = self.heartbeat_freq {
+            options.push(("heartbeatFrequencyMS", freq.as_millis().to_string()));
+        }
+
+        if let Some(load_balanced) = self.load_balanced {
+            options.push(("loadBalanced", load_balanced.to_string()));
+        }
+
+        if let Some(threshold) = self.local_threshold {
+            options.push(("localThresholdMS", threshold.as_millis().to_string()));
+        }
+
+        if let Some(max_connecting) = self.max_connecting {
+            options.push(("maxConnecting", max_connecting.to_string()));
+        }
+
+        if let Some(max_idle_time) = self.max_idle_time {
+            options.push(("maxIdleTimeMS", max_idle_time.as_millis().to_string()));
+        }
+
+        if let Some(max_pool_size) = self.max_pool_size {
+            options.push(("maxPoolSize", max_pool_size.to_string()));
+        }
+
+        if let Some(min_pool_size) = self.min_pool_size {
+            options.push(("minPoolSize", min_pool_size.to_string()));
+        }
+
+        if let Some(ref read_concern) = self.read_concern {
+            options.push(("readConcernLevel", read_concern.level.as_str().to_string()));
+        }
+
+        if let Some(read_pref) = self
+            .selection_criteria
+            .as_ref()
+            .and_then(SelectionCriteria::as_read_pref)
+        {
+            let (mode, read_pref_options) = match read_pref {
+                ReadPreference::Primary => ("primary", None),
+                ReadPreference::PrimaryPreferred { options } => ("primaryPreferred", Some(options)),
+                ReadPreference::Secondary { options } => ("secondary", Some(options)),
+                ReadPreference::SecondaryPreferred { options } => {
+                    ("secondaryPreferred", Some(options))
+                }
+                ReadPreference::Nearest { options } => ("nearest", Some(options)),
+            };
+            options.push(("readPreference", mode.to_string()));
+
+            if let Some(read_pref_options) = read_pref_options {
+                for tag_set in read_pref_options.tag_sets.iter().flatten() {
+                    let tags: Vec<String> = tag_set
+                        .iter()
+                        .map(|(key, value)| {
+                            format!(
+                                "{}:{}",
+                                percent_encode_option_value(key),
+                                percent_encode_option_value(value)
+                            )
+                        })
+                        .collect();
+                    options.push(("readPreferenceTags", tags.join(",")));
+                }
+
+                if let Some(max_staleness) = read_pref_options.effective_max_staleness() {
+                    options.push(("maxStalenessSeconds", max_staleness.as_secs().to_string()));
+                }
+            }
+        }
+
+        if let Some(ref repl_set_name) = self.repl_set_name {
+            options.push(("replicaSet", percent_encode_option_value(repl_set_name)));
+        }
+
+        if let Some(retry_reads) = self.retry_reads {
+            options.push(("retryReads", retry_reads.to_string()));
+        }
+
+        if let Some(retry_writes) = self.retry_writes {
+            options.push(("retryWrites", retry_writes.to_string()));
+        }
+
+        if let Some(timeout) = self.server_selection_timeout {
+            options.push(("serverSelectionTimeoutMS", timeout.as_millis().to_string()));
+        }
+
+        if let Some(timeout) = self.socket_timeout {
+            options.push(("socketTimeoutMS", timeout.as_millis().to_string()));
+        }
+
+        if let Some(timeout) = self.timeout {
+            options.push(("timeoutMS", timeout.as_millis().to_string()));
+        }
+
+        match self.tls {
+            Some(Tls::Enabled(ref tls_options)) => {
+                options.push(("tls", "true".to_string()));
+
+                if let Some(allow_invalid) = tls_options.allow_invalid_certificates {
+                    options.push(("tlsAllowInvalidCertificates", allow_invalid.to_string()));
+                }
+
+                if let Some(allow_invalid) = tls_options.allow_invalid_hostnames {
+                    options.push(("tlsAllowInvalidHostnames", allow_invalid.to_string()));
+                }
+
+                if let Some(ref ca_file_path) = tls_options.ca_file_path {
+                    options.push(("tlsCAFile", percent_encode_option_value(ca_file_path)));
+                }
+
+                if let Some(ref cert_key_file_path) = tls_options.cert_key_file_path {
+                    options.push((
+                        "tlsCertificateKeyFile",
+                        percent_encode_option_value(cert_key_file_path),
+                    ));
+                }
+            }
+            Some(Tls::Disabled) => options.push(("tls", "false".to_string())),
+            None => {}
+        }
+
+        if let Some(timeout) = self.wait_queue_timeout {
+            options.push(("waitQueueTimeoutMS", timeout.as_millis().to_string()));
+        }
+
+        if let Some(ref write_concern) = self.write_concern {
+            if let Some(ref w) = write_concern.w {
+                let w = match w {
+                    Acknowledgment::Nodes(n) => n.to_string(),
+                    Acknowledgment::Majority => "majority".to_string(),
+                    Acknowledgment::Custom(s) => percent_encode_option_value(s),
+                };
+                options.push(("w", w));
+            }
+
+            if let Some(journal) = write_concern.journal {
+                options.push(("journal", journal.to_string()));
+            }
+
+            if let Some(w_timeout) = write_concern.w_timeout {
+                options.push(("wtimeoutMS", w_timeout.as_millis().to_string()));
+            }
+        }
+
+        let zlib_level =
+            self.compressors
+                .iter()
+                .flatten()
+                .find_map(|compressor| match compressor {
+                    Compressor::Zlib { level } => *level,
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                });
+        if let Some(level) = zlib_level {
+            options.push(("zlibCompressionLevel", level.to_string()));
+        }
+
+        if !options.is_empty() {
+            let options: Vec<String> = options
+                .into_iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            uri.push('?');
+            uri.push_str(&options.join("&"));
+        }
+
+        uri
+    }
+
     /// Gets the original SRV hostname specified when this ClientOptions was parsed from a URI.
     pub(crate) fn original_srv_hostname(&self) -> Option<&String> {
         self.original_srv_hostname.as_ref()
@@ -779,6 +1695,19 @@ impl ClientOptions {
 
     /// Ensure the options set are valid, returning an error descirbing the problem if they are not.
     pub(crate) fn validate(&self) -> Result<()> {
+        if let Some(ref app_name) = self.app_name {
+            if app_name.len() > MAX_APP_NAME_LENGTH {
+                return Err(ErrorKind::ArgumentError {
+                    message: format!(
+                        "appName must be at most {} bytes long, but it is {} bytes long",
+                        MAX_APP_NAME_LENGTH,
+                        app_name.len()
+                    ),
+                }
+                .into());
+            }
+        }
+
         if let Some(true) = self.direct_connection {
             if self.hosts.len() > 1 {
                 return Err(ErrorKind::ArgumentError {
@@ -788,6 +1717,69 @@ impl ClientOptions {
             }
         }
 
+        if let Some(true) = self.load_balanced {
+            if self.hosts.len() > 1 {
+                return Err(ErrorKind::ArgumentError {
+                    message: "cannot specify multiple seeds with loadBalanced=true".to_string(),
+                }
+                .into());
+            }
+
+            if self.repl_set_name.is_some() {
+                return Err(ErrorKind::ArgumentError {
+                    message: "cannot specify replicaSet with loadBalanced=true".to_string(),
+                }
+                .into());
+            }
+
+            if let Some(true) = self.direct_connection {
+                return Err(ErrorKind::ArgumentError {
+                    message: "cannot specify directConnection=true with loadBalanced=true"
+                        .to_string(),
+                }
+                .into());
+            }
+        }
+
+        if let Some(heartbeat_freq) = self.heartbeat_freq {
+            let min_frequency = self.heartbeat_freq_test.unwrap_or(MIN_HEARTBEAT_FREQUENCY);
+            if heartbeat_freq < min_frequency {
+                return Err(ErrorKind::ArgumentError {
+                    message: format!(
+                        "heartbeat_freq must be at least {}ms, but {}ms was given",
+                        min_frequency.as_millis(),
+                        heartbeat_freq.as_millis()
+                    ),
+                }
+                .into());
+            }
+        }
+
+        if let Some(0) = self.max_connecting {
+            return Err(ErrorKind::ArgumentError {
+                message: "maxConnecting must be greater than zero".to_string(),
+            }
+            .into());
+        }
+
+        if let Some(0) = self.max_concurrent_operations {
+            return Err(ErrorKind::ArgumentError {
+                message: "max_concurrent_operations must be greater than zero".to_string(),
+            }
+            .into());
+        }
+
+        if let Some(backpressure) = self.async_command_event_backpressure {
+            if backpressure.capacity() == 0 {
+                return Err(ErrorKind::ArgumentError {
+                    message: "the capacity of async_command_event_backpressure must be greater \
+                              than zero"
+                        .to_string(),
+                }
+                .into());
+            }
+        }
+
         if let Some(ref write_concern) = self.write_concern {
             write_concern.validate()?;
         }
@@ -802,29 +1794,48 @@ impl ClientOptions {
             self,
             [
                 app_name,
+                async_command_event_backpressure,
+                async_command_event_handler,
                 compressors,
+                compression_threshold,
                 cmap_event_handler,
+                command_event_field_allowlist,
+                command_event_redaction,
                 command_event_handler,
+                comment_provider,
                 connect_timeout,
                 credential,
                 direct_connection,
                 driver_info,
+                emit_operation_events,
+                fail_fast_on_max_concurrent_operations,
+                fail_fast_on_no_primary,
+                handshake_timeout,
                 heartbeat_freq,
+                id_generator,
+                load_balanced,
                 local_threshold,
+                max_concurrent_operations,
+                max_connecting,
                 max_idle_time,
                 max_pool_size,
                 min_pool_size,
                 read_concern,
+                reconnect_policy,
                 repl_set_name,
                 retry_reads,
                 retry_writes,
+                sdam_event_handler,
                 selection_criteria,
+                server_api,
                 server_selection_timeout,
+                slow_operation_threshold,
                 socket_timeout,
+                srv_min_heartbeat_frequency,
+                timeout,
                 tls,
                 wait_queue_timeout,
                 write_concern,
-                zlib_compression,
                 original_srv_hostname,
                 original_uri
             ]
@@ -832,6 +1843,16 @@ impl ClientOptions {
     }
 }
 
+/// The error returned when `tlsInsecure` is combined with one of the options it implies.
+fn tls_insecure_conflict() -> Error {
+    ErrorKind::ArgumentError {
+        message: "'tlsInsecure' can't be combined with 'tlsAllowInvalidCertificates' or \
+                  'tlsAllowInvalidHostnames'"
+            .into(),
+    }
+    .into()
+}
+
 /// Splits a string into a section before a given index and a section exclusively after the index.
 /// Empty portions are returned as `None`.
 fn exclusive_split_at(s: &str, i: usize) -> (Option<&str>, Option<&str>) {
@@ -843,6 +1864,14 @@ fn exclusive_split_at(s: &str, i: usize) -> (Option<&str>, Option<&str>) {
     (lout, rout)
 }
 
+fn percent_encode(s: &str) -> String {
+    percent_encoding::utf8_percent_encode(s, USERINFO_ENCODE_SET).to_string()
+}
+
+fn percent_encode_option_value(s: &str) -> String {
+    percent_encoding::utf8_percent_encode(s, OPTION_VALUE_ENCODE_SET).to_string()
+}
+
 fn percent_decode(s: &str, err_message: &str) -> Result<String> {
     match percent_encoding::percent_decode_str(s).decode_utf8() {
         Ok(result) => Ok(result.to_string()),
@@ -1054,7 +2083,7 @@ impl ClientOptionsParser {
 
         // Set username and password.
         if let Some(u) = username {
-            let mut credential = options.credential.get_or_insert_with(Default::default);
+            let credential = options.credential.get_or_insert_with(Default::default);
             validate_userinfo(u, "username")?;
             let decoded_u = percent_decode(u, "username must be URL encoded")?;
 
@@ -1078,7 +2107,7 @@ impl ClientOptionsParser {
 
         match options.auth_mechanism {
             Some(ref mechanism) => {
-                let mut credential = options.credential.get_or_insert_with(Default::default);
+                let credential = options.credential.get_or_insert_with(Default::default);
 
                 credential.source = options
                     .auth_source
@@ -1218,6 +2247,22 @@ impl ClientOptionsParser {
         Ok(())
     }
 
+    /// Gets the TLS options to set a TLS URI option on, enabling TLS if it hasn't been configured
+    /// yet. `option` is the name of the URI option, which can't be set if TLS was disabled.
+    fn tls_options_mut(&mut self, option: &str) -> Result<&mut TlsOptions> {
+        if self.tls.is_none() {
+            self.tls = Some(Tls::Enabled(Default::default()));
+        }
+
+        match self.tls {
+            Some(Tls::Enabled(ref mut options)) => Ok(options),
+            _ => Err(ErrorKind::ArgumentError {
+                message: format!("'{}' can't be set if tls=false", option),
+            }
+            .into()),
+        }
+    }
+
     fn parse_option_pair(&mut self, key: &str, value: &str) -> Result<()> {
         macro_rules! get_bool {
             ($value:expr, $option:expr) => {
@@ -1343,9 +2388,12 @@ impl ClientOptionsParser {
                 self.heartbeat_freq = Some(Duration::from_millis(duration));
             }
             k @ "journal" => {
-                let mut write_concern = self.write_concern.get_or_insert_with(Default::default);
+                let write_concern = self.write_concern.get_or_insert_with(Default::default);
                 write_concern.journal = Some(get_bool!(value, k));
             }
+            k @ "loadbalanced" => {
+                self.load_balanced = Some(get_bool!(value, k));
+            }
             k @ "localthresholdms" => {
                 self.local_threshold = Some(Duration::from_millis(get_duration!(value, k)))
             }
@@ -1353,18 +2401,30 @@ impl ClientOptionsParser {
                 self.max_idle_time = Some(Duration::from_millis(get_duration!(value, k)));
             }
             k @ "maxstalenessseconds" => {
-                let max_staleness = Duration::from_secs(get_duration!(value, k));
-
-                if max_staleness > Duration::from_secs(0) && max_staleness < Duration::from_secs(90)
-                {
-                    return Err(ErrorKind::ArgumentError {
-                        message: "'maxStalenessSeconds' cannot be both positive and below 90"
-                            .into(),
+                self.max_staleness = match i64::from_str_radix(value, 10) {
+                    // -1 explicitly specifies that there is no max staleness.
+                    Ok(-1) => None,
+                    Ok(secs) if secs >= 90 => Some(Duration::from_secs(secs as u64)),
+                    Ok(_) => {
+                        return Err(ErrorKind::ArgumentError {
+                            message: "'maxStalenessSeconds' must be either -1 or at least 90"
+                                .into(),
+                        }
+                        .into())
                     }
-                    .into());
-                }
-
-                self.max_staleness = Some(max_staleness);
+                    Err(_) => {
+                        return Err(ErrorKind::ArgumentError {
+                            message: format!(
+                                "connection string `{}` option must be an integer",
+                                k
+                            ),
+                        }
+                        .into())
+                    }
+                };
+            }
+            k @ "maxconnecting" => {
+                self.max_connecting = Some(get_u32!(value, k));
             }
             k @ "maxpoolsize" => {
                 self.max_pool_size = Some(get_u32!(value, k));
@@ -1443,6 +2503,9 @@ impl ClientOptionsParser {
             k @ "sockettimeoutms" => {
                 self.socket_timeout = Some(Duration::from_millis(get_duration!(value, k)));
             }
+            k @ "timeoutms" => {
+                self.timeout = Some(Duration::from_millis(get_duration!(value, k)));
+            }
             k @ "tls" | k @ "ssl" => {
                 let tls = get_bool!(value, k);
 
@@ -1468,82 +2531,44 @@ impl ClientOptionsParser {
                     self.tls = Some(tls);
                 }
             }
-            k @ "tlsinsecure" | k @ "tlsallowinvalidcertificates" => {
+            k @ "tlsinsecure" => {
                 let val = get_bool!(value, k);
 
-                let allow_invalid_certificates = if k == "tlsinsecure" { !val } else { val };
-
-                match self.tls {
-                    Some(Tls::Disabled) => {
-                        return Err(ErrorKind::ArgumentError {
-                            message: "'tlsInsecure' can't be set if tls=false".into(),
-                        }
-                        .into())
-                    }
-                    Some(Tls::Enabled(ref options))
-                        if options.allow_invalid_certificates.is_some()
-                            && options.allow_invalid_certificates
-                                != Some(allow_invalid_certificates) =>
-                    {
-                        return Err(ErrorKind::ArgumentError {
-                            message: "all instances of 'tlsInsecure' and \
-                                      'tlsAllowInvalidCertificates' must be consistent (e.g. \
-                                      'tlsInsecure' cannot be true when \
-                                      'tlsAllowInvalidCertificates' is false, or vice-versa)"
-                                .into(),
-                        }
-                        .into());
-                    }
-                    Some(Tls::Enabled(ref mut options)) => {
-                        options.allow_invalid_certificates = Some(allow_invalid_certificates);
-                    }
-                    None => {
-                        self.tls = Some(Tls::Enabled(
-                            TlsOptions::builder()
-                                .allow_invalid_certificates(allow_invalid_certificates)
-                                .build(),
-                        ))
-                    }
+                let options = self.tls_options_mut("tlsInsecure")?;
+                if options.allow_invalid_certificates.is_some()
+                    || options.allow_invalid_hostnames.is_some()
+                {
+                    return Err(tls_insecure_conflict());
                 }
+
+                options.allow_invalid_certificates = Some(val);
+                options.allow_invalid_hostnames = Some(val);
+                self.tls_insecure = Some(val);
             }
-            "tlscafile" => match self.tls {
-                Some(Tls::Disabled) => {
-                    return Err(ErrorKind::ArgumentError {
-                        message: "'tlsCAFile' can't be set if tls=false".into(),
-                    }
-                    .into());
-                }
-                Some(Tls::Enabled(ref mut options)) => {
-                    options.ca_file_path = Some(value.to_string());
-                }
-                None => {
-                    self.tls = Some(Tls::Enabled(
-                        TlsOptions::builder()
-                            .ca_file_path(value.to_string())
-                            .build(),
-                    ))
-                }
-            },
-            "tlscertificatekeyfile" => match self.tls {
-                Some(Tls::Disabled) => {
-                    return Err(ErrorKind::ArgumentError {
-                        message: "'tlsCertificateKeyFile' can't be set if tls=false".into(),
-                    }
-                    .into());
-                }
-                Some(Tls::Enabled(ref mut options)) => {
-                    options.cert_key_file_path = Some(value.to_string());
+            k @ "tlsallowinvalidcertificates" | k @ "tlsallowinvalidhostnames" => {
+                let val = get_bool!(value, k);
+
+                if self.tls_insecure.is_some() {
+                    return Err(tls_insecure_conflict());
                 }
-                None => {
-                    self.tls = Some(Tls::Enabled(
-                        TlsOptions::builder()
-                            .cert_key_file_path(value.to_string())
-                            .build(),
-                    ))
+
+                if k == "tlsallowinvalidcertificates" {
+                    self.tls_options_mut("tlsAllowInvalidCertificates")?
+                        .allow_invalid_certificates = Some(val);
+                } else {
+                    self.tls_options_mut("tlsAllowInvalidHostnames")?
+                        .allow_invalid_hostnames = Some(val);
                 }
-            },
+            }
+            "tlscafile" => {
+                self.tls_options_mut("tlsCAFile")?.ca_file_path = Some(value.to_string());
+            }
+            "tlscertificatekeyfile" => {
+                self.tls_options_mut("tlsCertificateKeyFile")?
+                    .cert_key_file_path = Some(value.to_string());
+            }
             "w" => {
-                let mut write_concern = self.write_concern.get_or_insert_with(Default::default);
+                let write_concern = self.write_concern.get_or_insert_with(Default::default);
 
                 match i32::from_str_radix(value, 10) {
                     Ok(w) => {
@@ -1620,7 +2645,8 @@ mod tests {
 
     use super::{ClientOptions, StreamAddress};
     use crate::{
-        concern::{Acknowledgment, ReadConcernLevel, WriteConcern},
+        concern::{Acknowledgment, ReadConcern, ReadConcernLevel, WriteConcern},
+        error::ErrorKind,
         selection_criteria::{ReadPreference, ReadPreferenceOptions},
     };
 
@@ -1765,6 +2791,31 @@ mod tests {
         );
     }
 
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn with_snapshot_read_concern() {
+        let options = ClientOptions::parse("mongodb://localhost:27017/?readConcernLevel=snapshot")
+            .await
+            .unwrap();
+        assert_eq!(options.read_concern, Some(ReadConcern::snapshot()));
+    }
+
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn with_timeout() {
+        let options = ClientOptions::parse("mongodb://localhost:27017/?timeoutMS=1500")
+            .await
+            .unwrap();
+        assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+        assert!(options.to_connection_string().contains("timeoutMS=1500"));
+
+        assert!(
+            ClientOptions::parse("mongodb://localhost:27017/?timeoutMS=-1")
+                .await
+                .is_err()
+        );
+    }
+
     #[cfg_attr(feature = "tokio-runtime", tokio::test)]
     #[cfg_attr(feature = "async-std-runtime", async_std::test)]
     async fn with_w_negative_int() {
@@ -1845,6 +2896,15 @@ mod tests {
         );
     }
 
+    #[cfg_attr(feature = "tokio-runtime", tokio::test)]
+    #[cfg_attr(feature = "async-std-runtime", async_std::test)]
+    async fn with_w_0_and_j() {
+        let error = ClientOptions::parse("mongodb://localhost:27017/?w=0&journal=true")
+            .await
+            .expect_err("w=0 with journal=true should be rejected");
+        assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+    }
+
     #[cfg_attr(feature = "tokio-runtime", tokio::test)]
     #[cfg_attr(feature = "async-std-runtime", async_std::test)]
     async fn with_wtimeout_non_int() {
@@ -1,12 +1,33 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, TcpListener, UdpSocket},
+    time::{Duration, Instant},
+};
+
 use pretty_assertions::assert_eq;
+use rustls::{Certificate, RootCertStore, ServerCertVerifier};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use trust_dns_resolver::config::NameServerConfigGroup;
 
 use crate::{
     bson::{Bson, Document},
-    client::options::{ClientOptions, ClientOptionsParser, StreamAddress},
+    client::options::{
+        ClientOptions,
+        ClientOptionsParser,
+        Compressor,
+        PinnedCertVerifier,
+        ReconnectPolicy,
+        ResolverConfig,
+        StreamAddress,
+        Tls,
+        TlsOptions,
+    },
+    cmap::options::ConnectionPoolOptions,
+    options::sequential_object_ids,
     error::ErrorKind,
     selection_criteria::{ReadPreference, SelectionCriteria},
     test::run_spec_test,
+    Client,
 };
 #[derive(Debug, Deserialize)]
 struct TestFile {
@@ -75,6 +96,10 @@ fn document_from_client_options(mut options: ClientOptions) -> Document {
         doc.insert("localthresholdms", i.as_millis() as i32);
     }
 
+    if let Some(i) = options.max_connecting.take() {
+        doc.insert("maxconnecting", i as i32);
+    }
+
     if let Some(i) = options.max_idle_time.take() {
         doc.insert("maxidletimems", i.as_millis() as i32);
     }
@@ -150,6 +175,7 @@ fn document_from_client_options(mut options: ClientOptions) -> Document {
         let ca_file_path = opt.ca_file_path.take();
         let cert_key_file_path = opt.cert_key_file_path.take();
         let allow_invalid_certificates = opt.allow_invalid_certificates.take();
+        let allow_invalid_hostnames = opt.allow_invalid_hostnames.take();
 
         if let Some(s) = ca_file_path {
             doc.insert("tls", true);
@@ -163,13 +189,27 @@ fn document_from_client_options(mut options: ClientOptions) -> Document {
         if let Some(b) = allow_invalid_certificates {
             doc.insert("tlsallowinvalidcertificates", b);
         }
+
+        if let Some(b) = allow_invalid_hostnames {
+            doc.insert("tlsallowinvalidhostnames", b);
+        }
     }
 
-    if let Some(vec) = options.compressors.take() {
+    if let Some(compressors) = options.compressors.take() {
         doc.insert(
             "compressors",
-            Bson::Array(vec.into_iter().map(Bson::String).collect()),
+            Bson::Array(
+                compressors
+                    .iter()
+                    .map(|compressor| Bson::String(compressor.name().to_string()))
+                    .collect(),
+            ),
         );
+        for compressor in compressors {
+            if let Compressor::Zlib { level: Some(level) } = compressor {
+                doc.insert("zlibcompressionlevel", level);
+            }
+        }
     }
 
     if let Some(s) = options.read_concern.take() {
@@ -203,10 +243,6 @@ fn document_from_client_options(mut options: ClientOptions) -> Document {
         doc.insert("journal", b);
     }
 
-    if let Some(i) = options.zlib_compression.take() {
-        doc.insert("zlibcompressionlevel", i);
-    }
-
     doc
 }
 
@@ -221,7 +257,6 @@ async fn run_test(test_file: TestFile) {
                 .description
                 .contains("tlsCertificateKeyFilePassword")
             // Not Implementing
-            || test_case.description.contains("tlsAllowInvalidHostnames")
             || test_case.description.contains("single-threaded")
             || test_case.description.contains("serverSelectionTryOnce")
             || test_case.description.contains("Unix")
@@ -273,12 +308,19 @@ async fn run_test(test_file: TestFile) {
                         })
                         .collect();
 
-                    // tlsallowinvalidcertificates and tlsinsecure must be inverse of each other
-                    if !json_options.contains_key("tlsallowinvalidcertificates") {
-                        if let Some(val) = json_options.remove("tlsinsecure") {
-                            json_options
-                                .insert("tlsallowinvalidcertificates", !val.as_bool().unwrap());
-                        }
+                    // tlsinsecure is stored as the two options it implies
+                    if let Some(val) = json_options.remove("tlsinsecure") {
+                        json_options.insert("tlsallowinvalidcertificates", val.clone());
+                        json_options.insert("tlsallowinvalidhostnames", val);
+                    }
+
+                    // compressors that the driver doesn't support are ignored when parsing
+                    if let Some(Bson::Array(names)) = json_options.get_mut("compressors") {
+                        names.retain(|name| {
+                            name.as_str()
+                                .and_then(|name| Compressor::from_name(name, None))
+                                .is_some()
+                        });
                     }
 
                     options_doc = options_doc
@@ -372,3 +414,357 @@ async fn parse_unknown_options() {
     .await;
     parse_uri("maxstalenessms", Some("maxstalenessseconds")).await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_max_staleness() {
+    let options = ClientOptions::parse(
+        "mongodb://localhost/?readPreference=secondary&maxStalenessSeconds=120",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        options
+            .selection_criteria
+            .as_ref()
+            .and_then(SelectionCriteria::max_staleness),
+        Some(Duration::from_secs(120))
+    );
+
+    let options = ClientOptions::parse(
+        "mongodb://localhost/?readPreference=secondary&maxStalenessSeconds=-1",
+    )
+    .await
+    .unwrap();
+    match options.selection_criteria {
+        Some(SelectionCriteria::ReadPreference(ReadPreference::Secondary { options })) => {
+            assert_eq!(options.max_staleness, None)
+        }
+        other => panic!("expected secondary read preference, got {:?}", other),
+    }
+
+    // -1 doesn't require a non-primary read preference, since it doesn't specify a max staleness.
+    ClientOptions::parse("mongodb://localhost/?readPreference=primary&maxStalenessSeconds=-1")
+        .await
+        .unwrap();
+
+    for invalid in &["0", "89", "-2", "abc"] {
+        let uri = format!(
+            "mongodb://localhost/?readPreference=secondary&maxStalenessSeconds={}",
+            invalid
+        );
+        match ClientOptions::parse(&uri).await.map_err(|e| e.kind) {
+            Err(kind) if matches!(*kind, ErrorKind::ArgumentError { .. }) => {}
+            other => panic!("expected argument error for {}, got {:?}", invalid, other),
+        }
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_load_balanced() {
+    let options = ClientOptions::parse("mongodb://localhost/?loadBalanced=true")
+        .await
+        .unwrap();
+    assert_eq!(options.load_balanced, Some(true));
+
+    for invalid in &[
+        "mongodb://a,b/?loadBalanced=true",
+        "mongodb://localhost/?loadBalanced=true&replicaSet=foo",
+        "mongodb://localhost/?loadBalanced=true&directConnection=true",
+    ] {
+        match ClientOptions::parse(invalid).await.map_err(|e| e.kind) {
+            Err(kind) if matches!(*kind, ErrorKind::ArgumentError { .. }) => {}
+            other => panic!("expected argument error for {}, got {:?}", invalid, other),
+        }
+    }
+
+    ClientOptions::parse("mongodb://localhost/?loadBalanced=true&directConnection=false")
+        .await
+        .unwrap();
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_max_connecting() {
+    let options = ClientOptions::parse("mongodb://localhost/?maxConnecting=5")
+        .await
+        .unwrap();
+    assert_eq!(options.max_connecting, Some(5));
+    assert_eq!(
+        ConnectionPoolOptions::from_client_options(&options).max_connecting,
+        Some(5)
+    );
+
+    let error = ClientOptions::parse("mongodb://localhost/?maxConnecting=0")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+}
+
+#[test]
+fn zero_max_concurrent_operations() {
+    let options = ClientOptions::builder()
+        .max_concurrent_operations(0)
+        .build();
+    let error = options.validate().unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+
+    let options = ClientOptions::builder()
+        .max_concurrent_operations(1)
+        .build();
+    options.validate().unwrap();
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_heartbeat_frequency() {
+    let options = ClientOptions::parse("mongodb://localhost/?heartbeatFrequencyMS=500")
+        .await
+        .unwrap();
+    assert_eq!(options.heartbeat_freq, Some(Duration::from_millis(500)));
+
+    let error = ClientOptions::parse("mongodb://localhost/?heartbeatFrequencyMS=499")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+
+    let options = ClientOptions::builder()
+        .heartbeat_freq(Duration::from_millis(100))
+        .build();
+    match *Client::with_options(options).unwrap_err().kind {
+        ErrorKind::ArgumentError { .. } => {}
+        ref e => panic!("expected ArgumentError, got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_app_name() {
+    let app_name = "a".repeat(128);
+    let options = ClientOptions::parse(&format!("mongodb://localhost/?appName={}", app_name))
+        .await
+        .unwrap();
+    assert_eq!(options.app_name, Some(app_name));
+
+    let error = ClientOptions::parse(&format!("mongodb://localhost/?appName={}", "a".repeat(129)))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn parse_tls_insecure() {
+    let options = ClientOptions::parse("mongodb://localhost/?tlsInsecure=true")
+        .await
+        .unwrap();
+    let tls_options = options.tls_options().unwrap();
+    assert_eq!(tls_options.allow_invalid_certificates, Some(true));
+    assert_eq!(tls_options.allow_invalid_hostnames, Some(true));
+
+    let options = ClientOptions::parse("mongodb://localhost/?tlsAllowInvalidHostnames=true")
+        .await
+        .unwrap();
+    let tls_options = options.tls_options().unwrap();
+    assert_eq!(tls_options.allow_invalid_certificates, None);
+    assert_eq!(tls_options.allow_invalid_hostnames, Some(true));
+
+    for uri in &[
+        "mongodb://localhost/?tlsInsecure=true&tlsAllowInvalidHostnames=true",
+        "mongodb://localhost/?tlsAllowInvalidCertificates=false&tlsInsecure=false",
+        "mongodb://localhost/?tls=false&tlsAllowInvalidHostnames=true",
+    ] {
+        let error = ClientOptions::parse(uri).await.unwrap_err();
+        assert!(
+            matches!(error.kind.as_ref(), ErrorKind::ArgumentError { .. }),
+            "{}",
+            uri
+        );
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn tls_files_loaded_at_construction() {
+    let options = ClientOptions::parse("mongodb://localhost/?tlsCAFile=/nonexistent/ca.pem")
+        .await
+        .unwrap();
+    match *Client::with_options(options).unwrap_err().kind {
+        ErrorKind::ArgumentError { ref message } => {
+            assert!(message.contains("/nonexistent/ca.pem"), "{}", message)
+        }
+        ref e => panic!("expected ArgumentError, got {:?}", e),
+    }
+
+    let path = std::env::temp_dir().join("mongodb-rust-driver-malformed.pem");
+    std::fs::write(&path, "not a certificate").unwrap();
+    let options = ClientOptions::builder()
+        .tls(Tls::Enabled(
+            TlsOptions::builder()
+                .cert_key_file_path(path.to_string_lossy().into_owned())
+                .build(),
+        ))
+        .build();
+    let result = Client::with_options(options);
+    std::fs::remove_file(&path).unwrap();
+    match *result.unwrap_err().kind {
+        ErrorKind::ParseError { .. } => {}
+        ref e => panic!("expected ParseError, got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn to_connection_string() {
+    let uri = "mongodb://us%40er:secret@a,b:27018/?appName=app&authSource=auth&replicaSet=rs\
+               &readPreference=secondary&readPreferenceTags=dc:ny&maxStalenessSeconds=120\
+               &w=majority&journal=true&wtimeoutMS=100&tls=true&tlsAllowInvalidCertificates=true\
+               &compressors=zlib&zlibCompressionLevel=4&connectTimeoutMS=1000";
+    let options = ClientOptions::parse(uri).await.unwrap();
+
+    let connection_string = options.to_connection_string();
+    assert!(!connection_string.contains("secret"));
+    assert!(connection_string.starts_with("mongodb://us%40er:*****@a:27017,b:27018/?"));
+
+    let mut reparsed = ClientOptions::parse(&connection_string).await.unwrap();
+    assert_eq!(
+        reparsed.credential.as_ref().unwrap().password.as_deref(),
+        Some("*****")
+    );
+    reparsed.credential.as_mut().unwrap().password = Some("secret".to_string());
+    reparsed.original_uri = options.original_uri.clone();
+    assert_eq!(reparsed, options);
+
+    let uri = "mongodb://localhost:27017/?readConcernLevel=snapshot&w=dcTag&wtimeoutMS=50";
+    let options = ClientOptions::parse(uri).await.unwrap();
+    let mut reparsed = ClientOptions::parse(&options.to_connection_string())
+        .await
+        .unwrap();
+    reparsed.original_uri = options.original_uri.clone();
+    assert_eq!(reparsed, options);
+
+    let options = ClientOptions::parse("mongodb://localhost").await.unwrap();
+    assert_eq!(options.to_connection_string(), "mongodb://localhost:27017/");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn to_connection_string_encodes_values() {
+    let uri = "mongodb://localhost:27017/?appName=a%26b%3Dc%2Fd%2Ce%3Af\
+               &replicaSet=rs%2F1%3A2&readPreference=nearest\
+               &readPreferenceTags=dc%3Dx:a%26b%2Fc&tls=true&tlsCAFile=%2Ftmp%2Fa%2Cb%3Dc%26d.pem\
+               &w=tag%3A1%2C2";
+    let options = ClientOptions::parse(uri).await.unwrap();
+    assert_eq!(options.app_name.as_deref(), Some("a&b=c/d,e:f"));
+
+    let connection_string = options.to_connection_string();
+    assert!(connection_string.contains("appName=a%26b%3Dc%2Fd%2Ce%3Af"));
+    assert!(connection_string.contains("readPreferenceTags=dc%3Dx:a%26b%2Fc"));
+
+    let mut reparsed = ClientOptions::parse(&connection_string).await.unwrap();
+    reparsed.original_uri = options.original_uri.clone();
+    assert_eq!(reparsed, options);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn srv_lookup_timeout() {
+    // A name server that accepts queries but never responds to them.
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = tcp_listener.local_addr().unwrap().port();
+    let _udp_socket = UdpSocket::bind(("127.0.0.1", port)).unwrap();
+
+    let resolver_config = ResolverConfig::from_parts(
+        None,
+        Vec::new(),
+        NameServerConfigGroup::from_ips_clear(&[IpAddr::V4(Ipv4Addr::LOCALHOST)], port),
+    );
+
+    let start = Instant::now();
+    let error = ClientOptions::parse_with_srv_lookup_timeout(
+        "mongodb+srv://test1.test.build.10gen.cc",
+        resolver_config,
+        Duration::from_millis(200),
+    )
+    .await
+    .expect_err("lookup should time out");
+
+    assert!(start.elapsed() < Duration::from_secs(3));
+    match *error.kind {
+        ErrorKind::SrvLookupError { ref message } => assert!(message.contains("timed out")),
+        ref e => panic!("expected SRV lookup error, got {:?}", e),
+    }
+}
+
+#[test]
+fn reconnect_policy_backoff() {
+    let policy = ReconnectPolicy::builder().build();
+    assert_eq!(policy.backoff(0), Duration::from_millis(500));
+    assert_eq!(policy.backoff(1), Duration::from_secs(1));
+    assert_eq!(policy.backoff(5), Duration::from_secs(10));
+    assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(10));
+
+    let policy = ReconnectPolicy::builder()
+        .initial_backoff(Duration::from_millis(10))
+        .max_backoff(Duration::from_millis(30))
+        .build();
+    assert_eq!(policy.backoff(1), Duration::from_millis(20));
+    assert_eq!(policy.backoff(2), Duration::from_millis(30));
+}
+
+#[test]
+fn sequential_object_id_generator() {
+    let generator = sequential_object_ids(255);
+    let first = generator();
+    let second = generator();
+
+    assert_eq!(
+        first.as_object_id().unwrap().bytes(),
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255]
+    );
+    assert_eq!(
+        second.as_object_id().unwrap().bytes(),
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0]
+    );
+    assert_eq!(sequential_object_ids(255)(), first);
+}
+
+#[test]
+fn pinned_cert_verifier() {
+    let cert = Certificate(b"not really a certificate".to_vec());
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(&Sha256::digest(&cert.0));
+
+    let roots = RootCertStore::empty();
+    let dns_name = webpki::DNSNameRef::try_from_ascii_str("localhost").unwrap();
+
+    let verifier = PinnedCertVerifier {
+        fingerprints: vec![[1; 32], fingerprint],
+    };
+    assert!(verifier
+        .verify_server_cert(&roots, &[cert.clone()], dns_name, &[])
+        .is_ok());
+    assert!(verifier
+        .verify_server_cert(&roots, &[], dns_name, &[])
+        .is_err());
+
+    let verifier = PinnedCertVerifier {
+        fingerprints: vec![[1; 32]],
+    };
+    assert!(verifier
+        .verify_server_cert(&roots, &[cert], dns_name, &[])
+        .is_err());
+}
@@ -5,18 +5,30 @@ use crate::bson::{Document, Timestamp};
 
 /// Struct modeling a cluster time reported by the server.
 ///
+/// A cluster time obtained from one [`Client`](../struct.Client.html) can be passed to
+/// [`Client::advance_cluster_time`](../struct.Client.html#method.advance_cluster_time) on another
+/// to preserve causal ordering across them. It serializes to the signed `$clusterTime` document
+/// that the server reported, so it can also be sent between processes.
+///
 /// See [the MongoDB documentation](https://docs.mongodb.com/manual/core/read-isolation-consistency-recency/)
 /// for more information.
 #[derive(Debug, Deserialize, Clone, Serialize, Derivative)]
 #[derivative(PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct ClusterTime {
+pub struct ClusterTime {
     cluster_time: Timestamp,
 
     #[derivative(PartialEq = "ignore")]
     signature: Document,
 }
 
+impl ClusterTime {
+    /// The logical time of this cluster time.
+    pub fn timestamp(&self) -> Timestamp {
+        self.cluster_time
+    }
+}
+
 impl std::cmp::Ord for ClusterTime {
     // TODO: RUST-390 use Timestamp's Ord impl.
     fn cmp(&self, other: &ClusterTime) -> std::cmp::Ordering {
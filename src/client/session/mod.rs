@@ -5,37 +5,80 @@ mod test;
 
 use std::{
     collections::HashSet,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
+use futures::future::BoxFuture;
 use lazy_static::lazy_static;
 use uuid::Uuid;
 
 use crate::{
-    bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
+    bson::{doc, spec::BinarySubtype, Binary, Bson, Document, Timestamp},
+    error::{
+        Error,
+        ErrorKind,
+        Result,
+        WriteFailure,
+        TRANSIENT_TRANSACTION_ERROR,
+        UNKNOWN_TRANSACTION_COMMIT_RESULT,
+    },
+    operation::{AbortTransaction, CommitTransaction},
+    options::{
+        Acknowledgment,
+        ReadPreference,
+        SelectionCriteria,
+        SessionOptions,
+        StreamAddress,
+        TransactionOptions,
+    },
     Client,
     RUNTIME,
 };
-pub(crate) use cluster_time::ClusterTime;
+pub use cluster_time::ClusterTime;
 pub(super) use pool::ServerSessionPool;
 
+/// How long `ClientSession::with_transaction` keeps retrying a transaction.
+const WITH_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The code of the error the server returns when an operation exceeds its `maxTimeMS`.
+const MAX_TIME_MS_EXPIRED_CODE: i32 = 50;
+
+/// The codes of the write concern errors after which committing a transaction again can't succeed.
+const UNSATISFIABLE_WRITE_CONCERN_CODES: &[i32] = &[79, 100];
+
 lazy_static! {
     pub(crate) static ref SESSIONS_UNSUPPORTED_COMMANDS: HashSet<&'static str> = {
         let mut hash_set = HashSet::new();
         hash_set.insert("killcursors");
+        hash_set.insert("endsessions");
         hash_set.insert("parallelcollectionscan");
         hash_set
     };
 }
 
-/// Session to be used with client operations. This acts as a handle to a server session.
-/// This keeps the details of how server sessions are pooled opaque to users.
+/// A MongoDB client session, started with
+/// [`Client::start_session`](struct.Client.html#method.start_session). Operations executed with a
+/// session, e.g. with
+/// [`Collection::find_one_with_session`](struct.Collection.html#method.find_one_with_session),
+/// are causally consistent with each other unless the session was started with
+/// [`SessionOptions::causal_consistency`](options/struct.SessionOptions.html#structfield.causal_consistency)
+/// set to false.
+///
+/// A session can only be used with operations on the `Client` that started it, and only with one
+/// operation at a time.
 #[derive(Debug)]
-pub(crate) struct ClientSession {
+pub struct ClientSession {
     cluster_time: Option<ClusterTime>,
+    operation_time: Option<Timestamp>,
+    snapshot_time: Option<Timestamp>,
     server_session: ServerSession,
     client: Client,
     is_implicit: bool,
+    implicit_causal_consistency: bool,
+    options: Option<SessionOptions>,
+    deadline: Option<Instant>,
+    transaction: Transaction,
 }
 
 impl ClientSession {
@@ -45,34 +88,447 @@ impl ClientSession {
             client,
             server_session,
             cluster_time: None,
+            operation_time: None,
+            snapshot_time: None,
             is_implicit: true,
+            implicit_causal_consistency: false,
+            options: None,
+            deadline: None,
+            transaction: Default::default(),
+        }
+    }
+
+    /// Creates a new `ClientSession` started by the user that wraps the provided server session.
+    pub(crate) fn new_explicit(
+        server_session: ServerSession,
+        client: Client,
+        options: Option<SessionOptions>,
+    ) -> Self {
+        Self {
+            client,
+            server_session,
+            cluster_time: None,
+            operation_time: None,
+            snapshot_time: None,
+            is_implicit: false,
+            implicit_causal_consistency: false,
+            options,
+            deadline: None,
+            transaction: Default::default(),
         }
     }
 
     /// The id of this session.
-    pub(crate) fn id(&self) -> &Document {
+    pub fn id(&self) -> &Document {
         &self.server_session.id
     }
 
+    /// The options this session was started with.
+    pub fn options(&self) -> Option<&SessionOptions> {
+        self.options.as_ref()
+    }
+
+    /// The `Client` that started this session.
+    pub(crate) fn client(&self) -> &Client {
+        &self.client
+    }
+
     /// Whether this session was created implicitly by the driver or explcitly by the user.
     pub(crate) fn is_implicit(&self) -> bool {
         self.is_implicit
     }
 
+    /// Whether reads in this session should observe the operations that preceded them in it.
+    /// Sessions created implicitly by the driver are only causally consistent if they were made so
+    /// with `make_causally_consistent`.
+    pub(crate) fn is_causally_consistent(&self) -> bool {
+        if self.is_implicit {
+            return self.implicit_causal_consistency;
+        }
+
+        self.options
+            .as_ref()
+            .and_then(|options| options.causal_consistency)
+            != Some(false)
+    }
+
+    /// Makes the reads in this implicit session observe the operations that preceded them in it,
+    /// for when the driver runs several operations in one implicit session that depend on each
+    /// other.
+    pub(crate) fn make_causally_consistent(&mut self) {
+        self.implicit_causal_consistency = true;
+    }
+
+    /// The operation time of the latest operation executed in this session, as reported by the
+    /// server. This will be `None` if this session has not been used in an operation yet.
+    pub fn operation_time(&self) -> Option<Timestamp> {
+        self.operation_time
+    }
+
+    /// Set the operation time to the provided one if it is greater than this session's current
+    /// operation time or if this session's operation time is `None`. This can be used to make
+    /// the reads in this session causally consistent with the operations of another session.
+    pub fn advance_operation_time(&mut self, to: Timestamp) {
+        let is_greater =
+            |current: Timestamp| (current.time, current.increment) < (to.time, to.increment);
+        if self.operation_time.map(is_greater).unwrap_or(true) {
+            self.operation_time = Some(to);
+        }
+    }
+
+    /// The point in time that the reads with a "snapshot" read concern in this session read from.
+    /// This is pinned from the response to the first such read, and will be `None` until then.
+    pub fn snapshot_time(&self) -> Option<Timestamp> {
+        self.snapshot_time
+    }
+
+    /// Pins the snapshot time of this session if it hasn't been already.
+    pub(crate) fn pin_snapshot_time(&mut self, snapshot_time: Timestamp) {
+        if self.snapshot_time.is_none() {
+            self.snapshot_time = Some(snapshot_time);
+        }
+    }
+
     /// The highest seen cluster time this session has seen so far.
     /// This will be `None` if this session has not been used in an operation yet.
-    pub(crate) fn cluster_time(&self) -> Option<&ClusterTime> {
+    pub fn cluster_time(&self) -> Option<&ClusterTime> {
         self.cluster_time.as_ref()
     }
 
     /// Set the cluster time to the provided one if it is greater than this session's highest seen
     /// cluster time or if this session's cluster time is `None`.
-    pub(crate) fn advance_cluster_time(&mut self, to: &ClusterTime) {
+    pub fn advance_cluster_time(&mut self, to: &ClusterTime) {
         if self.cluster_time().map(|ct| ct < to).unwrap_or(true) {
             self.cluster_time = Some(to.clone());
         }
     }
 
+    /// The time by which the operations executed with this session must complete, if one was set
+    /// with [`ClientSession::set_deadline`](#method.set_deadline).
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Sets a time by which every operation executed with this session must complete, including
+    /// server selection, and the getMores of the cursors those operations return. An operation
+    /// still running when the deadline passes returns a
+    /// [`Timeout`](error/enum.ErrorKind.html#variant.Timeout) error labeled with
+    /// [`TIMEOUT_ERROR`](error/constant.TIMEOUT_ERROR.html).
+    ///
+    /// If an operation also has a `timeout`, from its options or from the client, the earlier of
+    /// the two limits applies.
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Starts a transaction in this session, which the operations subsequently executed with the
+    /// session are part of until it's committed with
+    /// [`commit_transaction`](#method.commit_transaction) or aborted with
+    /// [`abort_transaction`](#method.abort_transaction). Transactions require a replica set running
+    /// MongoDB 4.0 or newer, or a sharded cluster running MongoDB 4.2 or newer.
+    ///
+    /// The options that aren't set in `options` are taken from the `default_transaction_options`
+    /// the session was started with, and then from the `Client`.
+    pub async fn start_transaction(
+        &mut self,
+        options: impl Into<Option<TransactionOptions>>,
+    ) -> Result<()> {
+        if self.in_transaction() {
+            return Err(transaction_error("a transaction is already in progress"));
+        }
+
+        let default_options = self
+            .options
+            .as_ref()
+            .and_then(|options| options.default_transaction_options.as_ref());
+        let client_options = &self.client.inner.options;
+        let mut options = options.into().unwrap_or_default();
+        if options.read_concern.is_none() {
+            options.read_concern = default_options
+                .and_then(|options| options.read_concern.clone())
+                .or_else(|| client_options.read_concern.clone());
+        }
+        if options.write_concern.is_none() {
+            options.write_concern = default_options
+                .and_then(|options| options.write_concern.clone())
+                .or_else(|| client_options.write_concern.clone());
+        }
+        if options.max_commit_time.is_none() {
+            options.max_commit_time = default_options.and_then(|options| options.max_commit_time);
+        }
+
+        if let Some(ref write_concern) = options.write_concern {
+            if !write_concern.is_acknowledged() {
+                return Err(transaction_error(
+                    "transactions do not support unacknowledged write concerns",
+                ));
+            }
+        }
+
+        self.server_session.txn_number += 1;
+        self.transaction = Transaction {
+            state: TransactionState::Starting,
+            options: Some(options),
+            pinned_mongos: None,
+        };
+        Ok(())
+    }
+
+    /// Commits the transaction in progress in this session. If committing fails with an error
+    /// labeled with [`UNKNOWN_TRANSACTION_COMMIT_RESULT`][unknown], it's unknown whether the
+    /// transaction was committed, and this method can be called again to retry committing it.
+    ///
+    /// [unknown]: error/constant.UNKNOWN_TRANSACTION_COMMIT_RESULT.html
+    pub async fn commit_transaction(&mut self) -> Result<()> {
+        let is_retry = match self.transaction.state {
+            TransactionState::None => {
+                return Err(transaction_error("no transaction was started"));
+            }
+            TransactionState::Aborted => {
+                return Err(transaction_error(
+                    "cannot commit a transaction after aborting it",
+                ));
+            }
+            // No command was executed in the transaction, so there's nothing to commit.
+            TransactionState::Starting => {
+                self.transaction.state = TransactionState::Committed {
+                    data_committed: false,
+                };
+                return Ok(());
+            }
+            TransactionState::Committed {
+                data_committed: false,
+            } => return Ok(()),
+            TransactionState::InProgress => false,
+            TransactionState::Committed {
+                data_committed: true,
+            } => true,
+        };
+        self.transaction.state = TransactionState::Committed {
+            data_committed: true,
+        };
+
+        // A retried commit waits for a majority of the replica set, so that the transaction can't
+        // be rolled back after the retry reports it committed.
+        let mut options = self.transaction.options.clone();
+        if is_retry {
+            let options = options.get_or_insert_with(Default::default);
+            let mut write_concern = options.write_concern.clone().unwrap_or_default();
+            write_concern.w = Some(Acknowledgment::Majority);
+            write_concern.w_timeout = write_concern
+                .w_timeout
+                .or_else(|| Some(Duration::from_secs(10)));
+            options.write_concern = Some(write_concern);
+        }
+
+        let client = self.client.clone();
+        client
+            .execute_operation_with_session(CommitTransaction::new(options), self)
+            .await
+            .map_err(|error| {
+                if is_unknown_commit_result(&error) {
+                    error.with_label(UNKNOWN_TRANSACTION_COMMIT_RESULT)
+                } else {
+                    error
+                }
+            })
+    }
+
+    /// Aborts the transaction in progress in this session, rolling back the operations executed in
+    /// it. Errors returned by the server while aborting are ignored, since the server aborts
+    /// transactions that aren't committed on its own once they time out.
+    pub async fn abort_transaction(&mut self) -> Result<()> {
+        match self.transaction.state {
+            TransactionState::None => Err(transaction_error("no transaction was started")),
+            TransactionState::Committed { .. } => Err(transaction_error(
+                "cannot abort a transaction after committing it",
+            )),
+            TransactionState::Aborted => {
+                Err(transaction_error("the transaction was already aborted"))
+            }
+            TransactionState::Starting => {
+                self.transaction.state = TransactionState::Aborted;
+                Ok(())
+            }
+            TransactionState::InProgress => {
+                self.transaction.state = TransactionState::Aborted;
+                let write_concern = self
+                    .transaction
+                    .options
+                    .as_ref()
+                    .and_then(|options| options.write_concern.clone());
+                let client = self.client.clone();
+                let _ = client
+                    .execute_operation_with_session(AbortTransaction::new(write_concern), self)
+                    .await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs `callback` in a transaction started with the given options and commits the
+    /// transaction, retrying as needed:
+    ///
+    /// - If `callback` returns an error, the transaction is aborted. If the error is labeled with
+    ///   [`TRANSIENT_TRANSACTION_ERROR`][transient], `callback` is run again in a new transaction,
+    ///   and otherwise the error is returned.
+    /// - If committing fails with an error labeled with
+    ///   [`UNKNOWN_TRANSACTION_COMMIT_RESULT`][unknown], committing is retried. If it fails with an
+    ///   error labeled with `TRANSIENT_TRANSACTION_ERROR`, the whole transaction is retried.
+    ///
+    /// Retrying stops once 120 seconds have passed since this method was called, and the last
+    /// error is returned. `callback` must execute its operations with the session it's given. If it
+    /// commits or aborts the transaction itself, its result is returned as is.
+    ///
+    /// [transient]: error/constant.TRANSIENT_TRANSACTION_ERROR.html
+    /// [unknown]: error/constant.UNKNOWN_TRANSACTION_COMMIT_RESULT.html
+    ///
+    /// ```rust
+    /// # use futures::future::FutureExt;
+    /// # use mongodb::{bson::{doc, Document}, error::Result, Client};
+    /// #
+    /// # async fn func(client: Client) -> Result<()> {
+    /// let coll = client.database("bank").collection::<Document>("accounts");
+    /// let mut session = client.start_session(None).await?;
+    /// session
+    ///     .with_transaction(
+    ///         |session| {
+    ///             let coll = coll.clone();
+    ///             async move {
+    ///                 coll.update_one_with_session(
+    ///                     doc! { "_id": 1 },
+    ///                     doc! { "$inc": { "balance": -10 } },
+    ///                     None,
+    ///                     session,
+    ///                 )
+    ///                 .await?;
+    ///                 coll.update_one_with_session(
+    ///                     doc! { "_id": 2 },
+    ///                     doc! { "$inc": { "balance": 10 } },
+    ///                     None,
+    ///                     session,
+    ///                 )
+    ///                 .await?;
+    ///                 Ok(())
+    ///             }
+    ///             .boxed()
+    ///         },
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_transaction<R, F>(
+        &mut self,
+        mut callback: F,
+        options: impl Into<Option<TransactionOptions>>,
+    ) -> Result<R>
+    where
+        F: for<'a> FnMut(&'a mut ClientSession) -> BoxFuture<'a, Result<R>>,
+    {
+        let options = options.into();
+        let start = Instant::now();
+        let can_retry = || start.elapsed() < WITH_TRANSACTION_TIMEOUT;
+
+        'transaction: loop {
+            self.start_transaction(options.clone()).await?;
+
+            let result = match callback(self).await {
+                Ok(result) => result,
+                Err(error) => {
+                    // Whatever the error, the transaction is aborted rather than left to hold its
+                    // locks until it times out on the server. Failing to abort it doesn't replace
+                    // the callback's error.
+                    if self.in_transaction() {
+                        let _ = self.abort_transaction().await;
+                    }
+                    if error.contains_label(TRANSIENT_TRANSACTION_ERROR) && can_retry() {
+                        continue 'transaction;
+                    }
+                    return Err(error);
+                }
+            };
+
+            if !self.in_transaction() {
+                return Ok(result);
+            }
+
+            loop {
+                match self.commit_transaction().await {
+                    Ok(()) => return Ok(result),
+                    Err(error) => {
+                        if !can_retry() {
+                            return Err(error);
+                        }
+                        if error.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT)
+                            && !is_max_time_ms_expired(&error)
+                        {
+                            continue;
+                        }
+                        if error.contains_label(TRANSIENT_TRANSACTION_ERROR) {
+                            continue 'transaction;
+                        }
+                        return Err(error);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether a transaction was started in this session and hasn't been committed or aborted yet.
+    pub(crate) fn in_transaction(&self) -> bool {
+        matches!(
+            self.transaction.state,
+            TransactionState::Starting | TransactionState::InProgress
+        )
+    }
+
+    /// The state of this session's transaction.
+    pub(crate) fn transaction_state(&self) -> TransactionState {
+        self.transaction.state
+    }
+
+    /// The options of this session's transaction, if one was started.
+    pub(crate) fn transaction_options(&self) -> Option<&TransactionOptions> {
+        self.transaction.options.as_ref()
+    }
+
+    /// Records that the first command of the transaction was sent, on a connection to the server
+    /// with the given address. On a sharded cluster, the rest of the transaction must be executed
+    /// on the same mongos, so it's pinned to it.
+    pub(crate) fn mark_transaction_in_progress(&mut self, mongos: Option<StreamAddress>) {
+        self.transaction.state = TransactionState::InProgress;
+        self.transaction.pinned_mongos = mongos;
+    }
+
+    /// The criteria used to select the server for the operations in this session's transaction:
+    /// the mongos it's pinned to, if any, and the primary otherwise.
+    pub(crate) fn transaction_selection_criteria(&self) -> SelectionCriteria {
+        match self.transaction.pinned_mongos {
+            Some(ref address) => {
+                let address = address.clone();
+                SelectionCriteria::Predicate(Arc::new(move |server| server.address() == &address))
+            }
+            None => SelectionCriteria::ReadPreference(ReadPreference::Primary),
+        }
+    }
+
+    /// Forgets this session's transaction if it was committed or aborted, since the operations
+    /// executed afterwards aren't part of it.
+    pub(crate) fn clear_finished_transaction(&mut self) {
+        if let TransactionState::Committed { .. } | TransactionState::Aborted =
+            self.transaction.state
+        {
+            self.transaction = Default::default();
+        }
+    }
+
+    /// The number of the transaction this session is in.
+    pub(crate) fn txn_number(&self) -> u64 {
+        self.server_session.txn_number
+    }
+
     /// Mark this session (and the underlying server session) as dirty.
     pub(crate) fn mark_dirty(&mut self) {
         self.server_session.dirty = true;
@@ -107,6 +563,73 @@ impl Drop for ClientSession {
     }
 }
 
+/// The state of a session's transaction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TransactionState {
+    /// No transaction was started, or the last one ended before an operation outside of it was
+    /// executed.
+    None,
+
+    /// A transaction was started, but no command was sent in it yet.
+    Starting,
+
+    InProgress,
+
+    /// The transaction was committed. `data_committed` is false if no command was sent in it, in
+    /// which case committing it didn't send a command either.
+    Committed {
+        data_committed: bool,
+    },
+
+    Aborted,
+}
+
+/// A session's transaction.
+#[derive(Debug)]
+struct Transaction {
+    state: TransactionState,
+    options: Option<TransactionOptions>,
+    pinned_mongos: Option<StreamAddress>,
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Self {
+            state: TransactionState::None,
+            options: None,
+            pinned_mongos: None,
+        }
+    }
+}
+
+/// Creates an error for a transaction operation that isn't valid in the transaction's state.
+fn transaction_error(message: &str) -> Error {
+    ErrorKind::Transaction {
+        message: message.to_string(),
+    }
+    .into()
+}
+
+/// Whether it's unknown if a transaction was committed after committing it failed with `error`.
+fn is_unknown_commit_result(error: &Error) -> bool {
+    if error.is_network_error() || error.is_write_retryable() || is_max_time_ms_expired(error) {
+        return true;
+    }
+
+    match error.kind.as_ref() {
+        ErrorKind::ServerSelectionError { .. } => true,
+        ErrorKind::WriteError(WriteFailure::WriteConcernError(ref error)) => {
+            !UNSATISFIABLE_WRITE_CONCERN_CODES.contains(&error.code)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `error` was returned because an operation exceeded its `maxTimeMS`.
+fn is_max_time_ms_expired(error: &Error) -> bool {
+    error.kind.code_and_message().map(|(code, _)| code) == Some(MAX_TIME_MS_EXPIRED_CODE)
+}
+
 /// Client side abstraction of a server session. These are pooled and may be associated with
 /// multiple `ClientSession`s over the course of their lifetime.
 #[derive(Debug)]
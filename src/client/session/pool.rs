@@ -3,7 +3,6 @@ use std::{collections::VecDeque, time::Duration};
 use tokio::sync::Mutex;
 
 use super::ServerSession;
-#[cfg(test)]
 use crate::bson::Document;
 
 #[derive(Debug)]
@@ -52,6 +51,16 @@ impl ServerSessionPool {
         }
     }
 
+    /// Removes all of the sessions from the pool, returning their ids.
+    pub(crate) async fn drain(&self) -> Vec<Document> {
+        self.pool
+            .lock()
+            .await
+            .drain(..)
+            .map(|session| session.id)
+            .collect()
+    }
+
     #[cfg(test)]
     pub(crate) async fn clear(&self) {
         self.pool.lock().await.clear();
@@ -1,13 +1,43 @@
-use std::{future::Future, time::Duration};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use futures::stream::StreamExt;
-use tokio::sync::RwLockReadGuard;
+use futures::{future::FutureExt, stream::StreamExt};
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
-    bson::{doc, Bson},
-    error::Result,
-    options::{Acknowledgment, FindOptions, InsertOneOptions, ReadPreference, WriteConcern},
-    test::{EventClient, TestClient, CLIENT_OPTIONS, LOCK},
+    bson::{doc, Bson, Document},
+    error::{ErrorKind, Result, TIMEOUT_ERROR, TRANSIENT_TRANSACTION_ERROR},
+    options::{
+        Acknowledgment,
+        AggregateOptions,
+        ClientOptions,
+        CountOptions,
+        FindOneOptions,
+        FindOptions,
+        InsertOneOptions,
+        ReadConcern,
+        ReadPreference,
+        SelectionCriteria,
+        SessionOptions,
+        StreamAddress,
+        WriteConcern,
+    },
+    test::{
+        EventClient,
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        TestClient,
+        CLIENT_OPTIONS,
+        LOCK,
+    },
+    Client,
     Collection,
     RUNTIME,
 };
@@ -38,7 +68,9 @@ macro_rules! db_op {
 macro_rules! collection_op {
     ($test_name:expr, $coll:ident, $body:expr) => {
         |client| async move {
-            let $coll = client.database($test_name).collection($test_name);
+            let $coll = client
+                .database($test_name)
+                .collection::<Document>($test_name);
             $body.await.unwrap();
         }
     };
@@ -287,7 +319,7 @@ async fn cluster_time_in_commands() {
     cluster_time_test("aggregate", |client| async move {
         client
             .database(function_name!())
-            .collection(function_name!())
+            .collection::<Document>(function_name!())
             .aggregate(vec![doc! { "$match": { "x": 1 } }], None)
             .await
     })
@@ -296,7 +328,7 @@ async fn cluster_time_in_commands() {
     cluster_time_test("find", |client| async move {
         client
             .database(function_name!())
-            .collection(function_name!())
+            .collection::<Document>(function_name!())
             .find(doc! {}, None)
             .await
     })
@@ -512,3 +544,496 @@ async fn find_and_getmore_share_session() {
         run_test(&client, &coll, read_pref).await;
     }
 }
+
+/// Prose tests 1 through 3 from the causal consistency spec.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn causal_consistency() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    let mut session = client.start_session(None).await.unwrap();
+    assert!(session.operation_time().is_none());
+
+    // The first read in a session has no preceding operations to wait for.
+    coll.find_one_with_session(None, None, &mut session)
+        .await
+        .unwrap();
+    let (started, _) = client.get_successful_command_execution("find");
+    assert!(started
+        .command
+        .get_document("readConcern")
+        .map(|read_concern| !read_concern.contains_key("afterClusterTime"))
+        .unwrap_or(true));
+
+    coll.insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    let (_, succeeded) = client.get_successful_command_execution("insert");
+    let operation_time = succeeded.reply.get_timestamp("operationTime").unwrap();
+    assert_eq!(session.operation_time(), Some(operation_time));
+
+    coll.find_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    let (started, _) = client.get_successful_command_execution("find");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("afterClusterTime"),
+        Ok(operation_time)
+    );
+}
+
+/// Prose test 4 from the causal consistency spec.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn causal_consistency_disabled() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    let options = SessionOptions::builder().causal_consistency(false).build();
+    let mut session = client.start_session(options).await.unwrap();
+
+    coll.insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    assert!(session.operation_time().is_some());
+
+    coll.find_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    let (started, _) = client.get_successful_command_execution("find");
+    assert!(started.command.get("readConcern").is_none());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn session_from_other_client() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let other_client = TestClient::new().await;
+    let mut session = other_client.start_session(None).await.unwrap();
+
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    let error = coll
+        .find_one_with_session(None, None, &mut session)
+        .await
+        .unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn session_deadline_bounds_server_selection() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let mut session = client.start_session(None).await.unwrap();
+    session.set_deadline(Instant::now() + Duration::from_millis(500));
+
+    // No server matches the criteria, so server selection only ends when the session's deadline
+    // passes, well before the server selection timeout.
+    let options = FindOneOptions::builder()
+        .selection_criteria(SelectionCriteria::Predicate(Arc::new(|_| false)))
+        .build();
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+    let start = Instant::now();
+    let error = coll
+        .find_one_with_session(None, options, &mut session)
+        .await
+        .unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(matches!(*error.kind, ErrorKind::Timeout { .. }));
+    assert!(error.contains_label(TIMEOUT_ERROR));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn snapshot_reads() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() || client.server_version_lt(5, 0) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let write_concern = WriteConcern::builder().w(Acknowledgment::Majority).build();
+    let insert_options = InsertOneOptions::builder()
+        .write_concern(write_concern)
+        .build();
+    coll.insert_one(doc! { "x": 1 }, insert_options.clone())
+        .await
+        .unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let count_options = CountOptions::builder()
+        .read_concern(ReadConcern::snapshot())
+        .build();
+    let count = coll
+        .count_documents_with_session(None, count_options.clone(), &mut session)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    let snapshot_time = session.snapshot_time().unwrap();
+    let (started, _) = client.get_successful_command_execution("aggregate");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert!(!read_concern.contains_key("atClusterTime"));
+    assert!(!read_concern.contains_key("afterClusterTime"));
+
+    // Later reads in the session keep reading from the snapshot of the first one.
+    coll.insert_one(doc! { "x": 2 }, insert_options)
+        .await
+        .unwrap();
+    let count = coll
+        .count_documents_with_session(None, count_options, &mut session)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    let (started, _) = client.get_successful_command_execution("aggregate");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("atClusterTime"),
+        Ok(snapshot_time)
+    );
+
+    let find_options = FindOptions::builder()
+        .read_concern(ReadConcern::snapshot())
+        .build();
+    let mut cursor = coll
+        .find_with_session(None, find_options, &mut session)
+        .await
+        .unwrap();
+    let docs: Vec<Document> = cursor
+        .stream(&mut session)
+        .map(|doc| doc.unwrap())
+        .collect()
+        .await;
+    assert_eq!(docs.len(), 1);
+
+    let (started, _) = client.get_successful_command_execution("find");
+    let read_concern = started.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("atClusterTime"),
+        Ok(snapshot_time)
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn snapshot_read_requires_explicit_session() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    // The reads are rejected before a server is selected, so no server needs to be reachable.
+    let options = ClientOptions::builder()
+        .hosts(vec![StreamAddress::parse("localhost:1").unwrap()])
+        .server_selection_timeout(Duration::from_millis(100))
+        .build();
+    let client = Client::with_options(options).unwrap();
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    let options = CountOptions::builder()
+        .read_concern(ReadConcern::snapshot())
+        .build();
+    let error = coll.count_documents(None, options).await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+
+    let options = FindOptions::builder()
+        .read_concern(ReadConcern::snapshot())
+        .build();
+    let error = coll.find(None, options).await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+
+    let options = AggregateOptions::builder()
+        .read_concern(ReadConcern::snapshot())
+        .build();
+    let error = coll.aggregate(None, options).await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn cursors_with_session() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut session = client.start_session(None).await.unwrap();
+    coll.insert_many_with_session(
+        vec![doc! { "x": 1 }, doc! { "x": 2 }, doc! { "x": 3 }],
+        None,
+        &mut session,
+    )
+    .await
+    .unwrap();
+    let (_, succeeded) = client.get_successful_command_execution("insert");
+    let operation_time = succeeded.reply.get_timestamp("operationTime").unwrap();
+
+    let options = FindOptions::builder().batch_size(1).build();
+    let mut cursor = coll
+        .find_with_session(None, options, &mut session)
+        .await
+        .unwrap();
+    let first = cursor.next(&mut session).await.unwrap().unwrap();
+    assert_eq!(first.get_i32("x"), Ok(1));
+    let rest: Vec<Document> = cursor
+        .stream(&mut session)
+        .map(|doc| doc.unwrap())
+        .collect()
+        .await;
+    assert_eq!(rest.len(), 2);
+    assert!(cursor.next(&mut session).await.is_none());
+
+    // The find reads causally after the insert, and its getMores run in the same session.
+    let find = client.get_command_started_events("find").pop().unwrap();
+    assert_eq!(find.command.get_document("lsid"), Ok(session.id()));
+    let read_concern = find.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("afterClusterTime"),
+        Ok(operation_time)
+    );
+    let get_mores = client.get_command_started_events("getMore");
+    assert!(!get_mores.is_empty());
+    for get_more in get_mores {
+        assert_eq!(get_more.command.get_document("lsid"), Ok(session.id()));
+    }
+
+    let pipeline = vec![doc! { "$match": { "x": { "$gt": 1 } } }];
+    let options = AggregateOptions::builder().batch_size(1).build();
+    let mut cursor = coll
+        .aggregate_with_session(pipeline, options, &mut session)
+        .await
+        .unwrap();
+    let docs: Vec<Document> = cursor
+        .stream(&mut session)
+        .map(|doc| doc.unwrap())
+        .collect()
+        .await;
+    assert_eq!(docs.len(), 2);
+    let (started, _) = client.get_successful_command_execution("aggregate");
+    assert_eq!(started.command.get_document("lsid"), Ok(session.id()));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn transaction_commands() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut session = client.start_session(None).await.unwrap();
+
+    let error = session.commit_transaction().await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::Transaction { .. }));
+
+    session.start_transaction(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    coll.insert_one_with_session(doc! { "x": 2 }, None, &mut session)
+        .await
+        .unwrap();
+
+    // The inserts aren't visible outside of the transaction until it's committed.
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 0);
+    session.commit_transaction().await.unwrap();
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 2);
+
+    let error = session.abort_transaction().await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::Transaction { .. }));
+
+    let inserts = client.get_command_started_events("insert");
+    assert_eq!(inserts.len(), 2);
+    assert_eq!(inserts[0].command.get_bool("startTransaction"), Ok(true));
+    assert!(inserts[1].command.get("startTransaction").is_none());
+
+    let commits = client.get_command_started_events("commitTransaction");
+    assert_eq!(commits.len(), 1);
+
+    let txn_number = inserts[0].command.get("txnNumber").unwrap();
+    for started in inserts.iter().chain(commits.iter()) {
+        assert_eq!(started.command.get_bool("autocommit"), Ok(false));
+        assert_eq!(started.command.get("txnNumber"), Some(txn_number));
+    }
+    assert!(inserts
+        .iter()
+        .all(|started| !started.command.contains_key("writeConcern")));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn with_transaction_retries_transient_errors() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 4) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = FailCommandOptions::builder()
+        .error_code(112)
+        .error_labels(vec![TRANSIENT_TRANSACTION_ERROR.to_string()])
+        .build();
+    let failpoint = FailPoint::fail_command(&["insert"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let mut session = client.start_session(None).await.unwrap();
+    session
+        .with_transaction(
+            |session| {
+                let coll = coll.clone();
+                let attempts = attempts.clone();
+                async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    coll.insert_one_with_session(doc! { "x": 1 }, None, session)
+                        .await?;
+                    Ok(())
+                }
+                .boxed()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        client.get_command_started_events("abortTransaction").len(),
+        1
+    );
+    assert_eq!(
+        client.get_command_started_events("commitTransaction").len(),
+        1
+    );
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn with_transaction_aborts_on_callback_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut session = client.start_session(None).await.unwrap();
+
+    macro_rules! run_failing_transaction {
+        () => {
+            session
+                .with_transaction(
+                    |session| {
+                        let coll = coll.clone();
+                        async move {
+                            coll.insert_one_with_session(doc! { "x": 1 }, None, session)
+                                .await?;
+                            Err::<(), _>(
+                                ErrorKind::OperationError {
+                                    message: "callback failed".to_string(),
+                                }
+                                .into(),
+                            )
+                        }
+                        .boxed()
+                    },
+                    None,
+                )
+                .await
+                .unwrap_err()
+        };
+    }
+
+    let error = run_failing_transaction!();
+    assert!(matches!(*error.kind, ErrorKind::OperationError { .. }));
+    assert!(!session.in_transaction());
+    assert_eq!(
+        client.get_command_started_events("abortTransaction").len(),
+        1
+    );
+    assert!(client
+        .get_command_started_events("commitTransaction")
+        .is_empty());
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 0);
+
+    // An error from aborting the transaction doesn't replace the callback's error.
+    if !client.supports_fail_command().await {
+        return;
+    }
+    let options = FailCommandOptions::builder().error_code(2).build();
+    let failpoint =
+        FailPoint::fail_command(&["abortTransaction"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    let error = run_failing_transaction!();
+    assert!(matches!(*error.kind, ErrorKind::OperationError { .. }));
+    assert!(!session.in_transaction());
+    assert_eq!(
+        client.get_command_started_events("abortTransaction").len(),
+        2
+    );
+}
@@ -0,0 +1,57 @@
+//! Sends the KMS requests that libmongocrypt makes to encrypt and decrypt the material of data
+//! keys.
+
+use std::time::Duration;
+
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::{encryption_error, mongocrypt::KmsCtx};
+use crate::{
+    cmap::options::StreamOptions,
+    error::Result,
+    options::{StreamAddress, TlsOptions},
+    runtime::AsyncStream,
+    RUNTIME,
+};
+
+/// The port that KMS endpoints are reached on unless the endpoint specifies one.
+const DEFAULT_KMS_PORT: u16 = 443;
+
+/// How long a KMS has to accept a request and send its whole reply once connected to.
+const KMS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends the request of a KMS context to its endpoint over TLS and feeds the reply back to it.
+pub(super) async fn send(kms: &mut KmsCtx<'_>) -> Result<()> {
+    let mut address = StreamAddress::parse(&kms.endpoint()?)?;
+    address.port.get_or_insert(DEFAULT_KMS_PORT);
+
+    let options = StreamOptions::builder()
+        .address(address)
+        .tls_options(Some(TlsOptions::default()))
+        .build();
+    let mut stream = AsyncStream::connect(options).await?;
+    RUNTIME
+        .timeout(KMS_TIMEOUT, exchange(&mut stream, kms))
+        .await
+        .map_err(|_| encryption_error("timed out waiting for the KMS to reply"))?
+}
+
+/// Writes the request of a KMS context to `stream` and reads the reply until the context has all
+/// of it.
+async fn exchange(stream: &mut AsyncStream, kms: &mut KmsCtx<'_>) -> Result<()> {
+    stream.write_all(&kms.message()?).await?;
+
+    let mut buffer = Vec::new();
+    while kms.bytes_needed() > 0 {
+        buffer.resize(kms.bytes_needed(), 0);
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(encryption_error(
+                "the KMS closed the connection before sending its whole reply",
+            ));
+        }
+        kms.feed(&buffer[..read])?;
+    }
+
+    Ok(())
+}
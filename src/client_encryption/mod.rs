@@ -0,0 +1,315 @@
+//! Contains the functionality for explicit client-side field level encryption, which encrypts
+//! values in the application so that the server only ever stores and returns their ciphertexts.
+//! See the documentation [here](https://docs.mongodb.com/manual/core/security-client-side-encryption/)
+//! for more information.
+//!
+//! Values are encrypted with data keys stored in a key vault collection, and the material of each
+//! data key is itself encrypted with a master key managed by a KMS provider. Encrypted values are
+//! sent to and returned from the server as BSON binary values of subtype 6.
+//!
+//! The encryption itself is done by [libmongocrypt](https://github.com/mongodb/libmongocrypt),
+//! the library the other MongoDB drivers use, so data keys and encrypted values are interchangeable
+//! with theirs. This module is only available with the `csfle` feature, which links against
+//! libmongocrypt 1.1 or newer; the library has to be installed separately.
+pub(crate) mod options;
+
+mod kms;
+mod mongocrypt;
+#[cfg(test)]
+mod test;
+
+use std::sync::Arc;
+
+use futures::stream::StreamExt;
+
+use self::{
+    mongocrypt::{Crypt, Ctx, State},
+    options::{
+        ClientEncryptionOptions,
+        DataKeyOptions,
+        EncryptOptions,
+        KmsProvider,
+        KmsProviders,
+        MasterKey,
+    },
+};
+use crate::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
+    error::{Error, ErrorKind, Result},
+    options::{Acknowledgment, CollectionOptions, ReadConcern, ReadConcernLevel, WriteConcern},
+    Client,
+    Collection,
+};
+
+/// The length of the master key of the local KMS provider.
+const LOCAL_MASTER_KEY_LENGTH: usize = 96;
+
+/// Encrypts and decrypts values explicitly and creates the data keys they're encrypted with.
+///
+/// ```rust
+/// # use mongodb::{
+/// #     bson::{doc, Bson, Document},
+/// #     client_encryption::ClientEncryption,
+/// #     error::Result,
+/// #     options::{
+/// #         ClientEncryptionOptions,
+/// #         EncryptOptions,
+/// #         EncryptionAlgorithm,
+/// #         KmsProvider,
+/// #         KmsProviders,
+/// #         LocalKmsProviderOptions,
+/// #     },
+/// #     Client,
+/// #     Namespace,
+/// # };
+/// #
+/// # async fn func(master_key: Vec<u8>) -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com").await?;
+/// let options = ClientEncryptionOptions::builder()
+///     .key_vault_namespace(Namespace {
+///         db: "keyvault".to_string(),
+///         coll: "datakeys".to_string(),
+///     })
+///     .kms_providers(
+///         KmsProviders::builder()
+///             .local(LocalKmsProviderOptions::builder().key(master_key).build())
+///             .build(),
+///     )
+///     .build();
+/// let client_encryption = ClientEncryption::new(&client, options)?;
+///
+/// let key_id = client_encryption
+///     .create_data_key(KmsProvider::Local, None)
+///     .await?;
+/// let encrypted = client_encryption
+///     .encrypt(
+///         "457-55-5462",
+///         EncryptOptions::builder()
+///             .algorithm(EncryptionAlgorithm::Deterministic)
+///             .key_id(key_id)
+///             .build(),
+///     )
+///     .await?;
+///
+/// let coll = client.database("medical").collection::<Document>("patients");
+/// coll.insert_one(doc! { "name": "Jon Doe", "ssn": encrypted.clone() }, None)
+///     .await?;
+/// let patient = coll.find_one(doc! { "ssn": encrypted }, None).await?;
+///
+/// if let Some(Bson::Binary(ssn)) = patient.and_then(|mut patient| patient.remove("ssn")) {
+///     assert_eq!(client_encryption.decrypt(ssn).await?, Bson::from("457-55-5462"));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClientEncryption {
+    key_vault: Collection,
+    crypt: Arc<Crypt>,
+}
+
+impl ClientEncryption {
+    /// Creates a `ClientEncryption` that stores its data keys in the key vault collection given
+    /// in `options`, which is accessed through `key_vault_client`.
+    pub fn new(key_vault_client: &Client, options: ClientEncryptionOptions) -> Result<Self> {
+        if let Some(ref local) = options.kms_providers.local {
+            if local.key.len() != LOCAL_MASTER_KEY_LENGTH {
+                return Err(ErrorKind::ArgumentError {
+                    message: format!(
+                        "the local master key must be {} bytes long, but got {}",
+                        LOCAL_MASTER_KEY_LENGTH,
+                        local.key.len()
+                    ),
+                }
+                .into());
+            }
+        }
+
+        // Data keys are read and written with majority concerns so that a key which was created
+        // can always be found afterwards.
+        let collection_options = CollectionOptions::builder()
+            .read_concern(ReadConcern::from(ReadConcernLevel::Majority))
+            .write_concern(WriteConcern {
+                w: Some(Acknowledgment::Majority),
+                ..Default::default()
+            })
+            .build();
+        let namespace = options.key_vault_namespace;
+
+        Ok(Self {
+            key_vault: key_vault_client
+                .database(&namespace.db)
+                .collection_with_options(&namespace.coll, collection_options),
+            crypt: Arc::new(Crypt::new(&kms_providers_document(&options.kms_providers))?),
+        })
+    }
+
+    /// Gets the key vault collection that stores the data keys.
+    pub fn key_vault(&self) -> &Collection {
+        &self.key_vault
+    }
+
+    /// Creates a new data key encrypted with the given KMS provider and stores it in the key vault.
+    /// The id of the key, a UUID, is returned.
+    ///
+    /// Data keys encrypted with AWS KMS need the customer master key to be given as the
+    /// `master_key` of the options.
+    pub async fn create_data_key(
+        &self,
+        kms_provider: KmsProvider,
+        options: impl Into<Option<DataKeyOptions>>,
+    ) -> Result<Binary> {
+        let options = options.into().unwrap_or_default();
+
+        let master_key = match (kms_provider, options.master_key) {
+            (KmsProvider::Local, None) => doc! { "provider": KmsProvider::Local.as_str() },
+            (KmsProvider::Aws, Some(master_key @ MasterKey::Aws(_))) => {
+                bson::to_document(&master_key)?
+            }
+            (KmsProvider::Aws, None) => {
+                return Err(ErrorKind::ArgumentError {
+                    message: "a master key must be given for data keys encrypted with AWS KMS"
+                        .to_string(),
+                }
+                .into())
+            }
+            (kms_provider, Some(_)) => {
+                return Err(ErrorKind::ArgumentError {
+                    message: format!(
+                        "the master key doesn't belong to the {} KMS provider",
+                        kms_provider.as_str()
+                    ),
+                }
+                .into())
+            }
+        };
+
+        let mut ctx = self.crypt.ctx()?;
+        ctx.setopt_key_encryption_key(&master_key)?;
+        for key_alt_name in options.key_alt_names.unwrap_or_default() {
+            ctx.setopt_key_alt_name(&key_alt_name)?;
+        }
+        ctx.datakey_init()?;
+        let key = self.run_state_machine(ctx).await?;
+
+        let id = match key.get("_id") {
+            Some(Bson::Binary(id)) => id.clone(),
+            _ => return Err(encryption_error("the created data key has no valid id")),
+        };
+        self.key_vault.insert_one(key, None).await?;
+
+        Ok(id)
+    }
+
+    /// Encrypts a value with the data key given in `options`. The result is a BSON binary value of
+    /// subtype 6 that can be stored in place of the value.
+    ///
+    /// `null`, `undefined`, `MinKey` and `MaxKey` values can't be encrypted.
+    pub async fn encrypt(&self, value: impl Into<Bson>, options: EncryptOptions) -> Result<Binary> {
+        let mut ctx = self.crypt.ctx()?;
+        match (options.key_id, options.key_alt_name) {
+            (Some(key_id), None) => ctx.setopt_key_id(&key_id.bytes)?,
+            (None, Some(key_alt_name)) => ctx.setopt_key_alt_name(&key_alt_name)?,
+            _ => {
+                return Err(ErrorKind::ArgumentError {
+                    message: "exactly one of key_id and key_alt_name must be specified".to_string(),
+                }
+                .into())
+            }
+        }
+        ctx.setopt_algorithm(options.algorithm.as_str())?;
+        ctx.explicit_encrypt_init(&doc! { "v": value.into() })?;
+
+        match self.run_state_machine(ctx).await?.remove("v") {
+            Some(Bson::Binary(encrypted)) => Ok(encrypted),
+            _ => Err(encryption_error(
+                "libmongocrypt returned an invalid encrypted value",
+            )),
+        }
+    }
+
+    /// Decrypts a value encrypted by
+    /// [`ClientEncryption::encrypt`](struct.ClientEncryption.html#method.encrypt), looking up the
+    /// data key it was encrypted with in the key vault.
+    pub async fn decrypt(&self, value: Binary) -> Result<Bson> {
+        if value.subtype != BinarySubtype::Encrypted {
+            return Err(ErrorKind::ArgumentError {
+                message: "only binary values of subtype 6 can be decrypted".to_string(),
+            }
+            .into());
+        }
+
+        let mut ctx = self.crypt.ctx()?;
+        ctx.explicit_decrypt_init(&doc! { "v": value })?;
+
+        self.run_state_machine(ctx)
+            .await?
+            .remove("v")
+            .ok_or_else(|| encryption_error("libmongocrypt returned an invalid decrypted value"))
+    }
+
+    /// Runs a context until it's done, fetching the data keys and sending the KMS requests it asks
+    /// for, and returns the document it's finalized with.
+    async fn run_state_machine(&self, mut ctx: Ctx<'_>) -> Result<Document> {
+        loop {
+            match ctx.state() {
+                State::NeedMongoKeys => {
+                    let filter = ctx.mongo_op()?;
+                    let mut keys = self.key_vault.find(filter, None).await?;
+                    while let Some(key) = keys.next().await {
+                        ctx.mongo_feed(&key?)?;
+                    }
+                    ctx.mongo_done()?;
+                }
+                State::NeedKms => {
+                    while let Some(mut kms_ctx) = ctx.next_kms_ctx() {
+                        kms::send(&mut kms_ctx).await?;
+                    }
+                    ctx.kms_done()?;
+                }
+                State::Ready => return ctx.finalize(),
+                State::Error => return Err(ctx.error()),
+                state => {
+                    return Err(encryption_error(format!(
+                        "libmongocrypt entered the unsupported state {:?}",
+                        state
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Gets the credentials of the KMS providers in the format libmongocrypt expects.
+fn kms_providers_document(kms_providers: &KmsProviders) -> Document {
+    let mut document = Document::new();
+
+    if let Some(ref local) = kms_providers.local {
+        document.insert(
+            KmsProvider::Local.as_str(),
+            doc! {
+                "key": Binary { subtype: BinarySubtype::Generic, bytes: local.key.clone() },
+            },
+        );
+    }
+
+    if let Some(ref aws) = kms_providers.aws {
+        let mut credentials = doc! {
+            "accessKeyId": aws.access_key_id.as_str(),
+            "secretAccessKey": aws.secret_access_key.as_str(),
+        };
+        if let Some(ref session_token) = aws.session_token {
+            credentials.insert("sessionToken", session_token.as_str());
+        }
+        document.insert(KmsProvider::Aws.as_str(), credentials);
+    }
+
+    document
+}
+
+fn encryption_error(message: impl Into<String>) -> Error {
+    ErrorKind::EncryptionError {
+        message: message.into(),
+    }
+    .into()
+}
@@ -0,0 +1,540 @@
+//! A minimal safe wrapper around the parts of the libmongocrypt C API used for explicit encryption.
+//!
+//! libmongocrypt does all of the cryptography and decides which data keys and KMS requests are
+//! needed; the driver only runs the state machine of each context, fetching data keys from the key
+//! vault and sending KMS requests when libmongocrypt asks for them.
+
+use std::{
+    ffi::CStr,
+    marker::PhantomData,
+    os::raw::{c_char, c_int},
+    ptr,
+    slice,
+};
+
+use super::encryption_error;
+use crate::{
+    bson::{doc, Document},
+    error::{Error, ErrorKind, Result},
+};
+
+#[allow(non_camel_case_types)]
+mod sys {
+    use std::os::raw::{c_char, c_int};
+
+    #[repr(C)]
+    pub(super) struct mongocrypt_t {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub(super) struct mongocrypt_ctx_t {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub(super) struct mongocrypt_kms_ctx_t {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub(super) struct mongocrypt_binary_t {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub(super) struct mongocrypt_status_t {
+        _private: [u8; 0],
+    }
+
+    #[link(name = "mongocrypt")]
+    extern "C" {
+        pub(super) fn mongocrypt_binary_new() -> *mut mongocrypt_binary_t;
+        pub(super) fn mongocrypt_binary_new_from_data(
+            data: *mut u8,
+            len: u32,
+        ) -> *mut mongocrypt_binary_t;
+        pub(super) fn mongocrypt_binary_data(binary: *const mongocrypt_binary_t) -> *mut u8;
+        pub(super) fn mongocrypt_binary_len(binary: *const mongocrypt_binary_t) -> u32;
+        pub(super) fn mongocrypt_binary_destroy(binary: *mut mongocrypt_binary_t);
+
+        pub(super) fn mongocrypt_status_new() -> *mut mongocrypt_status_t;
+        pub(super) fn mongocrypt_status_message(
+            status: *mut mongocrypt_status_t,
+            len: *mut u32,
+        ) -> *const c_char;
+        pub(super) fn mongocrypt_status_destroy(status: *mut mongocrypt_status_t);
+
+        pub(super) fn mongocrypt_new() -> *mut mongocrypt_t;
+        pub(super) fn mongocrypt_setopt_kms_providers(
+            crypt: *mut mongocrypt_t,
+            kms_providers: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_init(crypt: *mut mongocrypt_t) -> bool;
+        pub(super) fn mongocrypt_status(
+            crypt: *mut mongocrypt_t,
+            status: *mut mongocrypt_status_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_destroy(crypt: *mut mongocrypt_t);
+
+        pub(super) fn mongocrypt_ctx_new(crypt: *mut mongocrypt_t) -> *mut mongocrypt_ctx_t;
+        pub(super) fn mongocrypt_ctx_status(
+            ctx: *mut mongocrypt_ctx_t,
+            status: *mut mongocrypt_status_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_setopt_key_id(
+            ctx: *mut mongocrypt_ctx_t,
+            key_id: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_setopt_key_alt_name(
+            ctx: *mut mongocrypt_ctx_t,
+            key_alt_name: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_setopt_key_encryption_key(
+            ctx: *mut mongocrypt_ctx_t,
+            key_encryption_key: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_setopt_algorithm(
+            ctx: *mut mongocrypt_ctx_t,
+            algorithm: *const c_char,
+            len: c_int,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_datakey_init(ctx: *mut mongocrypt_ctx_t) -> bool;
+        pub(super) fn mongocrypt_ctx_explicit_encrypt_init(
+            ctx: *mut mongocrypt_ctx_t,
+            msg: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_explicit_decrypt_init(
+            ctx: *mut mongocrypt_ctx_t,
+            msg: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_state(ctx: *mut mongocrypt_ctx_t) -> c_int;
+        pub(super) fn mongocrypt_ctx_mongo_op(
+            ctx: *mut mongocrypt_ctx_t,
+            op: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_mongo_feed(
+            ctx: *mut mongocrypt_ctx_t,
+            reply: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_mongo_done(ctx: *mut mongocrypt_ctx_t) -> bool;
+        pub(super) fn mongocrypt_ctx_next_kms_ctx(
+            ctx: *mut mongocrypt_ctx_t,
+        ) -> *mut mongocrypt_kms_ctx_t;
+        pub(super) fn mongocrypt_ctx_kms_done(ctx: *mut mongocrypt_ctx_t) -> bool;
+        pub(super) fn mongocrypt_ctx_finalize(
+            ctx: *mut mongocrypt_ctx_t,
+            out: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_ctx_destroy(ctx: *mut mongocrypt_ctx_t);
+
+        pub(super) fn mongocrypt_kms_ctx_message(
+            kms: *mut mongocrypt_kms_ctx_t,
+            msg: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_kms_ctx_endpoint(
+            kms: *mut mongocrypt_kms_ctx_t,
+            endpoint: *mut *const c_char,
+        ) -> bool;
+        pub(super) fn mongocrypt_kms_ctx_bytes_needed(kms: *mut mongocrypt_kms_ctx_t) -> u32;
+        pub(super) fn mongocrypt_kms_ctx_feed(
+            kms: *mut mongocrypt_kms_ctx_t,
+            bytes: *mut mongocrypt_binary_t,
+        ) -> bool;
+        pub(super) fn mongocrypt_kms_ctx_status(
+            kms: *mut mongocrypt_kms_ctx_t,
+            status: *mut mongocrypt_status_t,
+        ) -> bool;
+    }
+}
+
+/// The state of a context, which determines what the driver has to do next.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum State {
+    /// The context failed; its status describes the error.
+    Error,
+
+    /// The context needs information about the collection a command is run on.
+    NeedMongoCollInfo,
+
+    /// The context needs a command to be marked up by query analysis.
+    NeedMongoMarkings,
+
+    /// The context needs the data keys matching the filter returned by `Ctx::mongo_op`.
+    NeedMongoKeys,
+
+    /// The context needs the requests returned by `Ctx::next_kms_ctx` to be sent to a KMS.
+    NeedKms,
+
+    /// The context is ready to be finalized.
+    Ready,
+
+    /// The context has been finalized.
+    Done,
+
+    /// The context needs KMS credentials that weren't configured up front.
+    NeedKmsCredentials,
+}
+
+impl State {
+    fn from_raw(state: c_int) -> Self {
+        match state {
+            1 => State::NeedMongoCollInfo,
+            2 => State::NeedMongoMarkings,
+            3 => State::NeedMongoKeys,
+            4 => State::NeedKms,
+            5 => State::Ready,
+            6 => State::Done,
+            7 => State::NeedKmsCredentials,
+            _ => State::Error,
+        }
+    }
+}
+
+/// A libmongocrypt binary, which either views bytes owned by Rust or bytes owned by libmongocrypt.
+struct Binary<'a> {
+    raw: *mut sys::mongocrypt_binary_t,
+    _bytes: PhantomData<&'a [u8]>,
+}
+
+impl Binary<'static> {
+    /// Creates an empty binary for libmongocrypt to write into.
+    fn new() -> Result<Self> {
+        Self::from_raw(unsafe { sys::mongocrypt_binary_new() })
+    }
+}
+
+impl<'a> Binary<'a> {
+    /// Creates a binary that views `bytes`. libmongocrypt copies the bytes it needs to keep.
+    fn from_slice(bytes: &'a [u8]) -> Result<Self> {
+        // libmongocrypt never writes to the bytes of a binary that it was given.
+        Self::from_raw(unsafe {
+            sys::mongocrypt_binary_new_from_data(bytes.as_ptr() as *mut u8, bytes.len() as u32)
+        })
+    }
+
+    fn from_raw(raw: *mut sys::mongocrypt_binary_t) -> Result<Self> {
+        if raw.is_null() {
+            return Err(encryption_error(
+                "libmongocrypt failed to allocate a binary",
+            ));
+        }
+
+        Ok(Self {
+            raw,
+            _bytes: PhantomData,
+        })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe {
+            let data = sys::mongocrypt_binary_data(self.raw);
+            if data.is_null() {
+                return &[];
+            }
+            slice::from_raw_parts(data, sys::mongocrypt_binary_len(self.raw) as usize)
+        }
+    }
+
+    fn to_document(&self) -> Result<Document> {
+        Ok(Document::from_reader(&mut self.as_slice())?)
+    }
+}
+
+impl Drop for Binary<'_> {
+    fn drop(&mut self) {
+        unsafe { sys::mongocrypt_binary_destroy(self.raw) }
+    }
+}
+
+/// Serializes a document to bytes that can be passed to libmongocrypt.
+fn document_bytes(document: &Document) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    document.to_writer(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// The status of a libmongocrypt handle, which holds the error of the last failed call.
+struct Status(*mut sys::mongocrypt_status_t);
+
+impl Status {
+    fn new() -> Self {
+        Self(unsafe { sys::mongocrypt_status_new() })
+    }
+
+    fn to_error(&self) -> Error {
+        let message = unsafe {
+            let message = sys::mongocrypt_status_message(self.0, ptr::null_mut());
+            if message.is_null() {
+                "unknown libmongocrypt error".to_string()
+            } else {
+                CStr::from_ptr(message).to_string_lossy().into_owned()
+            }
+        };
+
+        ErrorKind::EncryptionError { message }.into()
+    }
+}
+
+impl Drop for Status {
+    fn drop(&mut self) {
+        unsafe { sys::mongocrypt_status_destroy(self.0) }
+    }
+}
+
+/// A libmongocrypt handle, which holds the KMS provider credentials and caches decrypted data keys.
+#[derive(Debug)]
+pub(super) struct Crypt {
+    raw: *mut sys::mongocrypt_t,
+}
+
+// SAFETY: libmongocrypt documents that a `mongocrypt_t` is thread-safe once `mongocrypt_init` has
+// succeeded, which `Crypt::new` ensures before returning a handle. Its contexts are not
+// thread-safe, which `Ctx` accounts for by not being `Sync`.
+unsafe impl Send for Crypt {}
+// SAFETY: see the `Send` impl above.
+unsafe impl Sync for Crypt {}
+
+impl Crypt {
+    /// Creates a handle that uses the given KMS provider credentials, which are in the format
+    /// expected by `mongocrypt_setopt_kms_providers`.
+    pub(super) fn new(kms_providers: &Document) -> Result<Self> {
+        let raw = unsafe { sys::mongocrypt_new() };
+        if raw.is_null() {
+            return Err(encryption_error(
+                "libmongocrypt failed to allocate a handle",
+            ));
+        }
+        let crypt = Self { raw };
+
+        let kms_providers = document_bytes(kms_providers)?;
+        let kms_providers = Binary::from_slice(&kms_providers)?;
+        crypt
+            .check(unsafe { sys::mongocrypt_setopt_kms_providers(crypt.raw, kms_providers.raw) })?;
+        crypt.check(unsafe { sys::mongocrypt_init(crypt.raw) })?;
+
+        Ok(crypt)
+    }
+
+    /// Creates a new context to create a data key or to encrypt or decrypt a value with.
+    pub(super) fn ctx(&self) -> Result<Ctx<'_>> {
+        let raw = unsafe { sys::mongocrypt_ctx_new(self.raw) };
+        if raw.is_null() {
+            return Err(self.error());
+        }
+
+        Ok(Ctx {
+            raw,
+            _crypt: PhantomData,
+        })
+    }
+
+    fn check(&self, ok: bool) -> Result<()> {
+        if ok {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn error(&self) -> Error {
+        let status = Status::new();
+        unsafe { sys::mongocrypt_status(self.raw, status.0) };
+        status.to_error()
+    }
+}
+
+impl Drop for Crypt {
+    fn drop(&mut self) {
+        unsafe { sys::mongocrypt_destroy(self.raw) }
+    }
+}
+
+/// A libmongocrypt context, which runs a single data key creation, encryption or decryption.
+pub(super) struct Ctx<'a> {
+    raw: *mut sys::mongocrypt_ctx_t,
+    _crypt: PhantomData<&'a Crypt>,
+}
+
+// SAFETY: libmongocrypt documents that a `mongocrypt_ctx_t` is not thread-safe, but it may be moved
+// between threads as long as only one thread uses it at a time, which holds because `Ctx` is not
+// `Sync`.
+unsafe impl Send for Ctx<'_> {}
+
+impl<'a> Ctx<'a> {
+    /// Sets the id of the data key to encrypt with.
+    pub(super) fn setopt_key_id(&mut self, key_id: &[u8]) -> Result<()> {
+        let key_id = Binary::from_slice(key_id)?;
+        self.check(unsafe { sys::mongocrypt_ctx_setopt_key_id(self.raw, key_id.raw) })
+    }
+
+    /// Sets the alternate name of the data key to encrypt with, or adds an alternate name to the
+    /// data key being created.
+    pub(super) fn setopt_key_alt_name(&mut self, key_alt_name: &str) -> Result<()> {
+        let key_alt_name = document_bytes(&doc! { "keyAltName": key_alt_name })?;
+        let key_alt_name = Binary::from_slice(&key_alt_name)?;
+        self.check(unsafe { sys::mongocrypt_ctx_setopt_key_alt_name(self.raw, key_alt_name.raw) })
+    }
+
+    /// Sets the master key that the data key being created is encrypted with.
+    pub(super) fn setopt_key_encryption_key(&mut self, master_key: &Document) -> Result<()> {
+        let master_key = document_bytes(master_key)?;
+        let master_key = Binary::from_slice(&master_key)?;
+        self.check(unsafe {
+            sys::mongocrypt_ctx_setopt_key_encryption_key(self.raw, master_key.raw)
+        })
+    }
+
+    /// Sets the name of the algorithm to encrypt with.
+    pub(super) fn setopt_algorithm(&mut self, algorithm: &str) -> Result<()> {
+        self.check(unsafe {
+            sys::mongocrypt_ctx_setopt_algorithm(
+                self.raw,
+                algorithm.as_ptr() as *const c_char,
+                algorithm.len() as c_int,
+            )
+        })
+    }
+
+    /// Initializes the context to create a data key.
+    pub(super) fn datakey_init(&mut self) -> Result<()> {
+        self.check(unsafe { sys::mongocrypt_ctx_datakey_init(self.raw) })
+    }
+
+    /// Initializes the context to encrypt the `v` field of `value`.
+    pub(super) fn explicit_encrypt_init(&mut self, value: &Document) -> Result<()> {
+        let value = document_bytes(value)?;
+        let value = Binary::from_slice(&value)?;
+        self.check(unsafe { sys::mongocrypt_ctx_explicit_encrypt_init(self.raw, value.raw) })
+    }
+
+    /// Initializes the context to decrypt the `v` field of `value`.
+    pub(super) fn explicit_decrypt_init(&mut self, value: &Document) -> Result<()> {
+        let value = document_bytes(value)?;
+        let value = Binary::from_slice(&value)?;
+        self.check(unsafe { sys::mongocrypt_ctx_explicit_decrypt_init(self.raw, value.raw) })
+    }
+
+    pub(super) fn state(&self) -> State {
+        State::from_raw(unsafe { sys::mongocrypt_ctx_state(self.raw) })
+    }
+
+    /// Gets the filter or command that has to be run against the server in the current state.
+    pub(super) fn mongo_op(&mut self) -> Result<Document> {
+        let op = Binary::new()?;
+        self.check(unsafe { sys::mongocrypt_ctx_mongo_op(self.raw, op.raw) })?;
+        op.to_document()
+    }
+
+    /// Feeds one of the documents returned by the server for `mongo_op`.
+    pub(super) fn mongo_feed(&mut self, reply: &Document) -> Result<()> {
+        let reply = document_bytes(reply)?;
+        let reply = Binary::from_slice(&reply)?;
+        self.check(unsafe { sys::mongocrypt_ctx_mongo_feed(self.raw, reply.raw) })
+    }
+
+    /// Signals that all of the documents returned by the server have been fed.
+    pub(super) fn mongo_done(&mut self) -> Result<()> {
+        self.check(unsafe { sys::mongocrypt_ctx_mongo_done(self.raw) })
+    }
+
+    /// Gets the next KMS request that has to be sent, if any.
+    pub(super) fn next_kms_ctx(&mut self) -> Option<KmsCtx<'_>> {
+        let raw = unsafe { sys::mongocrypt_ctx_next_kms_ctx(self.raw) };
+        if raw.is_null() {
+            return None;
+        }
+
+        Some(KmsCtx {
+            raw,
+            _ctx: PhantomData,
+        })
+    }
+
+    /// Signals that the replies to all of the KMS requests have been fed.
+    pub(super) fn kms_done(&mut self) -> Result<()> {
+        self.check(unsafe { sys::mongocrypt_ctx_kms_done(self.raw) })
+    }
+
+    /// Finishes the context, returning the created data key or the encrypted or decrypted value.
+    pub(super) fn finalize(&mut self) -> Result<Document> {
+        let out = Binary::new()?;
+        self.check(unsafe { sys::mongocrypt_ctx_finalize(self.raw, out.raw) })?;
+        out.to_document()
+    }
+
+    /// Gets the error that the context failed with.
+    pub(super) fn error(&self) -> Error {
+        let status = Status::new();
+        unsafe { sys::mongocrypt_ctx_status(self.raw, status.0) };
+        status.to_error()
+    }
+
+    fn check(&self, ok: bool) -> Result<()> {
+        if ok {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+}
+
+impl Drop for Ctx<'_> {
+    fn drop(&mut self) {
+        unsafe { sys::mongocrypt_ctx_destroy(self.raw) }
+    }
+}
+
+/// A request that has to be sent to a KMS, which is owned by the context that returned it.
+pub(super) struct KmsCtx<'a> {
+    raw: *mut sys::mongocrypt_kms_ctx_t,
+    _ctx: PhantomData<&'a mut ()>,
+}
+
+// SAFETY: a `mongocrypt_kms_ctx_t` is owned by its context and follows the same rules as `Ctx`.
+unsafe impl Send for KmsCtx<'_> {}
+
+impl KmsCtx<'_> {
+    /// Gets the HTTP request to send.
+    pub(super) fn message(&self) -> Result<Vec<u8>> {
+        let message = Binary::new()?;
+        self.check(unsafe { sys::mongocrypt_kms_ctx_message(self.raw, message.raw) })?;
+        Ok(message.as_slice().to_vec())
+    }
+
+    /// Gets the host, and optionally the port, to send the request to.
+    pub(super) fn endpoint(&self) -> Result<String> {
+        let mut endpoint = ptr::null();
+        self.check(unsafe { sys::mongocrypt_kms_ctx_endpoint(self.raw, &mut endpoint) })?;
+        if endpoint.is_null() {
+            return Err(encryption_error(
+                "libmongocrypt didn't provide a KMS endpoint",
+            ));
+        }
+        Ok(unsafe { CStr::from_ptr(endpoint) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Gets the number of bytes of the reply that still have to be fed, which is zero once the
+    /// whole reply has been fed.
+    pub(super) fn bytes_needed(&self) -> usize {
+        unsafe { sys::mongocrypt_kms_ctx_bytes_needed(self.raw) as usize }
+    }
+
+    /// Feeds bytes of the reply to the request.
+    pub(super) fn feed(&mut self, bytes: &[u8]) -> Result<()> {
+        let bytes = Binary::from_slice(bytes)?;
+        self.check(unsafe { sys::mongocrypt_kms_ctx_feed(self.raw, bytes.raw) })
+    }
+
+    fn check(&self, ok: bool) -> Result<()> {
+        if ok {
+            return Ok(());
+        }
+
+        let status = Status::new();
+        unsafe { sys::mongocrypt_kms_ctx_status(self.raw, status.0) };
+        Err(status.to_error())
+    }
+}
@@ -0,0 +1,172 @@
+//! Contains options for explicit client-side field level encryption.
+
+use derivative::Derivative;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{bson::Binary, Namespace};
+
+/// These are the valid options for creating a
+/// [`ClientEncryption`](../client_encryption/struct.ClientEncryption.html).
+#[derive(Clone, Debug, TypedBuilder)]
+#[non_exhaustive]
+pub struct ClientEncryptionOptions {
+    /// The namespace of the collection that stores the data keys, e.g. `keyvault.datakeys`. The
+    /// collection should have a unique index on `keyAltNames` so that key alternate names can't be
+    /// shared between keys.
+    pub key_vault_namespace: Namespace,
+
+    /// The credentials for each KMS provider that data keys are encrypted with.
+    pub kms_providers: KmsProviders,
+}
+
+/// The credentials for the KMS providers that encrypt and decrypt the data keys in the key vault.
+/// A data key can only be created or used if the provider it was encrypted with is configured.
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct KmsProviders {
+    /// The master key used for data keys created with `KmsProvider::Local`.
+    #[builder(default)]
+    pub local: Option<LocalKmsProviderOptions>,
+
+    /// The AWS credentials used for data keys created with `KmsProvider::Aws`.
+    #[builder(default)]
+    pub aws: Option<AwsKmsProviderOptions>,
+}
+
+/// The options for the `local` KMS provider, which encrypts data keys with a master key held by
+/// the application.
+#[derive(Clone, Derivative, TypedBuilder)]
+#[derivative(Debug)]
+#[non_exhaustive]
+pub struct LocalKmsProviderOptions {
+    /// The 96-byte master key.
+    #[derivative(Debug = "ignore")]
+    pub key: Vec<u8>,
+}
+
+/// The options for the `aws` KMS provider, which encrypts data keys with a customer master key
+/// stored in AWS KMS.
+#[derive(Clone, Derivative, TypedBuilder)]
+#[derivative(Debug)]
+#[non_exhaustive]
+pub struct AwsKmsProviderOptions {
+    /// The AWS access key id.
+    pub access_key_id: String,
+
+    /// The AWS secret access key.
+    #[derivative(Debug = "ignore")]
+    pub secret_access_key: String,
+
+    /// The session token to send along with temporary credentials.
+    #[builder(default)]
+    #[derivative(Debug = "ignore")]
+    pub session_token: Option<String>,
+}
+
+/// The KMS providers that data keys can be encrypted with.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum KmsProvider {
+    /// A master key held by the application.
+    Local,
+
+    /// A customer master key stored in AWS KMS.
+    Aws,
+}
+
+impl KmsProvider {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            KmsProvider::Local => "local",
+            KmsProvider::Aws => "aws",
+        }
+    }
+}
+
+/// These are the valid options for creating a data key with `ClientEncryption`'s
+/// [`create_data_key`](../client_encryption/struct.ClientEncryption.html#method.create_data_key).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct DataKeyOptions {
+    /// The master key that the data key is encrypted with. This is required for AWS and must not
+    /// be set for the local provider.
+    #[builder(default)]
+    pub master_key: Option<MasterKey>,
+
+    /// Alternate names that the data key can be referred to by instead of its id when encrypting.
+    #[builder(default)]
+    pub key_alt_names: Option<Vec<String>>,
+}
+
+/// The master key of a KMS provider that a data key is encrypted with.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "provider", rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum MasterKey {
+    /// A customer master key stored in AWS KMS.
+    Aws(AwsMasterKey),
+}
+
+/// A customer master key stored in AWS KMS.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AwsMasterKey {
+    /// The AWS region the key is stored in, e.g. `us-east-1`.
+    pub region: String,
+
+    /// The Amazon Resource Name (ARN) of the key.
+    pub key: String,
+
+    /// The host, and optionally the port, of the KMS endpoint to use instead of the default one
+    /// for the region.
+    #[builder(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// The algorithm used to encrypt a value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum EncryptionAlgorithm {
+    /// Encrypting the same value with the same key always produces the same ciphertext, so
+    /// encrypted fields can be queried for equality. Doubles, decimals, booleans, documents,
+    /// arrays and code with scope can't be encrypted deterministically.
+    Deterministic,
+
+    /// Encrypting the same value twice produces different ciphertexts.
+    Random,
+}
+
+impl EncryptionAlgorithm {
+    /// Gets the name libmongocrypt knows the algorithm by.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            EncryptionAlgorithm::Deterministic => "AEAD_AES_256_CBC_HMAC_SHA_512-Deterministic",
+            EncryptionAlgorithm::Random => "AEAD_AES_256_CBC_HMAC_SHA_512-Random",
+        }
+    }
+}
+
+/// These are the valid options for encrypting a value with
+/// [`ClientEncryption::encrypt`](../client_encryption/struct.ClientEncryption.html#method.encrypt).
+/// Exactly one of `key_id` and `key_alt_name` must be set.
+#[derive(Clone, Debug, TypedBuilder)]
+#[non_exhaustive]
+pub struct EncryptOptions {
+    /// The algorithm to encrypt the value with.
+    pub algorithm: EncryptionAlgorithm,
+
+    /// The id of the data key to encrypt the value with, as returned by
+    /// [`ClientEncryption::create_data_key`][create_data_key].
+    ///
+    /// [create_data_key]: ../client_encryption/struct.ClientEncryption.html#method.create_data_key
+    #[builder(default)]
+    pub key_id: Option<Binary>,
+
+    /// An alternate name of the data key to encrypt the value with.
+    #[builder(default)]
+    pub key_alt_name: Option<String>,
+}
@@ -0,0 +1,116 @@
+use super::{
+    kms_providers_document,
+    mongocrypt::{Crypt, Ctx, State},
+};
+use crate::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
+    error::ErrorKind,
+    options::{EncryptionAlgorithm, KmsProviders, LocalKmsProviderOptions},
+};
+
+fn local_crypt() -> Crypt {
+    let kms_providers = KmsProviders::builder()
+        .local(LocalKmsProviderOptions::builder().key(vec![3; 96]).build())
+        .build();
+    Crypt::new(&kms_providers_document(&kms_providers)).unwrap()
+}
+
+/// Runs a context to completion, feeding it `key` whenever it asks for data keys.
+fn run(mut ctx: Ctx, key: &Document) -> Document {
+    loop {
+        match ctx.state() {
+            State::NeedMongoKeys => {
+                ctx.mongo_op().unwrap();
+                ctx.mongo_feed(key).unwrap();
+                ctx.mongo_done().unwrap();
+            }
+            State::Ready => return ctx.finalize().unwrap(),
+            State::Error => panic!("context failed: {}", ctx.error()),
+            state => panic!("unexpected state {:?}", state),
+        }
+    }
+}
+
+fn encrypt(crypt: &Crypt, key: &Document, value: Bson, algorithm: EncryptionAlgorithm) -> Binary {
+    let key_id = match key.get("_id") {
+        Some(Bson::Binary(id)) => id.bytes.clone(),
+        other => panic!("expected data key id, got {:?}", other),
+    };
+
+    let mut ctx = crypt.ctx().unwrap();
+    ctx.setopt_key_id(&key_id).unwrap();
+    ctx.setopt_algorithm(algorithm.as_str()).unwrap();
+    ctx.explicit_encrypt_init(&doc! { "v": value }).unwrap();
+
+    match run(ctx, key).remove("v") {
+        Some(Bson::Binary(encrypted)) => encrypted,
+        other => panic!("expected encrypted value, got {:?}", other),
+    }
+}
+
+fn decrypt(crypt: &Crypt, key: &Document, value: Binary) -> Bson {
+    let mut ctx = crypt.ctx().unwrap();
+    ctx.explicit_decrypt_init(&doc! { "v": value }).unwrap();
+    run(ctx, key).remove("v").unwrap()
+}
+
+#[test]
+fn local_round_trip() {
+    let crypt = local_crypt();
+
+    let mut ctx = crypt.ctx().unwrap();
+    ctx.setopt_key_encryption_key(&doc! { "provider": "local" })
+        .unwrap();
+    ctx.setopt_key_alt_name("ssn").unwrap();
+    ctx.datakey_init().unwrap();
+    assert_eq!(ctx.state(), State::Ready);
+    let key = ctx.finalize().unwrap();
+    assert_eq!(
+        key.get_document("masterKey"),
+        Ok(&doc! { "provider": "local" })
+    );
+
+    let deterministic = encrypt(
+        &crypt,
+        &key,
+        Bson::from("457-55-5462"),
+        EncryptionAlgorithm::Deterministic,
+    );
+    assert_eq!(deterministic.subtype, BinarySubtype::Encrypted);
+    assert_eq!(
+        encrypt(
+            &crypt,
+            &key,
+            Bson::from("457-55-5462"),
+            EncryptionAlgorithm::Deterministic
+        ),
+        deterministic
+    );
+    assert_eq!(
+        decrypt(&crypt, &key, deterministic),
+        Bson::from("457-55-5462")
+    );
+
+    let value = Bson::Document(doc! { "a": 1.5, "b": [true] });
+    let random = encrypt(&crypt, &key, value.clone(), EncryptionAlgorithm::Random);
+    assert_ne!(
+        encrypt(&crypt, &key, value.clone(), EncryptionAlgorithm::Random),
+        random
+    );
+    assert_eq!(decrypt(&crypt, &key, random), value);
+}
+
+#[test]
+fn libmongocrypt_errors() {
+    let crypt = local_crypt();
+
+    let mut ctx = crypt.ctx().unwrap();
+    let error = ctx.setopt_algorithm("unknown").unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::EncryptionError { .. }));
+
+    let kms_providers = KmsProviders::builder()
+        .local(LocalKmsProviderOptions::builder().key(vec![3; 32]).build())
+        .build();
+    let error = Crypt::new(&kms_providers_document(&kms_providers)).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::EncryptionError { .. }));
+}
@@ -2,11 +2,11 @@ use serde::de::DeserializeOwned;
 
 use super::wire::Message;
 use crate::{
-    bson::{Bson, Document},
+    bson::{Bson, Document, Timestamp},
     bson_util,
     client::{ClientSession, ClusterTime},
-    error::{CommandError, ErrorKind, Result},
-    options::StreamAddress,
+    error::{CommandError, Error, ErrorKind, Result},
+    options::{ReadConcern, ServerApi, StreamAddress},
     selection_criteria::ReadPreference,
 };
 
@@ -57,9 +57,93 @@ impl Command {
         }
     }
 
+    /// Whether the command accepts a `comment` field when sent to a server with the given max wire
+    /// version. Servers before 4.4 (wire version 9) only accept one on `find` and `aggregate`, and
+    /// reject it as an unrecognized field on other commands, e.g. `getMore`.
+    pub(crate) fn accepts_comment(&self, max_wire_version: i32) -> bool {
+        match self.name.as_str() {
+            _ if max_wire_version >= 9 => true,
+            "find" => max_wire_version >= 4,
+            "aggregate" => max_wire_version >= 6,
+            _ => false,
+        }
+    }
+
+    /// Sets the command's comment, unless one has already been specified.
+    pub(crate) fn set_default_comment(&mut self, comment: Bson) {
+        if !self.body.contains_key("comment") {
+            self.body.insert("comment", comment);
+        }
+    }
+
+    /// Sets the `afterClusterTime` of the command's read concern, so that the server doesn't
+    /// execute it until its data reflects all the operations up to the given time.
+    pub(crate) fn set_after_cluster_time(&mut self, operation_time: Timestamp) {
+        let mut read_concern = match self.body.remove("readConcern") {
+            Some(Bson::Document(read_concern)) => read_concern,
+            _ => Document::new(),
+        };
+        read_concern.insert("afterClusterTime", operation_time);
+        self.body.insert("readConcern", read_concern);
+    }
+
+    /// Whether the command reads with a "snapshot" read concern.
+    pub(crate) fn is_snapshot_read(&self) -> bool {
+        self.body
+            .get_document("readConcern")
+            .and_then(|read_concern| read_concern.get_str("level"))
+            == Ok("snapshot")
+    }
+
+    /// Sets the `atClusterTime` of the command's read concern, so that its snapshot reflects the
+    /// data at the given time.
+    pub(crate) fn set_snapshot_time(&mut self, snapshot_time: Timestamp) {
+        if let Ok(read_concern) = self.body.get_document_mut("readConcern") {
+            read_concern.insert("atClusterTime", snapshot_time);
+        }
+    }
+
     pub(crate) fn set_txn_number(&mut self, txn_number: u64) {
         self.body.insert("txnNumber", txn_number);
     }
+
+    /// Marks the command as part of a transaction, rather than a write of its own.
+    pub(crate) fn set_autocommit(&mut self) {
+        self.body.insert("autocommit", false);
+    }
+
+    /// Marks the command as the first of a transaction, which reads with the given read concern.
+    pub(crate) fn set_start_transaction(
+        &mut self,
+        read_concern: Option<&ReadConcern>,
+    ) -> Result<()> {
+        self.body.insert("startTransaction", true);
+        if let Some(read_concern) = read_concern {
+            self.body
+                .insert("readConcern", bson::to_bson(read_concern)?);
+        }
+        Ok(())
+    }
+
+    /// Declares the given Stable API version on the command. `apiStrict` is not sent with the
+    /// `isMaster`/`hello` commands used for handshakes and monitoring so that they can't be
+    /// rejected by a strict server.
+    pub(crate) fn set_server_api(&mut self, server_api: &ServerApi) {
+        self.body.insert("apiVersion", server_api.version.as_str());
+
+        let is_is_master =
+            self.name.eq_ignore_ascii_case("ismaster") || self.name.eq_ignore_ascii_case("hello");
+        match server_api.strict {
+            Some(strict) if !is_is_master => {
+                self.body.insert("apiStrict", strict);
+            }
+            _ => {}
+        }
+
+        if let Some(deprecation_errors) = server_api.deprecation_errors {
+            self.body.insert("apiDeprecationErrors", deprecation_errors);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +151,7 @@ pub(crate) struct CommandResponse {
     source: StreamAddress,
     pub(crate) raw_response: Document,
     cluster_time: Option<ClusterTime>,
+    wire_bytes: Option<usize>,
 }
 
 impl CommandResponse {
@@ -76,6 +161,7 @@ impl CommandResponse {
             source,
             raw_response: doc,
             cluster_time: None,
+            wire_bytes: None,
         }
     }
 
@@ -91,7 +177,8 @@ impl CommandResponse {
         )
     }
 
-    pub(crate) fn new(source: StreamAddress, message: Message) -> Result<Self> {
+    /// Initialize a response from the message it was received in, which was `wire_bytes` long.
+    pub(crate) fn new(source: StreamAddress, message: Message, wire_bytes: usize) -> Result<Self> {
         let raw_response = message.single_document_response()?;
         let cluster_time = raw_response
             .get("$clusterTime")
@@ -101,6 +188,7 @@ impl CommandResponse {
             source,
             raw_response,
             cluster_time,
+            wire_bytes: Some(wire_bytes),
         })
     }
 
@@ -121,7 +209,8 @@ impl CommandResponse {
                         message: "invalid server response".to_string(),
                     }
                 })?;
-            Err(ErrorKind::CommandError(command_error).into())
+            Err(Error::from(ErrorKind::CommandError(command_error))
+                .with_response(self.raw_response.clone()))
         } else {
             Ok(())
         }
@@ -143,8 +232,141 @@ impl CommandResponse {
         self.cluster_time.as_ref()
     }
 
+    /// Gets the logical time of the operation the response is for, if the server reported one.
+    pub(crate) fn operation_time(&self) -> Option<Timestamp> {
+        self.raw_response.get_timestamp("operationTime").ok()
+    }
+
+    /// Gets the point in time that a read with a "snapshot" read concern read from, which is
+    /// reported either in the cursor the response contains or at its top level.
+    pub(crate) fn snapshot_time(&self) -> Option<Timestamp> {
+        self.raw_response
+            .get_document("cursor")
+            .and_then(|cursor| cursor.get_timestamp("atClusterTime"))
+            .or_else(|_| self.raw_response.get_timestamp("atClusterTime"))
+            .ok()
+    }
+
+    /// The length of the wire message the response was received in, if it was received from a
+    /// server.
+    pub(crate) fn wire_bytes(&self) -> Option<usize> {
+        self.wire_bytes
+    }
+
     /// The address of the server that sent this response.
     pub(crate) fn source_address(&self) -> &StreamAddress {
         &self.source
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Command, CommandResponse};
+    use crate::{
+        bson::{doc, Timestamp},
+        options::{ServerApi, ServerApiVersion},
+    };
+
+    #[test]
+    fn set_after_cluster_time() {
+        let operation_time = Timestamp {
+            time: 12,
+            increment: 3,
+        };
+
+        let mut command = Command::new("find".into(), "db".into(), doc! { "find": "coll" });
+        command.set_after_cluster_time(operation_time);
+        assert_eq!(
+            command.body,
+            doc! { "find": "coll", "readConcern": { "afterClusterTime": operation_time } }
+        );
+
+        let mut command = Command::new(
+            "find".into(),
+            "db".into(),
+            doc! { "find": "coll", "readConcern": { "level": "majority" } },
+        );
+        command.set_after_cluster_time(operation_time);
+        assert_eq!(
+            command.body,
+            doc! {
+                "find": "coll",
+                "readConcern": { "level": "majority", "afterClusterTime": operation_time },
+            }
+        );
+    }
+
+    #[test]
+    fn snapshot_reads() {
+        let snapshot_time = Timestamp {
+            time: 12,
+            increment: 3,
+        };
+
+        let mut command = Command::new(
+            "find".into(),
+            "db".into(),
+            doc! { "find": "coll", "readConcern": { "level": "majority" } },
+        );
+        assert!(!command.is_snapshot_read());
+
+        let mut command = Command::new(
+            "find".into(),
+            "db".into(),
+            doc! { "find": "coll", "readConcern": { "level": "snapshot" } },
+        );
+        assert!(command.is_snapshot_read());
+        command.set_snapshot_time(snapshot_time);
+        assert_eq!(
+            command.body,
+            doc! {
+                "find": "coll",
+                "readConcern": { "level": "snapshot", "atClusterTime": snapshot_time },
+            }
+        );
+
+        let response = CommandResponse::with_document(doc! {
+            "ok": 1,
+            "cursor": { "id": 0_i64, "firstBatch": [], "atClusterTime": snapshot_time },
+        });
+        assert_eq!(response.snapshot_time(), Some(snapshot_time));
+
+        let response = CommandResponse::with_document(
+            doc! { "ok": 1, "n": 2, "atClusterTime": snapshot_time },
+        );
+        assert_eq!(response.snapshot_time(), Some(snapshot_time));
+    }
+
+    #[test]
+    fn set_server_api() {
+        let server_api = ServerApi::builder()
+            .version(ServerApiVersion::V1)
+            .strict(true)
+            .deprecation_errors(false)
+            .build();
+
+        let mut command = Command::new("find".into(), "db".into(), doc! { "find": "coll" });
+        command.set_server_api(&server_api);
+        assert_eq!(
+            command.body,
+            doc! {
+                "find": "coll",
+                "apiVersion": "1",
+                "apiStrict": true,
+                "apiDeprecationErrors": false,
+            }
+        );
+
+        let mut command = Command::new("isMaster".into(), "admin".into(), doc! { "isMaster": 1 });
+        command.set_server_api(&server_api);
+        assert_eq!(
+            command.body,
+            doc! { "isMaster": 1, "apiVersion": "1", "apiDeprecationErrors": false }
+        );
+
+        let server_api = ServerApi::builder().version(ServerApiVersion::V1).build();
+        let mut command = Command::new("find".into(), "db".into(), doc! { "find": "coll" });
+        command.set_server_api(&server_api);
+        assert_eq!(command.body, doc! { "find": "coll", "apiVersion": "1" });
+    }
+}
@@ -8,12 +8,18 @@ use std::{
 };
 
 use derivative::Derivative;
+use futures::io::AsyncWriteExt;
+use tokio::sync::{Mutex, MutexGuard};
 
-use self::wire::Message;
+use self::{
+    stream_description::DEFAULT_MAX_MESSAGE_SIZE_BYTES,
+    wire::{CountReader, Message},
+};
 use super::manager::PoolManager;
 use crate::{
     cmap::options::{ConnectionOptions, StreamOptions},
-    error::{ErrorKind, Result},
+    compression::{self, Compressor},
+    error::{Error, ErrorKind, Result},
     event::cmap::{
         CmapEventHandler,
         ConnectionCheckedInEvent,
@@ -23,7 +29,7 @@ use crate::{
         ConnectionCreatedEvent,
         ConnectionReadyEvent,
     },
-    options::{StreamAddress, TlsOptions},
+    options::{ServerApi, StreamAddress, TlsOptions},
     runtime::AsyncStream,
 };
 pub(crate) use command::{Command, CommandResponse};
@@ -64,13 +70,23 @@ pub(crate) struct Connection {
     /// been read.
     command_executing: bool,
 
-    /// Whether or not this connection has experienced a network error while reading or writing.
-    /// Once the connection has received an error, it should not be used again or checked back
-    /// into a pool.
-    error: bool,
+    /// The network or protocol error this connection experienced while reading or writing, if
+    /// any. Once the connection has received an error, it should not be used again or checked
+    /// back into a pool.
+    error: Option<Error>,
 
     stream: AsyncStream,
 
+    /// The compressors the client is willing to use, in order of preference. The first one that
+    /// the server also supports is used to compress outgoing messages.
+    compressors: Vec<Compressor>,
+
+    /// Messages whose sections are smaller than this many bytes are sent uncompressed.
+    compression_threshold: usize,
+
+    /// The Stable API version declared on every command sent on this connection.
+    server_api: Option<ServerApi>,
+
     #[derivative(Debug = "ignore")]
     handler: Option<Arc<dyn CmapEventHandler>>,
 }
@@ -96,9 +112,18 @@ impl Connection {
             ready_and_available_time: None,
             stream: AsyncStream::connect(stream_options).await?,
             address,
+            compressors: options
+                .as_ref()
+                .and_then(|opts| opts.compressors.clone())
+                .unwrap_or_default(),
+            compression_threshold: options
+                .as_ref()
+                .and_then(|opts| opts.compression_threshold)
+                .unwrap_or(0),
+            server_api: options.as_ref().and_then(|opts| opts.server_api.clone()),
             handler: options.and_then(|options| options.event_handler),
             stream_description: None,
-            error: false,
+            error: None,
         };
 
         Ok(conn)
@@ -120,6 +145,7 @@ impl Connection {
         address: StreamAddress,
         connect_timeout: Option<Duration>,
         tls_options: Option<TlsOptions>,
+        server_api: Option<ServerApi>,
     ) -> Result<Self> {
         Self::new(
             0,
@@ -129,6 +155,9 @@ impl Connection {
                 connect_timeout,
                 tls_options,
                 event_handler: None,
+                compressors: None,
+                compression_threshold: None,
+                server_api,
             }),
         )
         .await
@@ -193,7 +222,7 @@ impl Connection {
 
     /// Checks if the connection experienced a network error and should be closed.
     pub(super) fn has_errored(&self) -> bool {
-        self.error
+        self.error.is_some()
     }
 
     /// Helper to create a `ConnectionCheckedOutEvent` for the connection.
@@ -201,6 +230,7 @@ impl Connection {
         ConnectionCheckedOutEvent {
             address: self.address.clone(),
             connection_id: self.id,
+            generation: self.generation,
         }
     }
 
@@ -209,6 +239,7 @@ impl Connection {
         ConnectionCheckedInEvent {
             address: self.address.clone(),
             connection_id: self.id,
+            generation: self.generation,
         }
     }
 
@@ -217,6 +248,7 @@ impl Connection {
         ConnectionReadyEvent {
             address: self.address.clone(),
             connection_id: self.id,
+            generation: self.generation,
         }
     }
 
@@ -225,6 +257,11 @@ impl Connection {
         ConnectionClosedEvent {
             address: self.address.clone(),
             connection_id: self.id,
+            generation: self.generation,
+            error: match reason {
+                ConnectionClosedReason::Error => self.error.clone(),
+                _ => None,
+            },
             reason,
         }
     }
@@ -239,18 +276,82 @@ impl Connection {
         command: Command,
         request_id: impl Into<Option<i32>>,
     ) -> Result<CommandResponse> {
+        let message = self.serialize_command(command, request_id).await?;
+        self.send_serialized_command(&message).await
+    }
+
+    /// Serializes `command` into the wire message that sends it over this connection, compressing
+    /// it if a compressor was negotiated with the server.
+    pub(crate) async fn serialize_command(
+        &self,
+        mut command: Command,
+        request_id: impl Into<Option<i32>>,
+    ) -> Result<Vec<u8>> {
+        if let Some(ref server_api) = self.server_api {
+            command.set_server_api(server_api);
+        }
+
+        let compressor = if compression::is_compressible(&command.name) {
+            self.negotiated_compressor()
+        } else {
+            None
+        };
         let message = Message::with_command(command, request_id.into());
 
+        let mut bytes = Vec::new();
+        match compressor {
+            Some(compressor) => {
+                message
+                    .write_compressed_to(&mut bytes, compressor, self.compression_threshold)
+                    .await?
+            }
+            None => message.write_to(&mut bytes).await?,
+        };
+
+        Ok(bytes)
+    }
+
+    /// Sends a message serialized by `serialize_command` and reads the server's response to it.
+    pub(crate) async fn send_serialized_command(
+        &mut self,
+        message: &[u8],
+    ) -> Result<CommandResponse> {
         self.command_executing = true;
-        let write_result = message.write_to(&mut self.stream).await;
-        self.error = write_result.is_err();
+        let stream = &mut self.stream;
+        let write_result: Result<()> = async {
+            stream.write_all(message).await?;
+            stream.flush().await?;
+            Ok(())
+        }
+        .await;
+        self.error = write_result.as_ref().err().cloned();
         write_result?;
 
-        let response_message_result = Message::read_from(&mut self.stream).await;
+        let max_message_size = self
+            .stream_description
+            .as_ref()
+            .map_or(DEFAULT_MAX_MESSAGE_SIZE_BYTES, |description| {
+                description.max_message_size_bytes
+            });
+        let mut reader = CountReader::new(&mut self.stream);
+        let response_message_result =
+            Message::read_from(&mut reader, max_message_size.max(0) as usize).await;
+        let wire_bytes = reader.bytes_read();
         self.command_executing = false;
-        self.error = response_message_result.is_err();
+        self.error = response_message_result.as_ref().err().cloned();
+
+        CommandResponse::new(self.address.clone(), response_message_result?, wire_bytes)
+    }
 
-        CommandResponse::new(self.address.clone(), response_message_result?)
+    /// Gets the first of the client's compressors that the server reported support for in the
+    /// handshake, if any.
+    fn negotiated_compressor(&self) -> Option<&Compressor> {
+        let server_compressors = self.stream_description.as_ref()?.compressors.as_ref()?;
+        self.compressors.iter().find(|compressor| {
+            server_compressors
+                .iter()
+                .any(|name| name == compressor.name())
+        })
     }
 
     /// Gets the connection's StreamDescription.
@@ -284,10 +385,13 @@ impl Connection {
             address: self.address.clone(),
             generation: self.generation,
             stream: std::mem::replace(&mut self.stream, AsyncStream::Null),
+            compressors: std::mem::take(&mut self.compressors),
+            compression_threshold: self.compression_threshold,
+            server_api: self.server_api.take(),
             handler: self.handler.take(),
             stream_description: self.stream_description.take(),
             command_executing: self.command_executing,
-            error: self.error,
+            error: self.error.take(),
             pool_manager: None,
             ready_and_available_time: None,
         }
@@ -317,6 +421,23 @@ impl Drop for Connection {
     }
 }
 
+/// A connection that is kept out of its pool for the lifetime of the cursor it was used to create,
+/// so that the cursor's getMores and killCursors reach the same backend server when connected to a
+/// load balancer. The connection is checked back into its pool once every handle to it is dropped.
+#[derive(Clone, Debug)]
+pub(crate) struct PinnedConnection(Arc<Mutex<Connection>>);
+
+impl PinnedConnection {
+    pub(crate) fn new(conn: Connection) -> Self {
+        Self(Arc::new(Mutex::new(conn)))
+    }
+
+    /// Waits for any other operation using the connection to finish and returns it.
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.0.lock().await
+    }
+}
+
 /// Struct encapsulating the information needed to establish a `Connection`.
 ///
 /// Creating a `PendingConnection` contributes towards the total connection count of a pool, despite
@@ -336,6 +457,7 @@ impl PendingConnection {
         ConnectionCreatedEvent {
             address: self.address.clone(),
             connection_id: self.id,
+            generation: self.generation,
         }
     }
 }
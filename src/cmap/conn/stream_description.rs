@@ -2,6 +2,16 @@ use std::time::Duration;
 
 use crate::{is_master::IsMasterReply, sdam::ServerType};
 
+/// The maximum document size assumed for servers that don't report `maxBsonObjectSize`.
+const DEFAULT_MAX_BSON_OBJECT_SIZE: i64 = 16 * 1024 * 1024;
+
+/// The maximum write batch size assumed for servers that don't report `maxWriteBatchSize`.
+const DEFAULT_MAX_WRITE_BATCH_SIZE: i64 = 100_000;
+
+/// The maximum message size assumed for servers that don't report `maxMessageSizeBytes`, which is
+/// also the limit on the size of the replies read before the handshake has completed.
+pub(crate) const DEFAULT_MAX_MESSAGE_SIZE_BYTES: i64 = 48_000_000;
+
 /// Contains information about a given server in a format digestible by a connection.
 #[derive(Debug, Default, Clone)]
 pub(crate) struct StreamDescription {
@@ -20,6 +30,19 @@ pub(crate) struct StreamDescription {
     /// How long sessions started on this server will stay alive without
     /// without executing an operation before the server kills them.
     pub(crate) logical_session_timeout: Option<Duration>,
+
+    /// The compressors that the server supports out of those that the driver offered in the
+    /// handshake.
+    pub(crate) compressors: Option<Vec<String>>,
+
+    /// The maximum size of a BSON document that the server accepts, in bytes.
+    pub(crate) max_bson_object_size: i64,
+
+    /// The maximum number of writes that the server accepts in a single write command.
+    pub(crate) max_write_batch_size: i64,
+
+    /// The maximum size of a wire protocol message that the server accepts or sends, in bytes.
+    pub(crate) max_message_size_bytes: i64,
 }
 
 impl StreamDescription {
@@ -35,6 +58,19 @@ impl StreamDescription {
                 .command_response
                 .logical_session_timeout_minutes
                 .map(|mins| Duration::from_secs(mins as u64 * 60)),
+            compressors: reply.command_response.compression,
+            max_bson_object_size: reply
+                .command_response
+                .max_bson_object_size
+                .unwrap_or(DEFAULT_MAX_BSON_OBJECT_SIZE),
+            max_write_batch_size: reply
+                .command_response
+                .max_write_batch_size
+                .unwrap_or(DEFAULT_MAX_WRITE_BATCH_SIZE),
+            max_message_size_bytes: reply
+                .command_response
+                .max_message_size_bytes
+                .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE_BYTES),
         }
     }
 
@@ -54,6 +90,10 @@ impl StreamDescription {
             min_wire_version: Some(8),
             sasl_supported_mechs: Default::default(),
             logical_session_timeout: Some(Duration::from_secs(30 * 60)),
+            compressors: None,
+            max_bson_object_size: DEFAULT_MAX_BSON_OBJECT_SIZE,
+            max_write_batch_size: DEFAULT_MAX_WRITE_BATCH_SIZE,
+            max_message_size_bytes: DEFAULT_MAX_MESSAGE_SIZE_BYTES,
         }
     }
 }
@@ -1,6 +1,8 @@
+use futures::io::{AsyncRead, AsyncWrite};
+
 use crate::{
     error::{ErrorKind, Result},
-    runtime::{AsyncLittleEndianRead, AsyncLittleEndianWrite, AsyncStream},
+    runtime::{AsyncLittleEndianRead, AsyncLittleEndianWrite},
 };
 
 /// The wire protocol op codes.
@@ -8,15 +10,17 @@ use crate::{
 pub(crate) enum OpCode {
     Reply = 1,
     Query = 2004,
+    Compressed = 2012,
     Message = 2013,
 }
 
 impl OpCode {
     /// Attempt to infer the op code based on the numeric value.
-    fn from_i32(i: i32) -> Result<Self> {
+    pub(crate) fn from_i32(i: i32) -> Result<Self> {
         match i {
             1 => Ok(OpCode::Reply),
             2004 => Ok(OpCode::Query),
+            2012 => Ok(OpCode::Compressed),
             2013 => Ok(OpCode::Message),
             other => Err(ErrorKind::OperationError {
                 message: format!("Invalid wire protocol opcode: {}", other),
@@ -39,7 +43,10 @@ impl Header {
     pub(crate) const LENGTH: usize = 4 * std::mem::size_of::<i32>();
 
     /// Serializes the Header and writes the bytes to `w`.
-    pub(crate) async fn write_to(&self, stream: &mut AsyncStream) -> Result<()> {
+    pub(crate) async fn write_to<W: AsyncWrite + Unpin + Send>(
+        &self,
+        stream: &mut W,
+    ) -> Result<()> {
         stream.write_i32(self.length).await?;
         stream.write_i32(self.request_id).await?;
         stream.write_i32(self.response_to).await?;
@@ -49,7 +56,7 @@ impl Header {
     }
 
     /// Reads bytes from `r` and deserializes them into a header.
-    pub(crate) async fn read_from(stream: &mut AsyncStream) -> Result<Self> {
+    pub(crate) async fn read_from<R: AsyncRead + Unpin + Send>(stream: &mut R) -> Result<Self> {
         let length = stream.read_i32().await?;
         let request_id = stream.read_i32().await?;
         let response_to = stream.read_i32().await?;
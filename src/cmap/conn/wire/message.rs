@@ -1,5 +1,5 @@
 use bitflags::bitflags;
-use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, Cursor};
 
 use super::{
     header::{Header, OpCode},
@@ -9,10 +9,15 @@ use crate::{
     bson::Document,
     bson_util::async_encoding,
     cmap::conn::command::Command,
+    compression::{self, Compressor},
     error::{ErrorKind, Result},
-    runtime::{AsyncLittleEndianRead, AsyncLittleEndianWrite, AsyncStream},
+    runtime::{AsyncLittleEndianRead, AsyncLittleEndianWrite},
 };
 
+/// The length of the fields of an `OP_COMPRESSED` message that follow its header: the original op
+/// code, the uncompressed size, and the compressor ID.
+const COMPRESSION_HEADER_LENGTH: usize = 2 * std::mem::size_of::<i32>() + std::mem::size_of::<u8>();
+
 /// Represents an OP_MSG wire protocol operation.
 #[derive(Debug)]
 pub(crate) struct Message {
@@ -74,9 +79,118 @@ impl Message {
             .collect()
     }
 
-    /// Reads bytes from `reader` and deserializes them into a Message.
-    pub(crate) async fn read_from(reader: &mut AsyncStream) -> Result<Self> {
+    /// Reads bytes from `reader` and deserializes them into a Message, decompressing them first if
+    /// they were sent as an `OP_COMPRESSED` message. Messages longer than `max_message_size`
+    /// bytes, either as sent or once decompressed, are rejected without being read.
+    pub(crate) async fn read_from<R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        max_message_size: usize,
+    ) -> Result<Self> {
         let header = Header::read_from(reader).await?;
+
+        if header.length < Header::LENGTH as i32 || header.length as usize > max_message_size {
+            return Err(ErrorKind::ResponseError {
+                message: format!(
+                    "The server indicated that the reply would be {} bytes long, which is not \
+                     between the header length of {} and the maximum message size of {}",
+                    header.length,
+                    Header::LENGTH,
+                    max_message_size,
+                ),
+            }
+            .into());
+        }
+
+        match header.op_code {
+            OpCode::Message => Self::read_op_msg(reader, header).await,
+            OpCode::Compressed => Self::read_op_compressed(reader, header, max_message_size).await,
+            other => Err(ErrorKind::ResponseError {
+                message: format!(
+                    "expected OP_MSG or OP_COMPRESSED reply from server, instead got {:?}",
+                    other
+                ),
+            }
+            .into()),
+        }
+    }
+
+    /// Reads the body of an `OP_COMPRESSED` message with the given header from `reader`,
+    /// decompresses it, and deserializes the result into a Message.
+    async fn read_op_compressed<R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        header: Header,
+        max_message_size: usize,
+    ) -> Result<Self> {
+        let compressed_length = (header.length as usize)
+            .checked_sub(Header::LENGTH + COMPRESSION_HEADER_LENGTH)
+            .ok_or_else(|| ErrorKind::ResponseError {
+                message: format!(
+                    "The server indicated that the compressed reply would be {} bytes long, which \
+                     is too short to hold its compression header",
+                    header.length,
+                ),
+            })?;
+
+        let original_op_code = OpCode::from_i32(reader.read_i32().await?)?;
+        let uncompressed_size = reader.read_i32().await?;
+        let compressor_id = reader.read_u8().await?;
+
+        if uncompressed_size < 0
+            || uncompressed_size as usize > max_message_size.saturating_sub(Header::LENGTH)
+        {
+            return Err(ErrorKind::ResponseError {
+                message: format!(
+                    "The server indicated that the decompressed reply would be {} bytes long, \
+                     which exceeds the maximum message size of {}",
+                    uncompressed_size, max_message_size,
+                ),
+            }
+            .into());
+        }
+
+        let mut compressed = vec![0; compressed_length];
+        reader.read_exact(&mut compressed).await?;
+
+        let decompressed =
+            compression::decompress(compressor_id, &compressed, uncompressed_size as usize)?;
+
+        if decompressed.len() != uncompressed_size as usize {
+            return Err(ErrorKind::ResponseError {
+                message: format!(
+                    "The server indicated that the decompressed reply would be {} bytes long, but \
+                     it instead was {}",
+                    uncompressed_size,
+                    decompressed.len(),
+                ),
+            }
+            .into());
+        }
+
+        if original_op_code != OpCode::Message {
+            return Err(ErrorKind::ResponseError {
+                message: format!(
+                    "expected compressed OP_MSG reply from server, instead got {:?}",
+                    original_op_code
+                ),
+            }
+            .into());
+        }
+
+        let header = Header {
+            length: (Header::LENGTH + decompressed.len()) as i32,
+            op_code: original_op_code,
+            ..header
+        };
+
+        Self::read_op_msg(&mut Cursor::new(decompressed), header).await
+    }
+
+    /// Reads the body of an `OP_MSG` message with the given header from `reader` and deserializes
+    /// it into a Message.
+    async fn read_op_msg<R: AsyncRead + Unpin + Send>(
+        reader: &mut R,
+        header: Header,
+    ) -> Result<Self> {
         let mut length_remaining = header.length - Header::LENGTH as i32;
 
         let flags = MessageFlags::from_bits_truncate(reader.read_u32().await?);
@@ -117,13 +231,63 @@ impl Message {
     }
 
     /// Serializes the Message to bytes and writes them to `writer`.
-    pub(crate) async fn write_to(&self, writer: &mut AsyncStream) -> Result<()> {
-        let mut sections_bytes = Vec::new();
+    pub(crate) async fn write_to<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        let sections_bytes = self.sections_bytes().await?;
+        self.write_op_msg(writer, &sections_bytes).await
+    }
 
-        for section in &self.sections {
-            section.write(&mut sections_bytes).await?;
+    /// Serializes the Message to bytes and writes them to `writer`. If the serialized sections are
+    /// at least `threshold` bytes long, the message is compressed with `compressor` and sent as an
+    /// `OP_COMPRESSED` message; otherwise it is sent uncompressed.
+    pub(crate) async fn write_compressed_to<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &mut W,
+        compressor: &Compressor,
+        threshold: usize,
+    ) -> Result<()> {
+        let sections_bytes = self.sections_bytes().await?;
+
+        if sections_bytes.len() < threshold {
+            return self.write_op_msg(writer, &sections_bytes).await;
         }
 
+        let mut uncompressed = Vec::new();
+        uncompressed.write_u32(self.flags.bits()).await?;
+        uncompressed.write_all(&sections_bytes).await?;
+
+        if let Some(checksum) = self.checksum {
+            uncompressed.write_u32(checksum).await?;
+        }
+
+        let compressed = compressor.compress(&uncompressed)?;
+
+        let header = Header {
+            length: (Header::LENGTH + COMPRESSION_HEADER_LENGTH + compressed.len()) as i32,
+            request_id: self.request_id.unwrap_or_else(super::util::next_request_id),
+            response_to: self.response_to,
+            op_code: OpCode::Compressed,
+        };
+
+        header.write_to(writer).await?;
+        writer.write_i32(OpCode::Message as i32).await?;
+        writer.write_i32(uncompressed.len() as i32).await?;
+        writer.write_u8(compressor.id()).await?;
+        writer.write_all(&compressed).await?;
+
+        writer.flush().await?;
+
+        Ok(())
+    }
+
+    /// Writes the Message to `writer` as an `OP_MSG` message with the given serialized sections.
+    async fn write_op_msg<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &mut W,
+        sections_bytes: &[u8],
+    ) -> Result<()> {
         let total_length = Header::LENGTH
             + std::mem::size_of::<u32>()
             + sections_bytes.len()
@@ -142,7 +306,7 @@ impl Message {
 
         header.write_to(writer).await?;
         writer.write_u32(self.flags.bits()).await?;
-        writer.write_all(&sections_bytes).await?;
+        writer.write_all(sections_bytes).await?;
 
         if let Some(checksum) = self.checksum {
             writer.write_u32(checksum).await?;
@@ -152,6 +316,17 @@ impl Message {
 
         Ok(())
     }
+
+    /// Serializes the Message's sections to bytes.
+    async fn sections_bytes(&self) -> Result<Vec<u8>> {
+        let mut sections_bytes = Vec::new();
+
+        for section in &self.sections {
+            section.write(&mut sections_bytes).await?;
+        }
+
+        Ok(sections_bytes)
+    }
 }
 
 bitflags! {
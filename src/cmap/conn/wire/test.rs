@@ -1,13 +1,21 @@
+use futures::io::Cursor;
 use tokio::sync::RwLockReadGuard;
 
-use super::message::{Message, MessageFlags, MessageSection};
+use super::{
+    header::OpCode,
+    message::{Message, MessageFlags, MessageSection},
+};
 use crate::{
     bson::{doc, Bson},
-    cmap::options::StreamOptions,
+    cmap::{conn::stream_description::DEFAULT_MAX_MESSAGE_SIZE_BYTES, options::StreamOptions},
+    compression::Compressor,
+    error::ErrorKind,
     runtime::AsyncStream,
     test::{CLIENT_OPTIONS, LOCK},
 };
 
+const MAX_MESSAGE_SIZE: usize = DEFAULT_MAX_MESSAGE_SIZE_BYTES as usize;
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn basic() {
@@ -36,7 +44,9 @@ async fn basic() {
     let mut stream = AsyncStream::connect(options).await.unwrap();
     message.write_to(&mut stream).await.unwrap();
 
-    let reply = Message::read_from(&mut stream).await.unwrap();
+    let reply = Message::read_from(&mut stream, MAX_MESSAGE_SIZE)
+        .await
+        .unwrap();
 
     let response_doc = match reply.sections.into_iter().next().unwrap() {
         MessageSection::Document(doc) => doc,
@@ -45,3 +55,92 @@ async fn basic() {
 
     assert_eq!(response_doc.get("ok"), Some(&Bson::Double(1.0)));
 }
+
+async fn write_compressed(message: &Message, threshold: usize) -> Vec<u8> {
+    let mut bytes = Cursor::new(Vec::new());
+    message
+        .write_compressed_to(&mut bytes, &Compressor::Zlib { level: None }, threshold)
+        .await
+        .unwrap();
+    bytes.into_inner()
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn compressed_round_trip() {
+    let message = Message {
+        response_to: 0,
+        flags: MessageFlags::empty(),
+        sections: vec![MessageSection::Document(
+            doc! { "insert": "coll", "documents": [{ "x": "y".repeat(500) }], "$db": "db" },
+        )],
+        checksum: None,
+        request_id: Some(7),
+    };
+
+    // The op code is stored right after the message length, request ID, and response ID.
+    let op_code = |bytes: &[u8]| i32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+
+    let compressed = write_compressed(&message, 0).await;
+    assert_eq!(op_code(&compressed), OpCode::Compressed as i32);
+
+    let read = Message::read_from(&mut Cursor::new(compressed), MAX_MESSAGE_SIZE)
+        .await
+        .unwrap();
+    assert_eq!(read.sections.len(), 1);
+    match (&read.sections[0], &message.sections[0]) {
+        (MessageSection::Document(read), MessageSection::Document(written)) => {
+            assert_eq!(read, written)
+        }
+        other => panic!("unexpected sections: {:?}", other),
+    }
+
+    // Messages smaller than the threshold are sent as a plain OP_MSG.
+    let uncompressed = write_compressed(&message, 1024 * 1024).await;
+    assert_eq!(op_code(&uncompressed), OpCode::Message as i32);
+    assert!(
+        Message::read_from(&mut Cursor::new(uncompressed), MAX_MESSAGE_SIZE)
+            .await
+            .is_ok()
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn invalid_lengths() {
+    let message = Message {
+        response_to: 0,
+        flags: MessageFlags::empty(),
+        sections: vec![MessageSection::Document(
+            doc! { "insert": "coll", "documents": [{ "x": "y".repeat(500) }], "$db": "db" },
+        )],
+        checksum: None,
+        request_id: Some(7),
+    };
+    let compressed = write_compressed(&message, 0).await;
+
+    let read = |bytes: Vec<u8>, max_message_size: usize| async move {
+        let error = Message::read_from(&mut Cursor::new(bytes), max_message_size)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error.kind.as_ref(), ErrorKind::ResponseError { .. }),
+            "{:?}",
+            error
+        );
+    };
+
+    // The message is longer than the maximum message size.
+    read(compressed.clone(), compressed.len() - 1).await;
+
+    // The message length is too short to hold the compression header.
+    let mut too_short = compressed.clone();
+    too_short[..4].copy_from_slice(&20i32.to_le_bytes());
+    read(too_short, MAX_MESSAGE_SIZE).await;
+
+    // The uncompressed size, which follows the header and the original op code, is larger than
+    // the maximum message size.
+    let mut too_large = compressed;
+    too_large[20..24].copy_from_slice(&std::i32::MAX.to_le_bytes());
+    read(too_large, MAX_MESSAGE_SIZE).await;
+}
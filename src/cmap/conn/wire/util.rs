@@ -33,14 +33,14 @@ pub(super) async fn write_cstring<W: AsyncWrite + Unpin>(
 }
 
 /// A wrapper around `futures::io::AsyncRead` that keeps track of the number of bytes it has read.
-pub(super) struct CountReader<'a, R: AsyncRead + Unpin + Send + 'a> {
+pub(crate) struct CountReader<'a, R: AsyncRead + Unpin + Send + 'a> {
     reader: &'a mut R,
     bytes_read: usize,
 }
 
 impl<'a, R: AsyncRead + Unpin + Send + 'a> CountReader<'a, R> {
     /// Constructs a new CountReader that wraps `reader`.
-    pub(super) fn new(reader: &'a mut R) -> Self {
+    pub(crate) fn new(reader: &'a mut R) -> Self {
         CountReader {
             reader,
             bytes_read: 0,
@@ -48,7 +48,7 @@ impl<'a, R: AsyncRead + Unpin + Send + 'a> CountReader<'a, R> {
     }
 
     /// Gets the number of bytes read so far.
-    pub(super) fn bytes_read(&self) -> usize {
+    pub(crate) fn bytes_read(&self) -> usize {
         self.bytes_read
     }
 }
@@ -2,12 +2,13 @@ use tokio::sync::{mpsc, oneshot};
 
 use super::{worker::PoolWorkerHandle, Connection};
 use crate::{
+    client::metrics::PoolCounters,
     error::{ErrorKind, Result},
     options::StreamAddress,
     runtime::AsyncJoinHandle,
     RUNTIME,
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 /// Handle for requesting Connections from the pool.
 /// This requester will keep the pool alive. Once all requesters have been dropped,
@@ -17,6 +18,9 @@ pub(super) struct ConnectionRequester {
     address: StreamAddress,
     sender: mpsc::UnboundedSender<oneshot::Sender<RequestedConnection>>,
     handle: PoolWorkerHandle,
+
+    /// The pool's counters, used to report the state of the pool when a request times out.
+    counters: Arc<PoolCounters>,
 }
 
 impl ConnectionRequester {
@@ -24,6 +28,7 @@ impl ConnectionRequester {
     pub(super) fn new(
         address: StreamAddress,
         handle: PoolWorkerHandle,
+        counters: Arc<PoolCounters>,
     ) -> (Self, ConnectionRequestReceiver) {
         let (sender, receiver) = mpsc::unbounded_channel();
         (
@@ -31,36 +36,44 @@ impl ConnectionRequester {
                 address,
                 sender,
                 handle,
+                counters,
             },
             ConnectionRequestReceiver::new(receiver),
         )
     }
 
     /// Request a connection from the pool that owns the receiver end of this requester.
-    /// Returns an error if it takes longer than wait_queue_timeout before either a connection is
-    /// received or an establishment begins.
+    /// Requests are served in the order they were made. Returns an error if it takes longer than
+    /// wait_queue_timeout before either a connection is received or an establishment begins, or if
+    /// the pool has been closed.
     pub(super) async fn request(&self, wait_queue_timeout: Option<Duration>) -> Result<Connection> {
         let (sender, receiver) = oneshot::channel();
 
-        // this only errors if the receiver end is dropped, which can't happen because
-        // we own a handle to the worker, keeping it alive.
-        self.sender.send(sender).unwrap();
+        // The handle we own keeps the worker alive, so this only errors if the pool was closed
+        // explicitly.
+        if self.sender.send(sender).is_err() {
+            return Err(ErrorKind::ClientShutdown.into());
+        }
 
+        // Similarly, the receiver only returns an error if the request was dropped because the
+        // pool was closed.
+        let closed = |_| ErrorKind::ClientShutdown.into();
         let response = match wait_queue_timeout {
             Some(timeout) => RUNTIME
                 .timeout(timeout, receiver)
                 .await
-                .map(|r| r.unwrap()) // see comment below as to why this is safe
                 .map_err(|_| {
+                    let metrics = self.counters.snapshot();
                     ErrorKind::WaitQueueTimeoutError {
                         address: self.address.clone(),
+                        connections: metrics.connections,
+                        available_connections: metrics.available_connections,
+                        pending_connections: metrics.pending_connections,
                     }
                     .into()
-                }),
-
-            // similarly, the receiver only returns an error if the sender is dropped, which
-            // can't happen due to the handle.
-            None => Ok(receiver.await.unwrap()),
+                })
+                .and_then(|r| r.map_err(closed)),
+            None => receiver.await.map_err(closed),
         };
 
         match response {
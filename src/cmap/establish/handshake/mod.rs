@@ -8,9 +8,10 @@ use os_info::{Type, Version};
 
 use crate::{
     bson::{doc, Bson, Document},
+    bson_util,
     client::auth::{ClientFirst, FirstRound},
     cmap::{options::ConnectionPoolOptions, Command, Connection, StreamDescription},
-    error::Result,
+    error::{ErrorKind, Result},
     is_master::{IsMasterCommandResponse, IsMasterReply},
     options::{AuthMechanism, Credential},
 };
@@ -24,6 +25,9 @@ const RUNTIME_NAME: &str = "async-std";
 #[cfg(feature = "sync")]
 const RUNTIME_NAME: &str = "sync (with async-std)";
 
+/// The maximum size in bytes of the `client` document that the server accepts in a handshake.
+const MAX_CLIENT_METADATA_SIZE: usize = 512;
+
 #[derive(Clone, Debug)]
 struct ClientMetadata {
     application: Option<AppMetadata>,
@@ -47,11 +51,39 @@ struct DriverMetadata {
 struct OsMetadata {
     os_type: String,
     name: Option<String>,
-    architecture: String,
+    architecture: Option<String>,
     version: Option<String>,
 }
 
+impl ClientMetadata {
+    /// Drops the least important information from the metadata until it fits in the size the
+    /// server accepts: first the platform, then everything about the OS but its type.
+    fn truncate_to_fit(&mut self) {
+        if self.size() <= MAX_CLIENT_METADATA_SIZE {
+            return;
+        }
+        self.platform = None;
+
+        if self.size() <= MAX_CLIENT_METADATA_SIZE {
+            return;
+        }
+        self.os.name = None;
+        self.os.architecture = None;
+        self.os.version = None;
+    }
+
+    fn size(&self) -> usize {
+        bson_util::doc_size_bytes(&Document::from(self.clone()))
+    }
+}
+
 impl From<ClientMetadata> for Bson {
+    fn from(metadata: ClientMetadata) -> Self {
+        Bson::Document(metadata.into())
+    }
+}
+
+impl From<ClientMetadata> for Document {
     fn from(metadata: ClientMetadata) -> Self {
         let mut metadata_doc = Document::new();
 
@@ -73,7 +105,7 @@ impl From<ClientMetadata> for Bson {
             metadata_doc.insert("platform", platform);
         }
 
-        Bson::Document(metadata_doc)
+        metadata_doc
     }
 }
 
@@ -85,7 +117,9 @@ impl From<OsMetadata> for Bson {
             doc.insert("name", name);
         }
 
-        doc.insert("architecture", metadata.architecture);
+        if let Some(architecture) = metadata.architecture {
+            doc.insert("architecture", architecture);
+        }
 
         if let Some(version) = metadata.version {
             doc.insert("version", version);
@@ -108,7 +142,7 @@ lazy_static! {
             },
             os: OsMetadata {
                 os_type: std::env::consts::OS.into(),
-                architecture: std::env::consts::ARCH.into(),
+                architecture: Some(std::env::consts::ARCH.into()),
                 name: None,
                 version: None,
             },
@@ -170,6 +204,8 @@ impl Handshaker {
             }
         }
 
+        metadata.truncate_to_fit();
+
         let mut db = "admin";
 
         let mut body = doc! {
@@ -177,6 +213,20 @@ impl Handshaker {
             "client": metadata,
         };
 
+        if let Some(true) = options.as_ref().and_then(|opts| opts.load_balanced) {
+            body.insert("loadBalanced", true);
+        }
+
+        if let Some(compressors) = options.as_ref().and_then(|opts| opts.compressors.as_ref()) {
+            if !compressors.is_empty() {
+                let names: Vec<Bson> = compressors
+                    .iter()
+                    .map(|compressor| Bson::String(compressor.name().to_string()))
+                    .collect();
+                body.insert("compression", names);
+            }
+        }
+
         if let Some(credential) = options.as_ref().and_then(|opts| opts.credential.as_ref()) {
             credential.append_needed_mechanism_negotiation(&mut body);
             db = credential.resolved_source();
@@ -196,6 +246,7 @@ impl Handshaker {
         let mut command = self.command.clone();
 
         let client_first = set_speculative_auth_info(&mut command.body, credential)?;
+        let load_balanced = command.body.get_bool("loadBalanced") == Ok(true);
 
         let start_time = PreciseTime::now();
         let response = conn.send_command(command, None).await?;
@@ -204,6 +255,17 @@ impl Handshaker {
         response.validate()?;
         let mut command_response: IsMasterCommandResponse = response.body()?;
 
+        // Servers behind a load balancer identify themselves with a serviceId, so a reply without
+        // one means that the load balancer doesn't support this mode.
+        if load_balanced && command_response.service_id.is_none() {
+            return Err(ErrorKind::ResponseError {
+                message: "the server does not support load balancing: its handshake reply did \
+                          not include a serviceId"
+                    .to_string(),
+            }
+            .into());
+        }
+
         // Record the client's message and the server's response from speculative authentication if
         // the server did send a response.
         let first_round = client_first.and_then(|client_first| {
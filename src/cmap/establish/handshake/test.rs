@@ -1,5 +1,11 @@
 use super::Handshaker;
-use crate::{bson::doc, cmap::options::ConnectionPoolOptions, options::DriverInfo};
+use crate::{
+    bson::{doc, Bson},
+    bson_util,
+    cmap::options::ConnectionPoolOptions,
+    compression::Compressor,
+    options::{AuthMechanism, Credential, DriverInfo},
+};
 
 #[test]
 fn metadata_no_options() {
@@ -57,3 +63,84 @@ fn metadata_with_options() {
     assert_eq!(os.get_str("type"), Ok(std::env::consts::OS));
     assert_eq!(os.get_str("architecture"), Ok(std::env::consts::ARCH));
 }
+
+#[test]
+fn metadata_truncated() {
+    let driver_info = |name: &str, platform: &str| {
+        let options = ConnectionPoolOptions::builder()
+            .driver_info(
+                DriverInfo::builder()
+                    .name(name.to_string())
+                    .platform(platform.to_string())
+                    .build(),
+            )
+            .build();
+        let handshaker = Handshaker::new(Some(&options));
+        let metadata = handshaker.command.body.get_document("client").unwrap();
+        metadata.clone()
+    };
+
+    let metadata = driver_info("framework", &"x".repeat(500));
+    assert!(!metadata.contains_key("platform"));
+    let os = metadata.get_document("os").unwrap();
+    assert_eq!(os.get_str("architecture"), Ok(std::env::consts::ARCH));
+
+    let metadata = driver_info(&"x".repeat(410), "framework");
+    assert!(!metadata.contains_key("platform"));
+    assert_eq!(
+        metadata.get_document("os"),
+        Ok(&doc! { "type": std::env::consts::OS })
+    );
+    assert!(bson_util::doc_size_bytes(&metadata) <= 512);
+}
+
+#[test]
+fn load_balanced() {
+    let handshaker = Handshaker::new(None);
+    assert!(!handshaker.command.body.contains_key("loadBalanced"));
+
+    let options = ConnectionPoolOptions::builder().load_balanced(true).build();
+    let handshaker = Handshaker::new(Some(&options));
+    assert_eq!(handshaker.command.body.get_bool("loadBalanced"), Ok(true));
+}
+
+#[test]
+fn compression() {
+    let handshaker = Handshaker::new(None);
+    assert!(!handshaker.command.body.contains_key("compression"));
+
+    let options = ConnectionPoolOptions::builder()
+        .compressors(vec![Compressor::Zlib { level: None }])
+        .build();
+    let handshaker = Handshaker::new(Some(&options));
+    assert_eq!(
+        handshaker.command.body.get_array("compression"),
+        Ok(&vec![Bson::String("zlib".to_string())])
+    );
+}
+
+#[test]
+fn sasl_supported_mechs() {
+    let credential = Credential::builder()
+        .username("user".to_string())
+        .source("authdb".to_string())
+        .build();
+    let options = ConnectionPoolOptions::builder()
+        .credential(credential.clone())
+        .build();
+    let handshaker = Handshaker::new(Some(&options));
+    assert_eq!(
+        handshaker.command.body.get_str("saslSupportedMechs"),
+        Ok("authdb.user")
+    );
+
+    // The list of mechanisms is only needed to negotiate a mechanism when none was specified.
+    let options = ConnectionPoolOptions::builder()
+        .credential(Credential {
+            mechanism: Some(AuthMechanism::ScramSha1),
+            ..credential
+        })
+        .build();
+    let handshaker = Handshaker::new(Some(&options));
+    assert!(!handshaker.command.body.contains_key("saslSupportedMechs"));
+}
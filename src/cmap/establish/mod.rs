@@ -2,9 +2,11 @@ mod handshake;
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use self::handshake::Handshaker;
 use super::{conn::PendingConnection, options::ConnectionPoolOptions, Connection};
-use crate::{client::auth::Credential, error::Result, runtime::HttpClient};
+use crate::{client::auth::Credential, error::Result, runtime::HttpClient, RUNTIME};
 
 /// Contains the logic to establish a connection, including handshaking, authenticating, and
 /// potentially more.
@@ -14,6 +16,7 @@ pub(super) struct ConnectionEstablisher {
     handshaker: Handshaker,
     http_client: HttpClient,
     credential: Option<Credential>,
+    handshake_timeout: Option<Duration>,
 }
 
 impl ConnectionEstablisher {
@@ -25,13 +28,30 @@ impl ConnectionEstablisher {
             handshaker,
             http_client,
             credential: options.and_then(|options| options.credential.clone()),
+            handshake_timeout: options.and_then(|options| options.handshake_timeout),
         }
     }
 
-    /// Establishes a connection.
+    /// Establishes a connection, failing with a timeout error if that takes longer than the
+    /// handshake timeout.
     pub(super) async fn establish_connection(
         &self,
         pending_connection: PendingConnection,
+    ) -> Result<Connection> {
+        match self.handshake_timeout {
+            Some(timeout) => {
+                RUNTIME
+                    .timeout(timeout, self.connect_and_handshake(pending_connection))
+                    .await?
+            }
+            None => self.connect_and_handshake(pending_connection).await,
+        }
+    }
+
+    /// Connects to the server, then handshakes and authenticates the connection.
+    async fn connect_and_handshake(
+        &self,
+        pending_connection: PendingConnection,
     ) -> Result<Connection> {
         let mut connection = Connection::connect(pending_connection).await?;
 
@@ -1,9 +1,21 @@
+use std::{
+    net::TcpListener,
+    time::{Duration, Instant},
+};
+
 use tokio::sync::RwLockWriteGuard;
 
+use super::ConnectionEstablisher;
 use crate::{
     bson::{doc, Bson},
-    cmap::{establish::Handshaker, Command, Connection, ConnectionPoolOptions},
-    options::{AuthMechanism, Credential, ReadPreference},
+    cmap::{
+        conn::PendingConnection,
+        establish::Handshaker,
+        Command,
+        Connection,
+        ConnectionPoolOptions,
+    },
+    options::{AuthMechanism, Credential, ReadPreference, StreamAddress},
     test::{TestClient, CLIENT_OPTIONS, LOCK},
 };
 
@@ -158,3 +170,33 @@ async fn speculative_auth_x509() {
     )
     .await;
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handshake_timeout() {
+    // The listener accepts connections via its backlog but never replies to the handshake.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = StreamAddress {
+        hostname: "127.0.0.1".to_string(),
+        port: Some(listener.local_addr().unwrap().port()),
+    };
+
+    let pool_options = ConnectionPoolOptions::builder()
+        .handshake_timeout(Duration::from_millis(100))
+        .build();
+    let establisher = ConnectionEstablisher::new(Default::default(), Some(&pool_options));
+    let pending_connection = PendingConnection {
+        id: 1,
+        address,
+        generation: 0,
+        options: Some(pool_options.into()),
+    };
+
+    let start = Instant::now();
+    let error = establisher
+        .establish_connection(pending_connection)
+        .await
+        .expect_err("handshake should time out");
+    assert!(error.is_network_error());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -1,4 +1,4 @@
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 
 use super::Connection;
 use crate::error::Error;
@@ -21,6 +21,14 @@ impl PoolManager {
         let _ = self.sender.send(PoolManagementRequest::Clear);
     }
 
+    /// Close the pool, closing its available connections. The returned receiver is notified once
+    /// the pool has been closed.
+    pub(super) fn close(&self) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        let _ = self.sender.send(PoolManagementRequest::Close(sender));
+        receiver
+    }
+
     /// Check in the given connection to the pool.
     /// This returns an error containing the connection if the pool has been dropped already.
     pub(crate) fn check_in(&self, connection: Connection) -> std::result::Result<(), Connection> {
@@ -60,6 +68,7 @@ impl ManagementRequestReceiver {
 #[derive(Debug)]
 pub(super) enum PoolManagementRequest {
     Clear,
+    Close(oneshot::Sender<()>),
     CheckIn(Connection),
     HandleConnectionFailed(Error),
     HandleConnectionSucceeded(Option<Connection>),
@@ -13,9 +13,16 @@ use std::{sync::Arc, time::Duration};
 use derivative::Derivative;
 
 pub use self::conn::ConnectionInfo;
-pub(crate) use self::conn::{Command, CommandResponse, Connection, StreamDescription};
+pub(crate) use self::conn::{
+    Command,
+    CommandResponse,
+    Connection,
+    PinnedConnection,
+    StreamDescription,
+};
 use self::options::ConnectionPoolOptions;
 use crate::{
+    client::metrics::{PoolCounters, PoolMetrics},
     error::{ErrorKind, Result},
     event::cmap::{
         CmapEventHandler,
@@ -33,6 +40,8 @@ use worker::ConnectionPoolWorker;
 
 const DEFAULT_MAX_POOL_SIZE: u32 = 100;
 
+const DEFAULT_MAX_CONNECTING: u32 = 2;
+
 /// A pool of connections implementing the CMAP spec. All state is kept internally in an `Arc`, and
 /// internal state that is mutable is additionally wrapped by a lock.
 #[derive(Clone, Derivative)]
@@ -42,6 +51,7 @@ pub(crate) struct ConnectionPool {
     manager: PoolManager,
     connection_requester: ConnectionRequester,
     wait_queue_timeout: Option<Duration>,
+    counters: Arc<PoolCounters>,
 
     #[derivative(Debug = "ignore")]
     event_handler: Option<Arc<dyn CmapEventHandler>>,
@@ -53,8 +63,13 @@ impl ConnectionPool {
         http_client: HttpClient,
         options: Option<ConnectionPoolOptions>,
     ) -> Self {
-        let (manager, connection_requester) =
-            ConnectionPoolWorker::start(address.clone(), http_client, options.clone());
+        let counters: Arc<PoolCounters> = Default::default();
+        let (manager, connection_requester) = ConnectionPoolWorker::start(
+            address.clone(),
+            http_client,
+            options.clone(),
+            counters.clone(),
+        );
 
         let event_handler = options.as_ref().and_then(|opts| opts.event_handler.clone());
         let wait_queue_timeout = options.as_ref().and_then(|opts| opts.wait_queue_timeout);
@@ -71,6 +86,7 @@ impl ConnectionPool {
             manager,
             connection_requester,
             wait_queue_timeout,
+            counters,
             event_handler,
         }
     }
@@ -134,4 +150,16 @@ impl ConnectionPool {
     pub(crate) fn clear(&self) {
         self.manager.clear();
     }
+
+    /// Closes the pool, emitting a `PoolClearedEvent` and then a `PoolClosedEvent` for it.
+    /// Connections that are checked out when the pool is closed are closed when they're checked
+    /// back in, and connections can no longer be checked out of it.
+    pub(crate) async fn close(&self) {
+        let _: std::result::Result<_, _> = self.manager.close().await;
+    }
+
+    /// Gets a snapshot of the pool's connection counters.
+    pub(crate) fn metrics(&self) -> PoolMetrics {
+        self.counters.snapshot()
+    }
 }
@@ -7,8 +7,9 @@ use typed_builder::TypedBuilder;
 use crate::{
     bson_util,
     client::auth::Credential,
+    compression::Compressor,
     event::cmap::CmapEventHandler,
-    options::{ClientOptions, DriverInfo, StreamAddress, TlsOptions},
+    options::{ClientOptions, DriverInfo, ServerApi, StreamAddress, TlsOptions},
 };
 
 /// Contains the options for creating a connection pool. While these options are specified at the
@@ -28,6 +29,17 @@ pub struct ConnectionPoolOptions {
     #[serde(skip)]
     pub connect_timeout: Option<Duration>,
 
+    /// The compressors to offer to the server when handshaking connections in this pool, in order
+    /// of preference.
+    #[builder(default)]
+    #[serde(skip)]
+    pub(crate) compressors: Option<Vec<Compressor>>,
+
+    /// The minimum size in bytes of a message sent to the server for it to be compressed.
+    #[builder(default)]
+    #[serde(skip)]
+    pub(crate) compression_threshold: Option<usize>,
+
     /// The credential to use for authenticating connections in this pool.
     #[builder(default)]
     #[serde(skip)]
@@ -39,12 +51,26 @@ pub struct ConnectionPoolOptions {
     #[serde(skip)]
     pub driver_info: Option<DriverInfo>,
 
+    /// The maximum amount of time establishing a connection may take, including connecting the
+    /// socket, the TLS handshake, the initial handshake with the server and authentication.
+    ///
+    /// The default is that only the individual steps are bounded, e.g. by `connect_timeout`.
+    #[builder(default)]
+    #[serde(skip)]
+    pub handshake_timeout: Option<Duration>,
+
     /// Processes all events generated by the pool.
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     #[builder(default)]
     #[serde(skip)]
     pub event_handler: Option<Arc<dyn CmapEventHandler>>,
 
+    /// Whether the pool's connections are to a load balancer. If so, the handshake indicates to the
+    /// server that the connection is load balanced.
+    #[builder(default)]
+    #[serde(skip)]
+    pub load_balanced: Option<bool>,
+
     /// Connections that have been ready for usage in the pool for longer than `max_idle_time` will
     /// not be used.
     ///
@@ -70,6 +96,14 @@ pub struct ConnectionPoolOptions {
     #[builder(default)]
     pub min_pool_size: Option<u32>,
 
+    /// The maximum number of connections that the pool can be establishing at a given time.
+    /// Check outs that need a new connection beyond that wait for one of the pending connections
+    /// to become ready instead.
+    ///
+    /// The default is 2.
+    #[builder(default)]
+    pub max_connecting: Option<u32>,
+
     /// The options specifying how a TLS connection should be configured. If `tls_options` is
     /// `None`, then TLS will not be used for the connections.
     ///
@@ -78,6 +112,12 @@ pub struct ConnectionPoolOptions {
     #[serde(skip)]
     pub tls_options: Option<TlsOptions>,
 
+    /// The Stable API version to declare on every command sent on the pool's connections,
+    /// including the handshake and authentication.
+    #[builder(default)]
+    #[serde(skip)]
+    pub(crate) server_api: Option<ServerApi>,
+
     /// Rather than wait indefinitely for a connection to become available, instead return an error
     /// after the given duration.
     ///
@@ -93,13 +133,19 @@ impl ConnectionPoolOptions {
     pub(crate) fn from_client_options(options: &ClientOptions) -> Self {
         Self::builder()
             .app_name(options.app_name.clone())
+            .compressors(options.compressors.clone())
+            .compression_threshold(options.compression_threshold)
             .connect_timeout(options.connect_timeout)
             .credential(options.credential.clone())
             .driver_info(options.driver_info.clone())
             .event_handler(options.cmap_event_handler.clone())
+            .handshake_timeout(options.handshake_timeout)
+            .load_balanced(options.load_balanced)
             .max_idle_time(options.max_idle_time)
             .max_pool_size(options.max_pool_size)
             .min_pool_size(options.min_pool_size)
+            .max_connecting(options.max_connecting)
+            .server_api(options.server_api.clone())
             .tls_options(options.tls_options())
             .wait_queue_timeout(options.wait_queue_timeout)
             .build()
@@ -111,8 +157,14 @@ impl ConnectionPoolOptions {
 #[derivative(Debug)]
 #[derive(Clone)]
 pub(crate) struct ConnectionOptions {
+    pub(crate) compressors: Option<Vec<Compressor>>,
+
+    pub(crate) compression_threshold: Option<usize>,
+
     pub(crate) connect_timeout: Option<Duration>,
 
+    pub(crate) server_api: Option<ServerApi>,
+
     pub(crate) tls_options: Option<TlsOptions>,
 
     #[derivative(Debug = "ignore")]
@@ -122,7 +174,10 @@ pub(crate) struct ConnectionOptions {
 impl From<ConnectionPoolOptions> for ConnectionOptions {
     fn from(pool_options: ConnectionPoolOptions) -> Self {
         Self {
+            compressors: pool_options.compressors,
+            compression_threshold: pool_options.compression_threshold,
             connect_timeout: pool_options.connect_timeout,
+            server_api: pool_options.server_api,
             tls_options: pool_options.tls_options,
             event_handler: pool_options.event_handler,
         }
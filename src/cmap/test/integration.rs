@@ -67,6 +67,107 @@ async fn acquire_connection_and_send_command() {
     assert!(names.iter().any(|name| name == "config"));
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn generation_in_events() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client_options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    let mut subscriber = handler.subscribe();
+    let mut pool_options = ConnectionPoolOptions::from_client_options(&client_options);
+    pool_options.event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+
+    let pool = ConnectionPool::new(
+        client_options.hosts[0].clone(),
+        Default::default(),
+        Some(pool_options),
+    );
+
+    let connection = pool.check_out().await.unwrap();
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| {
+            matches!(e, Event::ConnectionCheckedOut(event) if event.generation == 0)
+        })
+        .await
+        .expect("checked out event with generation 0 should have been seen");
+
+    // The connection checked out before the pool was cleared should be closed as stale when it is
+    // checked back in.
+    pool.clear();
+    drop(connection);
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| match e {
+            Event::ConnectionClosed(event) => {
+                event.generation == 0 && event.reason == ConnectionClosedReason::Stale
+            }
+            _ => false,
+        })
+        .await
+        .expect("stale closed event with generation 0 should have been seen");
+
+    let _connection = pool.check_out().await.unwrap();
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| {
+            matches!(e, Event::ConnectionCheckedOut(event) if event.generation == 1)
+        })
+        .await
+        .expect("checked out event with generation 1 should have been seen");
+
+    let events = handler.events.read().unwrap();
+    let created_generations: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::ConnectionCreated(event) => Some(event.generation),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(created_generations, vec![0, 1]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn idle_connections_closed_down_to_min_pool_size() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client_options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    let mut subscriber = handler.subscribe();
+    let mut pool_options = ConnectionPoolOptions::from_client_options(&client_options);
+    pool_options.event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    pool_options.max_idle_time = Some(Duration::from_millis(100));
+    pool_options.min_pool_size = Some(1);
+
+    let pool = ConnectionPool::new(
+        client_options.hosts[0].clone(),
+        Default::default(),
+        Some(pool_options),
+    );
+
+    let first = pool.check_out().await.unwrap();
+    let second = pool.check_out().await.unwrap();
+    drop(first);
+    drop(second);
+
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| match e {
+            Event::ConnectionClosed(event) => event.reason == ConnectionClosedReason::Idle,
+            _ => false,
+        })
+        .await
+        .expect("idle connection should have been closed in the background");
+
+    // The remaining connection is kept open to satisfy minPoolSize even though it's idle.
+    RUNTIME.delay_for(Duration::from_secs(1)).await;
+
+    let events = handler.events.read().unwrap();
+    let closed = events
+        .iter()
+        .filter(|e| matches!(e, Event::ConnectionClosed(_)))
+        .count();
+    assert_eq!(closed, 1);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn concurrent_connections() {
@@ -142,6 +243,52 @@ async fn concurrent_connections() {
         .expect("disabling fail point should succeed");
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn max_connecting_bounds_pending_connections() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let handler = Arc::new(EventHandler::new());
+    let client_options = CLIENT_OPTIONS.clone();
+    let mut options = ConnectionPoolOptions::from_client_options(&client_options);
+    options.event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    options.max_connecting = Some(1);
+    let pool = ConnectionPool::new(
+        client_options.hosts[0].clone(),
+        Default::default(),
+        Some(options),
+    );
+
+    let tasks = (0..3).map(|_| {
+        let pool_clone = pool.clone();
+        RUNTIME
+            .spawn(async move {
+                pool_clone.check_out().await.unwrap();
+            })
+            .unwrap()
+    });
+    futures::future::join_all(tasks).await;
+
+    // each connection must be ready before the next one starts being established.
+    let events = handler.events.read().unwrap();
+    let mut pending = 0;
+    for event in events.iter() {
+        match event {
+            Event::ConnectionCreated(_) => {
+                pending += 1;
+                assert!(
+                    pending <= 1,
+                    "more than one connection established at a time"
+                );
+            }
+            Event::ConnectionReady(_) => {
+                pending -= 1;
+            }
+            _ => (),
+        }
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test(threaded_scheduler))]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -182,7 +329,9 @@ async fn connection_error_during_establishment() {
     subscriber
         .wait_for_event(EVENT_TIMEOUT, |e| match e {
             Event::ConnectionClosed(event) => {
-                event.connection_id == 1 && event.reason == ConnectionClosedReason::Error
+                event.connection_id == 1
+                    && event.reason == ConnectionClosedReason::Error
+                    && event.error.is_some()
             }
             _ => false,
         })
@@ -226,7 +375,12 @@ async fn connection_error_during_operation() {
     subscriber
         .wait_for_event(EVENT_TIMEOUT, |e| match e {
             Event::ConnectionClosed(event) => {
-                event.connection_id == 1 && event.reason == ConnectionClosedReason::Error
+                event.connection_id == 1
+                    && event.reason == ConnectionClosedReason::Error
+                    && event
+                        .error
+                        .as_ref()
+                        .map_or(false, |error| error.is_network_error())
             }
             _ => false,
         })
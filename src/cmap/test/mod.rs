@@ -285,6 +285,7 @@ impl Matchable for ConnectionPoolOptions {
             && self.max_idle_time.matches(&expected.max_idle_time)
             && self.max_pool_size.matches(&expected.max_pool_size)
             && self.min_pool_size.matches(&expected.min_pool_size)
+            && self.max_connecting.matches(&expected.max_connecting)
             && self.tls_options.matches(&expected.tls_options)
             && self
                 .wait_queue_timeout
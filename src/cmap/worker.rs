@@ -12,9 +12,11 @@ use super::{
     manager::{ManagementRequestReceiver, PoolManagementRequest, PoolManager},
     options::{ConnectionOptions, ConnectionPoolOptions},
     Connection,
+    DEFAULT_MAX_CONNECTING,
     DEFAULT_MAX_POOL_SIZE,
 };
 use crate::{
+    client::metrics::PoolCounters,
     error::{Error, Result},
     event::cmap::{
         CmapEventHandler,
@@ -29,9 +31,7 @@ use crate::{
 };
 
 use std::{collections::VecDeque, sync::Arc, time::Duration};
-use tokio::sync::mpsc;
-
-const MAX_CONNECTING: u32 = 2;
+use tokio::sync::{mpsc, oneshot};
 
 /// A worker task that manages the shared state of the pool.
 #[derive(Derivative)]
@@ -72,6 +72,7 @@ pub(crate) struct ConnectionPoolWorker {
 
     /// Connections that have been ready for usage in the pool for longer than `max_idle_time` will
     /// be closed either by the background thread or when popped off of the set of available
+    /// connections, unless closing them would leave the pool with fewer than `min_pool_size`
     /// connections. If `max_idle_time` is `None`, then connections will not be closed due to being
     /// idle.
     max_idle_time: Option<Duration>,
@@ -88,6 +89,11 @@ pub(crate) struct ConnectionPoolWorker {
     /// wait_queue_timeout is exceeded.
     max_pool_size: u32,
 
+    /// The maximum number of connections that the pool can be establishing at a given time.
+    /// Requests that would need a new connection beyond that wait until one of the pending
+    /// connections is ready or another connection is checked back in.
+    max_connecting: u32,
+
     /// Receiver used to determine if any threads hold references to this pool. If all the
     /// sender ends of this receiver drop, this worker will be notified and drop too.
     handle_listener: HandleListener,
@@ -100,6 +106,9 @@ pub(crate) struct ConnectionPoolWorker {
 
     /// A pool manager that can be cloned and attached to connections checked out of the pool.
     manager: PoolManager,
+
+    /// The counters reported by `Client::metrics`, shared with the `ConnectionPool`.
+    counters: Arc<PoolCounters>,
 }
 
 impl ConnectionPoolWorker {
@@ -110,6 +119,7 @@ impl ConnectionPoolWorker {
         address: StreamAddress,
         http_client: HttpClient,
         options: Option<ConnectionPoolOptions>,
+        counters: Arc<PoolCounters>,
     ) -> (PoolManager, ConnectionRequester) {
         let establisher = ConnectionEstablisher::new(http_client, options.as_ref());
         let event_handler = options.as_ref().and_then(|opts| opts.event_handler.clone());
@@ -128,13 +138,18 @@ impl ConnectionPoolWorker {
 
         let min_pool_size = options.as_ref().and_then(|opts| opts.min_pool_size);
 
+        let max_connecting = options
+            .as_ref()
+            .and_then(|opts| opts.max_connecting)
+            .unwrap_or(DEFAULT_MAX_CONNECTING);
+
         let connection_options: Option<ConnectionOptions> = options
             .as_ref()
             .map(|pool_options| ConnectionOptions::from(pool_options.clone()));
 
         let (handle_listener, handle) = HandleListener::new();
         let (connection_requester, request_receiver) =
-            ConnectionRequester::new(address.clone(), handle);
+            ConnectionRequester::new(address.clone(), handle, counters.clone());
         let (manager, management_receiver) = PoolManager::new();
 
         let worker = ConnectionPoolWorker {
@@ -150,10 +165,12 @@ impl ConnectionPoolWorker {
             connection_options,
             available_connections: VecDeque::new(),
             max_pool_size,
+            max_connecting,
             request_receiver,
             management_receiver,
             manager: manager.clone(),
             handle_listener,
+            counters,
         };
 
         RUNTIME.execute(async move {
@@ -164,10 +181,11 @@ impl ConnectionPoolWorker {
     }
 
     /// Run the worker thread, listening on the various receivers until all handles have been
-    /// dropped. Once all handles are dropped, the pool will close any available connections and
-    /// emit a pool closed event.
+    /// dropped or the pool is explicitly closed. The pool will then close any available
+    /// connections and emit a pool closed event.
     async fn execute(mut self) {
         let mut maintenance_interval = RUNTIME.interval(Duration::from_millis(500));
+        let mut close_notifier = None;
 
         loop {
             let task = tokio::select! {
@@ -192,10 +210,20 @@ impl ConnectionPoolWorker {
                 PoolTask::CheckOut(result_sender) => self.check_out(result_sender).await,
                 PoolTask::CheckIn(connection) => self.check_in(connection),
                 PoolTask::Clear => self.clear(),
+                PoolTask::Close(notifier) => {
+                    self.clear();
+                    close_notifier = Some(notifier);
+                    break;
+                }
                 PoolTask::HandleConnectionSucceeded(c) => self.handle_connection_succeeded(c),
                 PoolTask::HandleConnectionFailed(error) => self.handle_connection_failed(error),
                 PoolTask::Maintenance => self.perform_maintenance(),
             }
+
+            self.counters.record_pool_state(
+                self.available_connections.len() as u32,
+                self.pending_connection_count,
+            );
         }
 
         while let Some(connection) = self.available_connections.pop_front() {
@@ -207,12 +235,21 @@ impl ConnectionPoolWorker {
                 address: self.address.clone(),
             });
         });
+
+        if let Some(notifier) = close_notifier {
+            let _: std::result::Result<_, _> = notifier.send(());
+        }
     }
 
     fn can_service_connection_request(&self) -> bool {
-        (self.total_connection_count < self.max_pool_size
-            && self.pending_connection_count < MAX_CONNECTING)
-            || !self.available_connections.is_empty()
+        self.can_create_connection() || !self.available_connections.is_empty()
+    }
+
+    /// Whether a new connection can be established without exceeding either `max_pool_size` or
+    /// `max_connecting`.
+    fn can_create_connection(&self) -> bool {
+        self.total_connection_count < self.max_pool_size
+            && self.pending_connection_count < self.max_connecting
     }
 
     async fn check_out(&mut self, request: ConnectionRequest) {
@@ -225,7 +262,7 @@ impl ConnectionPoolWorker {
             }
 
             // Close the connection if it's idle.
-            if conn.is_idle(self.max_idle_time) {
+            if self.should_close_idle(&conn) {
                 self.close_connection(conn, ConnectionClosedReason::Idle);
                 continue;
             }
@@ -243,7 +280,7 @@ impl ConnectionPoolWorker {
         }
 
         // otherwise, attempt to create a connection.
-        if self.total_connection_count < self.max_pool_size {
+        if self.can_create_connection() {
             let event_handler = self.event_handler.clone();
             let establisher = self.establisher.clone();
             let pending_connection = self.create_pending_connection();
@@ -290,6 +327,8 @@ impl ConnectionPoolWorker {
     fn create_pending_connection(&mut self) -> PendingConnection {
         self.total_connection_count += 1;
         self.pending_connection_count += 1;
+        self.counters
+            .record_connection_created(self.total_connection_count);
 
         let pending_connection = PendingConnection {
             id: self.next_connection_id,
@@ -315,6 +354,8 @@ impl ConnectionPoolWorker {
         // connection count.
         self.total_connection_count -= 1;
         self.pending_connection_count -= 1;
+        self.counters
+            .record_connection_closed(self.total_connection_count);
     }
 
     /// Process a successful connection establishment, optionally populating the pool with the
@@ -370,6 +411,8 @@ impl ConnectionPoolWorker {
     fn close_connection(&mut self, connection: Connection, reason: ConnectionClosedReason) {
         connection.close_and_drop(reason);
         self.total_connection_count -= 1;
+        self.counters
+            .record_connection_closed(self.total_connection_count);
     }
 
     /// Ensure all connections in the pool are valid and that the pool is managing at least
@@ -379,18 +422,27 @@ impl ConnectionPoolWorker {
         self.ensure_min_connections();
     }
 
-    /// Iterate over the connections and remove any that are stale or idle.
+    /// Whether the connection has been idle for longer than `max_idle_time` and can be closed
+    /// without the pool dropping below `min_pool_size`.
+    fn should_close_idle(&self, connection: &Connection) -> bool {
+        connection.is_idle(self.max_idle_time)
+            && self.total_connection_count > self.min_pool_size.unwrap_or(0)
+    }
+
+    /// Iterate over the connections and remove any that are stale or idle, keeping idle ones that
+    /// are needed to satisfy `min_pool_size`.
     fn remove_perished_connections(&mut self) {
         while let Some(connection) = self.available_connections.pop_front() {
             if connection.is_stale(self.generation) {
                 // the following unwrap is okay becaue we asserted the pool was nonempty
                 self.close_connection(connection, ConnectionClosedReason::Stale);
-            } else if connection.is_idle(self.max_idle_time) {
+            } else if self.should_close_idle(&connection) {
                 self.close_connection(connection, ConnectionClosedReason::Idle);
             } else {
                 self.available_connections.push_front(connection);
                 // All subsequent connections are either not idle or not stale since they were
-                // checked into the pool later, so we can just quit early.
+                // checked into the pool later, so we can just quit early. If the pool is at its
+                // minimum size, no more idle connections can be closed anyway.
                 break;
             };
         }
@@ -399,9 +451,7 @@ impl ConnectionPoolWorker {
     /// Populate the the pool with enough connections to meet the min_pool_size_requirement.
     fn ensure_min_connections(&mut self) {
         if let Some(min_pool_size) = self.min_pool_size {
-            while self.total_connection_count < min_pool_size
-                && self.pending_connection_count < MAX_CONNECTING
-            {
+            while self.total_connection_count < min_pool_size && self.can_create_connection() {
                 let pending_connection = self.create_pending_connection();
                 let event_handler = self.event_handler.clone();
                 let manager = self.manager.clone();
@@ -434,6 +484,7 @@ async fn establish_connection(
     event_handler: Option<&Arc<dyn CmapEventHandler>>,
 ) -> Result<Connection> {
     let connection_id = pending_connection.id;
+    let generation = pending_connection.generation;
     let address = pending_connection.address.clone();
 
     let mut establish_result = establisher.establish_connection(pending_connection).await;
@@ -445,6 +496,8 @@ async fn establish_connection(
                     address,
                     reason: ConnectionClosedReason::Error,
                     connection_id,
+                    generation,
+                    error: Some(e.clone()),
                 };
                 handler.handle_connection_closed_event(event);
             }
@@ -466,6 +519,9 @@ enum PoolTask {
     /// Clear the pool.
     Clear,
 
+    /// Close the pool, notifying the given sender once it's closed.
+    Close(oneshot::Sender<()>),
+
     /// Check in the given connection.
     CheckIn(Connection),
 
@@ -488,6 +544,7 @@ impl From<PoolManagementRequest> for PoolTask {
         match request {
             PoolManagementRequest::CheckIn(c) => PoolTask::CheckIn(c),
             PoolManagementRequest::Clear => PoolTask::Clear,
+            PoolManagementRequest::Close(notifier) => PoolTask::Close(notifier),
             PoolManagementRequest::HandleConnectionFailed(error) => {
                 PoolTask::HandleConnectionFailed(error)
             }
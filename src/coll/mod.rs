@@ -1,9 +1,18 @@
-mod batch;
 pub mod options;
 
-use std::{fmt, fmt::Debug, sync::Arc};
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    fmt::Debug,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
 use serde::{
     de::{DeserializeOwned, Error},
     Deserialize,
@@ -13,34 +22,53 @@ use serde::{
 
 use self::options::*;
 use crate::{
-    bson::{doc, ser, to_document, Bson, Document},
+    bson::{doc, from_document, ser, to_document, Bson, Document},
     bson_util,
+    change_stream::{event::ChangeStreamEvent, ChangeStream, ChangeStreamTarget},
+    client::ClientSession,
+    cmap::PinnedConnection,
     concern::{ReadConcern, WriteConcern},
     error::{convert_bulk_errors, BulkWriteError, BulkWriteFailure, ErrorKind, Result},
     operation::{
         Aggregate,
+        BulkWrite,
+        BulkWriteKind,
         Count,
         CountDocuments,
+        CreateIndexes,
         Delete,
         Distinct,
         DropCollection,
+        DropIndexes,
+        Explain,
         Find,
         FindAndModify,
         Insert,
+        ListIndexes,
+        RunCommand,
         Update,
+        Validate,
     },
-    results::{DeleteResult, InsertManyResult, InsertOneResult, UpdateResult},
-    selection_criteria::SelectionCriteria,
+    index::IndexModel,
+    options::{ChangeStreamOptions, CreateCollectionOptions},
+    results::{
+        BulkWriteResult,
+        CreateIndexResult,
+        CreateIndexesResult,
+        DeleteResult,
+        InsertManyResult,
+        InsertOneResult,
+        LatencyStats,
+        UpdateResult,
+        ValidationResult,
+    },
+    selection_criteria::{ReadPreference, SelectionCriteria},
     Client,
     Cursor,
     Database,
+    SessionCursor,
 };
 
-/// Maximum size in bytes of an insert batch.
-/// This is intentionally less than the actual max document size, which is 16*1024*1024 bytes, to
-/// allow for overhead in the command document.
-const MAX_INSERT_DOCS_BYTES: usize = 16 * 1000 * 1000;
-
 /// `Collection` is the client-side abstraction of a MongoDB Collection. It can be used to
 /// perform collection-level operations such as CRUD operations. A `Collection` can be obtained
 /// through a [`Database`](struct.Database.html) by calling either
@@ -79,6 +107,77 @@ const MAX_INSERT_DOCS_BYTES: usize = 16 * 1000 * 1000;
 /// # Ok(())
 /// # }
 /// ```
+///
+/// ## Serialization of `T`
+///
+/// Values of type `T` are converted to BSON documents with
+/// [`bson::to_document`](https://docs.rs/bson/1.2.0/bson/fn.to_document.html), which uses the
+/// default [`bson::Serializer`](https://docs.rs/bson/1.2.0/bson/struct.Serializer.html). This
+/// version of `bson` does not expose any serializer options, so the representation of a type is
+/// controlled entirely by its `Serialize` implementation. In particular, enums are encoded using
+/// serde's default "externally tagged" representation: unit variants become strings, and all
+/// other variants become a document with a single key containing the variant name.
+///
+/// To store enums in a form that is easier to query, use serde's container attributes, e.g.
+/// `#[serde(tag = "type")]` for an internally tagged representation, `#[serde(tag = "t", content
+/// = "c")]` for an adjacently tagged one, or `#[serde(untagged)]` to omit the variant name
+/// entirely:
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Debug, Deserialize, Serialize)]
+/// #[serde(tag = "kind", rename_all = "camelCase")]
+/// enum Shape {
+///     Circle { radius: f64 },
+///     Square { side: f64 },
+/// }
+///
+/// // `Shape::Circle { radius: 1.0 }` is stored as `{ "kind": "circle", "radius": 1.0 }`, so it
+/// // can be queried with a filter like `doc! { "shape.kind": "circle" }`.
+/// ```
+///
+/// Because the BSON representation of a value is determined by its type's `Serialize` and
+/// `Deserialize` implementations, there is no separate registry of codecs. To store a domain type
+/// in a non-default form everywhere it is used, implement (or derive) those traits on the type
+/// itself rather than annotating each field that contains it with `#[serde(with = "...")]`. For
+/// example, serde's `into` and `from` container attributes can be used to store an amount of money
+/// as an integer number of cents:
+///
+/// ```rust
+/// # use mongodb::bson::{doc, from_document, to_document};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+/// #[serde(into = "i64", from = "i64")]
+/// struct Money {
+///     cents: i64,
+/// }
+///
+/// impl From<Money> for i64 {
+///     fn from(money: Money) -> Self {
+///         money.cents
+///     }
+/// }
+///
+/// impl From<i64> for Money {
+///     fn from(cents: i64) -> Self {
+///         Money { cents }
+///     }
+/// }
+///
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Order {
+///     item: String,
+///     price: Money,
+/// }
+///
+/// let order = Order {
+///     item: "book".to_string(),
+///     price: Money { cents: 1250 },
+/// };
+/// let document = to_document(&order).unwrap();
+/// assert_eq!(document, doc! { "item": "book", "price": 1250_i64 });
+/// assert_eq!(from_document::<Order>(document).unwrap(), order);
+/// ```
 
 #[derive(Debug, Clone)]
 pub struct Collection<T = Document>
@@ -97,6 +196,10 @@ struct CollectionInner {
     selection_criteria: Option<SelectionCriteria>,
     read_concern: Option<ReadConcern>,
     write_concern: Option<WriteConcern>,
+    ensure_created_with: Option<CreateCollectionOptions>,
+
+    /// Whether the collection has been created with `ensure_created_with`.
+    ensured_created: AtomicBool,
 }
 
 impl<T> Collection<T>
@@ -123,6 +226,8 @@ where
                 selection_criteria,
                 read_concern,
                 write_concern,
+                ensure_created_with: options.ensure_created_with,
+                ensured_created: AtomicBool::new(false),
             }),
             _phantom: Default::default(),
         }
@@ -136,10 +241,36 @@ where
         options.selection_criteria = self.inner.selection_criteria.clone();
         options.read_concern = self.inner.read_concern.clone();
         options.write_concern = self.inner.write_concern.clone();
+        options.ensure_created_with = self.inner.ensure_created_with.clone();
 
         Collection::new(self.inner.db.clone(), &self.inner.name, Some(options))
     }
 
+    /// Creates the collection with the `ensure_created_with` options, if any were set and the
+    /// collection hasn't been created through this `Collection` yet.
+    async fn ensure_created(&self) -> Result<()> {
+        let options = match self.inner.ensure_created_with {
+            Some(ref options) if !self.inner.ensured_created.load(Ordering::SeqCst) => {
+                options.clone()
+            }
+            _ => return Ok(()),
+        };
+
+        match self
+            .inner
+            .db
+            .create_collection(&self.inner.name, options)
+            .await
+        {
+            Ok(()) => {}
+            Err(e) if e.is_ns_exists() => {}
+            Err(e) => return Err(e),
+        }
+
+        self.inner.ensured_created.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
     /// Get the `Client` that this collection descended from.
     fn client(&self) -> &Client {
         &self.inner.client
@@ -190,7 +321,8 @@ where
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
-    /// information on aggregations.
+    /// information on aggregations. The pipeline can be built with a
+    /// [`Pipeline`](agg/struct.Pipeline.html).
     pub async fn aggregate(
         &self,
         pipeline: impl IntoIterator<Item = Document>,
@@ -211,7 +343,200 @@ where
             .map(|(spec, session)| Cursor::new(client.clone(), spec, session))
     }
 
-    /// Estimates the number of documents in the collection using collection metadata.
+    /// Runs an aggregation operation using the provided `ClientSession`.
+    ///
+    /// The returned [`SessionCursor`](struct.SessionCursor.html) must be iterated using the same
+    /// session. See [`aggregate`](#method.aggregate) for more information on aggregations.
+    pub async fn aggregate_with_session(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<SessionCursor> {
+        let mut options = options.into();
+        resolve_options!(
+            self,
+            options,
+            [read_concern, write_concern, selection_criteria]
+        );
+
+        let aggregate = Aggregate::new(self.namespace(), pipeline, options);
+        let client = self.client();
+        client
+            .execute_cursor_operation_with_session(aggregate, session)
+            .await
+            .map(|spec| SessionCursor::new(client.clone(), spec))
+    }
+
+    /// Performs the writes in `requests`, which may be any mix of inserts, updates, replacements
+    /// and deletes. Consecutive writes of the same kind are sent to the server together, split
+    /// into as many commands as needed.
+    ///
+    /// If [`ordered`](options/struct.BulkWriteOptions.html#structfield.ordered) is true (the
+    /// default), the operation stops at the first write that fails; otherwise the remaining writes
+    /// are still attempted. Either way, a failure is returned as an `ErrorKind::BulkWriteError`
+    /// whose [`partial_result`](error/struct.BulkWriteFailure.html#structfield.partial_result)
+    /// holds the counts of the writes that succeeded. The indexes in the result and in the write
+    /// errors refer to the position of the write in `requests`.
+    ///
+    /// See the documentation of [`insert_one`](#method.insert_one) for details on how the `_id`
+    /// field of inserted documents is handled.
+    ///
+    /// Commands that contain neither `UpdateMany` nor `DeleteMany` writes will retry once upon
+    /// failure if the connection and encountered error support retryability. See the
+    /// documentation [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more
+    /// information on retryable writes.
+    pub async fn bulk_write(
+        &self,
+        requests: impl IntoIterator<Item = WriteModel<T>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+    ) -> Result<BulkWriteResult> {
+        // Group consecutive writes of the same kind, as each group is sent as one kind of command.
+        let mut groups: Vec<(BulkWriteKind, Vec<Document>)> = Vec::new();
+        for request in requests {
+            let (kind, statement) = self.bulk_write_statement(request)?;
+            match groups.last_mut() {
+                Some((last_kind, statements)) if *last_kind == kind => statements.push(statement),
+                _ => groups.push((kind, vec![statement])),
+            }
+        }
+
+        if groups.is_empty() {
+            return Err(ErrorKind::ArgumentError {
+                message: "No writes provided to bulk_write".to_string(),
+            }
+            .into());
+        }
+
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
+
+        let ordered = options.as_ref().and_then(|o| o.ordered).unwrap_or(true);
+
+        let mut cumulative_result = BulkWriteResult::default();
+        let mut cumulative_failure: Option<BulkWriteFailure> = None;
+
+        let mut n_attempted = 0;
+
+        for (kind, statements) in groups {
+            // Each command sends as many of the remaining statements of the group as the server
+            // accepts.
+            let mut n_group_attempted = 0;
+            while n_group_attempted < statements.len() {
+                let batch_len = AtomicUsize::new(0);
+                let bulk_write = BulkWrite::new(
+                    self.namespace(),
+                    kind,
+                    &statements[n_group_attempted..],
+                    options.clone(),
+                    &batch_len,
+                );
+                let result = self.client().execute_operation(bulk_write).await;
+
+                let offset = n_attempted;
+                let batch_len = batch_len.load(Ordering::SeqCst);
+                n_attempted += batch_len;
+                n_group_attempted += batch_len;
+
+                match result {
+                    Ok(result) => cumulative_result.merge(result, offset),
+                    Err(e) => match e.kind.as_ref() {
+                        ErrorKind::BulkWriteError(failure) => {
+                            if let Some(ref result) = failure.partial_result {
+                                cumulative_result.merge(result.clone(), offset);
+                            }
+
+                            let failure_ref =
+                                cumulative_failure.get_or_insert_with(BulkWriteFailure::new);
+                            if let Some(ref write_errors) = failure.write_errors {
+                                failure_ref
+                                    .write_errors
+                                    .get_or_insert_with(Default::default)
+                                    .extend(write_errors.iter().map(|error| BulkWriteError {
+                                        index: error.index + offset,
+                                        ..error.clone()
+                                    }));
+                            }
+                            if let Some(ref write_concern_error) = failure.write_concern_error {
+                                failure_ref.write_concern_error = Some(write_concern_error.clone());
+                            }
+
+                            if ordered && failure.write_errors.is_some() {
+                                let mut failure =
+                                    cumulative_failure.unwrap_or_else(BulkWriteFailure::new);
+                                failure.partial_result = Some(cumulative_result);
+                                return Err(ErrorKind::BulkWriteError(failure).into());
+                            }
+                        }
+                        _ => return Err(e),
+                    },
+                }
+            }
+        }
+
+        if let Some(mut failure) = cumulative_failure {
+            failure.partial_result = Some(cumulative_result);
+            return Err(ErrorKind::BulkWriteError(failure).into());
+        }
+
+        Ok(cumulative_result)
+    }
+
+    /// Converts a `WriteModel` into the statement sent to the server for it.
+    fn bulk_write_statement(&self, request: WriteModel<T>) -> Result<(BulkWriteKind, Document)> {
+        let statement = match request {
+            WriteModel::InsertOne { document } => {
+                let mut document = to_document(&document)?;
+                self.client().add_generated_id(&mut document);
+                (BulkWriteKind::Insert, document)
+            }
+            WriteModel::UpdateOne {
+                filter,
+                update,
+                upsert,
+            } => (
+                BulkWriteKind::Update,
+                update_statement(filter, update, upsert, false)?,
+            ),
+            WriteModel::UpdateMany {
+                filter,
+                update,
+                upsert,
+            } => (
+                BulkWriteKind::Update,
+                update_statement(filter, update, upsert, true)?,
+            ),
+            WriteModel::ReplaceOne {
+                filter,
+                replacement,
+                upsert,
+            } => {
+                let replacement = to_document(&replacement)?;
+                bson_util::replacement_document_check(&replacement)?;
+                let mut statement = doc! { "q": filter, "u": replacement };
+                if let Some(upsert) = upsert {
+                    statement.insert("upsert", upsert);
+                }
+                (BulkWriteKind::Update, statement)
+            }
+            WriteModel::DeleteOne { filter } => {
+                (BulkWriteKind::Delete, doc! { "q": filter, "limit": 1 })
+            }
+            WriteModel::DeleteMany { filter } => {
+                (BulkWriteKind::Delete, doc! { "q": filter, "limit": 0 })
+            }
+        };
+        Ok(statement)
+    }
+
+    /// Estimates the number of documents in the collection using collection metadata, which is
+    /// fast regardless of the size of the collection. The result may be inaccurate, e.g. right
+    /// after an unclean shutdown or while bulk operations or orphaned documents in a sharded
+    /// cluster are being processed; use
+    /// [`Collection::count_documents`](#method.count_documents) when an exact count is needed.
+    ///
+    /// The count is retried once as a retryable read if it fails due to a transient error.
     pub async fn estimated_document_count(
         &self,
         options: impl Into<Option<EstimatedDocumentCountOptions>>,
@@ -223,32 +548,117 @@ where
         self.client().execute_operation(op).await
     }
 
-    /// Gets the number of documents matching `filter`.
-    ///
-    /// Note that using [`Collection::estimated_document_count`](#method.estimated_document_count)
-    /// is recommended instead of this method is most cases.
-    pub async fn count_documents(
+    async fn count_documents_common(
         &self,
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<CountOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<i64> {
         let options = options.into();
         let filter = filter.into();
         let op = CountDocuments::new(self.namespace(), filter, options);
+        self.client()
+            .execute_operation_with_optional_session(op, session.into())
+            .await
+    }
+
+    /// Gets the number of documents matching `filter`.
+    ///
+    /// The count is exact, as it is computed by an aggregation with `$match` and `$group` stages
+    /// that scans the matching documents; for large collections this can be slow. When a fast
+    /// estimate of the total number of documents is enough,
+    /// [`Collection::estimated_document_count`](#method.estimated_document_count) should be used
+    /// instead.
+    pub async fn count_documents(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<CountOptions>>,
+    ) -> Result<i64> {
+        self.count_documents_common(filter, options, None).await
+    }
+
+    /// Gets the number of documents matching `filter` using the provided `ClientSession`.
+    pub async fn count_documents_with_session(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<CountOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<i64> {
+        self.count_documents_common(filter, options, session).await
+    }
+
+    /// Creates the given index on this collection.
+    pub async fn create_index(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<CreateIndexResult> {
+        self.create_indexes(vec![index], options)
+            .await
+            .map(CreateIndexesResult::into_create_index_result)
+    }
+
+    /// Creates the given indexes on this collection.
+    pub async fn create_indexes(
+        &self,
+        indexes: impl IntoIterator<Item = IndexModel>,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<CreateIndexesResult> {
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+
+        let op = CreateIndexes::new(self.namespace(), indexes.into_iter().collect(), options);
         self.client().execute_operation(op).await
     }
 
+    async fn delete_many_common(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<DeleteResult> {
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+
+        let delete = Delete::new(self.namespace(), query, None, options);
+        self.client()
+            .execute_operation_with_optional_session(delete, session.into())
+            .await
+    }
+
     /// Deletes all documents stored in the collection matching `query`.
     pub async fn delete_many(
         &self,
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
+    ) -> Result<DeleteResult> {
+        self.delete_many_common(query, options, None).await
+    }
+
+    /// Deletes all documents stored in the collection matching `query` using the provided
+    /// `ClientSession`.
+    pub async fn delete_many_with_session(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<DeleteResult> {
+        self.delete_many_common(query, options, session).await
+    }
+
+    async fn delete_one_common(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<DeleteResult> {
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
 
-        let delete = Delete::new(self.namespace(), query, None, options);
-        self.client().execute_operation(delete).await
+        let delete = Delete::new(self.namespace(), query, Some(1), options);
+        self.client()
+            .execute_operation_with_optional_session(delete, session.into())
+            .await
     }
 
     /// Deletes up to one document found matching `query`.
@@ -262,19 +672,25 @@ where
         query: Document,
         options: impl Into<Option<DeleteOptions>>,
     ) -> Result<DeleteResult> {
-        let mut options = options.into();
-        resolve_options!(self, options, [write_concern]);
+        self.delete_one_common(query, options, None).await
+    }
 
-        let delete = Delete::new(self.namespace(), query, Some(1), options);
-        self.client().execute_operation(delete).await
+    /// Deletes up to one document found matching `query` using the provided `ClientSession`.
+    pub async fn delete_one_with_session(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<DeleteResult> {
+        self.delete_one_common(query, options, session).await
     }
 
-    /// Finds the distinct values of the field specified by `field_name` across the collection.
-    pub async fn distinct(
+    async fn distinct_common(
         &self,
         field_name: &str,
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<DistinctOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<Vec<Bson>> {
         let mut options = options.into();
         resolve_options!(self, options, [read_concern, selection_criteria]);
@@ -285,9 +701,124 @@ where
             filter.into(),
             options,
         );
+        self.client()
+            .execute_operation_with_optional_session(op, session.into())
+            .await
+    }
+
+    /// Finds the distinct values of the field specified by `field_name` across the collection.
+    pub async fn distinct(
+        &self,
+        field_name: &str,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<DistinctOptions>>,
+    ) -> Result<Vec<Bson>> {
+        self.distinct_common(field_name, filter, options, None)
+            .await
+    }
+
+    /// Finds the distinct values of the field specified by `field_name` across the collection using
+    /// the provided `ClientSession`.
+    pub async fn distinct_with_session(
+        &self,
+        field_name: &str,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<DistinctOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<Vec<Bson>> {
+        self.distinct_common(field_name, filter, options, session)
+            .await
+    }
+
+    /// Drops the index with the given `name` from this collection. The name of an index created
+    /// without one specified is the one returned by
+    /// [`IndexModel::name`](index/struct.IndexModel.html#method.name).
+    pub async fn drop_index(
+        &self,
+        name: &str,
+        options: impl Into<Option<DropIndexOptions>>,
+    ) -> Result<()> {
+        if name == "*" {
+            return Err(ErrorKind::ArgumentError {
+                message: "to drop all indexes, use drop_indexes".to_string(),
+            }
+            .into());
+        }
+
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+
+        let op = DropIndexes::new(self.namespace(), Some(name.to_string()), options);
         self.client().execute_operation(op).await
     }
 
+    /// Drops all of the indexes on this collection, except for the one on `_id`.
+    pub async fn drop_indexes(&self, options: impl Into<Option<DropIndexOptions>>) -> Result<()> {
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+
+        let op = DropIndexes::new(self.namespace(), None, options);
+        self.client().execute_operation(op).await
+    }
+
+    /// Returns whether any documents in the collection match `filter`.
+    ///
+    /// This is cheaper than `find_one` for existence checks, as only the `_id` of at most one
+    /// matching document is returned by the server, and no documents are deserialized into `T`.
+    pub async fn exists(&self, filter: impl Into<Option<Document>>) -> Result<bool> {
+        let options = FindOptions::builder()
+            .limit(-1)
+            .projection(doc! { "_id": 1 })
+            .build();
+        let find = Find::new(self.namespace(), filter.into(), Some(options));
+        let client = self.client();
+
+        let (spec, session) = client.execute_cursor_operation(find).await?;
+        let mut cursor: Cursor<Document> = Cursor::new(client.clone(), spec, session);
+        cursor.next().await.transpose().map(|doc| doc.is_some())
+    }
+
+    /// Reports how the server would execute an aggregation with the given `pipeline` and
+    /// `options`, without running it. The raw output of the server's `explain` command is
+    /// returned; its contents are described
+    /// [here](https://docs.mongodb.com/manual/reference/explain-results/).
+    ///
+    /// Any read concern or write concern in `options` is ignored, since they can't be explained.
+    pub async fn explain_aggregate(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<Document> {
+        let mut options = options.into();
+        if let Some(ref mut options) = options {
+            options.read_concern = None;
+            options.write_concern = None;
+        }
+        resolve_options!(self, options, [selection_criteria]);
+
+        let aggregate = Aggregate::new(self.namespace(), pipeline, options);
+        let explain = Explain::new(aggregate, verbosity);
+        self.client().execute_operation(explain).await
+    }
+
+    /// Reports how the server would execute a find with the given `filter` and `options`, without
+    /// running it. The raw output of the server's `explain` command is returned; its contents are
+    /// described [here](https://docs.mongodb.com/manual/reference/explain-results/).
+    pub async fn explain_find(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOptions>>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<Document> {
+        let mut options = options.into();
+        resolve_options!(self, options, [selection_criteria]);
+
+        let find = Find::new(self.namespace(), filter.into(), options);
+        let explain = Explain::new(find, verbosity);
+        self.client().execute_operation(explain).await
+    }
+
     /// Finds the documents in the collection matching `filter`.
     pub async fn find(
         &self,
@@ -303,6 +834,26 @@ where
             .map(|(result, session)| Cursor::new(client.clone(), result, session))
     }
 
+    /// Finds the documents in the collection matching `filter` using the provided
+    /// `ClientSession`.
+    ///
+    /// The returned [`SessionCursor`](struct.SessionCursor.html) must be iterated using the same
+    /// session.
+    pub async fn find_with_session(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<SessionCursor<T>> {
+        let find = Find::new(self.namespace(), filter.into(), options.into());
+        let client = self.client();
+
+        client
+            .execute_cursor_operation_with_session(find, session)
+            .await
+            .map(|result| SessionCursor::new(client.clone(), result))
+    }
+
     /// Finds a single document in the collection matching `filter`.
     pub async fn find_one(
         &self,
@@ -318,6 +869,34 @@ where
         cursor.next().await.transpose()
     }
 
+    /// Finds a single document in the collection matching `filter` using the provided
+    /// `ClientSession`.
+    pub async fn find_one_with_session(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOneOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<Option<T>> {
+        let mut options: FindOptions = options
+            .into()
+            .map(Into::into)
+            .unwrap_or_else(Default::default);
+        options.limit = Some(-1);
+
+        // A negative limit makes the server return the document in the first batch and close the
+        // cursor, so no getMore is ever needed.
+        let find = Find::new(self.namespace(), filter.into(), Some(options));
+        let spec = self
+            .client()
+            .execute_operation_with_session(find, session)
+            .await?;
+        spec.initial_buffer
+            .into_iter()
+            .next()
+            .map(|doc| from_document(doc).map_err(Into::into))
+            .transpose()
+    }
+
     /// Atomically finds up to one document in the collection matching `filter` and deletes it.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
@@ -346,13 +925,14 @@ where
     pub async fn find_one_and_replace(
         &self,
         filter: Document,
-        replacement: T,
+        replacement: impl Borrow<T>,
         options: impl Into<Option<FindOneAndReplaceOptions>>,
     ) -> Result<Option<T>> {
-        let replacement = to_document(&replacement)?;
+        let replacement = to_document(replacement.borrow())?;
 
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
 
         let op = FindAndModify::<T>::with_replace(self.namespace(), filter, replacement, options)?;
         self.client().execute_operation(op).await
@@ -376,30 +956,31 @@ where
         let update = update.into();
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
 
         let op = FindAndModify::<T>::with_update(self.namespace(), filter, update, options)?;
         self.client().execute_operation(op).await
     }
 
-    /// Inserts the data in `docs` into the collection.
-    ///
-    /// This operation will retry once upon failure if the connection and encountered error support
-    /// retryability. See the documentation
-    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
-    /// retryable writes.
-    pub async fn insert_many(
+    async fn insert_many_common(
         &self,
-        docs: impl IntoIterator<Item = T>,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
         options: impl Into<Option<InsertManyOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<InsertManyResult> {
+        let session: Option<&mut ClientSession> = session.into();
         let docs: ser::Result<Vec<Document>> = docs
             .into_iter()
-            .map(|doc| bson::to_document(&doc))
+            .map(|doc| bson::to_document(doc.borrow()))
             .collect();
         let mut docs: Vec<Document> = docs?;
+        for doc in docs.iter_mut() {
+            self.client().add_generated_id(doc);
+        }
 
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
 
         if docs.is_empty() {
             return Err(ErrorKind::ArgumentError {
@@ -409,33 +990,51 @@ where
         }
 
         let ordered = options.as_ref().and_then(|o| o.ordered).unwrap_or(true);
+        let return_documents = options.as_ref().and_then(|o| o.return_documents) == Some(true);
+
+        // Without a session of the caller's, the documents are inserted and read back in one
+        // causally consistent implicit session so that the read observes the inserts.
+        let mut implicit_session = None;
+        if return_documents && session.is_none() {
+            let is_acknowledged = options
+                .as_ref()
+                .and_then(|o| o.write_concern.as_ref())
+                .map(WriteConcern::is_acknowledged)
+                .unwrap_or(true);
+            if is_acknowledged {
+                implicit_session = self
+                    .client()
+                    .start_causally_consistent_implicit_session()
+                    .await?;
+            }
+        }
+        let mut session = session.or(implicit_session.as_mut());
 
         let mut cumulative_failure: Option<BulkWriteFailure> = None;
-        let mut cumulative_result: Option<InsertManyResult> = None;
+        let mut cumulative_result = InsertManyResult::new();
 
+        // Each insert sends as many of the remaining documents as the server accepts in a single
+        // command. The indexes of the documents sent in each successful one are recorded so that
+        // they can be read back in batches that also fit in a single command.
         let mut n_attempted = 0;
-
-        while !docs.is_empty() {
-            let mut remaining_docs =
-                batch::split_off_batch(&mut docs, MAX_INSERT_DOCS_BYTES, bson_util::doc_size_bytes);
-            std::mem::swap(&mut docs, &mut remaining_docs);
-            let current_batch = remaining_docs;
-
-            let current_batch_size = current_batch.len();
-            n_attempted += current_batch_size;
-
-            let insert = Insert::new(self.namespace(), current_batch, options.clone());
-            match self.client().execute_operation(insert).await {
+        let mut batches = Vec::new();
+
+        while n_attempted < docs.len() {
+            let insert = Insert::new(self.namespace(), &mut docs[n_attempted..], options.clone());
+            match self
+                .client()
+                .execute_operation_with_optional_session(insert, session.as_deref_mut())
+                .await
+            {
                 Ok(result) => {
-                    if cumulative_failure.is_none() {
-                        let cumulative_result =
-                            cumulative_result.get_or_insert_with(InsertManyResult::new);
-                        for (index, id) in result.inserted_ids {
-                            cumulative_result
-                                .inserted_ids
-                                .insert(index + n_attempted - current_batch_size, id);
-                        }
+                    let current_batch_size = result.inserted_ids.len();
+                    for (index, id) in result.inserted_ids {
+                        cumulative_result
+                            .inserted_ids
+                            .insert(index + n_attempted, id);
                     }
+                    batches.push(n_attempted..n_attempted + current_batch_size);
+                    n_attempted += current_batch_size;
                 }
                 Err(e) => match e.kind.as_ref() {
                     ErrorKind::BulkWriteError(failure) => {
@@ -446,77 +1045,243 @@ where
                                 .write_errors
                                 .get_or_insert_with(Default::default)
                                 .extend(write_errors.iter().map(|error| BulkWriteError {
-                                    index: error.index + n_attempted - current_batch_size,
+                                    index: error.index + n_attempted,
                                     ..error.clone()
                                 }));
                         }
                         if let Some(ref write_concern_error) = failure.write_concern_error {
                             failure_ref.write_concern_error = Some(write_concern_error.clone());
                         }
+                        for (index, id) in failure.inserted_ids.iter() {
+                            cumulative_result
+                                .inserted_ids
+                                .insert(index + n_attempted, id.clone());
+                        }
 
-                        if ordered {
-                            return Err(ErrorKind::BulkWriteError(
-                                cumulative_failure.unwrap_or_else(BulkWriteFailure::new),
-                            )
-                            .into());
+                        if ordered && failure.write_errors.is_some() {
+                            let mut failure =
+                                cumulative_failure.unwrap_or_else(BulkWriteFailure::new);
+                            failure.inserted_ids = cumulative_result.inserted_ids;
+                            return Err(ErrorKind::BulkWriteError(failure).into());
                         }
+
+                        // The documents that weren't inserted are exactly those with write errors
+                        // when the insert is unordered.
+                        n_attempted += failure.inserted_ids.len()
+                            + failure.write_errors.as_ref().map(Vec::len).unwrap_or(0);
                     }
                     _ => return Err(e),
                 },
             }
         }
 
-        match cumulative_failure {
-            Some(failure) => Err(ErrorKind::BulkWriteError(failure).into()),
-            None => Ok(cumulative_result.unwrap_or_else(InsertManyResult::new)),
+        if let Some(mut failure) = cumulative_failure {
+            failure.inserted_ids = cumulative_result.inserted_ids;
+            return Err(ErrorKind::BulkWriteError(failure).into());
         }
+
+        let mut result = cumulative_result;
+        if return_documents {
+            result.inserted_documents = Some(
+                self.find_inserted_documents(&result, &batches, session)
+                    .await?,
+            );
+        }
+
+        Ok(result)
     }
 
-    /// Inserts `doc` into the collection.
+    /// Inserts the data in `docs` into the collection.
+    ///
+    /// See the documentation of [`insert_one`](#method.insert_one) for details on how the `_id`
+    /// field of each document is handled.
+    ///
+    /// The documents are split into as many `insert` commands as needed to stay within the
+    /// server's `maxWriteBatchSize` and `maxBsonObjectSize`. An ordered insert (the default) stops
+    /// at the first document that fails to be inserted, whereas an unordered one (see
+    /// [`InsertManyOptions::ordered`](options/struct.InsertManyOptions.html#structfield.ordered))
+    /// attempts all of the documents, but both return an `ErrorKind::BulkWriteError` if any write
+    /// failed. Its [`inserted_ids`](error/struct.BulkWriteFailure.html#structfield.inserted_ids)
+    /// contain the ids of the documents that were inserted.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
     /// retryability. See the documentation
     /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
     /// retryable writes.
-    pub async fn insert_one(
+    pub async fn insert_many(
+        &self,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
+        options: impl Into<Option<InsertManyOptions>>,
+    ) -> Result<InsertManyResult> {
+        self.insert_many_common(docs, options, None).await
+    }
+
+    /// Inserts the data in `docs` into the collection using the provided `ClientSession`.
+    pub async fn insert_many_with_session(
+        &self,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
+        options: impl Into<Option<InsertManyOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<InsertManyResult> {
+        self.insert_many_common(docs, options, session).await
+    }
+
+    /// Reads back the documents inserted by an `insert_many` from the primary, keyed by the index
+    /// of each document in the input. The documents are read with one `find` per insert batch,
+    /// since the ids of a batch that fit in one `insert` command also fit in one `find` filter.
+    /// The reads are executed in the session the documents were inserted in, if any, so that
+    /// they're causally consistent with the inserts.
+    async fn find_inserted_documents(
+        &self,
+        result: &InsertManyResult,
+        batches: &[Range<usize>],
+        mut session: Option<&mut ClientSession>,
+    ) -> Result<HashMap<usize, Document>> {
+        // `Bson` doesn't implement `Hash`, so the ids are keyed by their string representation.
+        // Because the inserted documents are read back as stored, each id will have the same
+        // representation as the one that was inserted.
+        let indexes: HashMap<String, usize> = result
+            .inserted_ids
+            .iter()
+            .map(|(index, id)| (id.to_string(), *index))
+            .collect();
+
+        let client = self.client();
+        let mut documents = HashMap::new();
+        for batch in batches {
+            let ids: Vec<Bson> = batch
+                .clone()
+                .filter_map(|index| result.inserted_ids.get(&index).cloned())
+                .collect();
+            if ids.is_empty() {
+                continue;
+            }
+
+            let options = FindOptions::builder()
+                .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+                .build();
+            let find = Find::new(
+                self.namespace(),
+                Some(doc! { "_id": { "$in": ids } }),
+                Some(options),
+            );
+            let found: Vec<Document> = match session.as_deref_mut() {
+                Some(session) => {
+                    let spec = client
+                        .execute_cursor_operation_with_session(find, session)
+                        .await?;
+                    let mut cursor: SessionCursor<Document> =
+                        SessionCursor::new(client.clone(), spec);
+                    cursor.stream(session).try_collect().await?
+                }
+                None => {
+                    let (spec, session) = client.execute_cursor_operation(find).await?;
+                    let cursor: Cursor<Document> = Cursor::new(client.clone(), spec, session);
+                    cursor.try_collect().await?
+                }
+            };
+
+            for document in found {
+                let index = document
+                    .get("_id")
+                    .and_then(|id| indexes.get(&id.to_string()));
+                if let Some(index) = index {
+                    documents.insert(*index, document);
+                }
+            }
+        }
+
+        Ok(documents)
+    }
+
+    async fn insert_one_common(
         &self,
-        doc: T,
+        doc: impl Borrow<T>,
         options: impl Into<Option<InsertOneOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<InsertOneResult> {
-        let doc = to_document(&doc)?;
+        let mut doc = to_document(doc.borrow())?;
+        self.client().add_generated_id(&mut doc);
 
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
 
+        let mut docs = vec![doc];
         let insert = Insert::new(
             self.namespace(),
-            vec![doc],
+            &mut docs,
             options.map(InsertManyOptions::from_insert_one_options),
         );
         self.client()
-            .execute_operation(insert)
+            .execute_operation_with_optional_session(insert, session.into())
             .await
             .map(InsertOneResult::from_insert_many_result)
             .map_err(convert_bulk_errors)
     }
 
-    /// Replaces up to one document matching `query` in the collection with `replacement`.
+    /// Inserts `doc` into the collection.
+    ///
+    /// If the serialized document does not contain an `_id` field, the driver will generate an
+    /// `ObjectId` for it. Any type whose `Serialize` implementation produces the desired BSON
+    /// value can be used for the `_id` field, including newtypes such as `struct UserId(ObjectId)`
+    /// (which serde serializes as the wrapped value). Note that an `Option` id should be annotated
+    /// with `#[serde(skip_serializing_if = "Option::is_none")]`; otherwise `None` will be
+    /// serialized as an explicit `null` `_id` rather than being generated by the driver. The
+    /// returned [`InsertOneResult::inserted_id`](results/struct.InsertOneResult.html) contains
+    /// the raw BSON value of the `_id`, which can be converted back into the id type with
+    /// [`bson::from_bson`](https://docs.rs/bson/1.2.0/bson/fn.from_bson.html).
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
     /// retryability. See the documentation
     /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
     /// retryable writes.
-    pub async fn replace_one(
+    pub async fn insert_one(
+        &self,
+        doc: impl Borrow<T>,
+        options: impl Into<Option<InsertOneOptions>>,
+    ) -> Result<InsertOneResult> {
+        self.insert_one_common(doc, options, None).await
+    }
+
+    /// Inserts `doc` into the collection using the provided `ClientSession`.
+    pub async fn insert_one_with_session(
+        &self,
+        doc: impl Borrow<T>,
+        options: impl Into<Option<InsertOneOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<InsertOneResult> {
+        self.insert_one_common(doc, options, session).await
+    }
+
+    /// Lists the indexes on this collection. The returned cursor is empty if the collection
+    /// doesn't exist.
+    pub async fn list_indexes(
+        &self,
+        options: impl Into<Option<ListIndexesOptions>>,
+    ) -> Result<Cursor<IndexModel>> {
+        let list_indexes = ListIndexes::new(self.namespace(), options.into());
+        let client = self.client();
+
+        client
+            .execute_cursor_operation(list_indexes)
+            .await
+            .map(|(spec, session)| Cursor::new(client.clone(), spec, session))
+    }
+
+    async fn replace_one_common(
         &self,
         query: Document,
-        replacement: T,
+        replacement: impl Borrow<T>,
         options: impl Into<Option<ReplaceOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<UpdateResult> {
-        let replacement = to_document(&replacement)?;
+        let replacement = to_document(replacement.borrow())?;
         bson_util::replacement_document_check(&replacement)?;
 
         let mut options = options.into();
         resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
 
         let update = Update::new(
             self.namespace(),
@@ -525,20 +1290,46 @@ where
             false,
             options.map(UpdateOptions::from_replace_options),
         );
-        self.client().execute_operation(update).await
+        self.client()
+            .execute_operation_with_optional_session(update, session.into())
+            .await
     }
 
-    /// Updates all documents matching `query` in the collection.
+    /// Replaces up to one document matching `query` in the collection with `replacement`.
     ///
-    /// Both `Document` and `Vec<Document>` implement `Into<UpdateModifications>`, so either can be
-    /// passed in place of constructing the enum case. Note: pipeline updates are only supported
-    /// in MongoDB 4.2+. See the official MongoDB
-    /// [documentation](https://docs.mongodb.com/manual/reference/command/update/#behavior) for more information on specifying updates.
-    pub async fn update_many(
+    /// This operation will retry once upon failure if the connection and encountered error support
+    /// retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub async fn replace_one(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T>,
+        options: impl Into<Option<ReplaceOptions>>,
+    ) -> Result<UpdateResult> {
+        self.replace_one_common(query, replacement, options, None)
+            .await
+    }
+
+    /// Replaces up to one document matching `query` in the collection with `replacement` using the
+    /// provided `ClientSession`.
+    pub async fn replace_one_with_session(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T>,
+        options: impl Into<Option<ReplaceOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<UpdateResult> {
+        self.replace_one_common(query, replacement, options, session)
+            .await
+    }
+
+    async fn update_many_common(
         &self,
         query: Document,
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<UpdateResult> {
         let update = update.into();
         let mut options = options.into();
@@ -548,9 +1339,57 @@ where
         };
 
         resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
 
         let update = Update::new(self.namespace(), query, update, true, options);
-        self.client().execute_operation(update).await
+        self.client()
+            .execute_operation_with_optional_session(update, session.into())
+            .await
+    }
+
+    /// Updates all documents matching `query` in the collection.
+    ///
+    /// Both `Document` and `Vec<Document>` implement `Into<UpdateModifications>`, so either can be
+    /// passed in place of constructing the enum case. Note: pipeline updates are only supported
+    /// in MongoDB 4.2+. See the official MongoDB
+    /// [documentation](https://docs.mongodb.com/manual/reference/command/update/#behavior) for more information on specifying updates.
+    pub async fn update_many(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+    ) -> Result<UpdateResult> {
+        self.update_many_common(query, update, options, None).await
+    }
+
+    /// Updates all documents matching `query` in the collection using the provided
+    /// `ClientSession`.
+    pub async fn update_many_with_session(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<UpdateResult> {
+        self.update_many_common(query, update, options, session)
+            .await
+    }
+
+    async fn update_one_common(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<UpdateResult> {
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern]);
+        self.ensure_created().await?;
+
+        let update = Update::new(self.namespace(), query, update.into(), false, options);
+        self.client()
+            .execute_operation_with_optional_session(update, session.into())
+            .await
     }
 
     /// Updates up to one document matching `query` in the collection.
@@ -570,27 +1409,102 @@ where
         update: impl Into<UpdateModifications>,
         options: impl Into<Option<UpdateOptions>>,
     ) -> Result<UpdateResult> {
+        self.update_one_common(query, update, options, None).await
+    }
+
+    /// Updates up to one document matching `query` in the collection using the provided
+    /// `ClientSession`.
+    pub async fn update_one_with_session(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<UpdateResult> {
+        self.update_one_common(query, update, options, session)
+            .await
+    }
+
+    /// Checks the collection's data and indexes for correctness using the `validate` command.
+    ///
+    /// Note that an `Ok` result does not mean that the collection is valid; check
+    /// [`ValidationResult::valid`](results/struct.ValidationResult.html#structfield.valid) for that.
+    /// See the documentation [here](https://docs.mongodb.com/manual/reference/command/validate/)
+    /// for more information on validating collections.
+    pub async fn validate(
+        &self,
+        options: impl Into<Option<ValidateOptions>>,
+    ) -> Result<ValidationResult> {
+        let validate = Validate::new(self.namespace(), options.into());
+        self.client().execute_operation(validate).await
+    }
+
+    /// Starts a new [`ChangeStream`](change_stream/struct.ChangeStream.html) that receives events
+    /// for all changes in this collection. Note that change streams are only supported on replica
+    /// sets and sharded clusters.
+    ///
+    /// See [`Client::watch`](struct.Client.html#method.watch) for more details.
+    pub async fn watch(
+        &self,
+        pipeline: impl Into<Option<Vec<Document>>>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream<ChangeStreamEvent<Document>>> {
         let mut options = options.into();
-        resolve_options!(self, options, [write_concern]);
+        resolve_options!(self, options, [read_concern, selection_criteria]);
 
-        let update = Update::new(self.namespace(), query, update.into(), false, options);
-        self.client().execute_operation(update).await
+        ChangeStream::execute(
+            self.client(),
+            ChangeStreamTarget::Collection(self.namespace()),
+            pipeline.into().unwrap_or_default(),
+            options,
+        )
+        .await
+    }
+
+    /// Gets the latency statistics of the collection, using the `$collStats` aggregation stage with
+    /// `latencyStats` and histograms enabled. For a sharded collection, the statistics reported by
+    /// each shard are combined. Note that `$collStats` is only supported in MongoDB 3.4 or greater.
+    ///
+    /// See the documentation
+    /// [here](https://docs.mongodb.com/manual/reference/operator/aggregation/collStats/) for more
+    /// information on `$collStats`.
+    pub async fn latency_stats(&self) -> Result<LatencyStats> {
+        #[derive(Deserialize)]
+        struct CollStats {
+            #[serde(rename = "latencyStats")]
+            latency_stats: LatencyStats,
+        }
+
+        let pipeline = vec![doc! { "$collStats": { "latencyStats": { "histograms": true } } }];
+        let mut cursor = self.aggregate(pipeline, None).await?;
+
+        let mut stats = LatencyStats::default();
+        while let Some(document) = cursor.next().await.transpose()? {
+            let shard_stats: CollStats = bson::from_document(document)?;
+            stats.merge(shard_stats.latency_stats);
+        }
+        Ok(stats)
     }
 
-    /// Kill the server side cursor that id corresponds to.
-    pub(super) async fn kill_cursor(&self, cursor_id: i64) -> Result<()> {
+    /// Kill the server side cursor that id corresponds to, on the connection the cursor is pinned
+    /// to if it is.
+    pub(super) async fn kill_cursor(
+        &self,
+        cursor_id: i64,
+        pinned_connection: Option<PinnedConnection>,
+    ) -> Result<()> {
         let ns = self.namespace();
 
-        self.client()
-            .database(ns.db.as_str())
-            .run_command(
-                doc! {
-                    "killCursors": ns.coll.as_str(),
-                    "cursors": [cursor_id]
-                },
-                None,
-            )
-            .await?;
+        let op = RunCommand::new(
+            ns.db,
+            doc! {
+                "killCursors": ns.coll.as_str(),
+                "cursors": [cursor_id]
+            },
+            None,
+        )?
+        .with_pinned_connection(pinned_connection);
+        self.client().execute_operation(op).await?;
         Ok(())
     }
 }
@@ -641,3 +1555,24 @@ impl<'de> Deserialize<'de> for Namespace {
         }
     }
 }
+
+/// Builds the statement for an update write in a bulk write.
+fn update_statement(
+    filter: Document,
+    update: UpdateModifications,
+    upsert: Option<bool>,
+    multi: bool,
+) -> Result<Document> {
+    if let UpdateModifications::Document(ref d) = update {
+        bson_util::update_document_check(d)?;
+    }
+
+    let mut statement = doc! { "q": filter, "u": update.to_bson() };
+    if let Some(upsert) = upsert {
+        statement.insert("upsert", upsert);
+    }
+    if multi {
+        statement.insert("multi", true);
+    }
+    Ok(statement)
+}
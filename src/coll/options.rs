@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::skip_serializing_none;
@@ -13,7 +13,7 @@ use crate::{
         serialize_u32_as_i32,
     },
     concern::{ReadConcern, WriteConcern},
-    options::Collation,
+    options::{Collation, CreateCollectionOptions},
     selection_criteria::SelectionCriteria,
 };
 
@@ -31,9 +31,21 @@ pub struct CollectionOptions {
     #[builder(default)]
     pub read_concern: Option<ReadConcern>,
 
-    /// The default write concern for operations.
+    /// The default write concern for operations. If not set, the write concern of the `Database`
+    /// is used. See the [`WriteConcern`](struct.WriteConcern.html) documentation for how write
+    /// concerns are resolved.
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
+
+    /// If set, the collection is created with these options before the first write made through
+    /// the `Collection`, rather than being created implicitly by the server with the default
+    /// options. The collection is left as is if it already exists.
+    ///
+    /// This applies to inserts, updates, replacements, and the `find_one_and_update` and
+    /// `find_one_and_replace` operations.
+    #[builder(default)]
+    #[serde(skip)]
+    pub ensure_created_with: Option<CreateCollectionOptions>,
 }
 
 /// Specifies whether a
@@ -99,6 +111,24 @@ pub enum CursorType {
     TailableAwait,
 }
 
+/// Specifies how much information the server should report about the execution of an explained
+/// operation, e.g. in
+/// [`Collection::explain_find`](../struct.Collection.html#method.explain_find).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ExplainVerbosity {
+    /// Report the plan selected by the query optimizer without executing it.
+    QueryPlanner,
+
+    /// Execute the selected plan and report statistics about its execution.
+    ExecutionStats,
+
+    /// Like `ExecutionStats`, but also report statistics about the plans that were considered
+    /// and rejected during plan selection.
+    AllPlansExecution,
+}
+
 /// Specifies the options to a
 /// [`Collection::insert_one`](../struct.Collection.html#method.insert_one) operation.
 #[derive(Clone, Debug, Default, Deserialize, TypedBuilder)]
@@ -109,6 +139,13 @@ pub struct InsertOneOptions {
     #[builder(default)]
     pub bypass_document_validation: Option<bool>,
 
+    /// The time limit for the operation, including server selection and any retry. If none is
+    /// specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
     /// The write concern for the operation.
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
@@ -132,6 +169,25 @@ pub struct InsertManyOptions {
     #[builder(default)]
     pub ordered: Option<bool>,
 
+    /// If true, the inserted documents will be read back from the primary after all of them have
+    /// been inserted and returned in
+    /// [`InsertManyResult::inserted_documents`](../results/struct.InsertManyResult.html#structfield.inserted_documents).
+    /// This can be used to get any fields of the documents that were filled in by the server. The
+    /// documents are read in the session they were inserted in, or in a causally consistent
+    /// implicit session if none was given, so the read observes the insert.
+    ///
+    /// Defaults to false.
+    #[builder(default)]
+    #[serde(skip)]
+    pub return_documents: Option<bool>,
+
+    /// The time limit for the operation, including server selection and any retry. If none is
+    /// specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
     /// The write concern for the operation.
     #[builder(default)]
     #[serde(skip_deserializing)]
@@ -143,11 +199,44 @@ impl InsertManyOptions {
         Self {
             bypass_document_validation: options.bypass_document_validation,
             ordered: None,
+            return_documents: None,
+            timeout: options.timeout,
             write_concern: options.write_concern,
         }
     }
 }
 
+/// Specifies the options to a
+/// [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write) operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BulkWriteOptions {
+    /// Opt out of document-level validation.
+    #[builder(default)]
+    pub bypass_document_validation: Option<bool>,
+
+    /// If true, when a write fails, return without performing the remaining writes. If false,
+    /// when a write fails, continue with the remaining writes, if any.
+    ///
+    /// Defaults to true.
+    #[builder(default)]
+    pub ordered: Option<bool>,
+
+    /// The time limit for each command sent as part of the operation, including server selection
+    /// and any retry. If none is specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
+    /// The write concern for the operation.
+    #[builder(default)]
+    #[serde(skip_deserializing)]
+    pub write_concern: Option<WriteConcern>,
+}
+
 /// Enum modeling the modifications to apply during an update.
 /// For details, see the official MongoDB
 /// [documentation](https://docs.mongodb.com/manual/reference/command/update/#update-command-behaviors)
@@ -186,6 +275,47 @@ impl From<Vec<Document>> for UpdateModifications {
     }
 }
 
+/// A single write to perform as part of a
+/// [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write) operation.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum WriteModel<T = Document> {
+    /// Inserts `document` into the collection. As with
+    /// [`Collection::insert_one`](../struct.Collection.html#method.insert_one), an `_id` is
+    /// generated for the document if it does not have one.
+    InsertOne { document: T },
+
+    /// Updates up to one document matching `filter` in the collection.
+    UpdateOne {
+        filter: Document,
+        update: UpdateModifications,
+        /// If true, insert a document if no matching document is found.
+        upsert: Option<bool>,
+    },
+
+    /// Updates all documents matching `filter` in the collection.
+    UpdateMany {
+        filter: Document,
+        update: UpdateModifications,
+        /// If true, insert a document if no matching document is found.
+        upsert: Option<bool>,
+    },
+
+    /// Replaces up to one document matching `filter` in the collection with `replacement`.
+    ReplaceOne {
+        filter: Document,
+        replacement: T,
+        /// If true, insert `replacement` if no matching document is found.
+        upsert: Option<bool>,
+    },
+
+    /// Deletes up to one document matching `filter` from the collection.
+    DeleteOne { filter: Document },
+
+    /// Deletes all documents matching `filter` from the collection.
+    DeleteMany { filter: Document },
+}
+
 /// Specifies the options to a
 /// [`Collection::update_one`](../struct.Collection.html#method.update_one) or
 /// [`Collection::update_many`](../struct.Collection.html#method.update_many) operation.
@@ -215,6 +345,11 @@ pub struct UpdateOptions {
     #[builder(default)]
     pub collation: Option<Collation>,
 
+    /// Tags the command with an arbitrary value to help trace the operation through the database
+    /// profiler, currentOp and logs. Only supported by MongoDB 4.4+.
+    #[builder(default)]
+    pub comment: Option<Bson>,
+
     /// A document or string that specifies the index to use to support the query predicate.
     ///
     /// Only available in MongoDB 4.2+. See the official MongoDB
@@ -222,6 +357,13 @@ pub struct UpdateOptions {
     #[builder(default)]
     pub hint: Option<Hint>,
 
+    /// The time limit for the operation, including server selection and any retry. If none is
+    /// specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
     /// The write concern for the operation.
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
@@ -233,6 +375,7 @@ impl UpdateOptions {
             bypass_document_validation: options.bypass_document_validation,
             upsert: options.upsert,
             hint: options.hint,
+            timeout: options.timeout,
             write_concern: options.write_concern,
             collation: options.collation,
             ..Default::default()
@@ -268,6 +411,13 @@ pub struct ReplaceOptions {
     #[builder(default)]
     pub hint: Option<Hint>,
 
+    /// The time limit for the operation, including server selection and any retry. If none is
+    /// specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
     /// The write concern for the operation.
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
@@ -288,6 +438,11 @@ pub struct DeleteOptions {
     #[builder(default)]
     pub collation: Option<Collation>,
 
+    /// Tags the command with an arbitrary value to help trace the operation through the database
+    /// profiler, currentOp and logs. Only supported by MongoDB 4.4+.
+    #[builder(default)]
+    pub comment: Option<Bson>,
+
     /// The write concern for the operation.
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
@@ -296,6 +451,13 @@ pub struct DeleteOptions {
     /// Only available in MongoDB 4.4+.
     #[builder(default)]
     pub hint: Option<Hint>,
+
+    /// The time limit for the operation, including server selection and any retry. If none is
+    /// specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -464,6 +626,10 @@ pub struct AggregateOptions {
     /// only the number of documents kept in memory at a given time (and by extension, the
     /// number of round trips needed to return the entire set of documents returned by the
     /// query).
+    ///
+    /// A batch size of 0 causes the server to return an empty initial batch, which can be used to
+    /// start running a pipeline (e.g. one ending in `$out` or `$merge`) and check for errors
+    /// without waiting for any results. Subsequent batches will use the server's default size.
     #[builder(default)]
     #[serde(serialize_with = "serialize_batch_size", rename(serialize = "cursor"))]
     pub batch_size: Option<u32>,
@@ -479,10 +645,10 @@ pub struct AggregateOptions {
     #[builder(default)]
     pub collation: Option<Collation>,
 
-    /// Tags the query with an arbitrary string to help trace the operation through the database
-    /// profiler, currentOp and logs.
+    /// Tags the command with an arbitrary value to help trace the operation through the database
+    /// profiler, currentOp and logs. Values other than strings are only supported by MongoDB 4.4+.
     #[builder(default)]
-    pub comment: Option<String>,
+    pub comment: Option<Bson>,
 
     /// The index to use for the operation.
     #[builder(default)]
@@ -502,7 +668,12 @@ pub struct AggregateOptions {
     /// The maximum amount of time to allow the query to run.
     ///
     /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
-    /// across the wire as an integer number of milliseconds.
+    /// across the wire as an integer number of milliseconds. A duration of less than one
+    /// millisecond is rejected with an `ArgumentError`.
+    ///
+    /// The time limit also bounds the lifetime of the returned cursor: once it has passed since
+    /// the aggregation was started, the cursor stops fetching further batches and returns a
+    /// `MaxTimeMSExpired` command error instead.
     #[builder(default)]
     #[serde(
         serialize_with = "serialize_duration_as_int_millis",
@@ -527,6 +698,13 @@ pub struct AggregateOptions {
     #[serde(rename = "readPreference")]
     pub selection_criteria: Option<SelectionCriteria>,
 
+    /// The time limit for the operation and for iterating the returned cursor, including server
+    /// selection and any retry. If none is specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
     /// The write concern to use for the operation.
     ///
     /// If none is specified, the write concern defined on the object executing this operation will
@@ -568,6 +746,11 @@ pub struct CountOptions {
     #[builder(default)]
     pub collation: Option<Collation>,
 
+    /// Tags the command with an arbitrary value to help trace the operation through the database
+    /// profiler, currentOp and logs. Values other than strings are only supported by MongoDB 4.4+.
+    #[builder(default)]
+    pub comment: Option<Bson>,
+
     /// The criteria used to select a server for this operation.
     ///
     /// If none specified, the default set on the collection will be used.
@@ -682,16 +865,36 @@ pub struct FindOptions {
     #[serde(serialize_with = "serialize_u32_as_i32")]
     pub batch_size: Option<u32>,
 
-    /// Tags the query with an arbitrary string to help trace the operation through the database
-    /// profiler, currentOp and logs.
+    /// Tags the command with an arbitrary value to help trace the operation through the database
+    /// profiler, currentOp and logs. Values other than strings are only supported by MongoDB 4.4+.
     #[builder(default)]
-    pub comment: Option<String>,
+    pub comment: Option<Bson>,
 
     /// The type of cursor to return.
     #[builder(default)]
     #[serde(skip)]
     pub cursor_type: Option<CursorType>,
 
+    /// Renames fields of the returned documents, mapping each alias to the name of the stored
+    /// field whose value it should hold. This is useful for deserializing documents from a legacy
+    /// schema into a type whose field names differ, without running an aggregation.
+    ///
+    /// The aliases are sent to the server as part of the `projection`, so only the `_id` field,
+    /// the aliased fields and the fields included by `projection` are returned. An alias may not
+    /// also be a field of `projection`. Requires MongoDB 4.4+.
+    #[builder(default)]
+    #[serde(skip)]
+    pub field_aliases: Option<HashMap<String, String>>,
+
+    /// The number of documents the server should return per batch for the getMore commands
+    /// after the initial batch. If unset, `batch_size` is used for all batches.
+    ///
+    /// This allows a small `batch_size` to be used to return the first results quickly, followed
+    /// by larger batches that need fewer round trips to return the rest of the results.
+    #[builder(default)]
+    #[serde(skip)]
+    pub get_more_batch_size: Option<u32>,
+
     /// The index to use for the operation.
     #[builder(default)]
     pub hint: Option<Hint>,
@@ -723,7 +926,12 @@ pub struct FindOptions {
     /// The maximum amount of time to allow the query to run.
     ///
     /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
-    /// across the wire as an integer number of milliseconds.
+    /// across the wire as an integer number of milliseconds. A duration of less than one
+    /// millisecond is rejected with an `ArgumentError`.
+    ///
+    /// The time limit also bounds the lifetime of the returned cursor: once it has passed since
+    /// the query was started, the cursor stops fetching further batches and returns a
+    /// `MaxTimeMSExpired` command error instead.
     #[builder(default)]
     #[serde(
         rename = "maxTimeMS",
@@ -772,6 +980,13 @@ pub struct FindOptions {
     #[builder(default)]
     pub sort: Option<Document>,
 
+    /// The time limit for the operation and for iterating the returned cursor, including server
+    /// selection and any retry. If none is specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
+
     /// The collation to use for the operation.
     ///
     /// See the [documentation](https://docs.mongodb.com/manual/reference/collation/) for more
@@ -800,10 +1015,13 @@ impl From<FindOneOptions> for FindOptions {
             skip: options.skip,
             batch_size: None,
             cursor_type: None,
+            field_aliases: None,
+            get_more_batch_size: None,
             limit: None,
             max_await_time: None,
             no_cursor_timeout: None,
             sort: options.sort,
+            timeout: options.timeout,
         }
     }
 }
@@ -840,10 +1058,10 @@ pub struct FindOneOptions {
     #[builder(default)]
     pub collation: Option<Collation>,
 
-    /// Tags the query with an arbitrary string to help trace the operation through the database
-    /// profiler, currentOp and logs.
+    /// Tags the command with an arbitrary value to help trace the operation through the database
+    /// profiler, currentOp and logs. Values other than strings are only supported by MongoDB 4.4+.
     #[builder(default)]
-    pub comment: Option<String>,
+    pub comment: Option<Bson>,
 
     /// The index to use for the operation.
     #[builder(default)]
@@ -903,6 +1121,13 @@ pub struct FindOneOptions {
     /// The order of the documents for the purposes of the operation.
     #[builder(default)]
     pub sort: Option<Document>,
+
+    /// The time limit for the operation, including server selection and any retry. If none is
+    /// specified, the client's
+    /// [`timeout`](../options/struct.ClientOptions.html#structfield.timeout) is used.
+    #[builder(default)]
+    #[serde(skip)]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a [`Collection::drop`](../struct.Collection.html#method.drop)
@@ -915,3 +1140,103 @@ pub struct DropCollectionOptions {
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
 }
+
+/// Specifies the options to a [`Collection::validate`](../struct.Collection.html#method.validate)
+/// operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ValidateOptions {
+    /// Whether to perform a more thorough but slower validation of the collection's data and
+    /// indexes. The server performs a faster, less thorough check by default.
+    #[builder(default)]
+    pub full: Option<bool>,
+
+    /// Whether to repair inconsistencies found during validation. This is only supported by
+    /// MongoDB 5.0+ when run against a standalone server.
+    #[builder(default)]
+    pub repair: Option<bool>,
+}
+
+/// Specifies the options to a
+/// [`Collection::create_index`](../struct.Collection.html#method.create_index) or
+/// [`Collection::create_indexes`](../struct.Collection.html#method.create_indexes) operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CreateIndexOptions {
+    /// The maximum amount of time to allow the index build to take.
+    ///
+    /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
+    /// across the wire as an integer number of milliseconds.
+    #[builder(default)]
+    #[serde(
+        serialize_with = "serialize_duration_as_int_millis",
+        rename = "maxTimeMS",
+        deserialize_with = "deserialize_duration_from_u64_millis",
+        default
+    )]
+    pub max_time: Option<Duration>,
+
+    /// The write concern for the operation.
+    #[builder(default)]
+    pub write_concern: Option<WriteConcern>,
+}
+
+/// Specifies the options to a
+/// [`Collection::drop_index`](../struct.Collection.html#method.drop_index) or
+/// [`Collection::drop_indexes`](../struct.Collection.html#method.drop_indexes) operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct DropIndexOptions {
+    /// The maximum amount of time to allow the index to be dropped.
+    ///
+    /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
+    /// across the wire as an integer number of milliseconds.
+    #[builder(default)]
+    #[serde(
+        serialize_with = "serialize_duration_as_int_millis",
+        rename = "maxTimeMS",
+        deserialize_with = "deserialize_duration_from_u64_millis",
+        default
+    )]
+    pub max_time: Option<Duration>,
+
+    /// The write concern for the operation.
+    #[builder(default)]
+    pub write_concern: Option<WriteConcern>,
+}
+
+/// Specifies the options to a
+/// [`Collection::list_indexes`](../struct.Collection.html#method.list_indexes) operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ListIndexesOptions {
+    /// The maximum amount of time to allow the query to run.
+    ///
+    /// This options maps to the `maxTimeMS` MongoDB query option, so the duration will be sent
+    /// across the wire as an integer number of milliseconds.
+    #[builder(default)]
+    #[serde(
+        serialize_with = "serialize_duration_as_int_millis",
+        rename = "maxTimeMS",
+        deserialize_with = "deserialize_duration_from_u64_millis",
+        default
+    )]
+    pub max_time: Option<Duration>,
+
+    /// The number of indexes the server should return per cursor batch.
+    ///
+    /// Note that this does not have any affect on the indexes that are returned by a cursor,
+    /// only the number of indexes kept in memory at a given time (and by extension, the number of
+    /// round trips needed to return all of them).
+    #[builder(default)]
+    #[serde(serialize_with = "serialize_batch_size", rename(serialize = "cursor"))]
+    pub batch_size: Option<u32>,
+}
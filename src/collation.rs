@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use typed_builder::TypedBuilder;
 
 /// A collation configuration. See the official MongoDB
@@ -16,7 +16,7 @@ pub struct Collation {
 
     /// The level of comparison to perform. Corresponds to [ICU Comparison Levels](http://userguide.icu-project.org/collation/concepts#TOC-Comparison-Levels).
     #[builder(default)]
-    pub strength: Option<i32>,
+    pub strength: Option<CollationStrength>,
 
     /// Whether to include case comparison when `strength` is level 1 or 2.
     #[builder(default)]
@@ -48,3 +48,63 @@ pub struct Collation {
     #[builder(default)]
     pub backwards: Option<bool>,
 }
+
+/// The level of comparison to perform, which is sent to the server as an integer from 1 to 5.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum CollationStrength {
+    /// Only compare base characters, ignoring other differences such as diacritics and case.
+    Primary,
+
+    /// Also compare diacritics.
+    Secondary,
+
+    /// Also compare case and letter variants. This is the server's default.
+    Tertiary,
+
+    /// Also compare punctuation when `alternate` is "shifted".
+    Quaternary,
+
+    /// Compare the code points of the strings when all of the other levels are equal.
+    Identical,
+}
+
+impl CollationStrength {
+    fn level(self) -> i32 {
+        match self {
+            CollationStrength::Primary => 1,
+            CollationStrength::Secondary => 2,
+            CollationStrength::Tertiary => 3,
+            CollationStrength::Quaternary => 4,
+            CollationStrength::Identical => 5,
+        }
+    }
+}
+
+impl Serialize for CollationStrength {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i32(self.level())
+    }
+}
+
+impl<'de> Deserialize<'de> for CollationStrength {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match i32::deserialize(deserializer)? {
+            1 => Ok(CollationStrength::Primary),
+            2 => Ok(CollationStrength::Secondary),
+            3 => Ok(CollationStrength::Tertiary),
+            4 => Ok(CollationStrength::Quaternary),
+            5 => Ok(CollationStrength::Identical),
+            level => Err(D::Error::custom(format!(
+                "collation strength must be between 1 and 5, got {}",
+                level
+            ))),
+        }
+    }
+}
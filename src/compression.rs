@@ -0,0 +1,353 @@
+//! Contains the compressors used for wire protocol compression.
+
+use crate::error::{Error, ErrorKind, Result};
+
+/// The ID of the noop compressor, which indicates that a message's contents are not compressed.
+const NOOP_ID: u8 = 0;
+
+/// The ID of the snappy compressor.
+#[cfg(feature = "snappy-compression")]
+const SNAPPY_ID: u8 = 1;
+
+/// The ID of the zlib compressor.
+const ZLIB_ID: u8 = 2;
+
+/// The ID of the zstd compressor.
+#[cfg(feature = "zstd-compression")]
+const ZSTD_ID: u8 = 3;
+
+/// The zlib compression level used when none is specified.
+const DEFAULT_ZLIB_LEVEL: u8 = 6;
+
+/// The commands that must never be compressed, as per the compression spec.
+const UNCOMPRESSIBLE_COMMANDS: &[&str] = &[
+    "ismaster",
+    "hello",
+    "saslstart",
+    "saslcontinue",
+    "getnonce",
+    "authenticate",
+    "createuser",
+    "updateuser",
+    "copydbsaslstart",
+    "copydbgetnonce",
+    "copydb",
+];
+
+/// A compressor that can be negotiated with the server to compress wire protocol messages. See
+/// [`ClientOptions::compressors`](../options/struct.ClientOptions.html#structfield.compressors)
+/// for how compressors are selected.
+///
+/// The zstd and snappy compressors are only available when the `zstd-compression` and
+/// `snappy-compression` features are enabled, respectively.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Compressor {
+    /// Compresses messages with zstd at the given level, from 1 (fastest) to 22 (best
+    /// compression). If no level is specified, the zstd library's default level is used.
+    #[cfg(feature = "zstd-compression")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zstd-compression")))]
+    Zstd {
+        /// The compression level.
+        level: Option<i32>,
+    },
+
+    /// Compresses messages with snappy.
+    #[cfg(feature = "snappy-compression")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "snappy-compression")))]
+    Snappy,
+
+    /// Compresses messages with zlib at the given level, from 0 (no compression) to 9 (best
+    /// compression). If no level is specified, level 6 is used.
+    Zlib {
+        /// The compression level.
+        level: Option<i32>,
+    },
+}
+
+impl Compressor {
+    /// Gets the compressor with the given name, or `None` if the driver does not support it.
+    /// `zlib_level` is only used if the compressor is zlib.
+    pub(crate) fn from_name(name: &str, zlib_level: Option<i32>) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            #[cfg(feature = "zstd-compression")]
+            "zstd" => Some(Compressor::Zstd { level: None }),
+            #[cfg(feature = "snappy-compression")]
+            "snappy" => Some(Compressor::Snappy),
+            "zlib" => Some(Compressor::Zlib { level: zlib_level }),
+            _ => None,
+        }
+    }
+
+    /// The name used to identify this compressor when negotiating compression with the server.
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "zstd-compression")]
+            Compressor::Zstd { .. } => "zstd",
+            #[cfg(feature = "snappy-compression")]
+            Compressor::Snappy => "snappy",
+            Compressor::Zlib { .. } => "zlib",
+        }
+    }
+
+    /// The ID written to `OP_COMPRESSED` messages compressed by this compressor.
+    pub(crate) fn id(&self) -> u8 {
+        match self {
+            #[cfg(feature = "zstd-compression")]
+            Compressor::Zstd { .. } => ZSTD_ID,
+            #[cfg(feature = "snappy-compression")]
+            Compressor::Snappy => SNAPPY_ID,
+            Compressor::Zlib { .. } => ZLIB_ID,
+        }
+    }
+
+    /// Compresses `bytes`.
+    pub(crate) fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "zstd-compression")]
+            Compressor::Zstd { level } => {
+                let level = level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL);
+                zstd::stream::encode_all(bytes, level).map_err(|e| {
+                    ErrorKind::InternalError {
+                        message: format!("failed to compress message with zstd: {}", e),
+                    }
+                    .into()
+                })
+            }
+            #[cfg(feature = "snappy-compression")]
+            Compressor::Snappy => snap::raw::Encoder::new().compress_vec(bytes).map_err(|e| {
+                ErrorKind::InternalError {
+                    message: format!("failed to compress message with snappy: {}", e),
+                }
+                .into()
+            }),
+            Compressor::Zlib { level } => {
+                let level = match level {
+                    Some(level) if *level >= 0 => (*level).min(9) as u8,
+                    _ => DEFAULT_ZLIB_LEVEL,
+                };
+                Ok(miniz_oxide::deflate::compress_to_vec_zlib(bytes, level))
+            }
+        }
+    }
+}
+
+/// Decompresses the contents of an `OP_COMPRESSED` message that was compressed by the compressor
+/// with the given ID. Decompression fails rather than producing more than `uncompressed_size`
+/// bytes, so a malformed reply can't make the driver allocate more than the server said it would.
+pub(crate) fn decompress(
+    compressor_id: u8,
+    bytes: &[u8],
+    uncompressed_size: usize,
+) -> Result<Vec<u8>> {
+    match compressor_id {
+        NOOP_ID => Ok(bytes.to_vec()),
+        #[cfg(feature = "snappy-compression")]
+        SNAPPY_ID => {
+            let decompressed_len =
+                snap::raw::decompress_len(bytes).map_err(|e| ErrorKind::ResponseError {
+                    message: format!("failed to decompress snappy-compressed reply: {}", e),
+                })?;
+            if decompressed_len > uncompressed_size {
+                return Err(too_long("snappy", uncompressed_size));
+            }
+
+            snap::raw::Decoder::new()
+                .decompress_vec(bytes)
+                .map_err(|e| {
+                    ErrorKind::ResponseError {
+                        message: format!("failed to decompress snappy-compressed reply: {}", e),
+                    }
+                    .into()
+                })
+        }
+        ZLIB_ID => decompress_zlib(bytes, uncompressed_size),
+        // `zstd::block` decompression fails if the output doesn't fit in the given capacity.
+        #[cfg(feature = "zstd-compression")]
+        ZSTD_ID => zstd::block::decompress(bytes, uncompressed_size).map_err(|e| {
+            ErrorKind::ResponseError {
+                message: format!("failed to decompress zstd-compressed reply: {}", e),
+            }
+            .into()
+        }),
+        other => Err(ErrorKind::ResponseError {
+            message: format!("reply was compressed with unsupported compressor {}", other),
+        }
+        .into()),
+    }
+}
+
+/// Inflates zlib-compressed `bytes` into a buffer of exactly `uncompressed_size` bytes.
+/// `decompress_to_vec_zlib_with_limit` isn't used because miniz_oxide 0.4 grows its buffer by
+/// doubling and gives up as soon as that would exceed the limit, even if the output would fit.
+fn decompress_zlib(bytes: &[u8], uncompressed_size: usize) -> Result<Vec<u8>> {
+    use miniz_oxide::inflate::{
+        core::{
+            decompress,
+            inflate_flags::{
+                TINFL_FLAG_PARSE_ZLIB_HEADER,
+                TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+            },
+            DecompressorOxide,
+        },
+        TINFLStatus,
+    };
+
+    let mut decompressed = vec![0; uncompressed_size];
+    let (status, _, decompressed_len) = decompress(
+        &mut Box::<DecompressorOxide>::default(),
+        bytes,
+        &mut decompressed,
+        0,
+        TINFL_FLAG_PARSE_ZLIB_HEADER | TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+    );
+
+    match status {
+        TINFLStatus::Done => {
+            decompressed.truncate(decompressed_len);
+            Ok(decompressed)
+        }
+        TINFLStatus::HasMoreOutput => Err(too_long("zlib", uncompressed_size)),
+        status => Err(ErrorKind::ResponseError {
+            message: format!("failed to decompress zlib-compressed reply: {:?}", status),
+        }
+        .into()),
+    }
+}
+
+/// The error returned when a reply compressed with `compressor` decompresses to more than
+/// `uncompressed_size` bytes.
+fn too_long(compressor: &str, uncompressed_size: usize) -> Error {
+    ErrorKind::ResponseError {
+        message: format!(
+            "The server indicated that the decompressed reply would be {} bytes long, but it \
+             decompresses to more than that with {}",
+            uncompressed_size, compressor
+        ),
+    }
+    .into()
+}
+
+/// Whether the command with the given name may be sent compressed.
+pub(crate) fn is_compressible(command_name: &str) -> bool {
+    !UNCOMPRESSIBLE_COMMANDS.contains(&command_name.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "snappy-compression")]
+    use super::SNAPPY_ID;
+    #[cfg(feature = "zstd-compression")]
+    use super::ZSTD_ID;
+    use super::{decompress, is_compressible, Compressor, ZLIB_ID};
+
+    #[test]
+    fn zlib_round_trip() {
+        let bytes = test_bytes();
+
+        for level in &[None, Some(0), Some(9), Some(-1)] {
+            let compressor = Compressor::Zlib { level: *level };
+            let compressed = compressor.compress(&bytes).unwrap();
+            assert_eq!(
+                decompress(compressor.id(), &compressed, bytes.len()).unwrap(),
+                bytes
+            );
+        }
+
+        assert!(
+            Compressor::Zlib { level: None }
+                .compress(&bytes)
+                .unwrap()
+                .len()
+                < bytes.len()
+        );
+        assert!(decompress(42, &bytes, bytes.len()).is_err());
+        assert_eq!(decompress(0, &bytes, bytes.len()).unwrap(), bytes);
+    }
+
+    #[test]
+    fn zlib_output_is_bounded() {
+        let bytes = test_bytes();
+        let compressed = Compressor::Zlib { level: None }.compress(&bytes).unwrap();
+
+        assert!(decompress(ZLIB_ID, &compressed, bytes.len() - 1).is_err());
+    }
+
+    #[cfg(feature = "zstd-compression")]
+    #[test]
+    fn zstd_round_trip() {
+        let bytes = test_bytes();
+
+        for level in &[None, Some(1), Some(22)] {
+            let compressor = Compressor::Zstd { level: *level };
+            let compressed = compressor.compress(&bytes).unwrap();
+            assert!(compressed.len() < bytes.len());
+            assert_eq!(
+                decompress(compressor.id(), &compressed, bytes.len()).unwrap(),
+                bytes
+            );
+        }
+    }
+
+    #[cfg(feature = "zstd-compression")]
+    #[test]
+    fn zstd_output_is_bounded() {
+        let bytes = test_bytes();
+        let compressed = Compressor::Zstd { level: None }.compress(&bytes).unwrap();
+
+        assert!(decompress(ZSTD_ID, &compressed, bytes.len() - 1).is_err());
+    }
+
+    #[cfg(feature = "snappy-compression")]
+    #[test]
+    fn snappy_round_trip() {
+        let bytes = test_bytes();
+
+        let compressed = Compressor::Snappy.compress(&bytes).unwrap();
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(
+            decompress(Compressor::Snappy.id(), &compressed, bytes.len()).unwrap(),
+            bytes
+        );
+    }
+
+    #[cfg(feature = "snappy-compression")]
+    #[test]
+    fn snappy_output_is_bounded() {
+        let bytes = test_bytes();
+        let compressed = Compressor::Snappy.compress(&bytes).unwrap();
+
+        assert!(decompress(SNAPPY_ID, &compressed, bytes.len() - 1).is_err());
+    }
+
+    #[test]
+    fn from_name() {
+        assert_eq!(
+            Compressor::from_name("ZLIB", Some(4)),
+            Some(Compressor::Zlib { level: Some(4) })
+        );
+        assert_eq!(Compressor::from_name("lz4", None), None);
+
+        #[cfg(feature = "zstd-compression")]
+        assert_eq!(
+            Compressor::from_name("zstd", Some(4)),
+            Some(Compressor::Zstd { level: None })
+        );
+        #[cfg(feature = "snappy-compression")]
+        assert_eq!(
+            Compressor::from_name("snappy", None),
+            Some(Compressor::Snappy)
+        );
+    }
+
+    fn test_bytes() -> Vec<u8> {
+        b"compress me ".iter().cycle().take(1200).cloned().collect()
+    }
+
+    #[test]
+    fn compressible_commands() {
+        assert!(is_compressible("find"));
+        assert!(!is_compressible("isMaster"));
+        assert!(!is_compressible("saslStart"));
+    }
+}
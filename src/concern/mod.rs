@@ -52,6 +52,16 @@ impl ReadConcern {
         ReadConcernLevel::Available.into()
     }
 
+    /// Creates a read concern with level "snapshot".
+    /// See the specific documentation for this read concern level [here](https://docs.mongodb.com/manual/reference/read-concern-snapshot/).
+    ///
+    /// Reads with this read concern can only be executed in a session started with
+    /// [`Client::start_session`](../struct.Client.html#method.start_session). All of the snapshot
+    /// reads in a session read from the same point in time, which is determined by the first one.
+    pub fn snapshot() -> Self {
+        ReadConcernLevel::Snapshot.into()
+    }
+
     /// Creates a read concern with a custom read concern level. This is present to provide forwards
     /// compatibility with any future read concerns which may be added to new versions of
     /// MongoDB.
@@ -85,6 +95,9 @@ pub enum ReadConcernLevel {
     /// See the specific documentation for this read concern level [here](https://docs.mongodb.com/manual/reference/read-concern-available/).
     Available,
 
+    /// See the specific documentation for this read concern level [here](https://docs.mongodb.com/manual/reference/read-concern-snapshot/).
+    Snapshot,
+
     /// Specify a custom read concern level. This is present to provide forwards compatibility with
     /// any future read concerns which may be added to new versions of MongoDB.
     Custom(String),
@@ -97,6 +110,7 @@ impl ReadConcernLevel {
             "majority" => ReadConcernLevel::Majority,
             "linearizable" => ReadConcernLevel::Linearizable,
             "available" => ReadConcernLevel::Available,
+            "snapshot" => ReadConcernLevel::Snapshot,
             s => ReadConcernLevel::Custom(s.to_string()),
         }
     }
@@ -108,6 +122,7 @@ impl ReadConcernLevel {
             ReadConcernLevel::Majority => "majority",
             ReadConcernLevel::Linearizable => "linearizable",
             ReadConcernLevel::Available => "available",
+            ReadConcernLevel::Snapshot => "snapshot",
             ReadConcernLevel::Custom(ref s) => s,
         }
     }
@@ -131,6 +146,14 @@ impl Serialize for ReadConcernLevel {
 
 /// Specifies the level of acknowledgement requested from the server for write operations.
 ///
+/// The write concern of an operation is the first one specified out of the operation's options,
+/// the `Collection`, the `Database` and the `Client`, in that order. Since `Collection`s and
+/// `Database`s inherit the write concern of the handle they were created from unless one is
+/// given in their options, setting one at any level overrides those above it. The resolved write
+/// concern is sent with each command, so it also takes precedence over the cluster-wide default
+/// write concern set on the server with `setDefaultRWConcern`, which only applies if no write
+/// concern is specified at any level.
+///
 /// See the documentation [here](https://docs.mongodb.com/manual/reference/write-concern/) for more
 /// information about write concerns.
 #[skip_serializing_none]
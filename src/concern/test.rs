@@ -2,10 +2,16 @@ use std::time::Duration;
 use tokio::sync::RwLockReadGuard;
 
 use crate::{
-    bson::{doc, Bson},
+    bson::{doc, Bson, Document},
     error::ErrorKind,
-    options::{Acknowledgment, InsertOneOptions, WriteConcern},
-    test::{TestClient, LOCK},
+    options::{
+        Acknowledgment,
+        CollectionOptions,
+        DatabaseOptions,
+        InsertOneOptions,
+        WriteConcern,
+    },
+    test::{EventClient, TestClient, CLIENT_OPTIONS, LOCK},
 };
 
 #[test]
@@ -144,3 +150,59 @@ async fn unacknowledged_write_concern_rejected() {
         .expect_err("insert should fail");
     assert!(matches!(error.kind.as_ref(), ErrorKind::ArgumentError { .. }));
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn write_concern_precedence() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let majority = WriteConcern::builder().w(Acknowledgment::Majority).build();
+    let w_1 = WriteConcern::builder().w(Acknowledgment::Nodes(1)).build();
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.write_concern = Some(majority);
+    let client = EventClient::with_options(options).await;
+
+    let db = client.database(function_name!());
+    db.collection(function_name!())
+        .insert_one(doc! {}, None)
+        .await
+        .unwrap();
+
+    let db_options = DatabaseOptions::builder().write_concern(w_1.clone()).build();
+    client
+        .database_with_options(function_name!(), db_options)
+        .collection(function_name!())
+        .insert_one(doc! {}, None)
+        .await
+        .unwrap();
+
+    let coll_options = CollectionOptions::builder()
+        .write_concern(w_1.clone())
+        .build();
+    let coll = db.collection_with_options(function_name!(), coll_options);
+    coll.insert_one(doc! {}, None).await.unwrap();
+
+    let op_wc = WriteConcern::builder()
+        .w(Acknowledgment::Nodes(1))
+        .w_timeout(Duration::from_secs(1))
+        .build();
+    let op_options = InsertOneOptions::builder().write_concern(op_wc).build();
+    coll.insert_one(doc! {}, op_options).await.unwrap();
+
+    let write_concerns: Vec<Document> = client
+        .get_command_started_events("insert")
+        .into_iter()
+        .map(|event| event.command.get_document("writeConcern").unwrap().clone())
+        .collect();
+    assert_eq!(
+        write_concerns,
+        vec![
+            doc! { "w": "majority" },
+            doc! { "w": 1 },
+            doc! { "w": 1 },
+            doc! { "w": 1, "wtimeout": 1000 },
+        ]
+    );
+}
@@ -2,14 +2,15 @@ use std::{
     collections::VecDeque,
     pin::Pin,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use derivative::Derivative;
 use futures::{Future, Stream};
 
 use crate::{
-    bson::Document,
+    bson::{Document, Timestamp},
+    cmap::PinnedConnection,
     error::{ErrorKind, Result},
     options::StreamAddress,
     results::GetMoreResult,
@@ -27,6 +28,7 @@ pub(super) struct GenericCursor<T: GetMoreProvider> {
     info: CursorInformation,
     buffer: VecDeque<Document>,
     exhausted: bool,
+    post_batch_resume_token: Option<Document>,
 }
 
 impl<T: GetMoreProvider> GenericCursor<T> {
@@ -38,6 +40,7 @@ impl<T: GetMoreProvider> GenericCursor<T> {
             provider: get_more_provider,
             buffer: spec.initial_buffer,
             info: spec.info,
+            post_batch_resume_token: spec.post_batch_resume_token,
         }
     }
 
@@ -45,6 +48,18 @@ impl<T: GetMoreProvider> GenericCursor<T> {
         std::mem::take(&mut self.buffer)
     }
 
+    /// Whether all of the documents in the batch most recently received from the server have been
+    /// returned.
+    pub(super) fn is_batch_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// The `postBatchResumeToken` of the batch most recently received from the server, if it
+    /// included one.
+    pub(super) fn post_batch_resume_token(&self) -> Option<&Document> {
+        self.post_batch_resume_token.as_ref()
+    }
+
     pub(super) fn is_exhausted(&self) -> bool {
         self.exhausted
     }
@@ -53,10 +68,22 @@ impl<T: GetMoreProvider> GenericCursor<T> {
         self.info.id
     }
 
+    pub(super) fn info(&self) -> &CursorInformation {
+        &self.info
+    }
+
     pub(super) fn namespace(&self) -> &Namespace {
         &self.info.ns
     }
 
+    pub(super) fn set_batch_size(&mut self, batch_size: u32) {
+        self.info.batch_size = Some(batch_size);
+    }
+
+    pub(super) fn pinned_connection(&self) -> Option<&PinnedConnection> {
+        self.info.pinned_connection.as_ref()
+    }
+
     fn start_get_more(&mut self) {
         let info = self.info.clone();
         let client = self.client.clone();
@@ -75,7 +102,15 @@ impl<T: GetMoreProvider> Stream for GenericCursor<T> {
                     // If a result is ready, retrieve the buffer and update the exhausted status.
                     Poll::Ready(mut get_more_result) => {
                         let buffer_result = get_more_result.take_buffer();
+                        if let Ok(result) = get_more_result.as_mut() {
+                            self.post_batch_resume_token = result.post_batch_resume_token.take();
+                        }
                         self.exhausted = get_more_result.exhausted();
+                        if self.exhausted {
+                            // Release the pinned connection, if any, back to its pool.
+                            self.info.pinned_connection = None;
+                            self.client.untrack_cursor(&self.info);
+                        }
 
                         self.provider.clear_execution(get_more_result);
                         self.buffer = buffer_result?;
@@ -148,6 +183,12 @@ pub(super) trait GetMoreProviderResult {
 pub(crate) struct CursorSpecification {
     pub(crate) info: CursorInformation,
     pub(crate) initial_buffer: VecDeque<Document>,
+
+    /// The `postBatchResumeToken` of the initial batch, which is only returned for change streams.
+    pub(crate) post_batch_resume_token: Option<Document>,
+
+    /// The `operationTime` of the command that created the cursor, if the server reported one.
+    pub(crate) operation_time: Option<Timestamp>,
 }
 
 impl CursorSpecification {
@@ -166,11 +207,36 @@ impl CursorSpecification {
                 address,
                 batch_size: batch_size.into(),
                 max_time: max_time.into(),
+                deadline: None,
+                timeout_deadline: None,
+                pinned_connection: None,
             },
             initial_buffer,
+            post_batch_resume_token: None,
+            operation_time: None,
         }
     }
 
+    /// Sets the time by which the cursor must be fully iterated.
+    pub(crate) fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.info.deadline = deadline;
+        self
+    }
+
+    /// Sets the time by which the cursor must be fully iterated because of the `timeout` of the
+    /// operation that created it.
+    pub(crate) fn with_timeout_deadline(mut self, timeout_deadline: Option<Instant>) -> Self {
+        self.info.timeout_deadline = timeout_deadline;
+        self
+    }
+
+    /// Pins the cursor to the connection that was used to create it, on which its getMores and
+    /// killCursors are sent from then on.
+    pub(crate) fn with_pinned_connection(mut self, conn: PinnedConnection) -> Self {
+        self.info.pinned_connection = Some(conn);
+        self
+    }
+
     pub(crate) fn id(&self) -> i64 {
         self.info.id
     }
@@ -189,6 +255,11 @@ impl CursorSpecification {
     pub(crate) fn max_time(&self) -> Option<Duration> {
         self.info.max_time
     }
+
+    #[cfg(test)]
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.info.deadline
+    }
 }
 
 /// Static information about a cursor.
@@ -199,4 +270,16 @@ pub(crate) struct CursorInformation {
     pub(crate) id: i64,
     pub(crate) batch_size: Option<u32>,
     pub(crate) max_time: Option<Duration>,
+
+    /// The time by which the cursor must be fully iterated, derived from the `max_time` of the
+    /// operation that created it. No further getMores are sent once it has passed.
+    pub(crate) deadline: Option<Instant>,
+
+    /// The time by which the cursor must be fully iterated, derived from the `timeout` of the
+    /// operation that created it. Its getMores fail with a timeout error once it has passed.
+    pub(crate) timeout_deadline: Option<Instant>,
+
+    /// The connection the cursor was created on, if it's pinned to it because the client is
+    /// connected to a load balancer.
+    pub(crate) pinned_connection: Option<PinnedConnection>,
 }
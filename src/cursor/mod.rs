@@ -1,6 +1,4 @@
 mod common;
-// TODO: RUST-52 use this
-#[allow(dead_code)]
 mod session;
 
 use std::{
@@ -8,13 +6,13 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures::{future::BoxFuture, Stream};
+use futures::{future::BoxFuture, Stream, StreamExt};
 use serde::de::DeserializeOwned;
 
 use crate::{
     bson::{from_document, Document},
     client::ClientSession,
-    error::Result,
+    error::{ErrorKind, Result},
     operation::GetMore,
     results::GetMoreResult,
     Client,
@@ -22,6 +20,7 @@ use crate::{
 };
 pub(crate) use common::{CursorInformation, CursorSpecification};
 use common::{GenericCursor, GetMoreProvider, GetMoreProviderResult};
+pub use session::{SessionCursor, SessionCursorStream};
 
 /// A `Cursor` streams the result of a query. When a query is made, a `Cursor` will be returned with
 /// the first batch of results from the server; the documents will be returned as the `Cursor` is
@@ -45,11 +44,11 @@ use common::{GenericCursor, GetMoreProvider, GetMoreProviderResult};
 ///
 /// ```rust
 /// # use futures::stream::StreamExt;
-/// # use mongodb::{Client, error::Result};
+/// # use mongodb::{bson::Document, Client, error::Result};
 /// #
 /// # async fn do_stuff() -> Result<()> {
 /// # let client = Client::with_uri_str("mongodb://example.com").await?;
-/// # let coll = client.database("foo").collection("bar");
+/// # let coll = client.database("foo").collection::<Document>("bar");
 /// # let mut cursor = coll.find(None, None).await?;
 /// #
 /// while let Some(doc) = cursor.next().await {
@@ -102,6 +101,9 @@ where
         session: Option<ClientSession>,
     ) -> Self {
         let provider = ImplicitSessionGetMoreProvider::new(&spec, session);
+        if spec.id() != 0 {
+            client.track_cursor(&spec.info);
+        }
 
         Self {
             client: client.clone(),
@@ -109,6 +111,65 @@ where
             _phantom: Default::default(),
         }
     }
+
+    /// Sets the number of documents the server should return per batch for the `getMore` commands
+    /// issued by this cursor from now on. Batches that have already been requested from the server
+    /// are not affected.
+    ///
+    /// Returns an error if `batch_size` is zero, since a batch size of zero is only meaningful for
+    /// the command that creates the cursor, or if it does not fit in a signed 32-bit integer.
+    pub fn set_batch_size(&mut self, batch_size: u32) -> Result<()> {
+        if batch_size == 0 || batch_size > std::i32::MAX as u32 {
+            return Err(ErrorKind::ArgumentError {
+                message: format!(
+                    "cursor batch size must be between 1 and {}, got {}",
+                    std::i32::MAX,
+                    batch_size
+                ),
+            }
+            .into());
+        }
+
+        self.wrapped_cursor.set_batch_size(batch_size);
+        Ok(())
+    }
+
+    /// Returns the `postBatchResumeToken` of the batch most recently received from the server if
+    /// all of the documents in it have been returned. This is only present for change streams.
+    pub(crate) fn post_batch_resume_token(&self) -> Option<&Document> {
+        if self.wrapped_cursor.is_batch_empty() {
+            self.wrapped_cursor.post_batch_resume_token()
+        } else {
+            None
+        }
+    }
+
+    /// Returns all of the documents remaining in the batch most recently received from the server,
+    /// first fetching the next batch if the current one has been fully iterated. Returns `None`
+    /// once the cursor is exhausted.
+    ///
+    /// This can be used to process the results in bulk rather than one document at a time; the
+    /// size of the batches can be controlled with the `batch_size` option of the method that
+    /// created the cursor and with [`Cursor::set_batch_size`](#method.set_batch_size).
+    pub async fn try_next_batch(&mut self) -> Result<Option<Vec<T>>> {
+        let mut batch = self.wrapped_cursor.take_buffer();
+        if batch.is_empty() {
+            match self.wrapped_cursor.next().await {
+                Some(Ok(doc)) => {
+                    batch.push_back(doc);
+                    batch.extend(self.wrapped_cursor.take_buffer());
+                }
+                Some(Err(e)) => return Err(e),
+                None => return Ok(None),
+            }
+        }
+
+        batch
+            .into_iter()
+            .map(|doc| from_document(doc).map_err(Into::into))
+            .collect::<Result<Vec<T>>>()
+            .map(Some)
+    }
 }
 
 impl<T> Stream for Cursor<T>
@@ -136,14 +197,16 @@ where
         if self.wrapped_cursor.is_exhausted() {
             return;
         }
+        self.client.untrack_cursor(self.wrapped_cursor.info());
 
         let ns = self.wrapped_cursor.namespace();
         let coll = self
             .client
             .database(ns.db.as_str())
-            .collection(ns.coll.as_str());
+            .collection::<Document>(ns.coll.as_str());
         let cursor_id = self.wrapped_cursor.id();
-        RUNTIME.execute(async move { coll.kill_cursor(cursor_id).await });
+        let pinned_connection = self.wrapped_cursor.pinned_connection().cloned();
+        RUNTIME.execute(async move { coll.kill_cursor(cursor_id, pinned_connection).await });
     }
 }
 
@@ -153,7 +216,7 @@ type ImplicitSessionCursor = GenericCursor<ImplicitSessionGetMoreProvider>;
 
 struct ImplicitSessionGetMoreResult {
     get_more_result: Result<GetMoreResult>,
-    session: Option<ClientSession>,
+    session: Option<Box<ClientSession>>,
 }
 
 impl GetMoreProviderResult for ImplicitSessionGetMoreResult {
@@ -169,7 +232,7 @@ impl GetMoreProviderResult for ImplicitSessionGetMoreResult {
 /// This is to be used with cursors associated with implicit sessions.
 enum ImplicitSessionGetMoreProvider {
     Executing(BoxFuture<'static, ImplicitSessionGetMoreResult>),
-    Idle(Option<ClientSession>),
+    Idle(Option<Box<ClientSession>>),
     Done,
 }
 
@@ -178,7 +241,7 @@ impl ImplicitSessionGetMoreProvider {
         if spec.id() == 0 {
             Self::Done
         } else {
-            Self::Idle(session)
+            Self::Idle(session.map(Box::new))
         }
     }
 }
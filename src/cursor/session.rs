@@ -1,10 +1,16 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
-use futures::future::BoxFuture;
+use futures::{future::BoxFuture, Stream, StreamExt};
+use serde::de::DeserializeOwned;
 
 use super::common::{CursorInformation, GenericCursor, GetMoreProvider, GetMoreProviderResult};
 use crate::{
-    bson::Document,
+    bson::{from_document, Document},
     client::ClientSession,
     cursor::CursorSpecification,
     error::Result,
@@ -14,31 +20,73 @@ use crate::{
     RUNTIME,
 };
 
-/// A cursor that was started with a session and must be iterated using one.
+/// A `SessionCursor` streams the result of a query that was run in a
+/// [`ClientSession`](struct.ClientSession.html), such as one returned by
+/// [`Collection::find_with_session`](struct.Collection.html#method.find_with_session). Its
+/// `getMore`s are run in the same session, so they take part in its causal consistency and, for
+/// snapshot reads, read from its snapshot.
+///
+/// Unlike a [`Cursor`](struct.Cursor.html), a `SessionCursor` doesn't own the session, so the
+/// session has to be passed in each time the cursor is iterated. It must be the session that the
+/// cursor was created in:
+///
+/// ```rust
+/// # use futures::stream::StreamExt;
+/// # use mongodb::{bson::Document, Client, error::Result};
+/// #
+/// # async fn do_stuff() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com").await?;
+/// # let coll = client.database("foo").collection::<Document>("bar");
+/// let mut session = client.start_session(None).await?;
+/// let mut cursor = coll.find_with_session(None, None, &mut session).await?;
+///
+/// while let Some(doc) = cursor.next(&mut session).await {
+///     println!("{}", doc?)
+/// }
+///
+/// // The session can also be lent to a stream over the cursor.
+/// let docs: Vec<Result<Document>> = cursor.stream(&mut session).collect().await;
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug)]
-pub(crate) struct SessionCursor {
+pub struct SessionCursor<T = Document>
+where
+    T: DeserializeOwned + Unpin,
+{
     exhausted: bool,
     client: Client,
     info: CursorInformation,
     buffer: VecDeque<Document>,
+    _phantom: PhantomData<T>,
 }
 
-impl SessionCursor {
-    fn new(client: Client, spec: CursorSpecification) -> Self {
+impl<T> SessionCursor<T>
+where
+    T: DeserializeOwned + Unpin,
+{
+    pub(crate) fn new(client: Client, spec: CursorSpecification) -> Self {
         let exhausted = spec.id() == 0;
+        if !exhausted {
+            client.track_cursor(&spec.info);
+        }
 
         Self {
             exhausted,
             client,
             info: spec.info,
             buffer: spec.initial_buffer,
+            _phantom: Default::default(),
         }
     }
 
-    fn with_session<'session>(
+    /// Returns a stream over the remaining results of the cursor which runs any `getMore`s in
+    /// `session`. The cursor keeps its position when the stream is dropped, so it can be iterated
+    /// further with a later stream.
+    pub fn stream<'session>(
         &mut self,
         session: &'session mut ClientSession,
-    ) -> SessionCursorHandle<'_, 'session> {
+    ) -> SessionCursorStream<'_, 'session, T> {
         let get_more_provider = ExplicitSessionGetMoreProvider::new(session);
 
         // Pass the buffer into this cursor handle for iteration.
@@ -46,8 +94,10 @@ impl SessionCursor {
         let spec = CursorSpecification {
             info: self.info.clone(),
             initial_buffer: std::mem::take(&mut self.buffer),
+            post_batch_resume_token: None,
+            operation_time: None,
         };
-        SessionCursorHandle {
+        SessionCursorStream {
             generic_cursor: ExplicitSessionCursor::new(
                 self.client.clone(),
                 spec,
@@ -56,21 +106,32 @@ impl SessionCursor {
             session_cursor: self,
         }
     }
+
+    /// Returns the next result of the cursor, running a `getMore` in `session` if the current
+    /// batch has been fully iterated. Returns `None` once the cursor is exhausted.
+    pub async fn next(&mut self, session: &mut ClientSession) -> Option<Result<T>> {
+        self.stream(session).next().await
+    }
 }
 
-impl Drop for SessionCursor {
+impl<T> Drop for SessionCursor<T>
+where
+    T: DeserializeOwned + Unpin,
+{
     fn drop(&mut self) {
         if self.exhausted {
             return;
         }
+        self.client.untrack_cursor(&self.info);
 
         let ns = &self.info.ns;
         let coll = self
             .client
             .database(ns.db.as_str())
-            .collection(ns.coll.as_str());
+            .collection::<Document>(ns.coll.as_str());
         let cursor_id = self.info.id;
-        RUNTIME.execute(async move { coll.kill_cursor(cursor_id).await });
+        let pinned_connection = self.info.pinned_connection.clone();
+        RUNTIME.execute(async move { coll.kill_cursor(cursor_id, pinned_connection).await });
     }
 }
 
@@ -78,20 +139,47 @@ impl Drop for SessionCursor {
 /// This is to be used with cursors associated with explicit sessions borrowed from the user.
 type ExplicitSessionCursor<'session> = GenericCursor<ExplicitSessionGetMoreProvider<'session>>;
 
-/// A handle that borrows a `ClientSession` temporarily for executing getMores or iterating through
-/// the current buffer.
+/// A stream over the results of a [`SessionCursor`](struct.SessionCursor.html) that borrows the
+/// `ClientSession` the cursor was created in to run its `getMore`s. It's returned by
+/// [`SessionCursor::stream`](struct.SessionCursor.html#method.stream).
 ///
-/// This updates the buffer of the parent cursor when dropped.
-struct SessionCursorHandle<'cursor, 'session> {
-    session_cursor: &'cursor mut SessionCursor,
+/// The cursor is updated with the progress made by the stream when the stream is dropped.
+pub struct SessionCursorStream<'cursor, 'session, T = Document>
+where
+    T: DeserializeOwned + Unpin,
+{
+    session_cursor: &'cursor mut SessionCursor<T>,
     generic_cursor: ExplicitSessionCursor<'session>,
 }
 
-impl<'cursor, 'session> Drop for SessionCursorHandle<'cursor, 'session> {
+impl<'cursor, 'session, T> Stream for SessionCursorStream<'cursor, 'session, T>
+where
+    T: DeserializeOwned + Unpin,
+{
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.generic_cursor)
+            .poll_next(cx)
+            .map(|next| {
+                next.map(|result| result.and_then(|doc| from_document(doc).map_err(Into::into)))
+            })
+    }
+}
+
+impl<'cursor, 'session, T> Drop for SessionCursorStream<'cursor, 'session, T>
+where
+    T: DeserializeOwned + Unpin,
+{
     fn drop(&mut self) {
         // Update the parent cursor's state based on any iteration performed on this handle.
         self.session_cursor.buffer = self.generic_cursor.take_buffer();
         self.session_cursor.exhausted = self.generic_cursor.is_exhausted();
+        if self.session_cursor.exhausted {
+            // Release the pinned connection, if any, back to its pool rather than holding it
+            // until the cursor is dropped.
+            self.session_cursor.info.pinned_connection = None;
+        }
     }
 }
 
@@ -1,24 +1,32 @@
 pub mod options;
 
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
-use futures::stream::TryStreamExt;
+use futures::stream::{self, StreamExt, TryStreamExt};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     bson::{Bson, Document},
+    change_stream::{event::ChangeStreamEvent, ChangeStream, ChangeStreamTarget},
     concern::{ReadConcern, WriteConcern},
     cursor::Cursor,
     error::{ErrorKind, Result},
-    operation::{Aggregate, Create, DropDatabase, ListCollections, RunCommand},
+    gridfs::GridFsBucket,
+    index::IndexModel,
+    operation::{Aggregate, Create, DropDatabase, ListCollections, RunCommand, RunCursorCommand},
     options::{
         AggregateOptions,
+        ChangeStreamOptions,
         CollectionOptions,
         CreateCollectionOptions,
+        CreateIndexesBulkOptions,
         DatabaseOptions,
         DropDatabaseOptions,
+        GridFsBucketOptions,
         ListCollectionsOptions,
+        RunCursorCommandOptions,
     },
+    results::CreateIndexesResult,
     selection_criteria::SelectionCriteria,
     Client,
     Collection,
@@ -37,7 +45,7 @@ use crate::{
 /// ```rust
 /// 
 /// # #[cfg(not(feature = "sync"))]
-/// # use mongodb::{Client, error::Result};
+/// # use mongodb::{bson::Document, Client, error::Result};
 /// # #[cfg(feature = "async-std-runtime")]
 /// # use async_std::task;
 /// # #[cfg(feature = "tokio-runtime")]
@@ -53,7 +61,7 @@ use crate::{
 ///     let db_ref = db.clone();
 ///
 ///     task::spawn(async move {
-///         let collection = db_ref.collection(&format!("coll{}", i));
+///         let collection = db_ref.collection::<Document>(&format!("coll{}", i));
 ///
 ///         // Do something with the collection
 ///     });
@@ -130,42 +138,27 @@ impl Database {
     /// Gets a handle to a collection specified by `name` of the database. The `Collection` options
     /// (e.g. read preference and write concern) will default to those of the `Database`.
     ///
-    /// This method does not send or receive anything across the wire to the database, so it can be
-    /// used repeatedly without incurring any costs from I/O.
-    pub fn collection(&self, name: &str) -> Collection {
-        Collection::new(self.clone(), name, None)
-    }
-
-    /// Gets a handle to a collection with type `T` specified by `name` of the database. The
-    /// `Collection` options (e.g. read preference and write concern) will default to those of the
-    /// `Database`.
+    /// The type `T` is the type that the documents in the collection are serialized from and
+    /// deserialized into, e.g. `db.collection::<Document>("coll")` for untyped documents or
+    /// `db.collection::<User>("users")` for a struct `User` implementing `Serialize` and
+    /// `Deserialize`.
     ///
     /// This method does not send or receive anything across the wire to the database, so it can be
     /// used repeatedly without incurring any costs from I/O.
-    pub fn collection_with_type<T>(&self, name: &str) -> Collection<T>
+    pub fn collection<T>(&self, name: &str) -> Collection<T>
     where
         T: Serialize + DeserializeOwned + Unpin + Debug,
     {
         Collection::new(self.clone(), name, None)
     }
 
-    /// Gets a handle to a collection specified by `name` in the cluster the `Client` is connected
-    /// to. Operations done with this `Collection` will use the options specified by `options` by
-    /// default and will otherwise default to those of the `Database`.
-    ///
-    /// This method does not send or receive anything across the wire to the database, so it can be
-    /// used repeatedly without incurring any costs from I/O.
-    pub fn collection_with_options(&self, name: &str, options: CollectionOptions) -> Collection {
-        Collection::new(self.clone(), name, Some(options))
-    }
-
     /// Gets a handle to a collection with type `T` specified by `name` in the cluster the `Client`
     /// is connected to. Operations done with this `Collection` will use the options specified by
     /// `options` by default and will otherwise default to those of the `Database`.
     ///
     /// This method does not send or receive anything across the wire to the database, so it can be
     /// used repeatedly without incurring any costs from I/O.
-    pub fn collection_with_type_and_options<T>(
+    pub fn collection_with_options<T>(
         &self,
         name: &str,
         options: CollectionOptions,
@@ -176,6 +169,15 @@ impl Database {
         Collection::new(self.clone(), name, Some(options))
     }
 
+    /// Gets a handle to a GridFS bucket in the database, which stores files in its files and
+    /// chunks collections. The bucket's options default to those of the `Database`.
+    ///
+    /// This method does not send or receive anything across the wire to the database, so it can be
+    /// used repeatedly without incurring any costs from I/O.
+    pub fn gridfs_bucket(&self, options: impl Into<Option<GridFsBucketOptions>>) -> GridFsBucket {
+        GridFsBucket::new(self, options.into().unwrap_or_default())
+    }
+
     /// Drops the database, deleting all data, collections, and indexes stored in it.
     pub async fn drop(&self, options: impl Into<Option<DropDatabaseOptions>>) -> Result<()> {
         let mut options = options.into();
@@ -254,6 +256,37 @@ impl Database {
         self.client().execute_operation(create).await
     }
 
+    /// Creates indexes on multiple collections in the database. `specs` maps the name of each
+    /// collection to the indexes that should be created on it.
+    ///
+    /// A `createIndexes` command is run for each collection, and up to
+    /// `options.max_concurrency` of them are run concurrently. A failure to create the indexes for
+    /// one collection does not prevent the indexes for the other collections from being created;
+    /// the result of each collection's `createIndexes` command is returned keyed by the name of the
+    /// collection.
+    pub async fn create_indexes_bulk(
+        &self,
+        specs: HashMap<String, Vec<IndexModel>>,
+        options: impl Into<Option<CreateIndexesBulkOptions>>,
+    ) -> HashMap<String, Result<CreateIndexesResult>> {
+        let options = options.into().unwrap_or_default();
+        let max_concurrency = options.max_concurrency.unwrap_or(1).max(1);
+
+        stream::iter(specs)
+            .map(|(name, indexes)| {
+                let coll = self.collection::<Document>(&name);
+                let index_options = options.index_options.clone();
+
+                async move {
+                    let result = coll.create_indexes(indexes, index_options).await;
+                    (name, result)
+                }
+            })
+            .buffer_unordered(max_concurrency)
+            .collect()
+            .await
+    }
+
     /// Runs a database-level command.
     ///
     /// Note that no inspection is done on `doc`, so the command will not use the database's default
@@ -268,10 +301,31 @@ impl Database {
         self.client().execute_operation(operation).await
     }
 
+    /// Runs a database-level command that returns a cursor, such as `listCollections` or
+    /// `listIndexes`, and returns a `Cursor` over the results. The cursor iterates the first batch
+    /// in the command's reply and then runs `getMore` commands on the server the command was run
+    /// on, killing the cursor if it's dropped before being exhausted.
+    ///
+    /// As with [`run_command`](#method.run_command), no inspection is done on `command`, so the
+    /// database's default read concern and write concern are not used.
+    pub async fn run_cursor_command(
+        &self,
+        command: Document,
+        options: impl Into<Option<RunCursorCommandOptions>>,
+    ) -> Result<Cursor> {
+        let operation = RunCursorCommand::new(self.name().into(), command, options.into())?;
+        let client = self.client();
+        client
+            .execute_cursor_operation(operation)
+            .await
+            .map(|(spec, session)| Cursor::new(client.clone(), spec, session))
+    }
+
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
-    /// information on aggregations.
+    /// information on aggregations. The pipeline can be built with a
+    /// [`Pipeline`](agg/struct.Pipeline.html).
     pub async fn aggregate(
         &self,
         pipeline: impl IntoIterator<Item = Document>,
@@ -291,4 +345,26 @@ impl Database {
             .await
             .map(|(spec, session)| Cursor::new(client.clone(), spec, session))
     }
+
+    /// Starts a new [`ChangeStream`](change_stream/struct.ChangeStream.html) that receives events
+    /// for all changes in the non-system collections of this database. Note that this method
+    /// (`watch` on a database) is only supported in MongoDB 4.0 or greater.
+    ///
+    /// See [`Client::watch`](struct.Client.html#method.watch) for more details.
+    pub async fn watch(
+        &self,
+        pipeline: impl Into<Option<Vec<Document>>>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream<ChangeStreamEvent<Document>>> {
+        let mut options = options.into();
+        resolve_options!(self, options, [read_concern, selection_criteria]);
+
+        ChangeStream::execute(
+            self.client(),
+            ChangeStreamTarget::Database(self.name().to_string()),
+            pipeline.into().unwrap_or_default(),
+            options,
+        )
+        .await
+    }
 }
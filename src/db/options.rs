@@ -6,7 +6,7 @@ use crate::{
     bson::Document,
     bson_util,
     concern::{ReadConcern, WriteConcern},
-    options::Collation,
+    options::{Collation, CreateIndexOptions},
     selection_criteria::SelectionCriteria,
 };
 
@@ -23,7 +23,9 @@ pub struct DatabaseOptions {
     #[builder(default)]
     pub read_concern: Option<ReadConcern>,
 
-    /// The default write concern for operations.
+    /// The default write concern for operations. If not set, the write concern of the `Client` is
+    /// used. See the [`WriteConcern`](struct.WriteConcern.html) documentation for how write
+    /// concerns are resolved.
     #[builder(default)]
     pub write_concern: Option<WriteConcern>,
 }
@@ -31,7 +33,7 @@ pub struct DatabaseOptions {
 /// These are the valid options for creating a collection with
 /// [`Database::create_collection`](../struct.Database.html#method.create_collection).
 #[skip_serializing_none]
-#[derive(Debug, Default, TypedBuilder, Serialize)]
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct CreateCollectionOptions {
@@ -99,7 +101,7 @@ pub struct CreateCollectionOptions {
 
 /// Specifies how strictly the database should apply validation rules to existing documents during
 /// an update.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub enum ValidationLevel {
@@ -114,7 +116,7 @@ pub enum ValidationLevel {
 
 /// Specifies whether the database should return an error or simply raise a warning if inserted
 /// documents do not pass the validation.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub enum ValidationAction {
@@ -162,6 +164,22 @@ pub struct ListCollectionsOptions {
     pub batch_size: Option<u32>,
 }
 
+/// Specifies the options to a
+/// [`Database::run_cursor_command`](../struct.Database.html#method.run_cursor_command) operation.
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct RunCursorCommandOptions {
+    /// The criteria used to select the server the command is run on. The `getMore` commands
+    /// that iterate the resulting cursor are sent to the same server.
+    #[builder(default)]
+    pub selection_criteria: Option<SelectionCriteria>,
+
+    /// The number of documents the server should return per `getMore` batch. This has no effect
+    /// on the first batch, whose size is set by the command itself.
+    #[builder(default)]
+    pub batch_size: Option<u32>,
+}
+
 /// Specifies the options to a
 /// [`Client::list_databases`](../struct.Client.html#method.list_databases) operation.
 #[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
@@ -173,3 +191,21 @@ pub struct ListDatabasesOptions {
     #[builder(default)]
     pub authorized_databases: Option<bool>,
 }
+
+/// These are the valid options for creating indexes on multiple collections with
+/// [`Database::create_indexes_bulk`](../struct.Database.html#method.create_indexes_bulk).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct CreateIndexesBulkOptions {
+    /// The maximum number of collections whose indexes will be created concurrently. A value of
+    /// 0 is treated as 1.
+    ///
+    /// The default value is 1, i.e. the indexes for each collection are created one collection at
+    /// a time.
+    #[builder(default)]
+    pub max_concurrency: Option<usize>,
+
+    /// The options to use for each `createIndexes` command.
+    #[builder(default)]
+    pub index_options: Option<CreateIndexOptions>,
+}
@@ -1,13 +1,21 @@
 //! Contains the `Error` and `Result` types that `mongodb` uses.
 
-use std::{fmt, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 use err_derive::Error;
 use lazy_static::lazy_static;
 use serde::Deserialize;
 use time::OutOfRangeError;
 
-use crate::{bson::Document, options::StreamAddress};
+use crate::{
+    bson::{Bson, Document},
+    options::StreamAddress,
+    results::BulkWriteResult,
+};
 
 lazy_static! {
     static ref RECOVERING_CODES: Vec<i32> = vec![11600, 11602, 13436, 189, 91];
@@ -17,8 +25,32 @@ lazy_static! {
         vec![11600, 11602, 10107, 13435, 13436, 189, 91, 7, 6, 89, 9001];
     static ref RETRYABLE_WRITE_CODES: Vec<i32> =
         vec![11600, 11602, 10107, 13435, 13436, 189, 91, 7, 6, 89, 9001, 262];
+    static ref RESUMABLE_CHANGE_STREAM_CODES: Vec<i32> = vec![
+        6, 7, 43, 63, 89, 91, 133, 150, 189, 234, 262, 9001, 10107, 11600, 11602, 13388, 13435,
+        13436
+    ];
 }
 
+/// The label the driver adds to errors after which a write can safely be retried, e.g. network
+/// errors. If a write still fails after being retried, the error returned for the retry carries
+/// this label too.
+pub const RETRYABLE_WRITE_ERROR: &str = "RetryableWriteError";
+
+/// The label on errors after which the whole transaction they occurred in can be retried from the
+/// start, e.g. network errors while the transaction was in progress. The server adds it to some of
+/// the errors it returns, and the driver adds it to network and server selection errors in
+/// transactions.
+pub const TRANSIENT_TRANSACTION_ERROR: &str = "TransientTransactionError";
+
+/// The label on errors returned when committing a transaction after which it's unknown whether the
+/// transaction was committed, e.g. network errors. Committing the transaction can safely be retried
+/// after such errors.
+pub const UNKNOWN_TRANSACTION_COMMIT_RESULT: &str = "UnknownTransactionCommitResult";
+
+/// The label the driver adds to the errors returned by operations that did not complete within
+/// their time limit, i.e. the `timeout` client or operation option.
+pub const TIMEOUT_ERROR: &str = "TimeoutError";
+
 /// The result type for all methods that can return an error in the `mongodb` crate.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -31,14 +63,16 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct Error {
     /// The type of error that occurred.
     pub kind: Arc<ErrorKind>,
-    labels: Vec<String>,
+    labels: HashSet<String>,
+    response: Option<Document>,
 }
 
 impl Error {
     pub(crate) fn new(e: Arc<ErrorKind>) -> Error {
         Error {
             kind: e,
-            labels: Vec::new(),
+            labels: HashSet::new(),
+            response: None,
         }
     }
 
@@ -55,6 +89,15 @@ impl Error {
         Error::authentication_error(mechanism_name, "internal error")
     }
 
+    /// Creates a `Timeout` error for an operation that did not complete within its time limit,
+    /// labeled with `TIMEOUT_ERROR`.
+    pub(crate) fn timeout() -> Self {
+        Error::from(ErrorKind::Timeout {
+            message: "the time limit expired before the operation completed".to_string(),
+        })
+        .with_label(TIMEOUT_ERROR)
+    }
+
     /// Creates an `AuthenticationError` for the given mechanism when the server response is
     /// invalid.
     pub(crate) fn invalid_authentication_response(mechanism_name: &str) -> Error {
@@ -80,6 +123,10 @@ impl Error {
         matches!(self.kind.as_ref(), ErrorKind::CommandError(err) if err.code == 26)
     }
 
+    pub(crate) fn is_ns_exists(&self) -> bool {
+        matches!(self.kind.as_ref(), ErrorKind::CommandError(err) if err.code == 48)
+    }
+
     /// Whether a read operation should be retried if this error occurs.
     pub(crate) fn is_read_retryable(&self) -> bool {
         if self.is_network_error() {
@@ -99,8 +146,27 @@ impl Error {
         }
     }
 
+    /// Whether a change stream should automatically resume after this error occurs. Servers with a
+    /// max wire version of 9 (4.4) or higher label the errors that are resumable, so only the label
+    /// is considered for them; for older servers, the error code is checked instead.
+    pub(crate) fn is_resumable(&self, max_wire_version: Option<i32>) -> bool {
+        if self.is_network_error() {
+            return true;
+        }
+        if max_wire_version
+            .map(|version| version >= 9)
+            .unwrap_or(false)
+        {
+            return self.contains_label("ResumableChangeStreamError");
+        }
+        match &self.kind.code_and_message() {
+            Some((code, _)) => RESUMABLE_CHANGE_STREAM_CODES.contains(&code),
+            None => false,
+        }
+    }
+
     pub(crate) fn is_write_retryable(&self) -> bool {
-        self.contains_label("RetryableWriteError")
+        self.contains_label(RETRYABLE_WRITE_ERROR)
     }
 
     /// Whether a "RetryableWriteError" label should be added to this error. If max_wire_version
@@ -129,7 +195,7 @@ impl Error {
     }
 
     /// Returns the labels for this error.
-    pub fn labels(&self) -> &[String] {
+    pub fn labels(&self) -> &HashSet<String> {
         match self.kind.as_ref() {
             ErrorKind::CommandError(err) => &err.labels,
             ErrorKind::WriteError(err) => match err {
@@ -146,26 +212,45 @@ impl Error {
 
     /// Whether this error contains the specified label.
     pub fn contains_label<T: AsRef<str>>(&self, label: T) -> bool {
-        self.labels().iter().any(|actual_label| actual_label.as_str() == label.as_ref())
+        self.labels().contains(label.as_ref())
+    }
+
+    /// The full reply the server sent for the command that failed, if this error was returned by
+    /// the server. This includes the fields not captured by the error's `kind`, such as the
+    /// `errInfo` describing which rules a document violated when it failed schema validation.
+    ///
+    /// This is set for `CommandError`s, and for `WriteError`s and `BulkWriteError`s returned by
+    /// a single command. It is `None` for errors that did not originate from a server reply, and
+    /// for the combined `BulkWriteError` returned by an `insert_many` split across several
+    /// commands.
+    pub fn error_response(&self) -> Option<&Document> {
+        self.response.as_ref()
+    }
+
+    /// Returns this Error with the given server reply attached as its `error_response`.
+    pub(crate) fn with_response(mut self, response: Document) -> Self {
+        self.response = Some(response);
+        self
     }
 
     /// Returns a copy of this Error with the specified label added.
     pub(crate) fn with_label<T: AsRef<str>>(mut self, label: T) -> Self {
         let label = label.as_ref().to_string();
-        match self.kind.as_ref() {
+        let response = self.response.take();
+        let error: Error = match self.kind.as_ref() {
             ErrorKind::CommandError(err) => {
                 let mut err = err.clone();
-                err.labels.push(label);
+                err.labels.insert(label);
                 ErrorKind::CommandError(err).into()
             }
             ErrorKind::WriteError(err) => match err {
                 WriteFailure::WriteError(_) => {
-                    self.labels.push(label);
+                    self.labels.insert(label);
                     self
                 }
                 WriteFailure::WriteConcernError(err) => {
                     let mut err = err.clone();
-                    err.labels.push(label);
+                    err.labels.insert(label);
                     ErrorKind::WriteError(WriteFailure::WriteConcernError(err)).into()
                 }
             },
@@ -173,20 +258,22 @@ impl Error {
                 Some(ref write_concern_error) => {
                     let mut err = err.clone();
                     let mut write_concern_error = write_concern_error.clone();
-                    write_concern_error.labels.push(label);
+                    write_concern_error.labels.insert(label);
                     err.write_concern_error = Some(write_concern_error);
                     ErrorKind::BulkWriteError(err).into()
                 }
                 None => {
-                    self.labels.push(label);
+                    self.labels.insert(label);
                     self
                 }
             },
             _ => {
-                self.labels.push(label);
+                self.labels.insert(label);
                 self
             }
-        }
+        };
+
+        Error { response, ..error }
     }
 }
 
@@ -197,7 +284,8 @@ where
     fn from(err: E) -> Self {
         Self {
             kind: Arc::new(err.into()),
-            labels: Vec::new(),
+            labels: HashSet::new(),
+            response: None,
         }
     }
 }
@@ -236,6 +324,11 @@ pub enum ErrorKind {
     #[non_exhaustive]
     AuthenticationError { message: String },
 
+    /// An operation was attempted on a [`Client`](../struct.Client.html) after it was shut down
+    /// with [`Client::shutdown`](../struct.Client.html#method.shutdown).
+    #[error(display = "Attempted to use a Client that has been shut down")]
+    ClientShutdown,
+
     /// Wrapper around `bson::de::Error`.
     #[error(display = "{}", _0)]
     BsonDecode(#[error(source)] crate::bson::de::Error),
@@ -261,6 +354,20 @@ pub enum ErrorKind {
     #[error(display = "{}", _0)]
     DnsResolve(trust_dns_resolver::error::ResolveError),
 
+    /// An error occurred while creating a data key or encrypting or decrypting a value with a
+    /// [`ClientEncryption`](../client_encryption/struct.ClientEncryption.html), e.g. because the
+    /// data key was not found or libmongocrypt rejected a value.
+    #[error(display = "Encryption error: {}", message)]
+    #[non_exhaustive]
+    EncryptionError { message: String },
+
+    /// An error occurred while uploading a file to or downloading a file from a
+    /// [`GridFsBucket`](../gridfs/struct.GridFsBucket.html), e.g. because the file does not exist
+    /// or some of its chunks are missing.
+    #[error(display = "GridFS error: {}", message)]
+    #[non_exhaustive]
+    GridFsError { message: String },
+
     #[error(display = "Internal error: {}", message)]
     #[non_exhaustive]
     InternalError { message: String },
@@ -278,6 +385,15 @@ pub enum ErrorKind {
     #[error(display = "{}", _0)]
     Io(#[error(source)] std::io::Error),
 
+    /// The Client already had the maximum number of operations in progress and was configured to
+    /// fail rather than wait for one of them to finish.
+    #[error(
+        display = "Cannot start operation: {} operations are already in progress",
+        max_concurrent_operations
+    )]
+    #[non_exhaustive]
+    MaxConcurrentOperationsExceeded { max_concurrent_operations: usize },
+
     #[error(display = "No DNS results for domain {}", _0)]
     NoDnsResults(StreamAddress),
 
@@ -313,11 +429,29 @@ pub enum ErrorKind {
     #[non_exhaustive]
     ServerSelectionError { message: String },
 
+    /// A session was started on a deployment that doesn't support sessions, e.g. a standalone
+    /// server.
+    #[error(
+        display = "Attempted to start a session on a deployment that does not support sessions"
+    )]
+    SessionsNotSupported,
+
     /// An error occurred during SRV record lookup.
     #[error(display = "An error occurred during SRV record lookup: {}", message)]
     #[non_exhaustive]
     SrvLookupError { message: String },
 
+    /// An operation did not complete within its time limit.
+    #[error(display = "Operation timed out: {}", message)]
+    #[non_exhaustive]
+    Timeout { message: String },
+
+    /// A transaction operation was not valid in the state the session's transaction was in, e.g.
+    /// committing a transaction that was never started.
+    #[error(display = "Transaction error: {}", message)]
+    #[non_exhaustive]
+    Transaction { message: String },
+
     /// A timeout occurred before a Tokio task could be completed.
     #[cfg(feature = "tokio-runtime")]
     #[error(display = "{}", _0)]
@@ -331,13 +465,31 @@ pub enum ErrorKind {
     #[non_exhaustive]
     TxtLookupError { message: String },
 
-    /// The Client timed out while checking out a connection from connection pool.
+    /// The Client timed out while checking out a connection from connection pool. The state of the
+    /// pool at the time is included for diagnosis.
     #[error(
-        display = "Timed out while checking out a connection from connection pool with address {}",
-        address
+        display = "Timed out while checking out a connection from connection pool with address {} \
+                   ({} connections, {} available, {} pending)",
+        address,
+        connections,
+        available_connections,
+        pending_connections
     )]
     #[non_exhaustive]
-    WaitQueueTimeoutError { address: StreamAddress },
+    WaitQueueTimeoutError {
+        /// The address of the server the pool is for.
+        address: StreamAddress,
+
+        /// The number of connections managed by the pool, including ones that were checked out
+        /// or being established.
+        connections: u32,
+
+        /// The number of connections that were available in the pool.
+        available_connections: u32,
+
+        /// The number of connections that were being established.
+        pending_connections: u32,
+    },
 
     /// An error occurred when trying to execute a write operation
     #[error(
@@ -366,6 +518,46 @@ impl ErrorKind {
         matches!(self, ErrorKind::Io(..))
     }
 
+    /// Gets the name of this error's variant, e.g. "CommandError".
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::AddrParse(..) => "AddrParse",
+            ErrorKind::ArgumentError { .. } => "ArgumentError",
+            #[cfg(feature = "async-std-runtime")]
+            ErrorKind::AsyncStdTimeout(..) => "AsyncStdTimeout",
+            ErrorKind::AuthenticationError { .. } => "AuthenticationError",
+            ErrorKind::BsonDecode(..) => "BsonDecode",
+            ErrorKind::BsonEncode(..) => "BsonEncode",
+            ErrorKind::BulkWriteError(..) => "BulkWriteError",
+            ErrorKind::ClientShutdown => "ClientShutdown",
+            ErrorKind::CommandError(..) => "CommandError",
+            ErrorKind::DnsResolve(..) => "DnsResolve",
+            ErrorKind::EncryptionError { .. } => "EncryptionError",
+            ErrorKind::GridFsError { .. } => "GridFsError",
+            ErrorKind::InternalError { .. } => "InternalError",
+            ErrorKind::InvalidDnsName(..) => "InvalidDnsName",
+            ErrorKind::InvalidHostname { .. } => "InvalidHostname",
+            ErrorKind::Io(..) => "Io",
+            ErrorKind::MaxConcurrentOperationsExceeded { .. } => "MaxConcurrentOperationsExceeded",
+            ErrorKind::NoDnsResults(..) => "NoDnsResults",
+            ErrorKind::OperationError { .. } => "OperationError",
+            ErrorKind::OutOfRangeError(..) => "OutOfRangeError",
+            ErrorKind::ParseError { .. } => "ParseError",
+            ErrorKind::ResponseError { .. } => "ResponseError",
+            ErrorKind::ServerSelectionError { .. } => "ServerSelectionError",
+            ErrorKind::SessionsNotSupported => "SessionsNotSupported",
+            ErrorKind::SrvLookupError { .. } => "SrvLookupError",
+            ErrorKind::Timeout { .. } => "Timeout",
+            ErrorKind::Transaction { .. } => "Transaction",
+            #[cfg(feature = "tokio-runtime")]
+            ErrorKind::TokioTimeoutElapsed(..) => "TokioTimeoutElapsed",
+            ErrorKind::RustlsConfig(..) => "RustlsConfig",
+            ErrorKind::TxtLookupError { .. } => "TxtLookupError",
+            ErrorKind::WaitQueueTimeoutError { .. } => "WaitQueueTimeoutError",
+            ErrorKind::WriteError(..) => "WriteError",
+        }
+    }
+
     /// Gets the code/message tuple from this error, if applicable. In the case of write errors, the
     /// code and message are taken from the write concern error, if there is one.
     pub(crate) fn code_and_message(&self) -> Option<(i32, &str)> {
@@ -453,7 +645,7 @@ pub struct CommandError {
 
     /// The error labels that the server returned.
     #[serde(rename = "errorLabels", default)]
-    pub labels: Vec<String>,
+    pub labels: HashSet<String>,
 }
 
 impl fmt::Display for CommandError {
@@ -483,7 +675,7 @@ pub struct WriteConcernError {
 
     /// The error labels that the server returned.
     #[serde(rename = "errorLabels", default)]
-    pub labels: Vec<String>,
+    pub labels: HashSet<String>,
 }
 
 /// An error that occurred during a write operation that wasn't due to being unable to satisfy a
@@ -536,6 +728,15 @@ pub struct BulkWriteFailure {
 
     /// The error that occurred on account of write concern failure.
     pub write_concern_error: Option<WriteConcernError>,
+
+    /// The combined result of the writes that succeeded. This is only set for errors returned by
+    /// [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write).
+    pub partial_result: Option<BulkWriteResult>,
+
+    /// The ids of the documents that were inserted, keyed by their index in the documents passed
+    /// to [`Collection::insert_many`](../struct.Collection.html#method.insert_many). This is empty
+    /// for errors returned by other operations.
+    pub inserted_ids: HashMap<usize, Bson>,
 }
 
 impl BulkWriteFailure {
@@ -543,6 +744,8 @@ impl BulkWriteFailure {
         BulkWriteFailure {
             write_errors: None,
             write_concern_error: None,
+            partial_result: None,
+            inserted_ids: HashMap::new(),
         }
     }
 }
@@ -581,7 +784,10 @@ pub(crate) fn convert_bulk_errors(error: Error) -> Error {
     match *error.kind {
         ErrorKind::BulkWriteError(ref bulk_failure) => {
             match WriteFailure::from_bulk_failure(bulk_failure.clone()) {
-                Ok(failure) => ErrorKind::WriteError(failure).into(),
+                Ok(failure) => Error {
+                    response: error.response.clone(),
+                    ..Error::from(ErrorKind::WriteError(failure))
+                },
                 Err(e) => e,
             }
         }
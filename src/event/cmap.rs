@@ -1,10 +1,11 @@
 //! Contains the events and functionality for monitoring behavior of the connection pooling of a
 //! `Client`.
 
+use derivative::Derivative;
 use serde::Deserialize;
 
 pub use crate::cmap::options::ConnectionPoolOptions;
-use crate::options::StreamAddress;
+use crate::{error::Error, options::StreamAddress};
 
 /// We implement `Deserialize` for all of the event types so that we can more easily parse the CMAP
 /// spec tests. However, we have no need to parse the address field from the JSON files (if it's
@@ -32,6 +33,11 @@ pub struct PoolCreatedEvent {
 }
 
 /// Event emitted when a connection pool is cleared.
+///
+/// Each pool has a generation, which is incremented each time the pool is cleared. The connection
+/// events report the generation of the pool at the time their connection was created, so a
+/// connection whose generation is lower than that of its pool was created before the most recent
+/// clear; it is stale and will be closed rather than reused.
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[non_exhaustive]
 pub struct PoolClearedEvent {
@@ -65,6 +71,11 @@ pub struct ConnectionCreatedEvent {
     /// to identify other events related to this connection.
     #[serde(default = "default_connection_id")]
     pub connection_id: u32,
+
+    /// The generation of the pool at the time the connection was created. See
+    /// [`PoolClearedEvent`](struct.PoolClearedEvent.html) for what the generation means.
+    #[serde(default)]
+    pub generation: u32,
 }
 
 /// Event emitted when a connection is ready to be used. This indicates that all the necessary
@@ -82,10 +93,16 @@ pub struct ConnectionReadyEvent {
     /// to identify other events related to this connection.
     #[serde(default = "default_connection_id")]
     pub connection_id: u32,
+
+    /// The generation of the pool at the time the connection was created. See
+    /// [`PoolClearedEvent`](struct.PoolClearedEvent.html) for what the generation means.
+    #[serde(default)]
+    pub generation: u32,
 }
 
 /// Event emitted when a connection is closed.
-#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Derivative)]
+#[derivative(PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct ConnectionClosedEvent {
@@ -99,8 +116,19 @@ pub struct ConnectionClosedEvent {
     #[serde(default)]
     pub connection_id: u32,
 
+    /// The generation of the pool at the time the connection was created. See
+    /// [`PoolClearedEvent`](struct.PoolClearedEvent.html) for what the generation means.
+    #[serde(default)]
+    pub generation: u32,
+
     /// The reason that the connection was closed.
     pub reason: ConnectionClosedReason,
+
+    /// The error that caused the connection to be closed, if `reason` is
+    /// `ConnectionClosedReason::Error`.
+    #[derivative(PartialEq = "ignore")]
+    #[serde(skip)]
+    pub error: Option<Error>,
 }
 
 /// The reasons that a connection may be closed.
@@ -114,7 +142,8 @@ pub enum ConnectionClosedReason {
     /// The connection has been available for longer than `max_idle_time` without being used.
     Idle,
 
-    /// An error occurred while using the connection.
+    /// An error occurred while establishing or using the connection, e.g. a network error or a
+    /// malformed reply. The error is included in the event.
     Error,
 
     /// The connection was dropped during read or write.
@@ -174,6 +203,11 @@ pub struct ConnectionCheckedOutEvent {
     /// to identify other events related to this connection.
     #[serde(default = "default_connection_id")]
     pub connection_id: u32,
+
+    /// The generation of the pool at the time the connection was created. See
+    /// [`PoolClearedEvent`](struct.PoolClearedEvent.html) for what the generation means.
+    #[serde(default)]
+    pub generation: u32,
 }
 
 /// Event emitted when a connection is checked back into a connection pool.
@@ -190,6 +224,11 @@ pub struct ConnectionCheckedInEvent {
     /// to identify other events related to this connection.
     #[serde(default = "default_connection_id")]
     pub connection_id: u32,
+
+    /// The generation of the pool at the time the connection was created. See
+    /// [`PoolClearedEvent`](struct.PoolClearedEvent.html) for what the generation means.
+    #[serde(default)]
+    pub generation: u32,
 }
 
 /// The default connection ID to use for deserialization of events from test files.
@@ -1,9 +1,12 @@
 //! Contains the events and functionality to monitor the commands and responses that a `Client`
 //! sends and receives from the server.
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use crate::{bson::Document, cmap::ConnectionInfo, error::Error};
+use async_trait::async_trait;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::{bson::Document, cmap::ConnectionInfo, error::Error, RUNTIME};
 
 /// An event that triggers when a database command is initiated.
 #[derive(Clone, Debug)]
@@ -26,6 +29,12 @@ pub struct CommandStartedEvent {
 
     /// Information about the connect the command will be run on.
     pub connection: ConnectionInfo,
+
+    /// The length in bytes of the wire message the command is sent in, including its header. If
+    /// the message is compressed, this is the length of the `OP_COMPRESSED` message, whereas
+    /// `command` is always the uncompressed document. This is `None` if the command couldn't be
+    /// serialized.
+    pub wire_bytes: Option<usize>,
 }
 
 /// An event that triggers when a database command completes without an error.
@@ -48,6 +57,11 @@ pub struct CommandSucceededEvent {
 
     /// Information about the connect the command will be run on.
     pub connection: ConnectionInfo,
+
+    /// The length in bytes of the wire message the reply was received in, including its header.
+    /// If the message was compressed, this is the length of the `OP_COMPRESSED` message, whereas
+    /// `reply` is always the uncompressed document.
+    pub wire_bytes: Option<usize>,
 }
 
 /// An event that triggers when a command failed to complete successfully.
@@ -72,6 +86,74 @@ pub struct CommandFailedEvent {
     pub connection: ConnectionInfo,
 }
 
+/// An event that triggers when a database command takes longer than the
+/// [`slow_operation_threshold`](../../options/struct.ClientOptions.html#structfield.slow_operation_threshold)
+/// configured on the `Client` to complete. This is emitted in addition to the
+/// [`CommandSucceededEvent`](struct.CommandSucceededEvent.html) or
+/// [`CommandFailedEvent`](struct.CommandFailedEvent.html) for the command.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct SlowCommandEvent {
+    /// The total execution time of the command (including the network round-trip).
+    pub duration: Duration,
+
+    /// The type of command that was run, e.g. "find" or "isMaster".
+    pub command_name: String,
+
+    /// The name of the database the command was run against.
+    pub db: String,
+
+    /// The name of the collection the command was run against, if the command targets a single
+    /// collection.
+    pub collection: Option<String>,
+
+    /// Whether the command completed successfully.
+    pub succeeded: bool,
+
+    /// The driver-generated identifier for the request. Applications can use this to identify the
+    /// corresponding [`CommandStartedEvent`](struct.CommandStartedEvent.html) that triggered
+    /// earlier.
+    pub request_id: i32,
+
+    /// Information about the connect the command was run on.
+    pub connection: ConnectionInfo,
+}
+
+/// An event that triggers when an operation such as an insert or a `find` completes, if the
+/// [`emit_operation_events`](../../options/struct.ClientOptions.html#structfield.emit_operation_events)
+/// option is set on the `Client`. Unlike the other command events, which are emitted for each
+/// command sent to the server, this is emitted once per operation and covers all of the work done
+/// for it, including retries. It is emitted in addition to the other command events.
+///
+/// Getting more results from a cursor is a separate operation from the one that created it.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct OperationEvent {
+    /// The name of the command the operation runs, e.g. "insert" or "find".
+    pub operation_name: String,
+
+    /// The total execution time of the operation. In addition to the phases below, this includes
+    /// e.g. time spent waiting for `max_concurrent_operations` to allow the operation to start.
+    pub duration: Duration,
+
+    /// The time spent selecting a server to execute the operation on.
+    pub server_selection_duration: Duration,
+
+    /// The time spent checking out a connection from the selected server's connection pool,
+    /// including establishing a new connection if one was needed.
+    pub connection_checkout_duration: Duration,
+
+    /// The time spent executing the command on the server (including the network round-trip).
+    pub command_duration: Duration,
+
+    /// Whether the operation was retried after a retryable error. If so, the durations above are
+    /// the totals across both attempts.
+    pub retried: bool,
+
+    /// Whether the operation completed successfully.
+    pub succeeded: bool,
+}
+
 /// Applications can implement this trait to specify custom logic to run on each command event sent
 /// by the driver.
 ///
@@ -122,4 +204,210 @@ pub trait CommandEventHandler: Send + Sync {
     /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
     /// whenever a database command fails to complete successfully.
     fn handle_command_failed_event(&self, _event: CommandFailedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a database command takes longer than the client's `slow_operation_threshold` to
+    /// complete.
+    fn handle_slow_command_event(&self, _event: SlowCommandEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever an operation completes if the client's `emit_operation_events` option is set.
+    fn handle_operation_event(&self, _event: OperationEvent) {}
+}
+
+/// Applications can implement this trait to run asynchronous logic, such as shipping events to an
+/// external service, on each command event sent by the driver.
+///
+/// Unlike a [`CommandEventHandler`](trait.CommandEventHandler.html), which is called in the middle
+/// of the operation that emitted the event, this handler is awaited on a background task that
+/// receives the events in the order they were emitted. The
+/// [`async_command_event_backpressure`](../../options/struct.ClientOptions.html#structfield.async_command_event_backpressure)
+/// client option controls what happens when the handler falls behind. Every `CommandEventHandler`
+/// is also a `CommandEventHandlerAsync`.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// #
+/// # use async_trait::async_trait;
+/// # use mongodb::{
+/// #     error::Result,
+/// #     event::command::{
+/// #         CommandEventHandlerAsync,
+/// #         CommandFailedEvent
+/// #     },
+/// #     options::ClientOptions,
+/// # };
+/// # #[cfg(feature = "sync")]
+/// # use mongodb::sync::Client;
+/// # #[cfg(not(feature = "sync"))]
+/// # use mongodb::Client;
+/// #
+/// struct FailedCommandReporter;
+///
+/// #[async_trait]
+/// impl CommandEventHandlerAsync for FailedCommandReporter {
+///     async fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+///         // Send the event somewhere, e.g. with an HTTP client.
+///     }
+/// }
+///
+/// # fn do_stuff() -> Result<()> {
+/// let handler: Arc<dyn CommandEventHandlerAsync> = Arc::new(FailedCommandReporter);
+/// let options = ClientOptions::builder()
+///                   .async_command_event_handler(handler)
+///                   .build();
+/// let client = Client::with_options(options)?;
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait CommandEventHandlerAsync: Send + Sync {
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a database command is initiated.
+    async fn handle_command_started_event(&self, _event: CommandStartedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a database command successfully completes.
+    async fn handle_command_succeeded_event(&self, _event: CommandSucceededEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a database command fails to complete successfully.
+    async fn handle_command_failed_event(&self, _event: CommandFailedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a database command takes longer than the client's `slow_operation_threshold` to
+    /// complete.
+    async fn handle_slow_command_event(&self, _event: SlowCommandEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever an operation completes if the client's `emit_operation_events` option is set.
+    async fn handle_operation_event(&self, _event: OperationEvent) {}
+}
+
+#[async_trait]
+impl<T: CommandEventHandler + ?Sized> CommandEventHandlerAsync for T {
+    async fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        CommandEventHandler::handle_command_started_event(self, event)
+    }
+
+    async fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        CommandEventHandler::handle_command_succeeded_event(self, event)
+    }
+
+    async fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        CommandEventHandler::handle_command_failed_event(self, event)
+    }
+
+    async fn handle_slow_command_event(&self, event: SlowCommandEvent) {
+        CommandEventHandler::handle_slow_command_event(self, event)
+    }
+
+    async fn handle_operation_event(&self, event: OperationEvent) {
+        CommandEventHandler::handle_operation_event(self, event)
+    }
+}
+
+/// What a [`Client`](../../struct.Client.html) does with the events for its
+/// [`CommandEventHandlerAsync`](trait.CommandEventHandlerAsync.html) when the handler falls behind.
+/// Events that the handler hasn't processed yet are kept in a queue of the given capacity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum EventBackpressure {
+    /// Operations wait for room in the queue before continuing, so no events are lost but a slow
+    /// handler slows down the operations emitting events.
+    Wait { capacity: usize },
+
+    /// Events that don't fit in the queue are dropped, so a slow handler never delays an
+    /// operation.
+    Drop { capacity: usize },
+}
+
+impl EventBackpressure {
+    pub(crate) fn capacity(self) -> usize {
+        match self {
+            EventBackpressure::Wait { capacity } | EventBackpressure::Drop { capacity } => capacity,
+        }
+    }
+}
+
+impl Default for EventBackpressure {
+    fn default() -> Self {
+        EventBackpressure::Drop { capacity: 1000 }
+    }
+}
+
+/// A command event of any type.
+#[derive(Clone, Debug)]
+pub(crate) enum CommandEvent {
+    Started(CommandStartedEvent),
+    Succeeded(CommandSucceededEvent),
+    Failed(CommandFailedEvent),
+    Slow(SlowCommandEvent),
+    Operation(OperationEvent),
+}
+
+impl CommandEvent {
+    pub(crate) fn emit(self, handler: &dyn CommandEventHandler) {
+        match self {
+            CommandEvent::Started(event) => handler.handle_command_started_event(event),
+            CommandEvent::Succeeded(event) => handler.handle_command_succeeded_event(event),
+            CommandEvent::Failed(event) => handler.handle_command_failed_event(event),
+            CommandEvent::Slow(event) => handler.handle_slow_command_event(event),
+            CommandEvent::Operation(event) => handler.handle_operation_event(event),
+        }
+    }
+
+    async fn emit_async(self, handler: &dyn CommandEventHandlerAsync) {
+        match self {
+            CommandEvent::Started(event) => handler.handle_command_started_event(event).await,
+            CommandEvent::Succeeded(event) => handler.handle_command_succeeded_event(event).await,
+            CommandEvent::Failed(event) => handler.handle_command_failed_event(event).await,
+            CommandEvent::Slow(event) => handler.handle_slow_command_event(event).await,
+            CommandEvent::Operation(event) => handler.handle_operation_event(event).await,
+        }
+    }
+}
+
+/// The queue of events waiting to be passed to a `CommandEventHandlerAsync`.
+#[derive(Debug)]
+pub(crate) struct CommandEventQueue {
+    sender: Mutex<mpsc::Sender<CommandEvent>>,
+    backpressure: EventBackpressure,
+}
+
+impl CommandEventQueue {
+    /// Creates a queue and starts the task that passes its events to `handler`. The task exits
+    /// once the queue is dropped.
+    pub(crate) fn start(
+        handler: Arc<dyn CommandEventHandlerAsync>,
+        backpressure: EventBackpressure,
+    ) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<CommandEvent>(backpressure.capacity());
+
+        RUNTIME.execute(async move {
+            while let Some(event) = receiver.recv().await {
+                event.emit_async(handler.as_ref()).await;
+            }
+        });
+
+        Self {
+            sender: Mutex::new(sender),
+            backpressure,
+        }
+    }
+
+    pub(crate) async fn push(&self, event: CommandEvent) {
+        let mut sender = self.sender.lock().await;
+
+        // The only error possible is the task having stopped, in which case there's nothing left
+        // to deliver the event to.
+        match self.backpressure {
+            EventBackpressure::Wait { .. } => {
+                let _ = sender.send(event).await;
+            }
+            EventBackpressure::Drop { .. } => {
+                let _ = sender.try_send(event);
+            }
+        }
+    }
 }
@@ -0,0 +1,189 @@
+//! Contains the events and functionality for monitoring the servers that a `Client` discovers and
+//! stops tracking as part of server discovery and monitoring (SDAM), the changes in their state and
+//! the heartbeats used to check them.
+
+use std::time::Duration;
+
+use crate::{
+    bson::Document,
+    error::Error,
+    options::StreamAddress,
+    ServerType,
+    TopologyDescription,
+    TopologyType,
+};
+
+/// Published when a server is added to the topology, before the driver starts monitoring it. This
+/// happens both for the hosts the `Client` is initially configured with and for hosts discovered
+/// later, e.g. a member newly added to a replica set.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ServerOpeningEvent {
+    /// The address of the server.
+    pub address: StreamAddress,
+}
+
+/// Published when a server is removed from the topology, e.g. because it is no longer listed as a
+/// member of the replica set. The driver stops monitoring the server after this event.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ServerClosedEvent {
+    /// The address of the server.
+    pub address: StreamAddress,
+}
+
+/// Published when the type of a server changes, e.g. when a primary steps down and becomes a
+/// secondary, or when a server becomes unreachable and its type reverts to `Unknown`.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ServerDescriptionChangedEvent {
+    /// The address of the server.
+    pub address: StreamAddress,
+
+    /// The type of the server before the change.
+    pub previous_type: ServerType,
+
+    /// The type of the server after the change.
+    pub new_type: ServerType,
+}
+
+/// Published when the driver's view of the topology changes: when the type of the topology or of
+/// any of its servers changes, or when servers are added to or removed from it. This is published
+/// after the `ServerDescriptionChangedEvent`s, `ServerOpeningEvent`s and `ServerClosedEvent`s for
+/// the same change.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct TopologyDescriptionChangedEvent {
+    /// The type of the topology before the change.
+    pub previous_type: TopologyType,
+
+    /// The type of the topology after the change.
+    pub new_type: TopologyType,
+
+    /// A snapshot of the topology before the change.
+    pub previous_description: TopologyDescription,
+
+    /// A snapshot of the topology after the change.
+    pub new_description: TopologyDescription,
+}
+
+/// Published when the driver starts a heartbeat, i.e. sends an `isMaster` command to a server to
+/// check its state.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ServerHeartbeatStartedEvent {
+    /// The address of the server.
+    pub address: StreamAddress,
+}
+
+/// Published when a heartbeat completes successfully.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ServerHeartbeatSucceededEvent {
+    /// The address of the server.
+    pub address: StreamAddress,
+
+    /// The execution time of the heartbeat, including establishing the monitoring connection if
+    /// one had to be established.
+    pub duration: Duration,
+
+    /// The server's reply to the `isMaster` command.
+    pub reply: Document,
+}
+
+/// Published when a heartbeat fails, e.g. because the server could not be reached.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ServerHeartbeatFailedEvent {
+    /// The address of the server.
+    pub address: StreamAddress,
+
+    /// The execution time of the heartbeat, including establishing the monitoring connection if
+    /// one had to be established.
+    pub duration: Duration,
+
+    /// The error that caused the heartbeat to fail.
+    pub failure: Error,
+}
+
+/// Applications can implement this trait to specify custom logic to run on each SDAM event sent
+/// by the driver.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// #
+/// # use mongodb::{
+/// #     error::Result,
+/// #     event::sdam::{
+/// #         SdamEventHandler,
+/// #         ServerClosedEvent,
+/// #         ServerDescriptionChangedEvent,
+/// #         ServerOpeningEvent,
+/// #     },
+/// #     options::ClientOptions,
+/// # };
+/// # #[cfg(feature = "sync")]
+/// # use mongodb::sync::Client;
+/// # #[cfg(not(feature = "sync"))]
+/// # use mongodb::Client;
+/// #
+/// struct MembershipLogger;
+///
+/// impl SdamEventHandler for MembershipLogger {
+///     fn handle_server_opening_event(&self, event: ServerOpeningEvent) {
+///         eprintln!("Server added: {}", event.address);
+///     }
+///
+///     fn handle_server_closed_event(&self, event: ServerClosedEvent) {
+///         eprintln!("Server removed: {}", event.address);
+///     }
+///
+///     fn handle_server_description_changed_event(&self, event: ServerDescriptionChangedEvent) {
+///         eprintln!(
+///             "Server {} changed from {:?} to {:?}",
+///             event.address, event.previous_type, event.new_type
+///         );
+///     }
+/// }
+///
+/// # fn do_stuff() -> Result<()> {
+/// let handler: Arc<dyn SdamEventHandler> = Arc::new(MembershipLogger);
+/// let options = ClientOptions::builder()
+///                   .sdam_event_handler(handler)
+///                   .build();
+/// let client = Client::with_options(options)?;
+///
+/// // Do things with the client, and changes to the set of servers and their types will be logged
+/// // to stderr.
+/// # Ok(())
+/// # }
+/// ```
+pub trait SdamEventHandler: Send + Sync {
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a server is added to the topology.
+    fn handle_server_opening_event(&self, _event: ServerOpeningEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a server is removed from the topology.
+    fn handle_server_closed_event(&self, _event: ServerClosedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever the type of a server changes.
+    fn handle_server_description_changed_event(&self, _event: ServerDescriptionChangedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever its view of the topology changes.
+    fn handle_topology_description_changed_event(&self, _event: TopologyDescriptionChangedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a heartbeat to a server is started.
+    fn handle_server_heartbeat_started_event(&self, _event: ServerHeartbeatStartedEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a heartbeat to a server succeeds.
+    fn handle_server_heartbeat_succeeded_event(&self, _event: ServerHeartbeatSucceededEvent) {}
+
+    /// A [`Client`](../../struct.Client.html) will call this method on each registered handler
+    /// whenever a heartbeat to a server fails.
+    fn handle_server_heartbeat_failed_event(&self, _event: ServerHeartbeatFailedEvent) {}
+}
@@ -0,0 +1,83 @@
+/// Gets the name of a field of a struct as a `&'static str`, verifying at compile time that the
+/// field exists on the struct.
+///
+/// This can be used to build filters, projections, and update documents that refer to the fields
+/// of a collection's document type without spelling the field names out as string literals, so
+/// that typos are caught by the compiler rather than silently matching nothing at runtime. Nested
+/// fields can be referenced by separating their names with `.`, which produces the corresponding
+/// dotted path.
+///
+/// Note that the name returned is the name of the Rust field; if the field is renamed via a serde
+/// attribute such as `#[serde(rename = "...")]` or `#[serde(rename_all = "...")]`, the name
+/// returned will not match the name of the field in the BSON document.
+///
+/// ```rust
+/// # use mongodb::{bson::doc, field};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Debug, Deserialize, Serialize)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(Debug, Deserialize, Serialize)]
+/// struct User {
+///     name: String,
+///     address: Address,
+/// }
+///
+/// let filter = doc! {
+///     field!(User, name): "Alice",
+///     field!(User, address.city): "New York",
+/// };
+///
+/// assert_eq!(filter, doc! { "name": "Alice", "address.city": "New York" });
+/// ```
+///
+/// Referring to a field that doesn't exist fails to compile:
+///
+/// ```compile_fail
+/// # use mongodb::field;
+/// struct User {
+///     name: String,
+/// }
+///
+/// let key = field!(User, naem);
+/// ```
+#[macro_export]
+macro_rules! field {
+    ($type:ty, $first:ident $(. $rest:ident)*) => {{
+        #[allow(dead_code, unused_variables)]
+        fn check_field_exists(value: &$type) {
+            let _ = &value.$first $(. $rest)*;
+        }
+
+        concat!(stringify!($first) $(, ".", stringify!($rest))*)
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bson::doc;
+
+    #[allow(dead_code)]
+    struct Inner {
+        value: i32,
+    }
+
+    #[allow(dead_code)]
+    struct Outer {
+        name: String,
+        inner: Inner,
+    }
+
+    #[test]
+    fn field_names() {
+        assert_eq!(field!(Outer, name), "name");
+        assert_eq!(field!(Outer, inner.value), "inner.value");
+
+        assert_eq!(
+            doc! { field!(Outer, inner.value): { "$gt": 1 } },
+            doc! { "inner.value": { "$gt": 1 } }
+        );
+    }
+}
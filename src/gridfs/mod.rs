@@ -0,0 +1,692 @@
+//! Contains the functionality for GridFS, which stores files that may exceed the BSON document size
+//! limit by splitting them into chunks. See the documentation
+//! [here](https://docs.mongodb.com/manual/core/gridfs/) for more information.
+pub(crate) mod options;
+
+use std::{
+    convert::TryFrom,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use chrono::Utc;
+use derivative::Derivative;
+use futures::{
+    future::{BoxFuture, FutureExt},
+    io::{AsyncRead, AsyncWrite},
+    ready,
+    Stream,
+};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use self::options::{
+    GridFsBucketOptions,
+    GridFsFindOneOptions,
+    GridFsFindOptions,
+    GridFsUploadOptions,
+};
+use crate::{
+    bson::{doc, oid::ObjectId, spec::BinarySubtype, Binary, Bson, DateTime, Document},
+    bson_util,
+    error::{Error, ErrorKind, Result},
+    index::{options::IndexOptions, IndexModel},
+    options::{CollectionOptions, FindOneOptions, FindOptions, ReadPreference, SelectionCriteria},
+    Collection,
+    Cursor,
+    Database,
+};
+
+/// The default size in bytes of the chunks that files are split into.
+pub const DEFAULT_CHUNK_SIZE_BYTES: u32 = 255 * 1024;
+
+/// A bucket of files stored in GridFS. A `GridFsBucket` is obtained through
+/// [`Database::gridfs_bucket`](../struct.Database.html#method.gridfs_bucket).
+///
+/// The contents of the files are stored in the bucket's chunks collection, split into documents
+/// of at most the bucket's chunk size each, and each file is described by a document in the
+/// bucket's files collection.
+///
+/// ```rust
+/// # use futures::io::{AsyncReadExt, AsyncWriteExt};
+/// # use mongodb::{bson::doc, error::Result, Client};
+/// #
+/// # async fn func() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com").await?;
+/// let bucket = client.database("media").gridfs_bucket(None);
+///
+/// let mut upload_stream = bucket.open_upload_stream("hello.txt", None).await?;
+/// upload_stream.write_all(b"hello world").await?;
+/// upload_stream.close().await?;
+///
+/// let mut download_stream = bucket.open_download_stream(upload_stream.id().clone()).await?;
+/// let mut contents = Vec::new();
+/// download_stream.read_to_end(&mut contents).await?;
+/// assert_eq!(contents, b"hello world");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GridFsBucket {
+    files: Collection,
+    chunks: Collection,
+    chunk_size_bytes: u32,
+    compute_md5: bool,
+    compute_sha256: bool,
+
+    /// Whether the indexes on the files and chunks collections are known to exist.
+    indexes_checked: Arc<AtomicBool>,
+}
+
+impl GridFsBucket {
+    pub(crate) fn new(db: &Database, options: GridFsBucketOptions) -> Self {
+        let bucket_name = options.bucket_name.as_deref().unwrap_or("fs");
+        let collection_options = CollectionOptions::builder()
+            .read_concern(options.read_concern.clone())
+            .selection_criteria(options.selection_criteria.clone())
+            .write_concern(options.write_concern.clone())
+            .build();
+
+        Self {
+            files: db.collection_with_options(
+                &format!("{}.files", bucket_name),
+                collection_options.clone(),
+            ),
+            chunks: db
+                .collection_with_options(&format!("{}.chunks", bucket_name), collection_options),
+            chunk_size_bytes: options.chunk_size_bytes.unwrap_or(DEFAULT_CHUNK_SIZE_BYTES),
+            compute_md5: options.compute_md5.unwrap_or(false),
+            compute_sha256: options.compute_sha256.unwrap_or(false),
+            indexes_checked: Default::default(),
+        }
+    }
+
+    /// Gets the collection that stores the documents describing the files in this bucket.
+    pub fn files(&self) -> &Collection {
+        &self.files
+    }
+
+    /// Gets the collection that stores the chunks of the files in this bucket.
+    pub fn chunks(&self) -> &Collection {
+        &self.chunks
+    }
+
+    /// Opens a stream that uploads a new file with the given name to the bucket. The file is given
+    /// a new `ObjectId` as its id, which can be obtained through
+    /// [`GridFsUploadStream::id`](struct.GridFsUploadStream.html#method.id).
+    ///
+    /// The file is only stored once the stream has been closed; if the stream is dropped instead,
+    /// the chunks written so far are left in the chunks collection. Use
+    /// [`GridFsUploadStream::abort`](struct.GridFsUploadStream.html#method.abort) to discard an
+    /// upload.
+    pub async fn open_upload_stream(
+        &self,
+        filename: impl Into<String>,
+        options: impl Into<Option<GridFsUploadOptions>>,
+    ) -> Result<GridFsUploadStream> {
+        let options = options.into().unwrap_or_default();
+        let chunk_size_bytes = options.chunk_size_bytes.unwrap_or(self.chunk_size_bytes);
+        // The chunk size is stored as a 32-bit integer in the files document.
+        let chunk_size_bytes = match i32::try_from(chunk_size_bytes) {
+            Ok(chunk_size_bytes) if chunk_size_bytes > 0 => chunk_size_bytes,
+            _ => {
+                return Err(ErrorKind::ArgumentError {
+                    message: format!(
+                        "chunk_size_bytes must be greater than 0 and at most {}, got {}",
+                        i32::MAX,
+                        chunk_size_bytes
+                    ),
+                }
+                .into())
+            }
+        };
+
+        self.ensure_indexes().await?;
+
+        Ok(GridFsUploadStream {
+            id: Bson::ObjectId(ObjectId::new()),
+            filename: filename.into(),
+            metadata: options.metadata,
+            chunk_size_bytes,
+            files: self.files.clone(),
+            chunks: self.chunks.clone(),
+            buffer: Vec::new(),
+            next_n: 0,
+            length: 0,
+            md5: if self.compute_md5 {
+                Some(Md5::new())
+            } else {
+                None
+            },
+            sha256: if self.compute_sha256 {
+                Some(Sha256::new())
+            } else {
+                None
+            },
+            pending_write: None,
+            error: None,
+            closed: false,
+        })
+    }
+
+    /// Opens a stream that downloads the contents of the file with the given id from the bucket.
+    pub async fn open_download_stream(&self, id: Bson) -> Result<GridFsDownloadStream> {
+        self.open_download_stream_with_range(id, None).await
+    }
+
+    /// Opens a stream that downloads the bytes from `start` up to, but not including, `end` of the
+    /// file with the given id from the bucket. Only the chunks that contain those bytes are
+    /// fetched.
+    ///
+    /// Returns an error if `start` is greater than `end` or `end` is greater than the length of
+    /// the file.
+    pub async fn open_download_stream_by_range(
+        &self,
+        id: Bson,
+        start: u64,
+        end: u64,
+    ) -> Result<GridFsDownloadStream> {
+        self.open_download_stream_with_range(id, Some((start, end)))
+            .await
+    }
+
+    async fn open_download_stream_with_range(
+        &self,
+        id: Bson,
+        range: Option<(u64, u64)>,
+    ) -> Result<GridFsDownloadStream> {
+        let file = match self
+            .files
+            .find_one(doc! { "_id": id.clone() }, None)
+            .await?
+        {
+            Some(file) => file,
+            None => return Err(gridfs_error(format!("no file with id {} was found", id))),
+        };
+
+        let length = file.get("length").and_then(bson_util::get_int);
+        let chunk_size = file.get("chunkSize").and_then(bson_util::get_int);
+        let (length, chunk_size) = match (length, chunk_size) {
+            (Some(length), Some(chunk_size)) if length >= 0 && chunk_size > 0 => {
+                (length as u64, chunk_size as u64)
+            }
+            _ => {
+                return Err(gridfs_error(format!(
+                    "the document for file {} has an invalid length or chunkSize",
+                    id
+                )))
+            }
+        };
+
+        let (start, end) = range.unwrap_or((0, length));
+        if start > end || end > length {
+            return Err(ErrorKind::ArgumentError {
+                message: format!(
+                    "the range {}..{} is not within file {}, which is {} bytes long",
+                    start, end, id, length
+                ),
+            }
+            .into());
+        }
+
+        // Only the chunks from the one containing `start` up to the one containing the last byte
+        // of the range are fetched.
+        let first_n = start / chunk_size;
+        let end_n = end / chunk_size + if end % chunk_size == 0 { 0 } else { 1 };
+        let options = FindOptions::builder().sort(doc! { "n": 1 }).build();
+        let filter = if range.is_some() {
+            doc! {
+                "files_id": id.clone(),
+                "n": { "$gte": first_n as i64, "$lt": end_n as i64 },
+            }
+        } else {
+            doc! { "files_id": id.clone() }
+        };
+        let chunks = self.chunks.find(filter, options).await?;
+
+        Ok(GridFsDownloadStream {
+            id,
+            length,
+            chunk_size,
+            chunks,
+            buffer: Vec::new(),
+            position: 0,
+            next_n: first_n as i64,
+            remaining: length - first_n * chunk_size,
+            skip: (start - first_n * chunk_size) as usize,
+            unread: end - start,
+        })
+    }
+
+    /// Finds the files in the bucket whose documents in the files collection match `filter`.
+    pub async fn find(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<GridFsFindOptions>>,
+    ) -> Result<Cursor<FilesDocument>> {
+        let options = options.into().map(FindOptions::from);
+        self.files
+            .clone_with_type::<FilesDocument>()
+            .find(filter, options)
+            .await
+    }
+
+    /// Finds a single file in the bucket whose document in the files collection matches `filter`.
+    pub async fn find_one(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<GridFsFindOneOptions>>,
+    ) -> Result<Option<FilesDocument>> {
+        let options = options.into().map(FindOneOptions::from);
+        self.files
+            .clone_with_type::<FilesDocument>()
+            .find_one(filter, options)
+            .await
+    }
+
+    /// Deletes the file with the given id and all of its chunks from the bucket.
+    pub async fn delete(&self, id: Bson) -> Result<()> {
+        let result = self
+            .files
+            .delete_one(doc! { "_id": id.clone() }, None)
+            .await?;
+        self.chunks
+            .delete_many(doc! { "files_id": id.clone() }, None)
+            .await?;
+
+        if result.deleted_count == 0 {
+            return Err(gridfs_error(format!("no file with id {} was found", id)));
+        }
+        Ok(())
+    }
+
+    /// Creates the indexes on the files and chunks collections that the GridFS specification
+    /// requires, unless the files collection already contains files.
+    async fn ensure_indexes(&self) -> Result<()> {
+        if self.indexes_checked.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let options = FindOneOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+            .build();
+        if self.files.find_one(None, options).await?.is_none() {
+            let files_index = IndexModel::builder()
+                .keys(doc! { "filename": 1, "uploadDate": 1 })
+                .build();
+            self.files.create_indexes(vec![files_index], None).await?;
+
+            let chunks_index = IndexModel::builder()
+                .keys(doc! { "files_id": 1, "n": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build();
+            self.chunks.create_indexes(vec![chunks_index], None).await?;
+        }
+
+        self.indexes_checked.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// The document describing a file stored in a [`GridFsBucket`](struct.GridFsBucket.html), as
+/// stored in the bucket's files collection and returned by
+/// [`GridFsBucket::find`](struct.GridFsBucket.html#method.find).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FilesDocument {
+    /// The id of the file.
+    #[serde(rename = "_id")]
+    pub id: Bson,
+
+    /// The length of the file in bytes.
+    pub length: i64,
+
+    /// The size in bytes of the chunks that the file is split into.
+    pub chunk_size: i32,
+
+    /// The time at which the upload of the file finished.
+    pub upload_date: DateTime,
+
+    /// The name of the file.
+    pub filename: String,
+
+    /// The metadata given when the file was uploaded, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Document>,
+}
+
+/// A stream that uploads a file to a [`GridFsBucket`](struct.GridFsBucket.html), obtained through
+/// [`GridFsBucket::open_upload_stream`](struct.GridFsBucket.html#method.open_upload_stream).
+///
+/// The bytes written to the stream are buffered until a full chunk is available, which is then
+/// inserted into the chunks collection. Closing the stream inserts the last, possibly partial,
+/// chunk and the document describing the file into the files collection.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct GridFsUploadStream {
+    id: Bson,
+    filename: String,
+    metadata: Option<Document>,
+    chunk_size_bytes: i32,
+    files: Collection,
+    chunks: Collection,
+
+    /// The bytes that have been written but not yet inserted as part of a chunk.
+    #[derivative(Debug = "ignore")]
+    buffer: Vec<u8>,
+
+    /// The number of the next chunk to insert.
+    next_n: u32,
+
+    /// The number of bytes written to the stream so far.
+    length: u64,
+
+    #[derivative(Debug = "ignore")]
+    md5: Option<Md5>,
+
+    #[derivative(Debug = "ignore")]
+    sha256: Option<Sha256>,
+
+    /// The insert of chunks or of the files document that is currently in progress, if any.
+    #[derivative(Debug = "ignore")]
+    pending_write: Option<BoxFuture<'static, Result<()>>>,
+
+    /// The error that a write to the chunks or files collection failed with, if any. Every later
+    /// write, flush and close of the stream fails with it, so that a file with missing chunks is
+    /// never stored.
+    error: Option<Error>,
+
+    closed: bool,
+}
+
+impl GridFsUploadStream {
+    /// Gets the id of the file being uploaded.
+    pub fn id(&self) -> &Bson {
+        &self.id
+    }
+
+    /// Aborts the upload, deleting the chunks that have already been inserted into the chunks
+    /// collection. An upload cannot be aborted once the stream has been closed.
+    pub async fn abort(mut self) -> Result<()> {
+        if self.closed {
+            return Err(gridfs_error(
+                "the upload stream has already been closed".to_string(),
+            ));
+        }
+        self.closed = true;
+
+        // Wait for any chunks that are being inserted so that they are deleted too.
+        if let Some(pending_write) = self.pending_write.take() {
+            let _ = pending_write.await;
+        }
+
+        self.chunks
+            .delete_many(doc! { "files_id": self.id.clone() }, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the chunks that are ready to be inserted from the buffer. If `include_partial` is
+    /// true, any remaining bytes are returned as a final, shorter chunk.
+    fn take_chunks(&mut self, include_partial: bool) -> Vec<Document> {
+        // The chunk size was checked to be positive when the stream was opened.
+        let chunk_size_bytes = self.chunk_size_bytes as usize;
+        let mut chunks = Vec::new();
+        while self.buffer.len() >= chunk_size_bytes || (include_partial && !self.buffer.is_empty())
+        {
+            let length = chunk_size_bytes.min(self.buffer.len());
+            let data: Vec<u8> = self.buffer.drain(..length).collect();
+            chunks.push(doc! {
+                "_id": ObjectId::new(),
+                "files_id": self.id.clone(),
+                "n": self.next_n as i32,
+                "data": Binary { subtype: BinarySubtype::Generic, bytes: data },
+            });
+            self.next_n += 1;
+        }
+        chunks
+    }
+
+    /// Builds the document describing the uploaded file.
+    fn files_document(&mut self) -> Document {
+        let mut document = doc! {
+            "_id": self.id.clone(),
+            "length": self.length as i64,
+            "chunkSize": self.chunk_size_bytes,
+            "uploadDate": Utc::now(),
+            "filename": self.filename.clone(),
+        };
+        if let Some(metadata) = self.metadata.take() {
+            document.insert("metadata", metadata);
+        }
+        if let Some(md5) = self.md5.take() {
+            document.insert("md5", hex::encode(md5.result()));
+        }
+        if let Some(sha256) = self.sha256.take() {
+            document.insert("sha256", hex::encode(sha256.result()));
+        }
+        document
+    }
+
+    /// Drives the pending write to completion, if there is one, and returns the error that any
+    /// write has failed with.
+    fn poll_pending_write(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(ref mut pending_write) = self.pending_write {
+            let result = ready!(pending_write.as_mut().poll(cx));
+            self.pending_write = None;
+            if let Err(error) = result {
+                self.error = Some(error);
+            }
+        }
+
+        match self.error {
+            Some(ref error) => Poll::Ready(Err(to_io_error(error.clone()))),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl AsyncWrite for GridFsUploadStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending_write(cx))?;
+        if this.closed {
+            return Poll::Ready(Err(to_io_error(gridfs_error(
+                "cannot write to an upload stream that has been closed or aborted".to_string(),
+            ))));
+        }
+
+        this.buffer.extend_from_slice(buf);
+        this.length += buf.len() as u64;
+        if let Some(ref mut md5) = this.md5 {
+            md5.input(buf);
+        }
+        if let Some(ref mut sha256) = this.sha256 {
+            sha256.input(buf);
+        }
+
+        let chunks = this.take_chunks(false);
+        if !chunks.is_empty() {
+            let collection = this.chunks.clone();
+            this.pending_write = Some(
+                async move {
+                    collection.insert_many(chunks, None).await?;
+                    Ok(())
+                }
+                .boxed(),
+            );
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending_write(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending_write(cx))?;
+
+        if !this.closed {
+            this.closed = true;
+
+            let chunks = this.take_chunks(true);
+            let files_document = this.files_document();
+            let chunks_collection = this.chunks.clone();
+            let files_collection = this.files.clone();
+            this.pending_write = Some(
+                async move {
+                    if !chunks.is_empty() {
+                        chunks_collection.insert_many(chunks, None).await?;
+                    }
+                    files_collection.insert_one(files_document, None).await?;
+                    Ok(())
+                }
+                .boxed(),
+            );
+
+            ready!(this.poll_pending_write(cx))?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A stream that downloads the contents of a file from a
+/// [`GridFsBucket`](struct.GridFsBucket.html), obtained through
+/// [`GridFsBucket::open_download_stream`](struct.GridFsBucket.html#method.open_download_stream).
+#[derive(Debug)]
+pub struct GridFsDownloadStream {
+    id: Bson,
+    length: u64,
+    chunk_size: u64,
+    chunks: Cursor,
+
+    /// The contents of the most recently read chunk.
+    buffer: Vec<u8>,
+
+    /// The number of bytes of `buffer` that have already been read.
+    position: usize,
+
+    /// The number of the next chunk expected from the cursor.
+    next_n: i64,
+
+    /// The number of bytes of the file that have not yet been read from the cursor.
+    remaining: u64,
+
+    /// The number of bytes at the start of the next chunk that are before the downloaded range.
+    skip: usize,
+
+    /// The number of bytes of the downloaded range that have not yet been read from the stream.
+    unread: u64,
+}
+
+impl GridFsDownloadStream {
+    /// Gets the id of the file being downloaded.
+    pub fn id(&self) -> &Bson {
+        &self.id
+    }
+
+    /// Gets the length of the file in bytes. For a stream opened with
+    /// [`GridFsBucket::open_download_stream_by_range`](struct.GridFsBucket.html#method.open_download_stream_by_range),
+    /// this is the length of the whole file rather than of the range.
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Checks that `chunk` is the next chunk of the file and has the expected size, returning its
+    /// contents.
+    fn chunk_data(&mut self, chunk: Document) -> Result<Vec<u8>> {
+        let n = chunk.get("n").and_then(bson_util::get_int);
+        if n != Some(self.next_n) {
+            return Err(gridfs_error(format!(
+                "chunk {} of file {} is missing",
+                self.next_n, self.id
+            )));
+        }
+
+        let data = match chunk.get("data") {
+            Some(Bson::Binary(binary)) => binary.bytes.clone(),
+            _ => {
+                return Err(gridfs_error(format!(
+                    "chunk {} of file {} has no binary data",
+                    self.next_n, self.id
+                )))
+            }
+        };
+
+        let expected_length = self.chunk_size.min(self.remaining);
+        if data.len() as u64 != expected_length {
+            return Err(gridfs_error(format!(
+                "chunk {} of file {} is {} bytes long, but {} bytes were expected",
+                self.next_n,
+                self.id,
+                data.len(),
+                expected_length
+            )));
+        }
+
+        self.next_n += 1;
+        self.remaining -= expected_length;
+        Ok(data)
+    }
+}
+
+impl AsyncRead for GridFsDownloadStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.unread == 0 {
+                return Poll::Ready(Ok(0));
+            }
+
+            if this.position < this.buffer.len() {
+                let length = (this.buffer.len() - this.position)
+                    .min(buf.len())
+                    .min(usize::try_from(this.unread).unwrap_or(usize::MAX));
+                buf[..length].copy_from_slice(&this.buffer[this.position..this.position + length]);
+                this.position += length;
+                this.unread -= length as u64;
+                return Poll::Ready(Ok(length));
+            }
+
+            let chunk = match ready!(Pin::new(&mut this.chunks).poll_next(cx)) {
+                Some(chunk) => chunk.map_err(to_io_error)?,
+                None => {
+                    return Poll::Ready(Err(to_io_error(gridfs_error(format!(
+                        "chunk {} of file {} is missing",
+                        this.next_n, this.id
+                    )))))
+                }
+            };
+            this.buffer = this.chunk_data(chunk).map_err(to_io_error)?;
+            this.position = std::mem::take(&mut this.skip);
+        }
+    }
+}
+
+fn gridfs_error(message: String) -> Error {
+    ErrorKind::GridFsError { message }.into()
+}
+
+fn to_io_error(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
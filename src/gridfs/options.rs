@@ -0,0 +1,164 @@
+//! Contains options for GridFS buckets.
+
+use std::time::Duration;
+
+use typed_builder::TypedBuilder;
+
+use crate::{
+    bson::Document,
+    coll::options::{FindOneOptions, FindOptions},
+    concern::{ReadConcern, WriteConcern},
+    selection_criteria::SelectionCriteria,
+};
+
+/// These are the valid options for creating a [`GridFsBucket`](../gridfs/struct.GridFsBucket.html)
+/// with [`Database::gridfs_bucket`](../struct.Database.html#method.gridfs_bucket).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct GridFsBucketOptions {
+    /// The name of the bucket. The files and chunks of the bucket are stored in the
+    /// `<bucket_name>.files` and `<bucket_name>.chunks` collections.
+    ///
+    /// The default value is `"fs"`.
+    #[builder(default)]
+    pub bucket_name: Option<String>,
+
+    /// The size in bytes of the chunks that files are split into, unless overridden for a file
+    /// when it is uploaded.
+    ///
+    /// The default value is 255 KiB.
+    #[builder(default)]
+    pub chunk_size_bytes: Option<u32>,
+
+    /// Whether to compute the MD5 hash of each uploaded file and store it as a hexadecimal string
+    /// in the `md5` field of the file's document in the files collection.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub compute_md5: Option<bool>,
+
+    /// Whether to compute the SHA-256 hash of each uploaded file and store it as a hexadecimal
+    /// string in the `sha256` field of the file's document in the files collection.
+    ///
+    /// The default value is false.
+    #[builder(default)]
+    pub compute_sha256: Option<bool>,
+
+    /// The read concern to use for reading files from the bucket. If none is specified, the read
+    /// concern of the `Database` is used.
+    #[builder(default)]
+    pub read_concern: Option<ReadConcern>,
+
+    /// The criteria used to select a server for reading files from the bucket. If none is
+    /// specified, the selection criteria of the `Database` are used.
+    #[builder(default)]
+    pub selection_criteria: Option<SelectionCriteria>,
+
+    /// The write concern to use for writing files to the bucket. If none is specified, the write
+    /// concern of the `Database` is used.
+    #[builder(default)]
+    pub write_concern: Option<WriteConcern>,
+}
+
+/// These are the valid options for uploading a file with
+/// [`GridFsBucket::open_upload_stream`](../gridfs/struct.GridFsBucket.html#method.open_upload_stream).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct GridFsUploadOptions {
+    /// The size in bytes of the chunks that the file is split into. If none is specified, the
+    /// chunk size of the bucket is used.
+    #[builder(default)]
+    pub chunk_size_bytes: Option<u32>,
+
+    /// Arbitrary data to store in the `metadata` field of the file's document in the files
+    /// collection.
+    #[builder(default)]
+    pub metadata: Option<Document>,
+}
+
+/// These are the valid options for finding files with
+/// [`GridFsBucket::find`](../gridfs/struct.GridFsBucket.html#method.find).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct GridFsFindOptions {
+    /// The number of documents the server should return per cursor batch.
+    #[builder(default)]
+    pub batch_size: Option<u32>,
+
+    /// The maximum number of files to return.
+    #[builder(default)]
+    pub limit: Option<i64>,
+
+    /// The maximum amount of time to allow the query to run.
+    #[builder(default)]
+    pub max_time: Option<Duration>,
+
+    /// Whether the server should close the cursor after a period of inactivity.
+    #[builder(default)]
+    pub no_cursor_timeout: Option<bool>,
+
+    /// Limits the fields of the files documents being returned. The fields of
+    /// [`FilesDocument`](../gridfs/struct.FilesDocument.html) that aren't optional must not be
+    /// excluded, as the documents can't be returned without them.
+    #[builder(default)]
+    pub projection: Option<Document>,
+
+    /// The number of files to skip before returning any.
+    #[builder(default)]
+    pub skip: Option<i64>,
+
+    /// The order in which to return the files.
+    #[builder(default)]
+    pub sort: Option<Document>,
+}
+
+impl From<GridFsFindOptions> for FindOptions {
+    fn from(options: GridFsFindOptions) -> Self {
+        FindOptions {
+            batch_size: options.batch_size,
+            limit: options.limit,
+            max_time: options.max_time,
+            no_cursor_timeout: options.no_cursor_timeout,
+            projection: options.projection,
+            skip: options.skip,
+            sort: options.sort,
+            ..Default::default()
+        }
+    }
+}
+
+/// These are the valid options for finding a file with
+/// [`GridFsBucket::find_one`](../gridfs/struct.GridFsBucket.html#method.find_one).
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[non_exhaustive]
+pub struct GridFsFindOneOptions {
+    /// The maximum amount of time to allow the query to run.
+    #[builder(default)]
+    pub max_time: Option<Duration>,
+
+    /// Limits the fields of the files document being returned. The fields of
+    /// [`FilesDocument`](../gridfs/struct.FilesDocument.html) that aren't optional must not be
+    /// excluded, as the document can't be returned without them.
+    #[builder(default)]
+    pub projection: Option<Document>,
+
+    /// The number of files to skip before returning one.
+    #[builder(default)]
+    pub skip: Option<i64>,
+
+    /// The order of the files, which determines the one that is returned.
+    #[builder(default)]
+    pub sort: Option<Document>,
+}
+
+impl From<GridFsFindOneOptions> for FindOneOptions {
+    fn from(options: GridFsFindOneOptions) -> Self {
+        FindOneOptions {
+            max_time: options.max_time,
+            projection: options.projection,
+            skip: options.skip,
+            sort: options.sort,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Contains the types related to creating and describing indexes.
+pub mod options;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use self::options::IndexOptions;
+use crate::bson::{Bson, Document};
+
+/// Specifies the fields and options for an index. For more information, see the
+/// [documentation](https://docs.mongodb.com/manual/indexes/).
+#[derive(Clone, Debug, Default, Deserialize, Serialize, TypedBuilder)]
+#[non_exhaustive]
+pub struct IndexModel {
+    /// Specifies the index’s fields. For each field, specify a key-value pair in which the key is
+    /// the name of the field to index and the value is index type.
+    #[serde(rename = "key")]
+    pub keys: Document,
+
+    /// The options for the index.
+    #[builder(default)]
+    #[serde(flatten)]
+    pub options: Option<IndexOptions>,
+}
+
+impl IndexModel {
+    /// Gets the name of the index. If no name was specified in the index's options, this is the
+    /// name the server would generate for it, e.g. `"x_1_y_-1"` for the keys `{ x: 1, y: -1 }`.
+    pub fn name(&self) -> String {
+        if let Some(name) = self
+            .options
+            .as_ref()
+            .and_then(|options| options.name.as_ref())
+        {
+            return name.clone();
+        }
+
+        self.keys
+            .iter()
+            .map(|(field, value)| {
+                let value = match value {
+                    Bson::String(s) => s.clone(),
+                    Bson::Int32(i) => i.to_string(),
+                    Bson::Int64(i) => i.to_string(),
+                    Bson::Double(f) if f.fract() == 0.0 => (*f as i64).to_string(),
+                    other => other.to_string(),
+                };
+                format!("{}_{}", field, value)
+            })
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// Gets the document that describes this index in a `createIndexes` command, i.e. the keys and
+    /// options with the name filled in.
+    pub(crate) fn to_index_spec(&self) -> crate::error::Result<Document> {
+        let mut spec = crate::bson::to_document(self)?;
+        if !spec.contains_key("name") {
+            spec.insert("name", self.name());
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{options::IndexOptions, IndexModel};
+    use crate::bson::doc;
+
+    #[test]
+    fn generated_names() {
+        let model = IndexModel::builder()
+            .keys(doc! { "x": 1, "y": -1, "z": 1.0, "loc": "2dsphere" })
+            .build();
+        assert_eq!(model.name(), "x_1_y_-1_z_1_loc_2dsphere");
+
+        let model = IndexModel::builder()
+            .keys(doc! { "x": 1 })
+            .options(IndexOptions::builder().name("custom".to_string()).build())
+            .build();
+        assert_eq!(model.name(), "custom");
+    }
+
+    #[test]
+    fn index_spec() {
+        let model = IndexModel::builder()
+            .keys(doc! { "x": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        assert_eq!(
+            model.to_index_spec().unwrap(),
+            doc! { "key": { "x": 1 }, "unique": true, "name": "x_1" }
+        );
+    }
+}
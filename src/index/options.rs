@@ -0,0 +1,75 @@
+//! Contains the options that can be specified for an index.
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{bson::Document, bson_util, collation::Collation};
+
+/// These are the valid options for creating an
+/// [`IndexModel`](../struct.IndexModel.html). See the server
+/// [documentation](https://docs.mongodb.com/manual/reference/method/db.collection.createIndex/#options)
+/// for more details on each option.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct IndexOptions {
+    /// Whether the index should be built in the background. This option is ignored on MongoDB
+    /// 4.2+, where all index builds use an optimized build process.
+    #[builder(default)]
+    pub background: Option<bool>,
+
+    /// Specifies the length of time after which documents in a TTL index are removed from the
+    /// collection.
+    #[builder(default)]
+    #[serde(
+        rename = "expireAfterSeconds",
+        serialize_with = "bson_util::serialize_duration_as_int_secs",
+        deserialize_with = "bson_util::deserialize_duration_from_u64_seconds",
+        default
+    )]
+    pub expire_after: Option<Duration>,
+
+    /// The name of the index. If none is specified, the name is generated from the index's keys.
+    #[builder(default)]
+    pub name: Option<String>,
+
+    /// If true, the index only references documents with the specified field.
+    #[builder(default)]
+    pub sparse: Option<bool>,
+
+    /// If true, the index will not accept the insertion of documents with a duplicate value for
+    /// the indexed key(s).
+    #[builder(default)]
+    pub unique: Option<bool>,
+
+    /// The language used by default for a text index.
+    #[builder(default)]
+    pub default_language: Option<String>,
+
+    /// The name of the field in the documents that contains the language to use for a text index.
+    #[builder(default)]
+    pub language_override: Option<String>,
+
+    /// The relative weights of the fields in a text index.
+    #[builder(default)]
+    pub weights: Option<Document>,
+
+    /// If specified, the index only references documents that match the filter.
+    #[builder(default)]
+    pub partial_filter_expression: Option<Document>,
+
+    /// The collation to use for the index.
+    #[builder(default)]
+    pub collation: Option<Collation>,
+
+    /// Specifies which fields to include or exclude in a wildcard index.
+    #[builder(default)]
+    pub wildcard_projection: Option<Document>,
+
+    /// Whether the index should be hidden from the query planner. Requires MongoDB 4.4+.
+    #[builder(default)]
+    pub hidden: Option<bool>,
+}
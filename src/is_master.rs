@@ -43,6 +43,11 @@ pub(crate) struct IsMasterCommandResponse {
     pub primary: Option<String>,
     pub sasl_supported_mechs: Option<Vec<String>>,
     pub speculative_authenticate: Option<Document>,
+    pub compression: Option<Vec<String>>,
+    pub service_id: Option<ObjectId>,
+    pub max_bson_object_size: Option<i64>,
+    pub max_write_batch_size: Option<i64>,
+    pub max_message_size_bytes: Option<i64>,
 }
 
 impl PartialEq for IsMasterCommandResponse {
@@ -101,16 +101,26 @@ define_if_single_runtime_enabled! {
 
     pub use ::bson;
 
+    pub mod agg;
     mod bson_util;
+    pub mod change_stream;
     mod client;
+    #[cfg(any(feature = "csfle", docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "csfle")))]
+    pub mod client_encryption;
     mod cmap;
     mod coll;
     mod collation;
+    mod compression;
     mod concern;
     mod cursor;
     mod db;
     pub mod error;
     pub mod event;
+    pub mod gridfs;
+    pub mod index;
+    #[macro_use]
+    mod field;
     mod is_master;
     mod operation;
     pub mod results;
@@ -123,6 +133,8 @@ define_if_single_runtime_enabled! {
     pub mod sync;
     #[cfg(test)]
     mod test;
+    #[cfg(feature = "tracing")]
+    mod trace;
 
     #[cfg(test)]
     #[macro_use]
@@ -132,7 +144,7 @@ define_if_single_runtime_enabled! {
     pub use crate::{
         client::Client,
         coll::Collection,
-        cursor::Cursor,
+        cursor::{Cursor, SessionCursor, SessionCursorStream},
         db::Database,
     };
 
@@ -140,11 +152,22 @@ define_if_single_runtime_enabled! {
     pub(crate) use crate::{
         client::Client,
         coll::Collection,
-        cursor::Cursor,
+        cursor::{Cursor, SessionCursor, SessionCursorStream},
         db::Database,
     };
 
+    pub use client::{
+        metrics::{ClientMetrics, PoolMetrics},
+        ClientSession,
+        ClusterTime,
+    };
     pub use coll::Namespace;
+    pub use index::IndexModel;
+    pub use sdam::{
+        public::{TopologyDescription, TopologyType},
+        ServerInfo,
+        ServerType,
+    };
 }
 
 #[cfg(all(
@@ -1,14 +1,16 @@
 #[cfg(test)]
 mod test;
 
+use std::time::{Duration, Instant};
+
 use crate::{
     bson::{doc, Bson, Document},
     bson_util,
     cmap::{Command, CommandResponse, StreamDescription},
     cursor::CursorSpecification,
-    error::Result,
+    error::{ErrorKind, Result},
     operation::{append_options, CursorBody, Operation, Retryability, WriteConcernOnlyBody},
-    options::{AggregateOptions, SelectionCriteria, WriteConcern},
+    options::{AggregateOptions, ReadConcern, SelectionCriteria, WriteConcern},
     Namespace,
 };
 
@@ -17,6 +19,10 @@ pub(crate) struct Aggregate {
     target: AggregateTarget,
     pipeline: Vec<Document>,
     options: Option<AggregateOptions>,
+
+    /// When the operation was started, which is used to bound the lifetime of the resulting
+    /// cursor by `max_time`.
+    started_at: Instant,
 }
 
 impl Aggregate {
@@ -34,6 +40,7 @@ impl Aggregate {
             target: target.into(),
             pipeline: pipeline.into_iter().collect(),
             options,
+            started_at: Instant::now(),
         }
     }
 }
@@ -48,9 +55,26 @@ impl Operation for Aggregate {
             "pipeline": bson_util::to_bson_array(&self.pipeline),
             "cursor": {}
         };
+
+        if self
+            .options
+            .as_ref()
+            .and_then(|opts| opts.max_time)
+            .map(|max_time| max_time.as_millis() == 0)
+            == Some(true)
+        {
+            return Err(ErrorKind::ArgumentError {
+                message: "The max time must be at least one millisecond".to_string(),
+            }
+            .into());
+        }
+
         append_options(&mut body, self.options.as_ref())?;
 
-        if self.is_out_or_merge() {
+        // A batch size of 0 requests an empty initial batch rather than limiting the size of the
+        // batches, so it is still sent for $out and $merge pipelines.
+        let batch_size = self.options.as_ref().and_then(|opts| opts.batch_size);
+        if self.is_out_or_merge() && batch_size != Some(0) {
             if let Ok(cursor_doc) = body.get_document_mut("cursor") {
                 cursor_doc.remove("batchSize");
             }
@@ -71,14 +95,23 @@ impl Operation for Aggregate {
             error_body.validate()?;
         }
 
-        Ok(CursorSpecification::new(
+        let mut spec = CursorSpecification::new(
             body.cursor.ns,
             response.source_address().clone(),
             body.cursor.id,
             self.options.as_ref().and_then(|opts| opts.batch_size),
             self.options.as_ref().and_then(|opts| opts.max_await_time),
             body.cursor.first_batch,
-        ))
+        )
+        .with_deadline(
+            self.options
+                .as_ref()
+                .and_then(|opts| opts.max_time)
+                .map(|max_time| self.started_at + max_time),
+        );
+        spec.post_batch_resume_token = body.cursor.post_batch_resume_token;
+        spec.operation_time = response.operation_time();
+        Ok(spec)
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
@@ -93,6 +126,16 @@ impl Operation for Aggregate {
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn read_concern(&self) -> Option<&ReadConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.read_concern.as_ref())
+    }
+
     fn retryability(&self) -> Retryability {
         if self.is_out_or_merge() {
             Retryability::None
@@ -100,6 +143,10 @@ impl Operation for Aggregate {
             Retryability::Read
         }
     }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
 }
 
 impl Aggregate {
@@ -1,8 +1,8 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use super::AggregateTarget;
 use crate::{
-    bson::{doc, Document},
+    bson::{doc, Document, Timestamp},
     bson_util,
     cmap::{CommandResponse, StreamDescription},
     concern::{ReadConcern, ReadConcernLevel},
@@ -116,7 +116,26 @@ async fn build_batch_size() {
         }
     }];
     expected_body.insert("pipeline", bson_util::to_bson_array(&merge_pipeline));
-    build_test(ns, merge_pipeline, Some(batch_size_options), expected_body);
+    build_test(
+        ns.clone(),
+        merge_pipeline.clone(),
+        Some(batch_size_options),
+        expected_body.clone(),
+    );
+
+    // A batch size of 0 is still sent for $out and $merge pipelines, since it requests an empty
+    // initial batch rather than limiting the size of the batches.
+    let zero_batch_size_options = AggregateOptions::builder().batch_size(0).build();
+    expected_body.insert("cursor", doc! { "batchSize": 0 });
+    build_test(
+        ns.clone(),
+        merge_pipeline,
+        Some(zero_batch_size_options.clone()),
+        expected_body.clone(),
+    );
+
+    expected_body.insert("pipeline", Vec::<crate::bson::Bson>::new());
+    build_test(ns, Vec::new(), Some(zero_batch_size_options), expected_body);
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -162,6 +181,35 @@ async fn build_max_await_time() {
     build_test("".to_string(), Vec::new(), Some(options), body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_allow_disk_use() {
+    let options = AggregateOptions::builder().allow_disk_use(true).build();
+
+    let body = doc! {
+        "aggregate": 1,
+        "allowDiskUse": true,
+        "cursor": {},
+        "pipeline": []
+    };
+
+    build_test("".to_string(), Vec::new(), Some(options), body);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_zero_max_time() {
+    let options = AggregateOptions::builder()
+        .max_time(Duration::from_micros(10))
+        .build();
+    let aggregate = Aggregate::new(Namespace::empty(), Vec::new(), Some(options));
+    let result = aggregate.build(&StreamDescription::new_testing());
+    match *result.unwrap_err().kind {
+        ErrorKind::ArgumentError { .. } => {}
+        ref e => panic!("expected argument error, got {:?}", e),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -281,6 +329,74 @@ async fn handle_max_await_time() {
     assert_eq!(spec.max_time(), Some(max_await));
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_max_time() {
+    let response = CommandResponse::with_document_and_address(
+        StreamAddress::default(),
+        doc! {
+            "cursor": {
+                "id": 123,
+                "ns": "a.b",
+                "firstBatch": []
+            },
+            "ok": 1.0
+        },
+    );
+
+    let aggregate = Aggregate::empty();
+    let spec = aggregate
+        .handle_response(response.clone())
+        .expect("handle should succeed");
+    assert!(spec.deadline().is_none());
+
+    let max_time = Duration::from_secs(60);
+    let options = AggregateOptions::builder().max_time(max_time).build();
+    let before = Instant::now();
+    let aggregate = Aggregate::new(Namespace::empty(), Vec::new(), Some(options));
+    let spec = aggregate
+        .handle_response(response)
+        .expect("handle should succeed");
+
+    // The deadline is measured from when the aggregate was started, not from when its reply was
+    // handled.
+    let deadline = spec.deadline().expect("deadline should be set");
+    assert!(deadline >= before + max_time);
+    assert!(deadline <= Instant::now() + max_time);
+    assert_eq!(spec.max_time(), None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_change_stream_resume_points() {
+    let operation_time = Timestamp {
+        time: 123,
+        increment: 4,
+    };
+    let response = CommandResponse::with_document_and_address(
+        StreamAddress::default(),
+        doc! {
+            "cursor": {
+                "id": 123,
+                "ns": "a.b",
+                "firstBatch": [],
+                "postBatchResumeToken": { "_data": "token" },
+            },
+            "operationTime": operation_time,
+            "ok": 1.0
+        },
+    );
+
+    let spec = Aggregate::empty()
+        .handle_response(response)
+        .expect("handle should succeed");
+    assert_eq!(
+        spec.post_batch_resume_token,
+        Some(doc! { "_data": "token" })
+    );
+    assert_eq!(spec.operation_time, Some(operation_time));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_write_concern_error() {
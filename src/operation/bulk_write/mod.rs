@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    bson::{doc, Bson, Document},
+    bson_util,
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::{ErrorKind, Result},
+    operation::{append_options, batch_len, Operation, Retryability, WriteResponseBody},
+    options::{BulkWriteOptions, WriteConcern},
+    results::BulkWriteResult,
+    Namespace,
+};
+
+/// The kind of command that a batch of a bulk write is sent as.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BulkWriteKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl BulkWriteKind {
+    fn command_name(self) -> &'static str {
+        match self {
+            BulkWriteKind::Insert => "insert",
+            BulkWriteKind::Update => "update",
+            BulkWriteKind::Delete => "delete",
+        }
+    }
+
+    fn statements_field(self) -> &'static str {
+        match self {
+            BulkWriteKind::Insert => "documents",
+            BulkWriteKind::Update => "updates",
+            BulkWriteKind::Delete => "deletes",
+        }
+    }
+}
+
+/// Sends as many of the given writes of the same kind as the server accepts in a single `insert`,
+/// `update` or `delete` command as part of a `Collection::bulk_write`. The indexes in the result
+/// and errors are relative to the start of `statements`.
+#[derive(Debug)]
+pub(crate) struct BulkWrite<'a> {
+    ns: Namespace,
+    kind: BulkWriteKind,
+    statements: &'a [Document],
+    options: Option<BulkWriteOptions>,
+
+    /// The number of statements at the front of `statements` that the most recently built command
+    /// contains, which tells the caller where the next batch starts.
+    batch_len: &'a AtomicUsize,
+}
+
+impl<'a> BulkWrite<'a> {
+    pub(crate) fn new(
+        ns: Namespace,
+        kind: BulkWriteKind,
+        statements: &'a [Document],
+        options: Option<BulkWriteOptions>,
+        batch_len: &'a AtomicUsize,
+    ) -> Self {
+        batch_len.store(statements.len(), Ordering::SeqCst);
+        Self {
+            ns,
+            kind,
+            statements,
+            options,
+            batch_len,
+        }
+    }
+
+    /// Whether any of the statements can affect more than one document, in which case the batch
+    /// isn't retryable. This conservatively considers all of the statements, as a retry may be
+    /// built for a server with different limits.
+    fn has_multi_statement(&self) -> bool {
+        self.statements.iter().any(|statement| match self.kind {
+            BulkWriteKind::Insert => false,
+            BulkWriteKind::Update => statement.get_bool("multi") == Ok(true),
+            BulkWriteKind::Delete => statement.get_i32("limit") == Ok(0),
+        })
+    }
+}
+
+impl<'a> Operation for BulkWrite<'a> {
+    type O = BulkWriteResult;
+    const NAME: &'static str = "bulkWrite";
+
+    fn build(&self, description: &StreamDescription) -> Result<Command> {
+        let batch_len = batch_len(self.statements, description);
+        self.batch_len.store(batch_len, Ordering::SeqCst);
+
+        let name = self.kind.command_name();
+        let mut body = doc! {
+            name: self.ns.coll.clone(),
+            self.kind.statements_field(): bson_util::to_bson_array(&self.statements[..batch_len]),
+        };
+        append_options(&mut body, self.options.as_ref())?;
+
+        let ordered = self
+            .options
+            .as_ref()
+            .and_then(|options| options.ordered)
+            .unwrap_or(true);
+        body.insert("ordered", ordered);
+
+        Ok(Command::new(name.to_string(), self.ns.db.clone(), body))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        let body: WriteResponseBody<BulkWriteBody> = response.body()?;
+
+        let mut result = BulkWriteResult::default();
+        match self.kind {
+            BulkWriteKind::Insert => result.inserted_count = body.n,
+            BulkWriteKind::Update => {
+                let upserted = body.upserted.as_deref().unwrap_or_default();
+                result.upserted_count = upserted.len() as i64;
+                result.matched_count = body.n - result.upserted_count;
+                result.modified_count = body.n_modified.unwrap_or(0);
+                result.upserted_ids = upserted
+                    .iter()
+                    .map(|upserted| (upserted.index, upserted.id.clone()))
+                    .collect();
+            }
+            BulkWriteKind::Delete => result.deleted_count = body.n,
+        }
+
+        if let Err(mut error) = body.validate() {
+            if let Some(ErrorKind::BulkWriteError(failure)) = Arc::get_mut(&mut error.kind) {
+                failure.partial_result = Some(result);
+            }
+            return Err(error);
+        }
+
+        Ok(result)
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+
+    fn retryability(&self) -> Retryability {
+        if self.has_multi_statement() {
+            Retryability::None
+        } else {
+            Retryability::Write
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+}
+
+#[derive(Deserialize)]
+struct BulkWriteBody {
+    #[serde(rename = "nModified")]
+    n_modified: Option<i64>,
+    upserted: Option<Vec<Upserted>>,
+}
+
+#[derive(Deserialize)]
+struct Upserted {
+    index: usize,
+    #[serde(rename = "_id")]
+    id: Bson,
+}
@@ -0,0 +1,214 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    bson::{doc, Bson, Document},
+    cmap::{CommandResponse, StreamDescription},
+    concern::{Acknowledgment, WriteConcern},
+    error::{BulkWriteError, ErrorKind},
+    operation::{BulkWrite, BulkWriteKind, Operation, Retryability},
+    options::BulkWriteOptions,
+    results::BulkWriteResult,
+    Namespace,
+};
+
+fn bulk_write<'a>(
+    kind: BulkWriteKind,
+    statements: &'a [Document],
+    batch_len: &'a AtomicUsize,
+) -> BulkWrite<'a> {
+    BulkWrite::new(
+        Namespace {
+            db: "test_db".to_string(),
+            coll: "test_coll".to_string(),
+        },
+        kind,
+        statements,
+        None,
+        batch_len,
+    )
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let options = BulkWriteOptions {
+        ordered: Some(false),
+        bypass_document_validation: Some(true),
+        write_concern: Some(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let statements = vec![
+        doc! { "q": { "x": 1 }, "u": { "$set": { "y": 1 } } },
+        doc! { "q": { "x": 2 }, "u": { "y": 2 }, "upsert": true },
+    ];
+    let batch_len = AtomicUsize::new(0);
+    let op = BulkWrite::new(
+        Namespace {
+            db: "test_db".to_string(),
+            coll: "test_coll".to_string(),
+        },
+        BulkWriteKind::Update,
+        &statements,
+        Some(options),
+        &batch_len,
+    );
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).unwrap();
+
+    assert_eq!(cmd.name.as_str(), "update");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "update": "test_coll",
+            "updates": statements.into_iter().map(Bson::Document).collect::<Vec<_>>(),
+            "bypassDocumentValidation": true,
+            "writeConcern": { "w": "majority" },
+            "ordered": false,
+        }
+    );
+
+    let statements = vec![doc! { "q": {}, "limit": 1 }];
+    let op = bulk_write(BulkWriteKind::Delete, &statements, &batch_len);
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.name.as_str(), "delete");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "delete": "test_coll",
+            "deletes": [{ "q": {}, "limit": 1 }],
+            "ordered": true,
+        }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn retryability() {
+    let batch_len = AtomicUsize::new(0);
+
+    let statements = vec![doc! { "q": {}, "u": { "$set": { "x": 1 } } }];
+    let single = bulk_write(BulkWriteKind::Update, &statements, &batch_len);
+    assert_eq!(single.retryability(), Retryability::Write);
+
+    let statements = vec![doc! { "q": {}, "u": { "$set": { "x": 1 } }, "multi": true }];
+    let multi = bulk_write(BulkWriteKind::Update, &statements, &batch_len);
+    assert_eq!(multi.retryability(), Retryability::None);
+
+    let statements = vec![doc! { "q": {}, "limit": 0 }];
+    let delete_many = bulk_write(BulkWriteKind::Delete, &statements, &batch_len);
+    assert_eq!(delete_many.retryability(), Retryability::None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let batch_len = AtomicUsize::new(0);
+    let op = bulk_write(BulkWriteKind::Update, &[], &batch_len);
+
+    let response = CommandResponse::with_document(doc! {
+        "ok": 1.0,
+        "n": 3,
+        "nModified": 1,
+        "upserted": [{ "index": 2, "_id": 5 }],
+    });
+    let result = op.handle_response(response).unwrap();
+
+    let mut expected = BulkWriteResult {
+        matched_count: 2,
+        modified_count: 1,
+        upserted_count: 1,
+        ..Default::default()
+    };
+    expected.upserted_ids.insert(2, Bson::Int32(5));
+    assert_eq!(result, expected);
+
+    let op = bulk_write(BulkWriteKind::Delete, &[], &batch_len);
+    let response = CommandResponse::with_document(doc! { "ok": 1.0, "n": 4 });
+    assert_eq!(op.handle_response(response).unwrap().deleted_count, 4);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_write_failure() {
+    let batch_len = AtomicUsize::new(0);
+    let op = bulk_write(BulkWriteKind::Insert, &[], &batch_len);
+
+    let response = CommandResponse::with_document(doc! {
+        "ok": 1.0,
+        "n": 2,
+        "writeErrors": [
+            {
+                "index": 1,
+                "code": 11000,
+                "errmsg": "duplicate key"
+            }
+        ]
+    });
+    let error = op.handle_response(response).unwrap_err();
+    match *error.kind {
+        ErrorKind::BulkWriteError(ref failure) => {
+            assert_eq!(
+                failure.write_errors,
+                Some(vec![BulkWriteError {
+                    index: 1,
+                    code: 11000,
+                    code_name: None,
+                    message: "duplicate key".to_string(),
+                }])
+            );
+            assert_eq!(
+                failure.partial_result,
+                Some(BulkWriteResult {
+                    inserted_count: 2,
+                    ..Default::default()
+                })
+            );
+        }
+        ref e => panic!("expected bulk write error, got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_batches() {
+    let statements: Vec<Document> = (0..10)
+        .map(|i| doc! { "q": { "x": i }, "limit": 1 })
+        .collect();
+    let batch_len = AtomicUsize::new(0);
+    let op = bulk_write(BulkWriteKind::Delete, &statements, &batch_len);
+    assert_eq!(batch_len.load(Ordering::SeqCst), 10);
+
+    // The batch is bounded by the maximum number of writes.
+    let mut description = StreamDescription::new_testing();
+    description.max_write_batch_size = 4;
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("deletes").unwrap().len(), 4);
+    assert_eq!(batch_len.load(Ordering::SeqCst), 4);
+
+    // ...and by the maximum document size, with each element of the array taking 34 bytes...
+    let mut description = StreamDescription::new_testing();
+    description.max_bson_object_size = 34 * 3;
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("deletes").unwrap().len(), 3);
+    assert_eq!(batch_len.load(Ordering::SeqCst), 3);
+
+    // ...and by the maximum message size.
+    let mut description = StreamDescription::new_testing();
+    description.max_message_size_bytes = 34 * 2;
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("deletes").unwrap().len(), 2);
+    assert_eq!(batch_len.load(Ordering::SeqCst), 2);
+
+    // A statement larger than the maximum size is still sent on its own.
+    description.max_message_size_bytes = 1;
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("deletes").unwrap().len(), 1);
+    assert_eq!(batch_len.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    bson::{self, doc},
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::Result,
+    operation::{Operation, Retryability, WriteConcernOnlyBody},
+    options::{TransactionOptions, WriteConcern},
+};
+
+#[derive(Debug)]
+pub(crate) struct CommitTransaction {
+    options: Option<TransactionOptions>,
+}
+
+impl CommitTransaction {
+    pub(crate) fn new(options: Option<TransactionOptions>) -> Self {
+        Self { options }
+    }
+}
+
+impl Operation for CommitTransaction {
+    type O = ();
+    const NAME: &'static str = "commitTransaction";
+
+    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+        let mut body = doc! {
+            Self::NAME: 1,
+        };
+
+        if let Some(ref options) = self.options {
+            if let Some(ref write_concern) = options.write_concern {
+                body.insert("writeConcern", bson::to_bson(write_concern)?);
+            }
+            if let Some(max_commit_time) = options.max_commit_time {
+                body.insert("maxTimeMS", max_commit_time.as_millis() as i64);
+            }
+        }
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            "admin".to_string(),
+            body,
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        response.body::<WriteConcernOnlyBody>()?.validate()
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+
+    fn retryability(&self) -> Retryability {
+        Retryability::Write
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::StreamDescription,
+    concern::{Acknowledgment, WriteConcern},
+    operation::{CommitTransaction, Operation},
+    options::TransactionOptions,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let options = TransactionOptions::builder()
+        .write_concern(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        })
+        .max_commit_time(Duration::from_millis(500))
+        .build();
+    let op = CommitTransaction::new(Some(options));
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "commitTransaction");
+    assert_eq!(cmd.target_db.as_str(), "admin");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "commitTransaction": 1,
+            "writeConcern": { "w": "majority" },
+            "maxTimeMS": 500i64,
+        }
+    );
+
+    let op = CommitTransaction::new(None);
+    let cmd = op.build(&description).expect("build should succeed");
+    assert_eq!(cmd.body, doc! { "commitTransaction": 1 });
+}
@@ -9,7 +9,7 @@ use crate::{
     cmap::{Command, CommandResponse, StreamDescription},
     error::{ErrorKind, Result},
     operation::aggregate::Aggregate,
-    options::{AggregateOptions, CountOptions},
+    options::{AggregateOptions, CountOptions, ReadConcern},
     selection_criteria::SelectionCriteria,
     Namespace,
 };
@@ -52,6 +52,7 @@ impl CountDocuments {
                 .hint(opts.hint)
                 .max_time(opts.max_time)
                 .collation(opts.collation)
+                .comment(opts.comment)
                 .selection_criteria(opts.selection_criteria)
                 .read_concern(opts.read_concern)
                 .build()
@@ -110,6 +111,14 @@ impl Operation for CountDocuments {
         self.aggregate.selection_criteria()
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn read_concern(&self) -> Option<&ReadConcern> {
+        self.aggregate.read_concern()
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    bson::{doc, Bson},
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::{ErrorKind, Result},
+    index::IndexModel,
+    operation::{append_options, Operation, WriteConcernOnlyBody},
+    options::{CreateIndexOptions, WriteConcern},
+    results::CreateIndexesResult,
+    Namespace,
+};
+
+#[derive(Debug)]
+pub(crate) struct CreateIndexes {
+    ns: Namespace,
+    indexes: Vec<IndexModel>,
+    options: Option<CreateIndexOptions>,
+}
+
+impl CreateIndexes {
+    #[cfg(test)]
+    fn with_indexes(indexes: Vec<IndexModel>) -> Self {
+        Self::new(
+            Namespace {
+                db: String::new(),
+                coll: String::new(),
+            },
+            indexes,
+            None,
+        )
+    }
+
+    pub(crate) fn new(
+        ns: Namespace,
+        indexes: Vec<IndexModel>,
+        options: Option<CreateIndexOptions>,
+    ) -> Self {
+        Self {
+            ns,
+            indexes,
+            options,
+        }
+    }
+}
+
+impl Operation for CreateIndexes {
+    type O = CreateIndexesResult;
+    const NAME: &'static str = "createIndexes";
+
+    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+        if self.indexes.is_empty() {
+            return Err(ErrorKind::ArgumentError {
+                message: "must specify at least one index to create".to_string(),
+            }
+            .into());
+        }
+
+        let indexes = self
+            .indexes
+            .iter()
+            .map(|index| index.to_index_spec().map(Bson::Document))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut body = doc! {
+            Self::NAME: self.ns.coll.clone(),
+            "indexes": indexes,
+        };
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        response.body::<WriteConcernOnlyBody>()?.validate()?;
+
+        Ok(CreateIndexesResult {
+            index_names: self.indexes.iter().map(IndexModel::name).collect(),
+        })
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+}
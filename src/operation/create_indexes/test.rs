@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    concern::{Acknowledgment, WriteConcern},
+    error::{ErrorKind, WriteFailure},
+    index::{options::IndexOptions, IndexModel},
+    operation::{CreateIndexes, Operation},
+    options::CreateIndexOptions,
+    Namespace,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let indexes = vec![
+        IndexModel::builder().keys(doc! { "x": 1 }).build(),
+        IndexModel::builder()
+            .keys(doc! { "y": -1 })
+            .options(
+                IndexOptions::builder()
+                    .name("y_index".to_string())
+                    .unique(true)
+                    .expire_after(Duration::from_secs(60))
+                    .build(),
+            )
+            .build(),
+    ];
+    let options = CreateIndexOptions::builder()
+        .max_time(Duration::from_millis(100))
+        .write_concern(WriteConcern {
+            w: Some(Acknowledgment::Custom("abc".to_string())),
+            ..Default::default()
+        })
+        .build();
+
+    let op = CreateIndexes::new(ns, indexes, Some(options));
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "createIndexes");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "createIndexes": "test_coll",
+            "indexes": [
+                { "key": { "x": 1 }, "name": "x_1" },
+                { "key": { "y": -1 }, "expireAfterSeconds": 60, "name": "y_index", "unique": true },
+            ],
+            "maxTimeMS": 100,
+            "writeConcern": { "w": "abc" },
+        }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_no_indexes() {
+    let op = CreateIndexes::with_indexes(Vec::new());
+    let result = op.build(&StreamDescription::new_testing());
+    match *result.unwrap_err().kind {
+        ErrorKind::ArgumentError { .. } => {}
+        ref e => panic!("expected argument error, got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let op = CreateIndexes::with_indexes(vec![
+        IndexModel::builder().keys(doc! { "a": 1, "b": -1 }).build(),
+        IndexModel::builder().keys(doc! { "c": "text" }).build(),
+    ]);
+
+    let response = CommandResponse::with_document(doc! {
+        "createdCollectionAutomatically": false,
+        "numIndexesBefore": 1,
+        "numIndexesAfter": 3,
+        "ok": 1.0,
+    });
+    let result = op.handle_response(response).expect("handle should succeed");
+    assert_eq!(result.index_names, vec!["a_1_b_-1", "c_text"]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_write_concern_error() {
+    let op = CreateIndexes::with_indexes(vec![IndexModel::builder().keys(doc! { "a": 1 }).build()]);
+
+    let response = CommandResponse::with_document(doc! {
+        "writeConcernError": {
+            "code": 100,
+            "codeName": "hello world",
+            "errmsg": "12345"
+        },
+        "ok": 1
+    });
+
+    match *op.handle_response(response).unwrap_err().kind {
+        ErrorKind::WriteError(WriteFailure::WriteConcernError(ref wc_err)) => {
+            assert_eq!(wc_err.code, 100);
+        }
+        ref e => panic!("expected write concern error, got {:?}", e),
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Document},
     cmap::{Command, CommandResponse, StreamDescription},
@@ -101,4 +103,8 @@ impl Operation for Delete {
             Retryability::None
         }
     }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
 }
@@ -1,7 +1,7 @@
 use pretty_assertions::assert_eq;
 
 use crate::{
-    bson::doc,
+    bson::{doc, Bson},
     bson_util,
     cmap::{CommandResponse, StreamDescription},
     concern::{Acknowledgment, WriteConcern},
@@ -24,7 +24,10 @@ async fn build_many() {
         w: Some(Acknowledgment::Majority),
         ..Default::default()
     };
-    let options = DeleteOptions::builder().write_concern(wc).build();
+    let options = DeleteOptions::builder()
+        .write_concern(wc)
+        .comment(Bson::Int32(1))
+        .build();
 
     let op = Delete::new(ns, filter.clone(), None, Some(options));
 
@@ -46,6 +49,7 @@ async fn build_many() {
         "writeConcern": {
             "w": "majority"
         },
+        "comment": 1,
         "ordered": true,
     };
 
@@ -192,7 +196,7 @@ async fn handle_write_concern_failure() {
                     "wtimeout": 0,
                     "provenance": "clientSupplied"
                 } }),
-                labels: Vec::new(),
+                labels: Default::default(),
             };
             assert_eq!(wc_error, &expected_wc_err);
         }
@@ -7,6 +7,7 @@ use crate::{
     bson::{doc, Bson, Document},
     cmap::{Command, CommandResponse, StreamDescription},
     coll::{options::DistinctOptions, Namespace},
+    concern::ReadConcern,
     error::Result,
     operation::{append_options, Operation, Retryability},
     selection_criteria::SelectionCriteria,
@@ -81,6 +82,16 @@ impl Operation for Distinct {
         None
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn read_concern(&self) -> Option<&ReadConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.read_concern.as_ref())
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    bson::doc,
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::Result,
+    operation::{append_options, Operation, WriteConcernOnlyBody},
+    options::{DropIndexOptions, WriteConcern},
+    Namespace,
+};
+
+/// Drops a single index by name, or all of the indexes other than the one on `_id` if no name is
+/// given.
+#[derive(Debug)]
+pub(crate) struct DropIndexes {
+    ns: Namespace,
+    name: Option<String>,
+    options: Option<DropIndexOptions>,
+}
+
+impl DropIndexes {
+    pub(crate) fn new(
+        ns: Namespace,
+        name: Option<String>,
+        options: Option<DropIndexOptions>,
+    ) -> Self {
+        Self { ns, name, options }
+    }
+}
+
+impl Operation for DropIndexes {
+    type O = ();
+    const NAME: &'static str = "dropIndexes";
+
+    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+        let mut body = doc! {
+            Self::NAME: self.ns.coll.clone(),
+            "index": self.name.as_deref().unwrap_or("*"),
+        };
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        response.body::<WriteConcernOnlyBody>()?.validate()
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    concern::{Acknowledgment, WriteConcern},
+    operation::{DropIndexes, Operation},
+    options::DropIndexOptions,
+    Namespace,
+};
+
+fn namespace() -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let options = DropIndexOptions::builder()
+        .max_time(Duration::from_millis(100))
+        .write_concern(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        })
+        .build();
+    let op = DropIndexes::new(namespace(), Some("x_1".to_string()), Some(options));
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "dropIndexes");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "dropIndexes": "test_coll",
+            "index": "x_1",
+            "maxTimeMS": 100,
+            "writeConcern": { "w": "majority" },
+        }
+    );
+
+    let op = DropIndexes::new(namespace(), None, None);
+    let cmd = op.build(&description).expect("build should succeed");
+    assert_eq!(cmd.body, doc! { "dropIndexes": "test_coll", "index": "*" });
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let op = DropIndexes::new(namespace(), None, None);
+
+    let response = CommandResponse::with_document(doc! { "ok": 1.0, "nIndexesWas": 3 });
+    assert!(op.handle_response(response).is_ok());
+
+    let response = CommandResponse::with_document(doc! {
+        "ok": 1.0,
+        "writeConcernError": { "code": 100, "errmsg": "error" },
+    });
+    assert!(op.handle_response(response).is_err());
+}
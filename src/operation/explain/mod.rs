@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod test;
+
+use std::time::Duration;
+
+use crate::{
+    bson::{doc, Document},
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::Result,
+    operation::Operation,
+    options::{ExplainVerbosity, SelectionCriteria},
+};
+
+/// Wraps the command of another operation in an `explain` command, which reports how the server
+/// would execute it instead of executing it. The raw explain output is returned.
+#[derive(Debug)]
+pub(crate) struct Explain<T: Operation> {
+    operation: T,
+    verbosity: ExplainVerbosity,
+}
+
+impl<T: Operation> Explain<T> {
+    pub(crate) fn new(operation: T, verbosity: ExplainVerbosity) -> Self {
+        Self {
+            operation,
+            verbosity,
+        }
+    }
+}
+
+impl<T: Operation> Operation for Explain<T> {
+    type O = Document;
+    const NAME: &'static str = "explain";
+
+    fn build(&self, description: &StreamDescription) -> Result<Command> {
+        let explained = self.operation.build(description)?;
+
+        let body = doc! {
+            Self::NAME: explained.body,
+            "verbosity": bson::to_bson(&self.verbosity)?,
+        };
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            explained.target_db,
+            body,
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        Ok(response.raw_response)
+    }
+
+    fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        self.operation.selection_criteria()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.operation.timeout()
+    }
+}
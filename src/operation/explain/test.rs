@@ -0,0 +1,83 @@
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    coll::Namespace,
+    operation::{Aggregate, Explain, Find, Operation},
+    options::{ExplainVerbosity, FindOptions, SelectionCriteria},
+    selection_criteria::{ReadPreference, ReadPreferenceOptions},
+};
+
+fn namespace() -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_find() {
+    let find = Find::new(namespace(), Some(doc! { "x": 1 }), None);
+    let op = Explain::new(find, ExplainVerbosity::ExecutionStats);
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+    assert_eq!(cmd.name.as_str(), "explain");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "explain": { "find": "test_coll", "filter": { "x": 1 } },
+            "verbosity": "executionStats",
+        }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_aggregate() {
+    let aggregate = Aggregate::new(namespace(), vec![doc! { "$match": { "x": 1 } }], None);
+    let op = Explain::new(aggregate, ExplainVerbosity::QueryPlanner);
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "explain": {
+                "aggregate": "test_coll",
+                "pipeline": [{ "$match": { "x": 1 } }],
+                "cursor": {},
+            },
+            "verbosity": "queryPlanner",
+        }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn selection_criteria() {
+    let selection_criteria = SelectionCriteria::ReadPreference(ReadPreference::Secondary {
+        options: ReadPreferenceOptions::default(),
+    });
+    let options = FindOptions::builder()
+        .selection_criteria(selection_criteria.clone())
+        .build();
+    let op = Explain::new(
+        Find::new(namespace(), None, Some(options)),
+        ExplainVerbosity::AllPlansExecution,
+    );
+
+    assert_eq!(op.selection_criteria(), Some(&selection_criteria));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_response() {
+    let op = Explain::new(
+        Find::new(namespace(), None, None),
+        ExplainVerbosity::QueryPlanner,
+    );
+
+    let output = doc! { "ok": 1.0, "queryPlanner": { "winningPlan": { "stage": "COLLSCAN" } } };
+    let response = CommandResponse::with_document(output.clone());
+    assert_eq!(op.handle_response(response).unwrap(), output);
+}
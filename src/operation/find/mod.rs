@@ -1,13 +1,18 @@
 #[cfg(test)]
 mod test;
 
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use crate::{
     bson::{doc, Document},
     cmap::{Command, CommandResponse, StreamDescription},
     cursor::CursorSpecification,
     error::{ErrorKind, Result},
     operation::{append_options, CursorBody, Operation, Retryability},
-    options::{CursorType, FindOptions, SelectionCriteria},
+    options::{CursorType, FindOptions, ReadConcern, SelectionCriteria},
     Namespace,
 };
 
@@ -16,6 +21,10 @@ pub(crate) struct Find {
     ns: Namespace,
     filter: Option<Document>,
     options: Option<FindOptions>,
+
+    /// When the operation was started, which is used to bound the lifetime of the resulting
+    /// cursor by `max_time`.
+    started_at: Instant,
 }
 
 impl Find {
@@ -40,15 +49,51 @@ impl Find {
             ns,
             filter,
             options,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// Adds a field to `projection` for each alias that sets it to the value of the field it aliases.
+fn alias_projection(
+    projection: Option<&Document>,
+    aliases: &HashMap<String, String>,
+) -> Result<Document> {
+    let mut projection = projection.cloned().unwrap_or_default();
+
+    // Sort the aliases so that the projection sent is the same each time.
+    let mut aliases: Vec<_> = aliases.iter().collect();
+    aliases.sort();
+
+    for (alias, field) in aliases {
+        if projection.contains_key(alias) {
+            return Err(ErrorKind::ArgumentError {
+                message: format!(
+                    "the field alias \"{}\" conflicts with a field of the projection",
+                    alias
+                ),
+            }
+            .into());
+        }
+
+        if field.is_empty() || field.starts_with('$') {
+            return Err(ErrorKind::ArgumentError {
+                message: format!("invalid field name \"{}\" for alias \"{}\"", field, alias),
+            }
+            .into());
         }
+
+        projection.insert(alias.as_str(), format!("${}", field));
     }
+
+    Ok(projection)
 }
 
 impl Operation for Find {
     type O = CursorSpecification;
     const NAME: &'static str = "find";
 
-    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+    fn build(&self, description: &StreamDescription) -> Result<Command> {
         let mut body = doc! {
             Self::NAME: self.ns.coll.clone(),
         };
@@ -70,6 +115,13 @@ impl Operation for Find {
                 .into());
             }
 
+            if options.max_time.map(|max_time| max_time.as_millis() == 0) == Some(true) {
+                return Err(ErrorKind::ArgumentError {
+                    message: "The max time must be at least one millisecond".to_string(),
+                }
+                .into());
+            }
+
             match options.cursor_type {
                 Some(CursorType::Tailable) => {
                     body.insert("tailable", true);
@@ -84,6 +136,26 @@ impl Operation for Find {
 
         append_options(&mut body, self.options.as_ref())?;
 
+        if let Some(aliases) = self
+            .options
+            .as_ref()
+            .and_then(|opts| opts.field_aliases.as_ref())
+        {
+            if description
+                .max_wire_version
+                .map_or(true, |version| version < 9)
+            {
+                return Err(ErrorKind::OperationError {
+                    message: "Specifying field aliases is not supported on server versions < 4.4"
+                        .to_string(),
+                }
+                .into());
+            }
+
+            let projection = alias_projection(body.get_document("projection").ok(), aliases)?;
+            body.insert("projection", projection);
+        }
+
         if let Some(ref filter) = self.filter {
             body.insert("filter", filter.clone());
         }
@@ -102,9 +174,17 @@ impl Operation for Find {
             self.ns.clone(),
             response.source_address().clone(),
             body.cursor.id,
-            self.options.as_ref().and_then(|opts| opts.batch_size),
+            self.options
+                .as_ref()
+                .and_then(|opts| opts.get_more_batch_size.or(opts.batch_size)),
             self.options.as_ref().and_then(|opts| opts.max_await_time),
             body.cursor.first_batch,
+        )
+        .with_deadline(
+            self.options
+                .as_ref()
+                .and_then(|opts| opts.max_time)
+                .map(|max_time| self.started_at + max_time),
         ))
     }
 
@@ -114,7 +194,21 @@ impl Operation for Find {
             .and_then(|opts| opts.selection_criteria.as_ref())
     }
 
+    fn supports_read_concern(&self) -> bool {
+        true
+    }
+
+    fn read_concern(&self) -> Option<&ReadConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.read_concern.as_ref())
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
 }
@@ -1,11 +1,24 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use crate::{
     bson::{doc, Document},
     bson_util,
     cmap::{CommandResponse, StreamDescription},
+    error::ErrorKind,
     operation::{test, Find, Operation},
-    options::{CursorType, FindOptions, Hint, ReadConcern, ReadConcernLevel, StreamAddress},
+    options::{
+        Collation,
+        CollationStrength,
+        CursorType,
+        FindOptions,
+        Hint,
+        ReadConcern,
+        ReadConcernLevel,
+        StreamAddress,
+    },
     Namespace,
 };
 
@@ -114,6 +127,32 @@ async fn build_cursor_type() {
     build_test(ns, None, Some(tailable_await_options), tailable_await_body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_collation() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+
+    let collation = Collation::builder()
+        .locale("en_US".to_string())
+        .strength(CollationStrength::Secondary)
+        .build();
+    let options = FindOptions::builder().collation(collation).build();
+
+    // Unset fields of the collation are omitted.
+    let body = doc! {
+        "find": "test_coll",
+        "collation": {
+            "locale": "en_US",
+            "strength": 2,
+        },
+    };
+
+    build_test(ns, None, Some(options), body);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build_max_await_time() {
@@ -135,6 +174,38 @@ async fn build_max_await_time() {
     build_test(ns, None, Some(options), body);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_allow_disk_use() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+
+    let options = FindOptions::builder().allow_disk_use(true).build();
+
+    let body = doc! {
+        "find": "test_coll",
+        "allowDiskUse": true
+    };
+
+    build_test(ns, None, Some(options), body);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_zero_max_time() {
+    let options = FindOptions::builder()
+        .max_time(Duration::from_secs(0))
+        .build();
+    let find = Find::new(Namespace::empty(), None, Some(options));
+    let result = find.build(&StreamDescription::new_testing());
+    match *result.unwrap_err().kind {
+        ErrorKind::ArgumentError { .. } => {}
+        ref e => panic!("expected argument error, got {:?}", e),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build_limit() {
@@ -180,6 +251,52 @@ async fn build_batch_size() {
     assert!(op.build(&StreamDescription::new_testing()).is_err())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_field_aliases() {
+    let mut description = StreamDescription::new_testing();
+    description.max_wire_version = Some(9);
+
+    let build = |projection: Option<Document>, aliases: Vec<(&str, &str)>| {
+        let aliases: HashMap<String, String> = aliases
+            .into_iter()
+            .map(|(alias, field)| (alias.to_string(), field.to_string()))
+            .collect();
+        let options = FindOptions::builder()
+            .projection(projection)
+            .field_aliases(aliases)
+            .build();
+        Find::new(Namespace::empty(), None, Some(options)).build(&description)
+    };
+
+    let cmd = build(None, vec![("name", "user_name"), ("age", "legacy.age")]).unwrap();
+    assert_eq!(
+        cmd.body.get_document("projection"),
+        Ok(&doc! { "age": "$legacy.age", "name": "$user_name" })
+    );
+
+    let cmd = build(Some(doc! { "email": 1 }), vec![("name", "user_name")]).unwrap();
+    assert_eq!(
+        cmd.body.get_document("projection"),
+        Ok(&doc! { "email": 1, "name": "$user_name" })
+    );
+
+    assert!(build(Some(doc! { "name": 1 }), vec![("name", "user_name")]).is_err());
+    assert!(build(None, vec![("name", "$user_name")]).is_err());
+    assert!(build(None, vec![("name", "")]).is_err());
+
+    // Aliases in a projection are only supported on 4.4+.
+    let options = FindOptions::builder()
+        .field_aliases(
+            vec![("name".to_string(), "user_name".to_string())]
+                .into_iter()
+                .collect::<HashMap<_, _>>(),
+        )
+        .build();
+    let op = Find::new(Namespace::empty(), None, Some(options));
+    assert!(op.build(&StreamDescription::new_testing()).is_err());
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -260,6 +377,40 @@ async fn handle_success() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn get_more_batch_size() {
+    let response = doc! {
+        "cursor": {
+            "id": 123,
+            "ns": "test_db.test_coll",
+            "firstBatch": [],
+        },
+        "ok": 1.0
+    };
+    let cursor_batch_size = |options: FindOptions| {
+        let find = Find::new(Namespace::empty(), None, Some(options));
+
+        // The batch size of the find command itself is unaffected.
+        let cmd = find.build(&StreamDescription::new_testing()).unwrap();
+        assert_eq!(cmd.body.get_i32("batchSize").ok(), Some(2));
+        assert!(!cmd.body.contains_key("getMoreBatchSize"));
+
+        find.handle_response(CommandResponse::with_document(response.clone()))
+            .unwrap()
+            .batch_size()
+    };
+
+    let options = FindOptions::builder()
+        .batch_size(2)
+        .get_more_batch_size(1000)
+        .build();
+    assert_eq!(cursor_batch_size(options), Some(1000));
+
+    let options = FindOptions::builder().batch_size(2).build();
+    assert_eq!(cursor_batch_size(options), Some(2));
+}
+
 fn verify_max_await_time(max_await_time: Option<Duration>, cursor_type: Option<CursorType>) {
     let ns = Namespace::empty();
     let address = StreamAddress {
@@ -310,6 +461,38 @@ async fn handle_max_await_time() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_max_time() {
+    let response = CommandResponse::with_document(doc! {
+        "cursor": {
+            "id": 123,
+            "ns": "a.b",
+            "firstBatch": [],
+        },
+        "ok": 1
+    });
+
+    let find = Find::empty();
+    let spec = find
+        .handle_response(response.clone())
+        .expect("should handle correctly");
+    assert!(spec.deadline().is_none());
+
+    let max_time = Duration::from_secs(60);
+    let options = FindOptions::builder().max_time(max_time).build();
+    let before = Instant::now();
+    let find = Find::new(Namespace::empty(), None, Some(options));
+    let spec = find
+        .handle_response(response)
+        .expect("should handle correctly");
+
+    let deadline = spec.deadline().expect("deadline should be set");
+    assert!(deadline >= before + max_time);
+    assert!(deadline <= Instant::now() + max_time);
+    assert_eq!(spec.max_time(), None);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_invalid_response() {
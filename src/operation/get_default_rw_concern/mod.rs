@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod test;
+
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    bson::{doc, from_document, Document},
+    cmap::{Command, CommandResponse, StreamDescription},
+    concern::{ReadConcern, WriteConcern},
+    error::{ErrorKind, Result},
+    operation::{Operation, Retryability},
+    results::DefaultRwConcern,
+};
+
+/// The value of the `default*ConcernSource` fields when the default was set with
+/// `setDefaultRWConcern`.
+const GLOBAL_SOURCE: &str = "global";
+
+#[derive(Debug)]
+pub(crate) struct GetDefaultRwConcern;
+
+impl Operation for GetDefaultRwConcern {
+    type O = DefaultRwConcern;
+    const NAME: &'static str = "getDefaultRWConcern";
+
+    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            "admin".to_string(),
+            doc! { Self::NAME: 1 },
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        let body: ResponseBody = response.body()?;
+
+        let read_concern: Option<ReadConcern> =
+            parse_default_concern(body.default_read_concern, "read")?;
+        let write_concern: Option<WriteConcern> =
+            parse_default_concern(body.default_write_concern, "write")?;
+
+        // Servers before 5.0 don't report the source of the defaults, and they only report
+        // defaults that were set explicitly.
+        let explicitly_set = |source: Option<String>, is_set: bool| match source {
+            Some(source) => source == GLOBAL_SOURCE,
+            None => is_set,
+        };
+
+        Ok(DefaultRwConcern {
+            read_concern_explicitly_set: explicitly_set(
+                body.default_read_concern_source,
+                read_concern.is_some(),
+            ),
+            write_concern_explicitly_set: explicitly_set(
+                body.default_write_concern_source,
+                write_concern.is_some(),
+            ),
+            read_concern,
+            write_concern,
+        })
+    }
+
+    fn retryability(&self) -> Retryability {
+        Retryability::Read
+    }
+}
+
+/// Parses a default concern reported by the server. Servers before 4.4 report an empty document
+/// when no default has been set.
+fn parse_default_concern<T: DeserializeOwned>(
+    concern: Option<Document>,
+    concern_type: &str,
+) -> Result<Option<T>> {
+    match concern {
+        Some(doc) if !doc.is_empty() => from_document(doc).map(Some).map_err(|e| {
+            ErrorKind::ResponseError {
+                message: format!("invalid default {} concern: {}", concern_type, e),
+            }
+            .into()
+        }),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseBody {
+    default_read_concern: Option<Document>,
+    default_write_concern: Option<Document>,
+    default_read_concern_source: Option<String>,
+    default_write_concern_source: Option<String>,
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    concern::{Acknowledgment, ReadConcern, ReadConcernLevel, WriteConcern},
+    operation::{GetDefaultRwConcern, Operation},
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let cmd = GetDefaultRwConcern
+        .build(&StreamDescription::new_testing())
+        .expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "getDefaultRWConcern");
+    assert_eq!(cmd.target_db.as_str(), "admin");
+    assert_eq!(cmd.body, doc! { "getDefaultRWConcern": 1 });
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    // 5.0+ reports implicit defaults along with their source.
+    let response = CommandResponse::with_document(doc! {
+        "defaultReadConcern": { "level": "local" },
+        "defaultWriteConcern": { "w": "majority", "wtimeout": 0 },
+        "defaultReadConcernSource": "implicit",
+        "defaultWriteConcernSource": "global",
+        "ok": 1.0,
+    });
+    let result = GetDefaultRwConcern
+        .handle_response(response)
+        .expect("handle should succeed");
+    assert_eq!(
+        result.read_concern,
+        Some(ReadConcern::from(ReadConcernLevel::Local))
+    );
+    assert!(!result.read_concern_explicitly_set);
+    assert_eq!(
+        result.write_concern,
+        Some(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            w_timeout: Some(Duration::from_millis(0)),
+            journal: None,
+        })
+    );
+    assert!(result.write_concern_explicitly_set);
+
+    // 4.4 only reports defaults that were explicitly set, and doesn't report their source.
+    let response = CommandResponse::with_document(doc! {
+        "defaultReadConcern": { "level": "majority" },
+        "ok": 1.0,
+    });
+    let result = GetDefaultRwConcern
+        .handle_response(response)
+        .expect("handle should succeed");
+    assert_eq!(result.read_concern, Some(ReadConcern::majority()));
+    assert!(result.read_concern_explicitly_set);
+    assert_eq!(result.write_concern, None);
+    assert!(!result.write_concern_explicitly_set);
+
+    let response = CommandResponse::with_document(doc! {
+        "defaultReadConcern": {},
+        "defaultWriteConcern": {},
+        "ok": 1.0,
+    });
+    let result = GetDefaultRwConcern
+        .handle_response(response)
+        .expect("handle should succeed");
+    assert_eq!(result.read_concern, None);
+    assert_eq!(result.write_concern, None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_invalid_response() {
+    let response = CommandResponse::with_document(doc! {
+        "defaultReadConcern": { "level": 1 },
+        "ok": 1.0,
+    });
+    assert!(GetDefaultRwConcern.handle_response(response).is_err());
+}
@@ -1,21 +1,27 @@
 #[cfg(test)]
 mod test;
 
-use std::{collections::VecDeque, time::Duration};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use serde::Deserialize;
 
 use crate::{
     bson::{doc, Document},
-    cmap::{Command, CommandResponse, StreamDescription},
+    cmap::{Command, CommandResponse, PinnedConnection, StreamDescription},
     cursor::CursorInformation,
-    error::{ErrorKind, Result},
+    error::{CommandError, ErrorKind, Result},
     operation::Operation,
     options::SelectionCriteria,
     results::GetMoreResult,
     Namespace,
 };
 
+/// The code of the error the server returns when an operation exceeds its `maxTimeMS`.
+const MAX_TIME_MS_EXPIRED_CODE: i32 = 50;
+
 #[derive(Debug)]
 pub(crate) struct GetMore {
     ns: Namespace,
@@ -23,6 +29,9 @@ pub(crate) struct GetMore {
     selection_criteria: SelectionCriteria,
     batch_size: Option<u32>,
     max_time: Option<Duration>,
+    deadline: Option<Instant>,
+    timeout_deadline: Option<Instant>,
+    pinned_connection: Option<PinnedConnection>,
 }
 
 impl GetMore {
@@ -33,6 +42,9 @@ impl GetMore {
             selection_criteria: SelectionCriteria::from_address(info.address),
             batch_size: info.batch_size,
             max_time: info.max_time,
+            deadline: info.deadline,
+            timeout_deadline: info.timeout_deadline,
+            pinned_connection: info.pinned_connection,
         }
     }
 }
@@ -58,7 +70,26 @@ impl Operation for GetMore {
             }
         }
 
-        if let Some(ref max_time) = self.max_time {
+        let mut max_time = self.max_time;
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.as_millis() == 0 {
+                return Err(ErrorKind::CommandError(CommandError {
+                    code: MAX_TIME_MS_EXPIRED_CODE,
+                    code_name: "MaxTimeMSExpired".to_string(),
+                    message: "the cursor's time limit expired before it was exhausted".to_string(),
+                    labels: Default::default(),
+                })
+                .into());
+            }
+
+            // The server only accepts a maxTimeMS on getMores for tailable await cursors, where
+            // it's the time to wait for new documents, so this never extends that wait past the
+            // cursor's deadline.
+            max_time = max_time.map(|max_time| max_time.min(remaining));
+        }
+
+        if let Some(ref max_time) = max_time {
             body.insert("maxTimeMS", max_time.as_millis() as i32);
         }
 
@@ -74,12 +105,21 @@ impl Operation for GetMore {
         Ok(GetMoreResult {
             batch: body.cursor.next_batch,
             exhausted: body.cursor.id == 0,
+            post_batch_resume_token: body.cursor.post_batch_resume_token,
         })
     }
 
     fn selection_criteria(&self) -> Option<&SelectionCriteria> {
         Some(&self.selection_criteria)
     }
+
+    fn timeout_deadline(&self) -> Option<Instant> {
+        self.timeout_deadline
+    }
+
+    fn pinned_connection(&self) -> Option<&PinnedConnection> {
+        self.pinned_connection.as_ref()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -92,4 +132,5 @@ struct GetMoreResponseBody {
 struct NextBatchBody {
     id: i64,
     next_batch: VecDeque<Document>,
+    post_batch_resume_token: Option<Document>,
 }
@@ -1,10 +1,11 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::{
     bson::{doc, Document},
     bson_util,
     cmap::{CommandResponse, StreamDescription},
     cursor::CursorInformation,
+    error::ErrorKind,
     operation::{GetMore, Operation},
     options::StreamAddress,
     sdam::{ServerDescription, ServerInfo, ServerType},
@@ -25,6 +26,9 @@ fn build_test(
         address,
         batch_size,
         max_time,
+        deadline: None,
+        timeout_deadline: None,
+        pinned_connection: None,
     };
     let get_more = GetMore::new(info);
 
@@ -117,11 +121,57 @@ async fn build_batch_size() {
         id: cursor_id,
         batch_size: Some((std::i32::MAX as u32) + 1),
         max_time: None,
+        deadline: None,
+        timeout_deadline: None,
+        pinned_connection: None,
     };
     let op = GetMore::new(info);
     assert!(op.build(&StreamDescription::new_testing()).is_err())
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_deadline() {
+    let info = |max_time: Option<Duration>, deadline: Instant| CursorInformation {
+        ns: Namespace::empty(),
+        address: StreamAddress::default(),
+        id: 123,
+        batch_size: None,
+        max_time,
+        deadline: Some(deadline),
+        timeout_deadline: None,
+        pinned_connection: None,
+    };
+    let description = StreamDescription::new_testing();
+
+    // A cursor with time remaining doesn't send a maxTimeMS unless it's a tailable await cursor.
+    let far = Instant::now() + Duration::from_secs(60);
+    let cmd = GetMore::new(info(None, far)).build(&description).unwrap();
+    assert!(!cmd.body.contains_key("maxTimeMS"));
+
+    let cmd = GetMore::new(info(Some(Duration::from_millis(500)), far))
+        .build(&description)
+        .unwrap();
+    assert_eq!(cmd.body.get_i32("maxTimeMS"), Ok(500));
+
+    // The time spent waiting for new documents is bounded by the cursor's remaining time.
+    let near = Instant::now() + Duration::from_secs(5);
+    let cmd = GetMore::new(info(Some(Duration::from_secs(60)), near))
+        .build(&description)
+        .unwrap();
+    let max_time_ms = cmd.body.get_i32("maxTimeMS").unwrap();
+    assert!(max_time_ms > 0 && max_time_ms <= 5000);
+
+    let expired = Instant::now();
+    let error = GetMore::new(info(None, expired))
+        .build(&description)
+        .expect_err("build should fail after the deadline");
+    match error.kind.as_ref() {
+        ErrorKind::CommandError(e) => assert_eq!(e.code_name, "MaxTimeMSExpired"),
+        other => panic!("expected command error, got {:?}", other),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn op_selection_criteria() {
@@ -136,6 +186,9 @@ async fn op_selection_criteria() {
         id: 123,
         batch_size: None,
         max_time: None,
+        deadline: None,
+        timeout_deadline: None,
+        pinned_connection: None,
     };
     let get_more = GetMore::new(info);
     let server_description = ServerDescription {
@@ -181,6 +234,9 @@ async fn handle_success() {
         id: cursor_id,
         batch_size: None,
         max_time: None,
+        deadline: None,
+        timeout_deadline: None,
+        pinned_connection: None,
     };
     let get_more = GetMore::new(info);
 
@@ -214,4 +270,23 @@ async fn handle_success() {
         .expect("handle success case failed");
     assert!(result.exhausted);
     assert_eq!(result.batch, batch);
+    assert_eq!(result.post_batch_resume_token, None);
+
+    let response = CommandResponse::with_document(doc! {
+        "cursor": {
+            "id": 123,
+            "ns": "test_db.test_coll",
+            "nextBatch": [],
+            "postBatchResumeToken": { "_data": "token" },
+        },
+        "ok": 1
+    });
+    let result = get_more
+        .handle_response(response)
+        .expect("handle success case failed");
+    assert!(result.batch.is_empty());
+    assert_eq!(
+        result.post_batch_resume_token,
+        Some(doc! { "_data": "token" })
+    );
 }
@@ -1,7 +1,14 @@
 #[cfg(test)]
 mod test;
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use crate::{
     bson::{doc, Document},
@@ -9,58 +16,70 @@ use crate::{
     client::ClientSession,
     cmap::{Command, CommandResponse, StreamDescription},
     error::{ErrorKind, Result},
-    operation::{append_options, Operation, Retryability, WriteResponseBody},
+    operation::{append_options, batch_len, Operation, Retryability, WriteResponseBody},
     options::{InsertManyOptions, WriteConcern},
     results::InsertManyResult,
     Namespace,
 };
 
+/// Inserts as many of the given documents as the server accepts in a single `insert` command.
+/// The number of documents that were sent is the number of ids in the result, or, if the command
+/// fails with write errors, the number of inserted ids and write errors.
 #[derive(Debug)]
-pub(crate) struct Insert {
+pub(crate) struct Insert<'a> {
     ns: Namespace,
-    documents: Vec<Document>,
+    documents: &'a [Document],
     options: Option<InsertManyOptions>,
     session: Option<ClientSession>,
+
+    /// The number of documents at the front of `documents` that the most recently built command
+    /// contains.
+    batch_len: AtomicUsize,
 }
 
-impl Insert {
+impl<'a> Insert<'a> {
+    /// Constructs an insert of `documents`, generating an `ObjectId` for the `_id` of each one that
+    /// doesn't have one.
     pub(crate) fn new(
         ns: Namespace,
-        documents: Vec<Document>,
+        documents: &'a mut [Document],
         options: Option<InsertManyOptions>,
     ) -> Self {
+        for doc in documents.iter_mut() {
+            bson_util::add_id(doc);
+        }
+
         Self {
             ns,
             options,
-            documents: documents
-                .into_iter()
-                .map(|mut d| {
-                    bson_util::add_id(&mut d);
-                    d
-                })
-                .collect(),
+            batch_len: AtomicUsize::new(documents.len()),
+            documents,
             session: None,
         }
     }
+
+    fn is_ordered(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|options| options.ordered)
+            .unwrap_or(true)
+    }
 }
 
-impl Operation for Insert {
+impl<'a> Operation for Insert<'a> {
     type O = InsertManyResult;
     const NAME: &'static str = "insert";
 
-    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+    fn build(&self, description: &StreamDescription) -> Result<Command> {
+        let batch_len = batch_len(self.documents, description);
+        self.batch_len.store(batch_len, Ordering::SeqCst);
+
         let mut body = doc! {
             Self::NAME: self.ns.coll.clone(),
-            "documents": bson_util::to_bson_array(&self.documents),
+            "documents": bson_util::to_bson_array(&self.documents[..batch_len]),
         };
         append_options(&mut body, self.options.as_ref())?;
-
-        let ordered = self
-            .options
-            .as_ref()
-            .and_then(|options| options.ordered)
-            .unwrap_or(true);
-        body.insert("ordered", ordered);
+        body.insert("ordered", self.is_ordered());
 
         Ok(Command::new(
             Self::NAME.to_string(),
@@ -71,10 +90,10 @@ impl Operation for Insert {
 
     fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
         let body: WriteResponseBody = response.body()?;
-        body.validate()?;
 
+        let batch = &self.documents[..self.batch_len.load(Ordering::SeqCst)];
         let mut map = HashMap::new();
-        for (i, doc) in self.documents.iter().enumerate() {
+        for (i, doc) in batch.iter().enumerate() {
             map.insert(
                 i,
                 doc.get("_id")
@@ -84,7 +103,28 @@ impl Operation for Insert {
                     .clone(),
             );
         }
-        Ok(InsertManyResult { inserted_ids: map })
+
+        if let Err(mut error) = body.validate() {
+            if let Some(ErrorKind::BulkWriteError(failure)) = Arc::get_mut(&mut error.kind) {
+                // The documents that failed weren't inserted, and neither were the ones after the
+                // first failure of an ordered insert.
+                let write_errors = failure.write_errors.as_deref().unwrap_or_default();
+                let first_failure = write_errors.iter().map(|error| error.index).min();
+                let ordered = self.is_ordered();
+                map.retain(|index, _| {
+                    let failed = write_errors.iter().any(|error| error.index == *index);
+                    let skipped = ordered && first_failure.filter(|first| index > first).is_some();
+                    !(failed || skipped)
+                });
+                failure.inserted_ids = map;
+            }
+            return Err(error);
+        }
+
+        Ok(InsertManyResult {
+            inserted_ids: map,
+            inserted_documents: None,
+        })
     }
 
     fn write_concern(&self) -> Option<&WriteConcern> {
@@ -96,4 +136,8 @@ impl Operation for Insert {
     fn retryability(&self) -> Retryability {
         Retryability::Write
     }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
 }
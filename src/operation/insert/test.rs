@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    bson::{doc, Bson, Document},
+    bson::{doc, from_bson, oid::ObjectId, to_document, Bson, Document},
     cmap::{CommandResponse, StreamDescription},
     concern::WriteConcern,
     error::{BulkWriteError, ErrorKind, WriteConcernError},
@@ -9,12 +11,26 @@ use crate::{
 };
 
 struct TestFixtures {
-    op: Insert,
+    /// The documents to insert, which the op is constructed with a copy of.
     documents: Vec<Document>,
+    inserted_documents: Vec<Document>,
     options: InsertManyOptions,
 }
 
-/// Get an Insert operation and the documents/options used to construct it.
+impl TestFixtures {
+    fn op(&mut self) -> Insert<'_> {
+        Insert::new(
+            Namespace {
+                db: "test_db".to_string(),
+                coll: "test_coll".to_string(),
+            },
+            &mut self.inserted_documents,
+            Some(self.options.clone()),
+        )
+    }
+}
+
+/// Get the documents/options used to construct an Insert operation.
 fn fixtures() -> TestFixtures {
     let documents = vec![
         Document::new(),
@@ -28,17 +44,8 @@ fn fixtures() -> TestFixtures {
         ..Default::default()
     };
 
-    let op = Insert::new(
-        Namespace {
-            db: "test_db".to_string(),
-            coll: "test_coll".to_string(),
-        },
-        documents.clone(),
-        Some(options.clone()),
-    );
-
     TestFixtures {
-        op,
+        inserted_documents: documents.clone(),
         documents,
         options,
     }
@@ -47,10 +54,10 @@ fn fixtures() -> TestFixtures {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build() {
-    let fixtures = fixtures();
+    let mut fixtures = fixtures();
 
     let description = StreamDescription::new_testing();
-    let cmd = fixtures.op.build(&description).unwrap();
+    let cmd = fixtures.op().build(&description).unwrap();
 
     assert_eq!(cmd.name.as_str(), "insert");
     assert_eq!(cmd.target_db.as_str(), "test_db");
@@ -108,7 +115,7 @@ async fn build() {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn build_ordered() {
-    let insert = Insert::new(Namespace::empty(), Vec::new(), None);
+    let insert = Insert::new(Namespace::empty(), &mut [], None);
     let cmd = insert
         .build(&StreamDescription::new_testing())
         .expect("should succeed");
@@ -116,7 +123,7 @@ async fn build_ordered() {
 
     let insert = Insert::new(
         Namespace::empty(),
-        Vec::new(),
+        &mut [],
         Some(InsertManyOptions::builder().ordered(false).build()),
     );
     let cmd = insert
@@ -126,7 +133,7 @@ async fn build_ordered() {
 
     let insert = Insert::new(
         Namespace::empty(),
-        Vec::new(),
+        &mut [],
         Some(InsertManyOptions::builder().ordered(true).build()),
     );
     let cmd = insert
@@ -138,10 +145,10 @@ async fn build_ordered() {
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_success() {
-    let fixtures = fixtures();
+    let mut fixtures = fixtures();
 
     let ok_response = CommandResponse::with_document(doc! { "ok": 1.0, "n": 3 });
-    let ok_result = fixtures.op.handle_response(ok_response);
+    let ok_result = fixtures.op().handle_response(ok_response);
     assert!(ok_result.is_ok());
 
     let inserted_ids = ok_result.unwrap().inserted_ids;
@@ -152,19 +159,58 @@ async fn handle_success() {
     );
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+struct UserId(ObjectId);
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success_newtype_id() {
+    #[derive(Serialize)]
+    struct User {
+        #[serde(rename = "_id")]
+        id: UserId,
+    }
+
+    #[derive(Serialize)]
+    struct NewUser {
+        #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+        id: Option<UserId>,
+    }
+
+    let id = UserId(ObjectId::new());
+    let mut documents = vec![
+        to_document(&User { id: id.clone() }).unwrap(),
+        to_document(&NewUser { id: None }).unwrap(),
+    ];
+    let op = Insert::new(Namespace::empty(), &mut documents, None);
+
+    let response = CommandResponse::with_document(doc! { "ok": 1.0, "n": 2 });
+    let inserted_ids = op.handle_response(response).unwrap().inserted_ids;
+
+    // The provided id is sent as-is and can be converted back into the newtype.
+    let inserted_id: UserId = from_bson(inserted_ids.get(&0).unwrap().clone()).unwrap();
+    assert_eq!(inserted_id, id);
+
+    // An omitted id is generated by the driver as an `ObjectId`, which the newtype can also
+    // represent.
+    let generated_id: UserId = from_bson(inserted_ids.get(&1).unwrap().clone()).unwrap();
+    assert_ne!(generated_id, id);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_invalid_response() {
-    let fixtures = fixtures();
+    let mut fixtures = fixtures();
 
     let invalid_response = CommandResponse::with_document(doc! { "ok": 1.0, "asdfadsf": 123123 });
-    assert!(fixtures.op.handle_response(invalid_response).is_err());
+    assert!(fixtures.op().handle_response(invalid_response).is_err());
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn handle_write_failure() {
-    let fixtures = fixtures();
+    let mut fixtures = fixtures();
 
     let write_error_response = CommandResponse::with_document(doc! {
         "ok": 1.0,
@@ -190,7 +236,8 @@ async fn handle_write_failure() {
         }
     });
 
-    let write_error_result = fixtures.op.handle_response(write_error_response);
+    let op = fixtures.op();
+    let write_error_result = op.handle_response(write_error_response);
     assert!(write_error_result.is_err());
 
     match *write_error_result.unwrap_err().kind {
@@ -215,10 +262,68 @@ async fn handle_write_failure() {
                     "wtimeout": 0,
                     "provenance": "clientSupplied"
                 } }),
-                labels: Vec::new(),
+                labels: Default::default(),
             };
             assert_eq!(write_concern_error, expected_wc_err);
+
+            // The insert is ordered, so only the document before the failed one was inserted.
+            assert_eq!(error.inserted_ids.len(), 1);
+            assert!(error.inserted_ids.contains_key(&0));
         }
         ref e => panic!("expected bulk write error, got {:?}", e),
     };
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_unordered_write_failure() {
+    let mut documents = vec![doc! { "_id": 1 }, doc! { "_id": 2 }, doc! { "_id": 3 }];
+    let op = Insert::new(
+        Namespace::empty(),
+        &mut documents,
+        Some(InsertManyOptions::builder().ordered(false).build()),
+    );
+
+    let response = CommandResponse::with_document(doc! {
+        "ok": 1.0,
+        "n": 2,
+        "writeErrors": [{ "index": 1, "code": 11000, "errmsg": "duplicate key" }],
+    });
+    match *op.handle_response(response).unwrap_err().kind {
+        ErrorKind::BulkWriteError(ref error) => {
+            let mut inserted: Vec<_> = error.inserted_ids.iter().collect();
+            inserted.sort_by_key(|(index, _)| **index);
+            assert_eq!(inserted, vec![(&0, &Bson::Int32(1)), (&2, &Bson::Int32(3))]);
+        }
+        ref e => panic!("expected bulk write error, got {:?}", e),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build_batches() {
+    let mut documents: Vec<Document> = (0..10).map(|i| doc! { "_id": i }).collect();
+
+    // The batch is bounded by the maximum number of writes.
+    let mut description = StreamDescription::new_testing();
+    description.max_write_batch_size = 4;
+    let op = Insert::new(Namespace::empty(), &mut documents, None);
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("documents").unwrap().len(), 4);
+
+    let response = CommandResponse::with_document(doc! { "ok": 1.0, "n": 4 });
+    let inserted_ids = op.handle_response(response).unwrap().inserted_ids;
+    assert_eq!(inserted_ids.len(), 4);
+    assert_eq!(inserted_ids.get(&3), Some(&Bson::Int32(3)));
+
+    // ...and by the maximum document size, with each element of the array taking 17 bytes.
+    let mut description = StreamDescription::new_testing();
+    description.max_bson_object_size = 17 * 3;
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("documents").unwrap().len(), 3);
+
+    // A document larger than the maximum size is still sent on its own.
+    description.max_bson_object_size = 1;
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.body.get_array("documents").unwrap().len(), 1);
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::VecDeque;
+
+use crate::{
+    bson::doc,
+    cmap::{Command, CommandResponse, StreamDescription},
+    cursor::CursorSpecification,
+    error::{Error, Result},
+    operation::{append_options, CursorBody, Operation, Retryability},
+    options::{ListIndexesOptions, ReadPreference, SelectionCriteria, StreamAddress},
+    Namespace,
+};
+
+#[derive(Debug)]
+pub(crate) struct ListIndexes {
+    ns: Namespace,
+    options: Option<ListIndexesOptions>,
+}
+
+impl ListIndexes {
+    pub(crate) fn new(ns: Namespace, options: Option<ListIndexesOptions>) -> Self {
+        Self { ns, options }
+    }
+}
+
+impl Operation for ListIndexes {
+    type O = CursorSpecification;
+    const NAME: &'static str = "listIndexes";
+
+    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+        let mut body = doc! {
+            Self::NAME: self.ns.coll.clone(),
+        };
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        let body: CursorBody = response.body()?;
+
+        Ok(CursorSpecification::new(
+            body.cursor.ns,
+            response.source_address().clone(),
+            body.cursor.id,
+            self.options.as_ref().and_then(|opts| opts.batch_size),
+            None,
+            body.cursor.first_batch,
+        ))
+    }
+
+    /// A collection that doesn't exist has no indexes, so an empty cursor is returned for it.
+    fn handle_error(&self, error: Error) -> Result<Self::O> {
+        if error.is_ns_not_found() {
+            Ok(CursorSpecification::new(
+                self.ns.clone(),
+                StreamAddress::default(),
+                0,
+                None,
+                None,
+                VecDeque::new(),
+            ))
+        } else {
+            Err(error)
+        }
+    }
+
+    fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)).as_ref()
+    }
+
+    fn retryability(&self) -> Retryability {
+        Retryability::Read
+    }
+}
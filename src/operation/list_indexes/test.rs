@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    error::{CommandError, ErrorKind},
+    operation::{ListIndexes, Operation},
+    options::ListIndexesOptions,
+    Namespace,
+};
+
+fn namespace() -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let options = ListIndexesOptions::builder()
+        .max_time(Duration::from_millis(100))
+        .batch_size(5)
+        .build();
+    let op = ListIndexes::new(namespace(), Some(options));
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "listIndexes");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "listIndexes": "test_coll",
+            "maxTimeMS": 100,
+            "cursor": { "batchSize": 5 },
+        }
+    );
+
+    let op = ListIndexes::new(namespace(), None);
+    let cmd = op.build(&description).expect("build should succeed");
+    assert_eq!(cmd.body, doc! { "listIndexes": "test_coll" });
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn op_selection_criteria() {
+    assert!(ListIndexes::new(namespace(), None)
+        .selection_criteria()
+        .expect("should have criteria")
+        .is_read_pref_primary());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let op = ListIndexes::new(namespace(), None);
+
+    let first_batch = vec![doc! { "v": 2, "key": { "_id": 1 }, "name": "_id_" }];
+    let response = CommandResponse::with_document(doc! {
+        "cursor": {
+            "id": 123,
+            "ns": "test_db.test_coll",
+            "firstBatch": first_batch.clone(),
+        },
+        "ok": 1.0,
+    });
+
+    let spec = op.handle_response(response).expect("handle should succeed");
+    assert_eq!(spec.id(), 123);
+    assert_eq!(Vec::from(spec.initial_buffer), first_batch);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_ns_not_found() {
+    let op = ListIndexes::new(namespace(), None);
+
+    let error = ErrorKind::CommandError(CommandError {
+        code: 26,
+        code_name: "NamespaceNotFound".to_string(),
+        message: "ns does not exist".to_string(),
+        labels: Default::default(),
+    })
+    .into();
+    let spec = op.handle_error(error).expect("handle should succeed");
+    assert_eq!(spec.id(), 0);
+    assert!(spec.initial_buffer.is_empty());
+}
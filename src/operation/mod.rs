@@ -1,27 +1,43 @@
+mod abort_transaction;
 mod aggregate;
+mod bulk_write;
+mod commit_transaction;
 mod count;
 mod count_documents;
 mod create;
+mod create_indexes;
 mod delete;
 mod distinct;
 mod drop_collection;
 mod drop_database;
+mod drop_indexes;
+mod explain;
 mod find;
 mod find_and_modify;
+mod get_default_rw_concern;
 mod get_more;
 mod insert;
 mod list_collections;
 mod list_databases;
+mod list_indexes;
 mod run_command;
+mod run_cursor_command;
 mod update;
+mod validate;
 
-use std::{collections::VecDeque, fmt::Debug, ops::Deref};
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    ops::Deref,
+    time::{Duration, Instant},
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
     bson::{self, Bson, Document},
-    cmap::{Command, CommandResponse, StreamDescription},
+    bson_util,
+    cmap::{Command, CommandResponse, PinnedConnection, StreamDescription},
     error::{
         BulkWriteError,
         BulkWriteFailure,
@@ -31,27 +47,37 @@ use crate::{
         WriteConcernError,
         WriteFailure,
     },
-    options::WriteConcern,
+    options::{ReadConcern, WriteConcern},
     selection_criteria::SelectionCriteria,
     Namespace,
 };
 
-pub(crate) use aggregate::Aggregate;
+pub(crate) use abort_transaction::AbortTransaction;
+pub(crate) use aggregate::{Aggregate, AggregateTarget};
+pub(crate) use bulk_write::{BulkWrite, BulkWriteKind};
+pub(crate) use commit_transaction::CommitTransaction;
 pub(crate) use count::Count;
 pub(crate) use count_documents::CountDocuments;
 pub(crate) use create::Create;
+pub(crate) use create_indexes::CreateIndexes;
 pub(crate) use delete::Delete;
 pub(crate) use distinct::Distinct;
 pub(crate) use drop_collection::DropCollection;
 pub(crate) use drop_database::DropDatabase;
+pub(crate) use drop_indexes::DropIndexes;
+pub(crate) use explain::Explain;
 pub(crate) use find::Find;
 pub(crate) use find_and_modify::FindAndModify;
+pub(crate) use get_default_rw_concern::GetDefaultRwConcern;
 pub(crate) use get_more::GetMore;
 pub(crate) use insert::Insert;
 pub(crate) use list_collections::ListCollections;
 pub(crate) use list_databases::ListDatabases;
+pub(crate) use list_indexes::ListIndexes;
 pub(crate) use run_command::RunCommand;
+pub(crate) use run_cursor_command::RunCursorCommand;
 pub(crate) use update::Update;
+pub(crate) use validate::Validate;
 
 /// A trait modeling the behavior of a server side operation.
 pub(crate) trait Operation {
@@ -95,10 +121,64 @@ pub(crate) trait Operation {
         true
     }
 
+    /// Whether the command this operation sends accepts a read concern. Such commands are sent with
+    /// an `afterClusterTime` when executed in a causally consistent session.
+    fn supports_read_concern(&self) -> bool {
+        false
+    }
+
+    /// The read concern specified in this operation's options, if any.
+    fn read_concern(&self) -> Option<&ReadConcern> {
+        None
+    }
+
     /// The level of retryability the operation supports.
     fn retryability(&self) -> Retryability {
         Retryability::None
     }
+
+    /// The time limit for this operation, if one was specified in its options. If none was, the
+    /// client's `timeout` applies.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The time by which this operation must complete because it is part of a larger unit of work
+    /// that was started with a time limit, e.g. a getMore for a cursor. This takes precedence over
+    /// `timeout`.
+    fn timeout_deadline(&self) -> Option<Instant> {
+        None
+    }
+
+    /// The connection this operation must be executed on, if it continues the work of an earlier
+    /// operation that was pinned to a connection, e.g. a getMore for a cursor created through a
+    /// load balancer. Such operations skip server selection and are never retried.
+    fn pinned_connection(&self) -> Option<&PinnedConnection> {
+        None
+    }
+}
+
+/// Gets the number of documents at the front of `documents` that fit into a single write command
+/// given the server's limits on the number of writes in a batch, the size of a document and the
+/// size of a message. At least one document is always included so that a document that is too
+/// large is reported by the server.
+fn batch_len(documents: &[Document], description: &StreamDescription) -> usize {
+    let max_count = description.max_write_batch_size.max(1) as usize;
+    let max_size = description
+        .max_bson_object_size
+        .min(description.max_message_size_bytes)
+        .max(0) as usize;
+
+    let mut size = 0;
+    for (i, doc) in documents.iter().take(max_count).enumerate() {
+        // Each document is an element of an array, whose key is its index.
+        size += 1 + i.to_string().len() + 1 + bson_util::doc_size_bytes(doc);
+        if i > 0 && size > max_size {
+            return i;
+        }
+    }
+
+    documents.len().min(max_count)
 }
 
 /// Appends a serializable struct to the input document.
@@ -171,7 +251,7 @@ impl<T> WriteResponseBody<T> {
             Some(ref write_concern_error) => {
                 let mut write_concern_error = write_concern_error.clone();
                 if let Some(ref labels) = self.labels {
-                    write_concern_error.labels.append(&mut labels.clone());
+                    write_concern_error.labels.extend(labels.iter().cloned());
                 }
                 Some(write_concern_error)
             }
@@ -181,6 +261,7 @@ impl<T> WriteResponseBody<T> {
         let failure = BulkWriteFailure {
             write_errors: self.write_errors.clone(),
             write_concern_error,
+            ..BulkWriteFailure::new()
         };
 
         Err(ErrorKind::BulkWriteError(failure).into())
@@ -206,6 +287,8 @@ struct CursorInfo {
     ns: Namespace,
     #[serde(rename = "firstBatch")]
     first_batch: VecDeque<Document>,
+    #[serde(rename = "postBatchResumeToken")]
+    post_batch_resume_token: Option<Document>,
 }
 
 #[derive(Debug, PartialEq)]
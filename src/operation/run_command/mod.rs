@@ -5,7 +5,7 @@ use super::Operation;
 use crate::{
     bson::Document,
     client::SESSIONS_UNSUPPORTED_COMMANDS,
-    cmap::{Command, CommandResponse, StreamDescription},
+    cmap::{Command, CommandResponse, PinnedConnection, StreamDescription},
     error::{ErrorKind, Result},
     options::WriteConcern,
     selection_criteria::SelectionCriteria,
@@ -17,6 +17,7 @@ pub(crate) struct RunCommand {
     command: Document,
     selection_criteria: Option<SelectionCriteria>,
     write_concern: Option<WriteConcern>,
+    pinned_connection: Option<PinnedConnection>,
 }
 
 impl RunCommand {
@@ -35,9 +36,19 @@ impl RunCommand {
             command,
             selection_criteria,
             write_concern,
+            pinned_connection: None,
         })
     }
 
+    /// Runs the command on the given connection rather than on one checked out from a pool.
+    pub(crate) fn with_pinned_connection(
+        mut self,
+        pinned_connection: Option<PinnedConnection>,
+    ) -> Self {
+        self.pinned_connection = pinned_connection;
+        self
+    }
+
     fn command_name(&self) -> Option<&str> {
         self.command.keys().next().map(String::as_str)
     }
@@ -83,4 +94,8 @@ impl Operation for RunCommand {
             })
             .unwrap_or(false)
     }
+
+    fn pinned_connection(&self) -> Option<&PinnedConnection> {
+        self.pinned_connection.as_ref()
+    }
 }
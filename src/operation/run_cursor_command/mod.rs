@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod test;
+
+use super::{CursorBody, Operation, RunCommand};
+use crate::{
+    bson::Document,
+    cmap::{Command, CommandResponse, PinnedConnection, StreamDescription},
+    cursor::CursorSpecification,
+    error::Result,
+    options::{RunCursorCommandOptions, WriteConcern},
+    selection_criteria::SelectionCriteria,
+};
+
+/// Runs an arbitrary command that returns a cursor, e.g. `listCollections` or `listIndexes`.
+#[derive(Debug)]
+pub(crate) struct RunCursorCommand {
+    run_command: RunCommand,
+    options: Option<RunCursorCommandOptions>,
+}
+
+impl RunCursorCommand {
+    pub(crate) fn new(
+        db: String,
+        command: Document,
+        options: Option<RunCursorCommandOptions>,
+    ) -> Result<Self> {
+        let selection_criteria = options
+            .as_ref()
+            .and_then(|options| options.selection_criteria.clone());
+
+        Ok(Self {
+            run_command: RunCommand::new(db, command, selection_criteria)?,
+            options,
+        })
+    }
+}
+
+impl Operation for RunCursorCommand {
+    type O = CursorSpecification;
+
+    const NAME: &'static str = RunCommand::NAME;
+
+    fn build(&self, description: &StreamDescription) -> Result<Command> {
+        self.run_command.build(description)
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        let body: CursorBody = response.body()?;
+
+        Ok(CursorSpecification::new(
+            body.cursor.ns,
+            response.source_address().clone(),
+            body.cursor.id,
+            self.options.as_ref().and_then(|opts| opts.batch_size),
+            None,
+            body.cursor.first_batch,
+        ))
+    }
+
+    fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        self.run_command.selection_criteria()
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.run_command.write_concern()
+    }
+
+    fn supports_sessions(&self) -> bool {
+        self.run_command.supports_sessions()
+    }
+
+    fn pinned_connection(&self) -> Option<&PinnedConnection> {
+        self.run_command.pinned_connection()
+    }
+}
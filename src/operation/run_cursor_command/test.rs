@@ -0,0 +1,85 @@
+use super::RunCursorCommand;
+use crate::{
+    bson::{doc, Document},
+    cmap::{CommandResponse, StreamDescription},
+    operation::Operation,
+    options::{ReadPreference, RunCursorCommandOptions, SelectionCriteria, StreamAddress},
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let options = RunCursorCommandOptions::builder()
+        .selection_criteria(SelectionCriteria::ReadPreference(
+            ReadPreference::Secondary {
+                options: Default::default(),
+            },
+        ))
+        .build();
+    let op = RunCursorCommand::new(
+        "foo".into(),
+        doc! { "listCollections": 1, "cursor": { "batchSize": 2 } },
+        Some(options),
+    )
+    .unwrap();
+    assert!(matches!(
+        op.selection_criteria(),
+        Some(SelectionCriteria::ReadPreference(
+            ReadPreference::Secondary { .. }
+        ))
+    ));
+
+    let command = op.build(&StreamDescription::new_testing()).unwrap();
+    assert_eq!(command.name, "listCollections");
+    assert_eq!(command.target_db, "foo");
+    assert_eq!(
+        command.body,
+        doc! { "listCollections": 1, "cursor": { "batchSize": 2 } }
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let op = RunCursorCommand::new(
+        "foo".into(),
+        doc! { "listIndexes": "bar" },
+        Some(RunCursorCommandOptions::builder().batch_size(5).build()),
+    )
+    .unwrap();
+
+    let address = StreamAddress {
+        hostname: "localhost".to_string(),
+        port: Some(27018),
+    };
+    let response = CommandResponse::with_document_and_address(
+        address.clone(),
+        doc! {
+            "ok": 1,
+            "cursor": {
+                "id": 123_i64,
+                "ns": "foo.bar",
+                "firstBatch": [{ "name": "_id_" }],
+            },
+        },
+    );
+
+    let spec = op.handle_response(response).unwrap();
+    assert_eq!(spec.id(), 123);
+    assert_eq!(spec.address(), &address);
+    assert_eq!(spec.batch_size(), Some(5));
+    assert_eq!(spec.info.ns.to_string(), "foo.bar");
+    assert_eq!(
+        spec.initial_buffer.into_iter().collect::<Vec<Document>>(),
+        vec![doc! { "name": "_id_" }]
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_response_without_cursor() {
+    let op = RunCursorCommand::new("foo".into(), doc! { "ping": 1 }, None).unwrap();
+
+    let response = CommandResponse::with_document(doc! { "ok": 1 });
+    assert!(op.handle_response(response).is_err());
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use serde::Deserialize;
 
 use crate::{
@@ -86,6 +88,10 @@ impl Operation for Update {
                 update.insert("collation", bson::to_bson(collation)?);
             }
 
+            if let Some(ref comment) = options.comment {
+                body.insert("comment", comment.clone());
+            }
+
             if let Some(bypass_doc_validation) = options.bypass_document_validation {
                 body.insert("bypassDocumentValidation", bypass_doc_validation);
             }
@@ -143,6 +149,10 @@ impl Operation for Update {
             Retryability::None
         }
     }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
 }
 
 #[derive(Deserialize)]
@@ -28,6 +28,7 @@ async fn build() {
     let options = UpdateOptions {
         upsert: Some(false),
         bypass_document_validation: Some(true),
+        comment: Some(Bson::Document(doc! { "source": "build" })),
         write_concern: Some(wc),
         ..Default::default()
     };
@@ -54,6 +55,7 @@ async fn build() {
             "w": "majority"
         },
         "bypassDocumentValidation": true,
+        "comment": { "source": "build" },
         "ordered": true,
     };
 
@@ -277,7 +279,7 @@ async fn handle_write_concern_failure() {
                     "wtimeout": 0,
                     "provenance": "clientSupplied"
                 } }),
-                labels: Vec::new(),
+                labels: Default::default(),
             };
             assert_eq!(wc_error, &expected_wc_err);
         }
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    bson::doc,
+    cmap::{Command, CommandResponse, StreamDescription},
+    error::Result,
+    operation::{append_options, Operation},
+    options::ValidateOptions,
+    results::ValidationResult,
+    Namespace,
+};
+
+#[derive(Debug)]
+pub(crate) struct Validate {
+    ns: Namespace,
+    options: Option<ValidateOptions>,
+}
+
+impl Validate {
+    pub(crate) fn new(ns: Namespace, options: Option<ValidateOptions>) -> Self {
+        Validate { ns, options }
+    }
+
+    #[cfg(test)]
+    fn empty() -> Self {
+        Self::new(Namespace::empty(), None)
+    }
+}
+
+impl Operation for Validate {
+    type O = ValidationResult;
+    const NAME: &'static str = "validate";
+
+    fn build(&self, _description: &StreamDescription) -> Result<Command> {
+        let mut body = doc! {
+            Self::NAME: self.ns.coll.clone(),
+        };
+
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(&self, response: CommandResponse) -> Result<Self::O> {
+        response.body()
+    }
+}
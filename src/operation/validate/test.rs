@@ -0,0 +1,66 @@
+use crate::{
+    bson::doc,
+    cmap::{CommandResponse, StreamDescription},
+    operation::{Operation, Validate},
+    options::ValidateOptions,
+    Namespace,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn build() {
+    let ns = Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    };
+    let options = ValidateOptions::builder().full(true).build();
+
+    let op = Validate::new(ns.clone(), Some(options));
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "validate");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(cmd.read_pref.as_ref(), None);
+    assert_eq!(cmd.body, doc! { "validate": "test_coll", "full": true });
+
+    let op = Validate::new(ns, None);
+    let cmd = op.build(&description).expect("build should succeed");
+    assert_eq!(cmd.body, doc! { "validate": "test_coll" });
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn handle_success() {
+    let op = Validate::empty();
+
+    let response = CommandResponse::with_document(doc! {
+        "ns": "test_db.test_coll",
+        "nInvalidDocuments": 0,
+        "nrecords": 12_i64,
+        "nIndexes": 2,
+        "keysPerIndex": { "_id_": 12, "x_1": 12 },
+        "valid": false,
+        "warnings": ["some warning"],
+        "errors": ["some error"],
+        "ok": 1.0,
+    });
+
+    let result = op.handle_response(response).expect("handle should succeed");
+    assert!(!result.valid);
+    assert_eq!(result.warnings, vec!["some warning".to_string()]);
+    assert_eq!(result.errors, vec!["some error".to_string()]);
+    assert_eq!(result.n_records, Some(12));
+    assert_eq!(result.n_indexes, Some(2));
+    assert_eq!(result.n_invalid_documents, Some(0));
+    assert_eq!(result.keys_per_index.get("x_1"), Some(&12));
+    assert_eq!(result.repaired, None);
+
+    // Older servers omit most of the counts.
+    let response = CommandResponse::with_document(doc! { "valid": true, "ok": 1.0 });
+    let result = op.handle_response(response).expect("handle should succeed");
+    assert!(result.valid);
+    assert!(result.warnings.is_empty());
+    assert!(result.errors.is_empty());
+    assert_eq!(result.n_records, None);
+}
@@ -16,14 +16,20 @@
 //! ```
 
 pub use crate::{
+    change_stream::options::*,
     client::{auth::*, options::*},
     coll::options::*,
     collation::*,
     concern::*,
     db::options::*,
+    gridfs::options::*,
+    index::options::*,
     selection_criteria::*,
 };
 
+#[cfg(any(feature = "csfle", docsrs))]
+pub use crate::client_encryption::options::*;
+
 /// Updates an options struct with the read preference/read concern/write concern of a
 /// client/database/collection.
 macro_rules! resolve_options {
@@ -2,9 +2,12 @@
 
 use std::collections::{HashMap, VecDeque};
 
-use crate::bson::{Bson, Document};
+use crate::{
+    bson::{Bson, Document},
+    concern::{ReadConcern, WriteConcern},
+};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// The result of a [`Collection::insert_one`](../struct.Collection.html#method.insert_one)
 /// operation.
@@ -32,12 +35,19 @@ impl InsertOneResult {
 pub struct InsertManyResult {
     /// The `_id` field of the documents inserted.
     pub inserted_ids: HashMap<usize, Bson>,
+
+    /// The documents inserted, as read back from the server after the insert. This is only
+    /// populated if `return_documents` was set in the
+    /// [`InsertManyOptions`](../options/struct.InsertManyOptions.html) for the operation. Like
+    /// `inserted_ids`, the documents are keyed by the index of the document in the input.
+    pub inserted_documents: Option<HashMap<usize, Document>>,
 }
 
 impl InsertManyResult {
     pub(crate) fn new() -> Self {
         InsertManyResult {
             inserted_ids: HashMap::new(),
+            inserted_documents: None,
         }
     }
 }
@@ -66,8 +76,218 @@ pub struct DeleteResult {
     pub deleted_count: i64,
 }
 
+/// The result of a [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write)
+/// operation.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BulkWriteResult {
+    /// The number of documents inserted by `InsertOne` writes.
+    pub inserted_count: i64,
+
+    /// The number of documents that matched the filters of update and replace writes.
+    pub matched_count: i64,
+
+    /// The number of documents modified by update and replace writes.
+    pub modified_count: i64,
+
+    /// The number of documents deleted by `DeleteOne` and `DeleteMany` writes.
+    pub deleted_count: i64,
+
+    /// The number of documents upserted by update and replace writes.
+    pub upserted_count: i64,
+
+    /// The `_id` field of the upserted documents, keyed by the index of the write in the input.
+    pub upserted_ids: HashMap<usize, Bson>,
+}
+
+impl BulkWriteResult {
+    /// Adds the counts of `other`, whose indexes start at `offset` in the input, to this result.
+    pub(crate) fn merge(&mut self, other: BulkWriteResult, offset: usize) {
+        self.inserted_count += other.inserted_count;
+        self.matched_count += other.matched_count;
+        self.modified_count += other.modified_count;
+        self.deleted_count += other.deleted_count;
+        self.upserted_count += other.upserted_count;
+        self.upserted_ids.extend(
+            other
+                .upserted_ids
+                .into_iter()
+                .map(|(index, id)| (index + offset, id)),
+        );
+    }
+}
+
+/// The result of a [`Collection::create_index`](../struct.Collection.html#method.create_index)
+/// operation.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CreateIndexResult {
+    /// The name of the index created.
+    pub index_name: String,
+}
+
+/// The result of a
+/// [`Collection::create_indexes`](../struct.Collection.html#method.create_indexes) operation.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CreateIndexesResult {
+    /// The names of the indexes created, in the order the indexes were specified.
+    pub index_names: Vec<String>,
+}
+
+impl CreateIndexesResult {
+    pub(crate) fn into_create_index_result(mut self) -> CreateIndexResult {
+        CreateIndexResult {
+            index_name: self.index_names.remove(0),
+        }
+    }
+}
+
+/// The result of a [`Collection::validate`](../struct.Collection.html#method.validate) operation.
+///
+/// See the documentation [here](https://docs.mongodb.com/manual/reference/command/validate/#output)
+/// for more information on the output of the `validate` command. Which of the counts are reported
+/// depends on the server version and on whether a full validation was requested.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ValidationResult {
+    /// Whether the collection was found to be valid.
+    pub valid: bool,
+
+    /// Issues found during validation that do not make the collection invalid.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+
+    /// The problems that made the collection invalid, if any.
+    #[serde(default)]
+    pub errors: Vec<String>,
+
+    /// The number of documents in the collection.
+    #[serde(rename = "nrecords")]
+    pub n_records: Option<i64>,
+
+    /// The number of indexes on the collection.
+    pub n_indexes: Option<i64>,
+
+    /// The number of documents in the collection that are invalid.
+    pub n_invalid_documents: Option<i64>,
+
+    /// The number of keys in each index, keyed by index name.
+    #[serde(default)]
+    pub keys_per_index: HashMap<String, i64>,
+
+    /// Whether the collection was repaired. Only reported when a repair was requested.
+    pub repaired: Option<bool>,
+}
+
+/// The latency statistics of a collection, as returned by
+/// [`Collection::latency_stats`](../struct.Collection.html#method.latency_stats).
+///
+/// All latencies are in microseconds. See the documentation
+/// [here](https://docs.mongodb.com/manual/reference/operator/aggregation/collStats/#latencystats-document)
+/// for more information on how the server tracks latencies.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct LatencyStats {
+    /// The latency of read operations.
+    pub reads: OperationLatency,
+
+    /// The latency of write operations.
+    pub writes: OperationLatency,
+
+    /// The latency of database commands other than reads and writes.
+    pub commands: OperationLatency,
+
+    /// The latency of transactions. This is only reported by MongoDB 4.0 or greater.
+    #[serde(default)]
+    pub transactions: OperationLatency,
+}
+
+impl LatencyStats {
+    /// Adds the statistics reported by another shard to these ones.
+    pub(crate) fn merge(&mut self, other: LatencyStats) {
+        self.reads.merge(other.reads);
+        self.writes.merge(other.writes);
+        self.commands.merge(other.commands);
+        self.transactions.merge(other.transactions);
+    }
+}
+
+/// The latency statistics of one category of operation.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct OperationLatency {
+    /// The total latency of all operations in this category.
+    pub latency: i64,
+
+    /// The number of operations in this category.
+    pub ops: i64,
+
+    /// The distribution of the operations' latencies, ordered by ascending latency. Buckets that
+    /// are empty are omitted.
+    #[serde(default)]
+    pub histogram: Vec<LatencyHistogramBucket>,
+}
+
+impl OperationLatency {
+    fn merge(&mut self, other: OperationLatency) {
+        self.latency += other.latency;
+        self.ops += other.ops;
+
+        for bucket in other.histogram {
+            match self
+                .histogram
+                .binary_search_by_key(&bucket.micros, |b| b.micros)
+            {
+                Ok(i) => self.histogram[i].count += bucket.count,
+                Err(i) => self.histogram.insert(i, bucket),
+            }
+        }
+    }
+}
+
+/// A bucket of a latency histogram.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct LatencyHistogramBucket {
+    /// The inclusive lower bound of the latencies of the operations in this bucket.
+    pub micros: i64,
+
+    /// The number of operations in this bucket.
+    pub count: i64,
+}
+
+/// The result of a
+/// [`Client::server_default_rw_concern`](../struct.Client.html#method.server_default_rw_concern)
+/// operation.
+///
+/// These are the concerns that the server applies to operations that don't specify a read or
+/// write concern of their own.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct DefaultRwConcern {
+    /// The default read concern, if the server reported one.
+    pub read_concern: Option<ReadConcern>,
+
+    /// Whether the default read concern was set explicitly with `setDefaultRWConcern` rather than
+    /// being the server's implicit default.
+    pub read_concern_explicitly_set: bool,
+
+    /// The default write concern, if the server reported one.
+    pub write_concern: Option<WriteConcern>,
+
+    /// Whether the default write concern was set explicitly with `setDefaultRWConcern` rather
+    /// than being the server's implicit default.
+    pub write_concern_explicitly_set: bool,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct GetMoreResult {
     pub(crate) batch: VecDeque<Document>,
     pub(crate) exhausted: bool,
+    pub(crate) post_batch_resume_token: Option<Document>,
 }
@@ -25,6 +25,7 @@ pub enum ServerType {
     RSArbiter,
     RSOther,
     RSGhost,
+    LoadBalancer,
     Unknown,
 }
 
@@ -36,6 +37,7 @@ impl ServerType {
                 | ServerType::RSPrimary
                 | ServerType::RSSecondary
                 | ServerType::Mongos
+                | ServerType::LoadBalancer
         )
     }
 
@@ -46,6 +48,7 @@ impl ServerType {
                 | ServerType::RSPrimary
                 | ServerType::RSSecondary
                 | ServerType::Mongos
+                | ServerType::LoadBalancer
         )
     }
 }
@@ -273,7 +276,6 @@ impl ServerDescription {
         Ok(me)
     }
 
-    #[cfg(test)]
     pub(crate) fn max_wire_version(&self) -> Result<Option<i32>> {
         let me = self
             .reply
@@ -321,9 +323,10 @@ impl ServerDescription {
             _ => return false,
         };
 
+        // An empty tag set matches every server, including those that don't report any tags.
         let server_tags = match reply.command_response.tags {
             Some(ref tags) => tags,
-            None => return false,
+            None => return tag_set.is_empty(),
         };
 
         tag_set
@@ -16,18 +16,37 @@ use crate::{
     error::{ErrorKind, Result},
     options::{ClientOptions, StreamAddress},
     sdam::description::server::{ServerDescription, ServerType},
-    selection_criteria::{ReadPreference, SelectionCriteria},
+    selection_criteria::{ReadPreference, SelectionCriteria, MIN_MAX_STALENESS},
 };
 
 const DEFAULT_HEARTBEAT_FREQUENCY: Duration = Duration::from_secs(10);
 
-/// The TopologyType type, as described by the SDAM spec.
+/// How often a primary writes a no-op to the oplog when there are no other writes, which bounds
+/// how far a secondary's last write date lags behind when it is fully caught up.
+const IDLE_WRITE_PERIOD: Duration = Duration::from_secs(10);
+
+const DEFAULT_LOGICAL_SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The possible types for a topology.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
-pub(crate) enum TopologyType {
+#[non_exhaustive]
+pub enum TopologyType {
+    /// A single mongod server.
     Single,
+
+    /// A replica set with no primary.
     ReplicaSetNoPrimary,
+
+    /// A replica set with a primary.
     ReplicaSetWithPrimary,
+
+    /// A sharded topology.
     Sharded,
+
+    /// A load balancer fronting a set of mongos servers.
+    LoadBalanced,
+
+    /// A topology whose type is not known.
     Unknown,
 }
 
@@ -118,9 +137,16 @@ impl TopologyDescription {
                 .selection_criteria
                 .as_ref()
                 .and_then(|criteria| criteria.max_staleness()),
+            options
+                .heartbeat_freq
+                .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY),
         )?;
 
-        let topology_type = if let Some(true) = options.direct_connection {
+        let load_balanced = options.load_balanced == Some(true);
+
+        let topology_type = if load_balanced {
+            TopologyType::LoadBalanced
+        } else if let Some(true) = options.direct_connection {
             TopologyType::Single
         } else if options.repl_set_name.is_some() {
             TopologyType::ReplicaSetNoPrimary
@@ -132,12 +158,28 @@ impl TopologyDescription {
             .hosts
             .into_iter()
             .map(|address| {
-                let description = ServerDescription::new(address.clone(), None);
+                let mut description = ServerDescription::new(address.clone(), None);
+
+                // A load balancer is never monitored, so it is assumed to be available from the
+                // start.
+                if load_balanced {
+                    description.server_type = ServerType::LoadBalancer;
+                }
 
                 (address, description)
             })
             .collect();
 
+        // Since the load balancer is never checked, sessions are assumed to be supported with the
+        // server's default timeout.
+        let session_support_status = if load_balanced {
+            SessionSupportStatus::Supported {
+                logical_session_timeout: DEFAULT_LOGICAL_SESSION_TIMEOUT,
+            }
+        } else {
+            SessionSupportStatus::Undetermined
+        };
+
         Ok(Self {
             single_seed: servers.len() == 1,
             topology_type,
@@ -145,7 +187,7 @@ impl TopologyDescription {
             max_set_version: None,
             max_election_id: None,
             compatibility_error: None,
-            session_support_status: SessionSupportStatus::Undetermined,
+            session_support_status,
             cluster_time: None,
             local_threshold: options.local_threshold,
             heartbeat_freq: options.heartbeat_freq,
@@ -158,10 +200,21 @@ impl TopologyDescription {
         self.topology_type
     }
 
+    /// Gets the replica set name of the topology, if any.
+    pub(crate) fn set_name(&self) -> Option<&str> {
+        self.set_name.as_deref()
+    }
+
     pub(crate) fn server_addresses(&self) -> impl Iterator<Item = &StreamAddress> {
         self.servers.keys()
     }
 
+    pub(crate) fn server_descriptions(
+        &self,
+    ) -> impl Iterator<Item = (&StreamAddress, &ServerDescription)> {
+        self.servers.iter()
+    }
+
     pub(crate) fn cluster_time(&self) -> Option<&ClusterTime> {
         self.cluster_time.as_ref()
     }
@@ -181,7 +234,8 @@ impl TopologyDescription {
     ) {
         match (self.topology_type, server_type) {
             (TopologyType::Sharded, ServerType::Mongos)
-            | (TopologyType::Single, ServerType::Mongos) => {
+            | (TopologyType::Single, ServerType::Mongos)
+            | (TopologyType::LoadBalanced, _) => {
                 self.update_command_read_pref_for_mongos(command, criteria);
             }
             (TopologyType::Single, ServerType::Standalone) => {}
@@ -194,14 +248,14 @@ impl TopologyDescription {
                     Some(ReadPreference::Primary) | None => ReadPreference::PrimaryPreferred {
                         options: Default::default(),
                     },
-                    Some(other) => other,
+                    Some(other) => other.without_hedge(),
                 };
 
                 command.read_pref = Some(resolved_read_pref);
             }
             _ => {
                 command.read_pref = match criteria {
-                    Some(SelectionCriteria::ReadPreference(rp)) => Some(rp.clone()),
+                    Some(SelectionCriteria::ReadPreference(rp)) => Some(rp.clone().without_hedge()),
                     Some(SelectionCriteria::Predicate(_)) => {
                         Some(ReadPreference::PrimaryPreferred {
                             options: Default::default(),
@@ -233,7 +287,10 @@ impl TopologyDescription {
             }
             Some(SelectionCriteria::ReadPreference(ReadPreference::SecondaryPreferred {
                 ref options,
-            })) if options.max_staleness.is_some() || options.tag_sets.is_some() => {
+            })) if options.effective_max_staleness().is_some()
+                || options.tag_sets.is_some()
+                || options.hedge.is_some() =>
+            {
                 command.read_pref = Some(ReadPreference::SecondaryPreferred {
                     options: options.clone(),
                 });
@@ -422,7 +479,7 @@ impl TopologyDescription {
 
         // Update the topology description based on the current topology type.
         match self.topology_type {
-            TopologyType::Single => {}
+            TopologyType::Single | TopologyType::LoadBalanced => {}
             TopologyType::Unknown => self.update_unknown_topology(server_description)?,
             TopologyType::Sharded => self.update_sharded_topology(server_description),
             TopologyType::ReplicaSetNoPrimary => {
@@ -442,7 +499,8 @@ impl TopologyDescription {
     /// Update the Unknown topology description based on the server description.
     fn update_unknown_topology(&mut self, server_description: ServerDescription) -> Result<()> {
         match server_description.server_type {
-            ServerType::Unknown | ServerType::RSGhost => {}
+            // Load balancers are only ever part of LoadBalanced topologies, which are not updated.
+            ServerType::Unknown | ServerType::RSGhost | ServerType::LoadBalancer => {}
             ServerType::Standalone => {
                 self.update_unknown_with_standalone_server(server_description)
             }
@@ -477,7 +535,7 @@ impl TopologyDescription {
     ) -> Result<()> {
         match server_description.server_type {
             ServerType::Unknown | ServerType::RSGhost => {}
-            ServerType::Standalone | ServerType::Mongos => {
+            ServerType::Standalone | ServerType::Mongos | ServerType::LoadBalancer => {
                 self.servers.remove(&server_description.address);
             }
             ServerType::RSPrimary => {
@@ -501,7 +559,7 @@ impl TopologyDescription {
             ServerType::Unknown | ServerType::RSGhost => {
                 self.record_primary_state();
             }
-            ServerType::Standalone | ServerType::Mongos => {
+            ServerType::Standalone | ServerType::Mongos | ServerType::LoadBalancer => {
                 self.servers.remove(&server_description.address);
                 self.record_primary_state();
             }
@@ -732,13 +790,37 @@ pub(crate) struct TopologyDescriptionDiff {
     pub(crate) new_addresses: HashSet<StreamAddress>,
 }
 
-fn verify_max_staleness(max_staleness: Option<Duration>) -> Result<()> {
-    if max_staleness
-        .map(|staleness| staleness > Duration::from_secs(0) && staleness < Duration::from_secs(90))
-        .unwrap_or(false)
-    {
+/// Checks that `max_staleness`, if any, is at least 90 seconds and leaves room for a heartbeat
+/// plus the primary's idle write period so that secondaries aren't spuriously considered stale.
+fn verify_max_staleness(
+    max_staleness: Option<Duration>,
+    heartbeat_frequency: Duration,
+) -> Result<()> {
+    let max_staleness = match max_staleness {
+        Some(max_staleness) => max_staleness,
+        None => return Ok(()),
+    };
+
+    if max_staleness < MIN_MAX_STALENESS {
+        return Err(ErrorKind::ArgumentError {
+            message: format!(
+                "max staleness must be at least {} seconds, got {:?}",
+                MIN_MAX_STALENESS.as_secs(),
+                max_staleness
+            ),
+        }
+        .into());
+    }
+
+    if max_staleness < heartbeat_frequency + IDLE_WRITE_PERIOD {
         return Err(ErrorKind::ArgumentError {
-            message: "max staleness cannot be both positive and below 90 seconds".into(),
+            message: format!(
+                "max staleness must be at least the heartbeat frequency plus {} seconds, got {:?} \
+                 with a heartbeat frequency of {:?}",
+                IDLE_WRITE_PERIOD.as_secs(),
+                max_staleness,
+                heartbeat_frequency
+            ),
         }
         .into());
     }
@@ -8,6 +8,7 @@ use rand::seq::IteratorRandom;
 use super::TopologyDescription;
 use crate::{
     error::{ErrorKind, Result},
+    options::StreamAddress,
     sdam::{
         description::{
             server::{ServerDescription, ServerType},
@@ -26,10 +27,20 @@ impl TopologyDescription {
         criteria: &SelectionCriteria,
     ) -> String {
         if self.has_available_servers() {
+            let is_replica_set = matches!(
+                self.topology_type,
+                TopologyType::ReplicaSetWithPrimary | TopologyType::ReplicaSetNoPrimary
+            );
+            let staleness = match criteria.max_staleness() {
+                Some(max_staleness) if is_replica_set => {
+                    self.secondary_staleness_message(max_staleness)
+                }
+                _ => String::new(),
+            };
             format!(
                 "Server selection timeout: None of the available servers suitable for criteria \
-                 {:?}. Topology: {}",
-                criteria, self
+                 {:?}.{} Topology: {}",
+                criteria, staleness, self
             )
         } else {
             format!(
@@ -39,9 +50,49 @@ impl TopologyDescription {
         }
     }
 
+    /// Returns an error message if the topology is a replica set that is known to have no primary,
+    /// i.e. at least one of its members has been successfully checked but none of them is the
+    /// primary. This is the case during an election, for example.
+    pub(crate) fn no_primary_error_message(&self) -> Option<String> {
+        if self.topology_type == TopologyType::ReplicaSetNoPrimary && self.has_available_servers()
+        {
+            Some(format!(
+                "Server selection failed: No primary is available and fail fast on no primary is \
+                 enabled. Topology: {}",
+                self
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Describes the computed staleness of each secondary, for explaining why no secondary is
+    /// suitable for a read preference with the given max staleness.
+    fn secondary_staleness_message(&self, max_staleness: Duration) -> String {
+        let mut stalenesses: Vec<String> = self
+            .servers_with_type(&[ServerType::RSSecondary])
+            .map(|server| match self.calculate_secondary_staleness(server) {
+                Some(staleness) => {
+                    format!("{}: {}s", server.address, staleness as f64 / 1000.0)
+                }
+                None => format!("{}: unknown", server.address),
+            })
+            .collect();
+        stalenesses.sort();
+
+        format!(
+            " Staleness of secondaries (max staleness {}s): [{}].",
+            max_staleness.as_secs(),
+            stalenesses.join(", ")
+        )
+    }
+
+    /// Selects a server suitable for `criteria`. If `deprioritized` is given, that server is only
+    /// selected if no other server is suitable, e.g. when retrying an operation that failed on it.
     pub(crate) fn select_server<'a>(
         &'a self,
         criteria: &'a SelectionCriteria,
+        deprioritized: Option<&StreamAddress>,
     ) -> Result<Option<&'a ServerDescription>> {
         if let Some(message) = self.compatibility_error() {
             return Err(ErrorKind::ServerSelectionError {
@@ -56,9 +107,9 @@ impl TopologyDescription {
             return Ok(None);
         }
 
-        // Similarly, if the topology type is Single, we skip the below logic as well and just
-        // return the only server in the topology.
-        if let TopologyType::Single = self.topology_type {
+        // Similarly, if the topology type is Single or LoadBalanced, we skip the below logic as well
+        // and just return the only server in the topology.
+        if let TopologyType::Single | TopologyType::LoadBalanced = self.topology_type {
             return Ok(self.servers.values().next());
         }
 
@@ -71,6 +122,15 @@ impl TopologyDescription {
                 .collect(),
         };
 
+        if let Some(deprioritized) = deprioritized {
+            if suitable_servers
+                .iter()
+                .any(|server| &server.address != deprioritized)
+            {
+                suitable_servers.retain(|server| &server.address != deprioritized);
+            }
+        }
+
         // If the read preference is primary, we skip the overhead of calculating the latency window
         // because we know that there's only one server selected.
         if !criteria.is_read_pref_primary() {
@@ -91,7 +151,7 @@ impl TopologyDescription {
     ) -> Result<Vec<&'a ServerDescription>> {
         let servers = match self.topology_type {
             TopologyType::Unknown => Vec::new(),
-            TopologyType::Single => self.servers.values().collect(),
+            TopologyType::Single | TopologyType::LoadBalanced => self.servers.values().collect(),
             TopologyType::Sharded => self.servers_with_type(&[ServerType::Mongos]).collect(),
             TopologyType::ReplicaSetWithPrimary | TopologyType::ReplicaSetNoPrimary => {
                 self.suitable_servers_in_replica_set(read_preference)?
@@ -147,7 +207,7 @@ impl TopologyDescription {
                 .suitable_servers_for_read_preference(
                     &[ServerType::RSSecondary],
                     options.tag_sets.as_ref(),
-                    options.max_staleness,
+                    options.effective_max_staleness(),
                 )?,
             ReadPreference::PrimaryPreferred { ref options } => {
                 match self.servers_with_type(&[ServerType::RSPrimary]).next() {
@@ -155,7 +215,7 @@ impl TopologyDescription {
                     None => self.suitable_servers_for_read_preference(
                         &[ServerType::RSSecondary],
                         options.tag_sets.as_ref(),
-                        options.max_staleness,
+                        options.effective_max_staleness(),
                     )?,
                 }
             }
@@ -163,7 +223,7 @@ impl TopologyDescription {
                 let suitable_servers = self.suitable_servers_for_read_preference(
                     &[ServerType::RSSecondary],
                     options.tag_sets.as_ref(),
-                    options.max_staleness,
+                    options.effective_max_staleness(),
                 )?;
 
                 if suitable_servers.is_empty() {
@@ -175,7 +235,7 @@ impl TopologyDescription {
             ReadPreference::Nearest { ref options } => self.suitable_servers_for_read_preference(
                 &[ServerType::RSPrimary, ServerType::RSSecondary],
                 options.tag_sets.as_ref(),
-                options.max_staleness,
+                options.effective_max_staleness(),
             )?,
         };
 
@@ -188,17 +248,14 @@ impl TopologyDescription {
         tag_sets: Option<&'a Vec<TagSet>>,
         max_staleness: Option<Duration>,
     ) -> Result<Vec<&'a ServerDescription>> {
-        super::verify_max_staleness(max_staleness)?;
+        super::verify_max_staleness(max_staleness, self.heartbeat_frequency())?;
 
         let mut servers = self.servers_with_type(types).collect();
 
         // We don't need to check for the Client's default max_staleness because it would be passed
         // in as part of the Client's default ReadPreference if none is specified for the operation.
         if let Some(max_staleness) = max_staleness {
-            // According to the spec, max staleness <= 0 is the same as no max staleness.
-            if max_staleness > Duration::from_secs(0) {
-                self.filter_servers_by_max_staleness(&mut servers, max_staleness);
-            }
+            self.filter_servers_by_max_staleness(&mut servers, max_staleness);
         }
 
         if let Some(tag_sets) = tag_sets {
@@ -249,20 +306,7 @@ impl TopologyDescription {
         max_staleness: Duration,
     ) {
         let max_staleness = max_staleness.as_millis() as i64;
-        let max_write_date = self
-            .servers
-            .values()
-            .filter(|server| server.server_type == ServerType::RSSecondary)
-            .filter_map(|server| {
-                server
-                    .last_write_date()
-                    .ok()
-                    .and_then(std::convert::identity)
-            })
-            .map(|last_write_date| last_write_date.timestamp_millis())
-            .max();
-
-        let secondary_max_write_date = match max_write_date {
+        let secondary_max_write_date = match self.secondary_max_write_date() {
             Some(max_write_date) => max_write_date,
             None => return,
         };
@@ -277,6 +321,35 @@ impl TopologyDescription {
         })
     }
 
+    /// The most recent last write date of any secondary, in milliseconds.
+    fn secondary_max_write_date(&self) -> Option<i64> {
+        self.servers_with_type(&[ServerType::RSSecondary])
+            .filter_map(|server| {
+                server
+                    .last_write_date()
+                    .ok()
+                    .and_then(std::convert::identity)
+            })
+            .map(|last_write_date| last_write_date.timestamp_millis())
+            .max()
+    }
+
+    /// Calculates the staleness of `secondary` in milliseconds, using the primary if there is one.
+    fn calculate_secondary_staleness(&self, secondary: &ServerDescription) -> Option<i64> {
+        let primary = self
+            .servers
+            .values()
+            .find(|server| server.server_type == ServerType::RSPrimary);
+
+        match primary {
+            Some(primary) => self.calculate_secondary_staleness_with_primary(secondary, primary),
+            None => self.calculate_secondary_staleness_without_primary(
+                secondary,
+                self.secondary_max_write_date()?,
+            ),
+        }
+    }
+
     fn calculate_secondary_staleness_with_primary(
         &self,
         secondary: &ServerDescription,
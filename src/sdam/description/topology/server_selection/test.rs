@@ -1,17 +1,18 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use chrono::{naive::NaiveDateTime, offset::Utc, DateTime};
 use serde::Deserialize;
 
 use crate::{
     bson::DateTime as BsonDateTime,
+    error::{ErrorKind, Result},
     is_master::{IsMasterCommandResponse, IsMasterReply, LastWrite},
     options::StreamAddress,
     sdam::description::{
         server::{ServerDescription, ServerType},
         topology::{test::f64_ms_as_duration, TopologyDescription, TopologyType},
     },
-    selection_criteria::{ReadPreference, ReadPreferenceOptions, TagSet},
+    selection_criteria::{ReadPreference, ReadPreferenceOptions, SelectionCriteria, TagSet},
     test::run_spec_test,
 };
 
@@ -90,41 +91,25 @@ pub struct TestReadPreference {
     pub max_staleness_seconds: Option<u64>,
 }
 
-fn convert_read_preference(test_read_pref: TestReadPreference) -> Option<ReadPreference> {
-    let max_staleness = test_read_pref
-        .max_staleness_seconds
-        .map(Duration::from_secs);
+/// Converts the test's read preference, returning an error if its max staleness is rejected.
+fn convert_read_preference(test_read_pref: TestReadPreference) -> Option<Result<ReadPreference>> {
+    let options = ReadPreferenceOptions::builder()
+        .tag_sets(test_read_pref.tag_sets.clone())
+        .build();
 
     let read_pref = match &test_read_pref.mode.as_ref()?[..] {
         "Primary" => ReadPreference::Primary,
-        "Secondary" => ReadPreference::Secondary {
-            options: ReadPreferenceOptions::builder()
-                .tag_sets(test_read_pref.tag_sets)
-                .max_staleness(max_staleness)
-                .build(),
-        },
-        "PrimaryPreferred" => ReadPreference::PrimaryPreferred {
-            options: ReadPreferenceOptions::builder()
-                .tag_sets(test_read_pref.tag_sets)
-                .max_staleness(max_staleness)
-                .build(),
-        },
-        "SecondaryPreferred" => ReadPreference::SecondaryPreferred {
-            options: ReadPreferenceOptions::builder()
-                .tag_sets(test_read_pref.tag_sets)
-                .max_staleness(max_staleness)
-                .build(),
-        },
-        "Nearest" => ReadPreference::Nearest {
-            options: ReadPreferenceOptions::builder()
-                .tag_sets(test_read_pref.tag_sets)
-                .max_staleness(max_staleness)
-                .build(),
-        },
+        "Secondary" => ReadPreference::Secondary { options },
+        "PrimaryPreferred" => ReadPreference::PrimaryPreferred { options },
+        "SecondaryPreferred" => ReadPreference::SecondaryPreferred { options },
+        "Nearest" => ReadPreference::Nearest { options },
         _ => panic!("invalid read preference: {:?}", test_read_pref),
     };
 
-    Some(read_pref)
+    Some(match test_read_pref.max_staleness_seconds {
+        Some(max_staleness) => read_pref.with_max_staleness(Duration::from_secs(max_staleness)),
+        None => Ok(read_pref),
+    })
 }
 
 fn is_master_response_from_server_type(server_type: ServerType) -> IsMasterCommandResponse {
@@ -165,6 +150,9 @@ fn is_master_response_from_server_type(server_type: ServerType) -> IsMasterComma
         ServerType::Standalone => {
             response.ok = Some(1.0);
         }
+        ServerType::LoadBalancer => {
+            unreachable!("load balancers are never described by an isMaster response")
+        }
     };
 
     response
@@ -221,10 +209,25 @@ macro_rules! get_sorted_addresses {
 
 async fn run_test(test_file: TestFile) {
     let read_pref = match convert_read_preference(test_file.read_preference) {
-        Some(read_pref) => read_pref,
+        Some(Ok(read_pref)) => read_pref,
+        Some(Err(error)) => {
+            // A max staleness below 90 seconds is rejected when the read preference is constructed,
+            // whereas the spec leaves validating it to the server for non-replica set topologies.
+            let is_replica_set = matches!(
+                test_file.topology_description.topology_type,
+                TopologyType::ReplicaSetWithPrimary | TopologyType::ReplicaSetNoPrimary
+            );
+            assert!(
+                test_file.error == Some(true) || !is_replica_set,
+                "unexpected error: {}",
+                error
+            );
+            return;
+        }
         None => return,
     };
 
+    let num_servers = test_file.topology_description.servers.len();
     let servers: Option<Vec<ServerDescription>> = test_file
         .topology_description
         .servers
@@ -257,6 +260,15 @@ async fn run_test(test_file: TestFile) {
             .collect(),
     };
 
+    if test_file.error == Some(true) {
+        // The errors expected for topologies with servers that don't support max staleness can't
+        // be reproduced, since those servers are filtered out above.
+        if topology.servers.len() == num_servers {
+            assert!(topology.suitable_servers(&read_pref).is_err());
+        }
+        return;
+    }
+
     if let Some(ref expected_suitable_servers) = test_file.suitable_servers {
         let mut actual_servers: Vec<_> = topology.suitable_servers(&read_pref).unwrap();
 
@@ -365,3 +377,236 @@ async fn max_staleness_single() {
 async fn max_staleness_unknown() {
     run_spec_test(&["max-staleness", "Unknown"], run_test).await;
 }
+
+fn replica_set_with_server_types(
+    topology_type: TopologyType,
+    server_types: &[ServerType],
+) -> TopologyDescription {
+    let servers = server_types.iter().enumerate().map(|(i, server_type)| {
+        let address = StreamAddress::parse(&format!("localhost:{}", 27017 + i)).unwrap();
+        let reply = IsMasterReply {
+            command_response: is_master_response_from_server_type(*server_type),
+            round_trip_time: Some(Duration::from_millis(1)),
+            cluster_time: None,
+        };
+
+        (address.clone(), ServerDescription::new(address, Some(Ok(reply))))
+    });
+
+    TopologyDescription {
+        single_seed: false,
+        topology_type,
+        set_name: Some("foo".into()),
+        max_set_version: None,
+        max_election_id: None,
+        compatibility_error: None,
+        session_support_status: Default::default(),
+        cluster_time: None,
+        local_threshold: None,
+        heartbeat_freq: None,
+        servers: servers.collect(),
+    }
+}
+
+#[test]
+fn no_primary_error_message() {
+    let topology = replica_set_with_server_types(
+        TopologyType::ReplicaSetNoPrimary,
+        &[ServerType::RSSecondary, ServerType::Unknown],
+    );
+    assert!(topology.no_primary_error_message().is_some());
+
+    // If no members have been checked yet, a primary may still be discovered.
+    let topology = replica_set_with_server_types(
+        TopologyType::ReplicaSetNoPrimary,
+        &[ServerType::Unknown, ServerType::Unknown],
+    );
+    assert!(topology.no_primary_error_message().is_none());
+
+    let topology = replica_set_with_server_types(
+        TopologyType::ReplicaSetWithPrimary,
+        &[ServerType::RSPrimary, ServerType::RSSecondary],
+    );
+    assert!(topology.no_primary_error_message().is_none());
+}
+
+#[test]
+fn max_staleness_error_message() {
+    let server = |address: &str, server_type, last_write_date| {
+        convert_server_description(TestServerDescription {
+            address: address.to_string(),
+            avg_rtt_ms: Some(1.0),
+            server_type,
+            tags: None,
+            last_update_time: Some(0),
+            last_write: Some(LastWriteDate { last_write_date }),
+            max_wire_version: Some(6),
+        })
+        .unwrap()
+    };
+    let mut topology = replica_set_with_server_types(TopologyType::ReplicaSetWithPrimary, &[]);
+    for server in vec![
+        server("a:27017", TestServerType::RSPrimary, 1_000_000),
+        server("b:27017", TestServerType::RSSecondary, 1_000),
+        server("c:27017", TestServerType::RSSecondary, 500_000),
+    ] {
+        topology.servers.insert(server.address.clone(), server);
+    }
+
+    let read_pref = ReadPreference::Secondary {
+        options: Default::default(),
+    }
+    .with_max_staleness(Duration::from_secs(120))
+    .unwrap();
+    assert!(topology.suitable_servers(&read_pref).unwrap().is_empty());
+
+    // The staleness of each secondary is the lag behind the primary plus the heartbeat frequency.
+    let message = topology
+        .server_selection_timeout_error_message(&SelectionCriteria::ReadPreference(read_pref));
+    assert!(
+        message.contains(
+            "Staleness of secondaries (max staleness 120s): [b:27017: 1009s, c:27017: 510s]."
+        ),
+        "{}",
+        message
+    );
+
+    let error = ReadPreference::Primary
+        .with_max_staleness(Duration::from_secs(120))
+        .unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+    let error = ReadPreference::Nearest {
+        options: Default::default(),
+    }
+    .with_max_staleness(Duration::from_secs(89))
+    .unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+}
+
+#[test]
+fn deprioritized_server() {
+    let topology = replica_set_with_server_types(
+        TopologyType::Sharded,
+        &[ServerType::Mongos, ServerType::Mongos],
+    );
+    let criteria = SelectionCriteria::ReadPreference(ReadPreference::Primary);
+    let first = StreamAddress::parse("localhost:27017").unwrap();
+    let second = StreamAddress::parse("localhost:27018").unwrap();
+
+    for _ in 0..10 {
+        let selected = topology
+            .select_server(&criteria, Some(&first))
+            .unwrap()
+            .unwrap();
+        assert_eq!(selected.address, second);
+    }
+
+    // The deprioritized server is still selected if it's the only suitable one.
+    let topology = replica_set_with_server_types(
+        TopologyType::ReplicaSetWithPrimary,
+        &[ServerType::RSPrimary, ServerType::RSSecondary],
+    );
+    let selected = topology
+        .select_server(&criteria, Some(&first))
+        .unwrap()
+        .unwrap();
+    assert_eq!(selected.address, first);
+}
+
+#[test]
+fn nearest_tag_sets_and_latency_window() {
+    let server = |address: &str, avg_rtt_ms, tags: Option<TagSet>| {
+        convert_server_description(TestServerDescription {
+            address: address.to_string(),
+            avg_rtt_ms: Some(avg_rtt_ms),
+            server_type: TestServerType::RSSecondary,
+            tags,
+            last_update_time: None,
+            last_write: None,
+            max_wire_version: None,
+        })
+        .unwrap()
+    };
+    let tag_set = |value: &str| {
+        let mut tag_set = TagSet::new();
+        tag_set.insert("dc".into(), value.into());
+        tag_set
+    };
+
+    let mut topology = replica_set_with_server_types(TopologyType::ReplicaSetNoPrimary, &[]);
+    topology.local_threshold = Some(Duration::from_millis(10));
+    for server in vec![
+        server("a:27017", 5.0, Some(tag_set("ny"))),
+        server("b:27017", 14.0, Some(tag_set("ny"))),
+        server("c:27017", 16.0, Some(tag_set("ny"))),
+        server("d:27017", 1.0, None),
+    ] {
+        topology.servers.insert(server.address.clone(), server);
+    }
+
+    let select = |tag_sets: Vec<TagSet>| {
+        let criteria = SelectionCriteria::ReadPreference(ReadPreference::Nearest {
+            options: ReadPreferenceOptions::builder().tag_sets(tag_sets).build(),
+        });
+        let mut selected: Vec<_> = (0..50)
+            .map(|_| {
+                topology
+                    .select_server(&criteria, None)
+                    .unwrap()
+                    .unwrap()
+                    .address
+                    .to_string()
+            })
+            .collect();
+        selected.sort_unstable();
+        selected.dedup();
+        selected
+    };
+
+    // The first tag set matches nothing, so the second is used, and "c" is outside the window
+    // around the fastest of the matching servers.
+    assert_eq!(
+        select(vec![tag_set("sf"), tag_set("ny")]),
+        vec!["a:27017", "b:27017"]
+    );
+
+    // An empty tag set matches every server, whether or not it has tags, so the window is now
+    // around "d".
+    assert_eq!(
+        select(vec![tag_set("sf"), TagSet::new()]),
+        vec!["a:27017", "d:27017"]
+    );
+}
+
+#[test]
+fn predicate() {
+    let topology = replica_set_with_server_types(
+        TopologyType::ReplicaSetWithPrimary,
+        &[
+            ServerType::RSPrimary,
+            ServerType::RSSecondary,
+            ServerType::RSSecondary,
+        ],
+    );
+    let address = StreamAddress::parse("localhost:27018").unwrap();
+    let criteria = SelectionCriteria::from_address(address.clone());
+    for _ in 0..10 {
+        let selected = topology.select_server(&criteria, None).unwrap().unwrap();
+        assert_eq!(selected.address, address);
+    }
+
+    let criteria = SelectionCriteria::Predicate(Arc::new(|_| false));
+    assert!(topology.select_server(&criteria, None).unwrap().is_none());
+    let message = topology.server_selection_timeout_error_message(&criteria);
+    assert!(
+        message.contains("suitable for criteria Predicate."),
+        "{}",
+        message
+    );
+}
@@ -3,6 +3,74 @@ mod sdam;
 
 use std::time::Duration;
 
+use crate::{
+    cmap::Command,
+    options::{ClientOptions, HedgedReadOptions, ReadPreferenceOptions, StreamAddress},
+    sdam::description::{
+        server::ServerType,
+        topology::{SessionSupportStatus, TopologyDescription, TopologyType},
+    },
+    selection_criteria::{ReadPreference, SelectionCriteria},
+};
+
 pub(crate) fn f64_ms_as_duration(f: f64) -> Duration {
     Duration::from_micros((f * 1000.0) as u64)
 }
+
+#[test]
+fn load_balanced() {
+    let address = StreamAddress::parse("localhost:27017").unwrap();
+    let options = ClientOptions::builder()
+        .hosts(vec![address.clone()])
+        .load_balanced(true)
+        .build();
+
+    let description = TopologyDescription::new(options).unwrap();
+    assert_eq!(description.topology_type(), TopologyType::LoadBalanced);
+    assert!(matches!(
+        description.session_support_status(),
+        SessionSupportStatus::Supported { .. }
+    ));
+
+    // The load balancer is selectable without ever having been checked, regardless of the read
+    // preference.
+    let criteria = SelectionCriteria::ReadPreference(ReadPreference::Secondary {
+        options: Default::default(),
+    });
+    let selected = description.select_server(&criteria, None).unwrap().unwrap();
+    assert_eq!(selected.address, address);
+    assert_eq!(selected.server_type, ServerType::LoadBalancer);
+}
+
+#[test]
+fn hedge_only_sent_to_mongos() {
+    let options = ClientOptions::builder()
+        .hosts(vec![StreamAddress::parse("localhost:27017").unwrap()])
+        .build();
+    let mut description = TopologyDescription::new(options).unwrap();
+
+    let options = ReadPreferenceOptions::builder()
+        .hedge(HedgedReadOptions::with_enabled(true))
+        .build();
+    let criteria =
+        SelectionCriteria::ReadPreference(ReadPreference::SecondaryPreferred { options });
+    let read_pref = |description: &TopologyDescription, server_type| {
+        let mut command = Command::new("find".into(), "db".into(), Default::default());
+        description.update_command_with_read_pref(server_type, &mut command, Some(&criteria));
+        command.read_pref
+    };
+
+    description.topology_type = TopologyType::Sharded;
+    assert_eq!(
+        read_pref(&description, ServerType::Mongos),
+        criteria.as_read_pref().cloned()
+    );
+
+    description.topology_type = TopologyType::ReplicaSetWithPrimary;
+    assert_eq!(
+        read_pref(&description, ServerType::RSSecondary),
+        Some(ReadPreference::SecondaryPreferred {
+            options: Default::default()
+        })
+    );
+}
@@ -54,7 +54,7 @@ pub(crate) struct TopologyMessageSubscriber {
 }
 
 impl TopologyMessageSubscriber {
-    async fn new(receiver: &Receiver<()>) -> Self {
+    pub(super) async fn new(receiver: &Receiver<()>) -> Self {
         let mut receiver = receiver.clone();
         receiver.recv().await;
         Self { receiver }
@@ -1,5 +1,9 @@
-use std::{sync::Weak, time::Duration};
+use std::{
+    sync::Weak,
+    time::{Duration, Instant},
+};
 
+use futures::{future, pin_mut};
 use time::PreciseTime;
 
 use super::{
@@ -7,9 +11,14 @@ use super::{
     state::{server::Server, Topology, WeakTopology},
 };
 use crate::{
-    bson::doc,
+    bson::{doc, Document},
     cmap::{Command, Connection},
     error::Result,
+    event::sdam::{
+        ServerHeartbeatFailedEvent,
+        ServerHeartbeatStartedEvent,
+        ServerHeartbeatSucceededEvent,
+    },
     is_master::IsMasterReply,
     options::StreamAddress,
     RUNTIME,
@@ -25,6 +34,7 @@ pub(super) struct Monitor {
     server: Weak<Server>,
     server_type: ServerType,
     topology: WeakTopology,
+    schedule: CheckSchedule,
 }
 
 impl Monitor {
@@ -38,6 +48,7 @@ impl Monitor {
             server,
             server_type: ServerType::Unknown,
             topology,
+            schedule: CheckSchedule::default(),
         };
 
         RUNTIME.execute(async move {
@@ -53,9 +64,10 @@ impl Monitor {
             .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY);
 
         while self.topology.is_alive() {
-            if self.server.upgrade().is_none() {
-                break;
-            }
+            let server = match self.server.upgrade() {
+                Some(server) => server,
+                None => break,
+            };
 
             let topology = match self.topology.upgrade() {
                 Some(topology) => topology,
@@ -68,6 +80,11 @@ impl Monitor {
 
             let mut topology_check_requests_subscriber =
                 topology.subscribe_to_topology_check_requests().await;
+            let mut server_check_requests_subscriber = server.subscribe_to_check_requests().await;
+
+            // Neither the server nor the topology is kept alive while waiting for the next check.
+            drop(server);
+            drop(topology);
 
             let min_frequency = self
                 .topology
@@ -76,9 +93,17 @@ impl Monitor {
                 .unwrap_or(MIN_HEARTBEAT_FREQUENCY);
             RUNTIME.delay_for(min_frequency).await;
 
-            topology_check_requests_subscriber
-                .wait_for_message(heartbeat_frequency - min_frequency)
-                .await;
+            let wait = self
+                .schedule
+                .wait_before_next_check(heartbeat_frequency, min_frequency);
+            if wait > Duration::from_millis(0) {
+                let topology_check_requested =
+                    topology_check_requests_subscriber.wait_for_message(wait);
+                let server_check_requested =
+                    server_check_requests_subscriber.wait_for_message(wait);
+                pin_mut!(topology_check_requested, server_check_requested);
+                future::select(topology_check_requested, server_check_requested).await;
+            }
         }
     }
 
@@ -90,6 +115,8 @@ impl Monitor {
         // Send an isMaster to the server.
         let server_description = self.check_server().await;
         self.server_type = server_description.server_type;
+        self.schedule
+            .record_check(server_description.reply.is_err());
 
         topology.update(server_description).await
     }
@@ -111,9 +138,43 @@ impl Monitor {
         }
     }
 
+    /// Runs an `isMaster` on the monitoring connection, emitting the heartbeat events for it.
     async fn perform_is_master(&mut self) -> Result<IsMasterReply> {
-        let connection = self.resolve_connection().await?;
-        let result = is_master(connection).await;
+        let handler = self.topology.client_options().sdam_event_handler.clone();
+        if let Some(ref handler) = handler {
+            handler.handle_server_heartbeat_started_event(ServerHeartbeatStartedEvent {
+                address: self.address.clone(),
+            });
+        }
+
+        let start = Instant::now();
+        let result = match self.resolve_connection().await {
+            Ok(connection) => is_master(connection).await,
+            Err(e) => Err(e),
+        };
+        let duration = start.elapsed();
+
+        if let Some(ref handler) = handler {
+            match result {
+                Ok((_, ref reply)) => {
+                    handler.handle_server_heartbeat_succeeded_event(
+                        ServerHeartbeatSucceededEvent {
+                            address: self.address.clone(),
+                            duration,
+                            reply: reply.clone(),
+                        },
+                    );
+                }
+                Err(ref failure) => {
+                    handler.handle_server_heartbeat_failed_event(ServerHeartbeatFailedEvent {
+                        address: self.address.clone(),
+                        duration,
+                        failure: failure.clone(),
+                    });
+                }
+            }
+        }
+        let result = result.map(|(is_master_reply, _)| is_master_reply);
 
         if result
             .as_ref()
@@ -136,6 +197,7 @@ impl Monitor {
             self.address.clone(),
             self.topology.client_options().connect_timeout,
             self.topology.client_options().tls_options(),
+            self.topology.client_options().server_api.clone(),
         )
         .await?;
 
@@ -151,7 +213,45 @@ impl Monitor {
     }
 }
 
-async fn is_master(connection: &mut Connection) -> Result<IsMasterReply> {
+/// Decides when a monitor checks its server next. After a check fails, the server is re-checked
+/// once right away so that a failover is noticed quickly; if that check fails too, the server is
+/// considered down and is checked at the normal frequency until a check succeeds again.
+#[derive(Debug, Default)]
+struct CheckSchedule {
+    /// Whether the most recent check of the server failed.
+    last_check_failed: bool,
+
+    /// Whether the next check should be performed without waiting for the heartbeat frequency.
+    recheck_immediately: bool,
+}
+
+impl CheckSchedule {
+    /// Records the outcome of a check of the server.
+    fn record_check(&mut self, failed: bool) {
+        self.recheck_immediately = failed && !self.last_check_failed;
+        self.last_check_failed = failed;
+    }
+
+    /// Gets how long to wait for a check to be requested before checking the server anyway, once
+    /// `min_frequency` has passed since the last check.
+    fn wait_before_next_check(
+        &self,
+        heartbeat_frequency: Duration,
+        min_frequency: Duration,
+    ) -> Duration {
+        if self.recheck_immediately {
+            Duration::from_millis(0)
+        } else {
+            heartbeat_frequency
+                .checked_sub(min_frequency)
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Runs an `isMaster` on the given connection, returning the parsed reply along with the raw reply
+/// document.
+async fn is_master(connection: &mut Connection) -> Result<(IsMasterReply, Document)> {
     let command = Command::new_read(
         "isMaster".into(),
         "admin".into(),
@@ -165,9 +265,55 @@ async fn is_master(connection: &mut Connection) -> Result<IsMasterReply> {
 
     command_response.validate()?;
     let is_master_response = command_response.body()?;
-    Ok(IsMasterReply {
+    let is_master_reply = IsMasterReply {
         command_response: is_master_response,
         round_trip_time: Some(start_time.to(end_time).to_std().unwrap()),
         cluster_time: command_response.cluster_time().cloned(),
-    })
+    };
+    Ok((is_master_reply, command_response.raw_response))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::CheckSchedule;
+
+    #[test]
+    fn failed_check_is_rechecked_once() {
+        let heartbeat_frequency = Duration::from_secs(10);
+        let min_frequency = Duration::from_millis(500);
+        let normal_wait = heartbeat_frequency - min_frequency;
+
+        let mut schedule = CheckSchedule::default();
+        schedule.record_check(false);
+        assert_eq!(
+            schedule.wait_before_next_check(heartbeat_frequency, min_frequency),
+            normal_wait
+        );
+
+        // The first failure is re-checked right away.
+        schedule.record_check(true);
+        assert_eq!(
+            schedule.wait_before_next_check(heartbeat_frequency, min_frequency),
+            Duration::from_millis(0)
+        );
+
+        // A server that is still down is checked at the normal frequency.
+        for _ in 0..3 {
+            schedule.record_check(true);
+            assert_eq!(
+                schedule.wait_before_next_check(heartbeat_frequency, min_frequency),
+                normal_wait
+            );
+        }
+
+        // Once it's back, the next failure is re-checked right away again.
+        schedule.record_check(false);
+        schedule.record_check(true);
+        assert_eq!(
+            schedule.wait_before_next_check(heartbeat_frequency, min_frequency),
+            Duration::from_millis(0)
+        );
+    }
 }
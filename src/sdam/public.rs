@@ -1,14 +1,55 @@
-use std::{fmt, time::Duration};
+use std::{collections::HashMap, fmt, time::Duration};
 
-pub use crate::sdam::description::server::ServerType;
+pub use crate::sdam::description::{server::ServerType, topology::TopologyType};
 use crate::{
     bson::DateTime,
     is_master::IsMasterCommandResponse,
     options::StreamAddress,
-    sdam::description::server::ServerDescription,
+    sdam::description::{server::ServerDescription, topology},
     selection_criteria::TagSet,
 };
 
+/// A snapshot of the driver's most recent view of the topology of the cluster that a client is
+/// connected to. This can be obtained via
+/// [`Client::topology_description`](../struct.Client.html#method.topology_description).
+///
+/// The snapshot is not updated as the driver learns about changes to the cluster; to get newer
+/// information, a new snapshot must be requested.
+#[derive(Clone, Debug)]
+pub struct TopologyDescription {
+    description: topology::TopologyDescription,
+}
+
+impl TopologyDescription {
+    pub(crate) fn new(description: topology::TopologyDescription) -> Self {
+        Self { description }
+    }
+
+    /// Gets the type of the topology.
+    pub fn topology_type(&self) -> TopologyType {
+        self.description.topology_type()
+    }
+
+    /// Gets the name of the replica set that the topology is part of, if any.
+    pub fn replica_set_name(&self) -> Option<&str> {
+        self.description.set_name()
+    }
+
+    /// Gets information about each of the servers in the topology, keyed by their addresses.
+    pub fn servers(&self) -> HashMap<&StreamAddress, ServerInfo<'_>> {
+        self.description
+            .server_descriptions()
+            .map(|(address, description)| (address, ServerInfo::new(description)))
+            .collect()
+    }
+}
+
+impl fmt::Display for TopologyDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> std::result::Result<(), fmt::Error> {
+        self.description.fmt(f)
+    }
+}
+
 /// Provides information about a given server in the cluster that a client is connected to. This is
 /// used when providing a predicate as a `SelectionCriteria`.
 pub struct ServerInfo<'a> {
@@ -75,7 +75,10 @@ impl SrvPollingMonitor {
             std::mem::drop(topology);
 
             RUNTIME
-                .delay_for(self.rescan_interval.unwrap_or(DEFAULT_RESCAN_SRV_INTERVAL))
+                .delay_for(
+                    self.rescan_interval
+                        .unwrap_or_else(|| self.min_rescan_interval()),
+                )
                 .await;
         }
     }
@@ -100,7 +103,13 @@ impl SrvPollingMonitor {
             }
         };
 
-        self.rescan_interval = lookup.min_ttl;
+        // The records' TTL determines when to look them up again, within the configured minimum.
+        let min_rescan_interval = self.min_rescan_interval();
+        self.rescan_interval = Some(
+            lookup
+                .min_ttl
+                .map_or(min_rescan_interval, |ttl| ttl.max(min_rescan_interval)),
+        );
 
         let diff =
             topology_state.update_hosts(&lookup.hosts.into_iter().collect(), &self.client_options);
@@ -135,7 +144,11 @@ impl SrvPollingMonitor {
             return Ok(resolver);
         }
 
-        let resolver = SrvResolver::new(self.client_options.resolver_config.clone()).await?;
+        let resolver = SrvResolver::new(
+            self.client_options.resolver_config.clone(),
+            self.client_options.srv_lookup_timeout,
+        )
+        .await?;
 
         // Since the connection was not `Some` above, this will always insert the new connection and
         // return a reference to it.
@@ -148,6 +161,12 @@ impl SrvPollingMonitor {
         self.rescan_interval = Some(self.heartbeat_freq());
     }
 
+    fn min_rescan_interval(&self) -> Duration {
+        self.client_options
+            .srv_min_heartbeat_frequency
+            .unwrap_or(DEFAULT_RESCAN_SRV_INTERVAL)
+    }
+
     fn heartbeat_freq(&self) -> Duration {
         self.client_options
             .heartbeat_freq
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use pretty_assertions::assert_eq;
 
@@ -102,3 +102,26 @@ async fn timeout_error() {
 async fn no_results() {
     run_test(Ok(Vec::new()), DEFAULT_HOSTS.iter().cloned().collect()).await;
 }
+
+// The TTL of the records should be respected, but rescans shouldn't happen more often than the
+// configured minimum.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn rescan_interval() {
+    let topology = Topology::new_from_hosts(DEFAULT_HOSTS.iter());
+    let mut monitor = SrvPollingMonitor::new(topology.downgrade()).unwrap();
+    monitor.client_options.srv_min_heartbeat_frequency = Some(Duration::from_secs(30));
+
+    for (min_ttl, expected) in vec![(None, 30), (Some(10), 30), (Some(120), 120)] {
+        let lookup = LookupHosts {
+            hosts: DEFAULT_HOSTS.clone(),
+            min_ttl: min_ttl.map(Duration::from_secs),
+        };
+        let state = topology.clone_state().await;
+        monitor
+            .update_hosts(Ok(lookup), topology.clone(), state)
+            .await;
+
+        assert_eq!(monitor.rescan_interval, Some(Duration::from_secs(expected)));
+    }
+}
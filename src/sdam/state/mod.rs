@@ -1,4 +1,6 @@
 pub(super) mod server;
+#[cfg(test)]
+mod test;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -18,9 +20,16 @@ use super::{
     TopologyDescription,
 };
 use crate::{
-    client::ClusterTime,
+    client::{metrics::PoolMetrics, ClusterTime},
     cmap::{Command, Connection},
     error::{Error, Result},
+    event::sdam::{
+        SdamEventHandler,
+        ServerClosedEvent,
+        ServerDescriptionChangedEvent,
+        ServerOpeningEvent,
+        TopologyDescriptionChangedEvent,
+    },
     options::{ClientOptions, SelectionCriteria, StreamAddress},
     runtime::HttpClient,
     sdam::{
@@ -133,6 +142,11 @@ impl Topology {
         let state = Arc::new(RwLock::new(topology_state.clone()));
         let topology = Topology { state, common };
 
+        // A load balancer is not monitored, and the set of hosts behind it is never expanded.
+        if topology_state.is_load_balanced() {
+            return Ok(topology);
+        }
+
         for address in hosts {
             topology_state.start_monitoring_server(address.clone(), topology.downgrade());
         }
@@ -146,12 +160,43 @@ impl Topology {
         self.common.is_alive.store(false, Ordering::SeqCst);
     }
 
+    /// Whether the topology is still open, i.e. it hasn't been closed by the `Client` being
+    /// dropped or shut down.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.common.is_alive.load(Ordering::SeqCst)
+    }
+
+    /// Closes the topology, stopping the monitoring of its servers and closing their connection
+    /// pools.
+    pub(crate) async fn shutdown(&self) {
+        self.mark_closed();
+
+        // Wake the monitors up so that they notice the topology has been closed.
+        self.request_topology_check();
+
+        let servers: Vec<_> = self.state.read().await.servers.values().cloned().collect();
+        for server in servers {
+            server.close_connection_pool().await;
+        }
+    }
+
     /// Gets the addresses of the servers in the cluster.
     #[cfg(test)]
     pub(crate) async fn servers(&self) -> HashSet<StreamAddress> {
         self.state.read().await.servers.keys().cloned().collect()
     }
 
+    /// Gets a snapshot of the counters of each server's connection pool.
+    pub(crate) async fn pool_metrics(&self) -> HashMap<StreamAddress, PoolMetrics> {
+        self.state
+            .read()
+            .await
+            .servers
+            .iter()
+            .map(|(address, server)| (address.clone(), server.pool_metrics()))
+            .collect()
+    }
+
     /// Creates and returns a weak reference to the topology.
     pub(super) fn downgrade(&self) -> WeakTopology {
         WeakTopology {
@@ -172,12 +217,13 @@ impl Topology {
     pub(crate) async fn attempt_to_select_server(
         &self,
         criteria: &SelectionCriteria,
+        deprioritized: Option<&StreamAddress>,
     ) -> Result<Option<Arc<Server>>> {
         let topology_state = self.state.read().await;
 
         Ok(topology_state
             .description
-            .select_server(criteria)?
+            .select_server(criteria, deprioritized)?
             .and_then(|server| topology_state.servers.get(&server.address).cloned()))
     }
 
@@ -193,6 +239,12 @@ impl Topology {
             .server_selection_timeout_error_message(criteria)
     }
 
+    /// Creates an error message for failing server selection early if the topology is known to have
+    /// no primary, returning `None` if it might still have one.
+    pub(crate) async fn no_primary_error_message(&self) -> Option<String> {
+        self.state.read().await.description.no_primary_error_message()
+    }
+
     /// Signals the SDAM background threads that they should wake up and check the topology.
     pub(crate) fn request_topology_check(&self) {
         self.common.message_manager.request_topology_check();
@@ -222,7 +274,11 @@ impl Topology {
     /// Handles an error that occurs before the handshake has completed during an operation.
     pub(crate) async fn handle_pre_handshake_error(&self, error: Error, address: StreamAddress) {
         if error.is_network_error() {
+            let server = self.state.read().await.servers.get(&address).cloned();
             self.mark_server_as_unknown(error, address).await;
+            if let Some(server) = server {
+                server.request_check();
+            }
         }
     }
 
@@ -238,12 +294,13 @@ impl Topology {
         if error.is_non_timeout_network_error() {
             self.mark_server_as_unknown(error, server.address.clone())
                 .await;
+            server.request_check();
             server.clear_connection_pool();
         } else if error.is_recovering() || error.is_not_master() {
             self.mark_server_as_unknown(error.clone(), server.address.clone())
                 .await;
 
-            self.common.message_manager.request_topology_check();
+            server.request_check();
 
             let wire_version = conn
                 .stream_description()
@@ -261,7 +318,14 @@ impl Topology {
     }
 
     /// Marks a server in the cluster as unknown due to the given `error`.
+    ///
+    /// A load balancer is always considered available, so this has no effect on load balanced
+    /// topologies.
     async fn mark_server_as_unknown(&self, error: Error, address: StreamAddress) {
+        if self.state.read().await.is_load_balanced() {
+            return;
+        }
+
         let description = ServerDescription::new(address, Some(Err(error)));
         self.update(description).await;
     }
@@ -299,6 +363,11 @@ impl Topology {
             .advance_cluster_time(cluster_time);
     }
 
+    /// Gets a copy of the current topology description.
+    pub(crate) async fn description(&self) -> TopologyDescription {
+        self.state.read().await.description.clone()
+    }
+
     /// Get the topology's currently highest seen cluster time.
     pub(crate) async fn cluster_time(&self) -> Option<ClusterTime> {
         self.state
@@ -324,6 +393,12 @@ impl Topology {
                 // Now that we have the proper state in the copy, acquire a lock on the proper
                 // topology and move the info over.
                 let mut state_lock = self.state.write().await;
+                let previous_description = self
+                    .common
+                    .options
+                    .sdam_event_handler
+                    .as_ref()
+                    .map(|_| state_lock.description.clone());
 
                 // Advance the new state's cluster time in case the topology had been updated with a
                 // newer one since the new state was created.
@@ -339,8 +414,24 @@ impl Topology {
                     state_lock.start_monitoring_server(new_address, self.downgrade());
                 }
 
+                let new_description = state_lock.description.clone();
+                drop(state_lock);
+
                 self.common.message_manager.notify_topology_changed();
 
+                // The events are emitted after the lock is released so that handlers can inspect
+                // the topology.
+                if let (Some(handler), Some(previous_description)) = (
+                    self.common.options.sdam_event_handler.as_ref(),
+                    previous_description,
+                ) {
+                    emit_description_changed_events(
+                        handler.as_ref(),
+                        previous_description,
+                        new_description,
+                    );
+                }
+
                 true
             }
         }
@@ -392,6 +483,10 @@ impl TopologyState {
         self.description.topology_type() == TopologyType::Unknown
     }
 
+    pub(super) fn is_load_balanced(&self) -> bool {
+        self.description.topology_type() == TopologyType::LoadBalanced
+    }
+
     /// Adds a new server to the cluster.
     ///
     /// A reference to the containing Topology is needed in order to start the monitoring task.
@@ -400,6 +495,12 @@ impl TopologyState {
             return;
         }
 
+        if let Some(ref handler) = options.sdam_event_handler {
+            handler.handle_server_opening_event(ServerOpeningEvent {
+                address: address.clone(),
+            });
+        }
+
         let server = Arc::new(Server::new(
             address.clone(),
             &options,
@@ -467,7 +568,20 @@ impl TopologyState {
             self.add_new_server(address.clone(), options.clone());
         }
 
-        self.servers.retain(|host, _| hosts.contains(host));
+        let removed: Vec<_> = self
+            .servers
+            .keys()
+            .filter(|host| !hosts.contains(host))
+            .cloned()
+            .collect();
+
+        for address in removed {
+            self.servers.remove(&address);
+
+            if let Some(ref handler) = options.sdam_event_handler {
+                handler.handle_server_closed_event(ServerClosedEvent { address });
+            }
+        }
     }
 
     /// Start a monitor for the server at the given address if it is part of the topology.
@@ -477,3 +591,39 @@ impl TopologyState {
         }
     }
 }
+
+/// Emits a `ServerDescriptionChangedEvent` for each server whose type differs between `previous`
+/// and `new`, followed by a `TopologyDescriptionChangedEvent` if the type of the topology, the set
+/// of servers or the type of any server changed.
+fn emit_description_changed_events(
+    handler: &dyn SdamEventHandler,
+    previous: TopologyDescription,
+    new: TopologyDescription,
+) {
+    let mut changed = previous.topology_type() != new.topology_type()
+        || previous.server_addresses().count() != new.server_addresses().count();
+
+    for (address, new_server) in new.server_descriptions() {
+        match previous.get_server_description(address) {
+            Some(previous_server) if previous_server.server_type != new_server.server_type => {
+                changed = true;
+                handler.handle_server_description_changed_event(ServerDescriptionChangedEvent {
+                    address: address.clone(),
+                    previous_type: previous_server.server_type,
+                    new_type: new_server.server_type,
+                });
+            }
+            Some(_) => {}
+            None => changed = true,
+        }
+    }
+
+    if changed {
+        handler.handle_topology_description_changed_event(TopologyDescriptionChangedEvent {
+            previous_type: previous.topology_type(),
+            new_type: new.topology_type(),
+            previous_description: crate::TopologyDescription::new(previous),
+            new_description: crate::TopologyDescription::new(new),
+        });
+    }
+}
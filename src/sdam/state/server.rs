@@ -1,8 +1,12 @@
+use tokio::sync::watch::{self, Receiver, Sender};
+
 use crate::{
+    client::metrics::PoolMetrics,
     cmap::{options::ConnectionPoolOptions, Connection, ConnectionPool},
     error::Result,
     options::{ClientOptions, StreamAddress},
     runtime::HttpClient,
+    sdam::message_manager::TopologyMessageSubscriber,
 };
 
 /// Contains the state for a given server in the topology.
@@ -12,6 +16,11 @@ pub(crate) struct Server {
 
     /// The connection pool for the server.
     pool: ConnectionPool,
+
+    /// Used to ask the server's monitor to check it immediately, e.g. because an operation on it
+    /// failed with an error that indicates its state may have changed.
+    check_requester: Sender<()>,
+    check_listener: Receiver<()>,
 }
 
 impl Server {
@@ -20,6 +29,7 @@ impl Server {
         options: &ClientOptions,
         http_client: HttpClient,
     ) -> Self {
+        let (check_requester, check_listener) = watch::channel(());
         Self {
             pool: ConnectionPool::new(
                 address.clone(),
@@ -27,6 +37,8 @@ impl Server {
                 Some(ConnectionPoolOptions::from_client_options(options)),
             ),
             address,
+            check_requester,
+            check_listener,
         }
     }
 
@@ -40,4 +52,25 @@ impl Server {
     pub(crate) fn clear_connection_pool(&self) {
         self.pool.clear();
     }
+
+    /// Closes the connection pool associated with the server.
+    pub(crate) async fn close_connection_pool(&self) {
+        self.pool.close().await;
+    }
+
+    /// Requests that the server's monitor check it immediately rather than waiting for its next
+    /// heartbeat.
+    pub(crate) fn request_check(&self) {
+        let _ = self.check_requester.broadcast(());
+    }
+
+    /// Subscribe to requests to check this server.
+    pub(crate) async fn subscribe_to_check_requests(&self) -> TopologyMessageSubscriber {
+        TopologyMessageSubscriber::new(&self.check_listener).await
+    }
+
+    /// Gets a snapshot of the counters of the server's connection pool.
+    pub(crate) fn pool_metrics(&self) -> PoolMetrics {
+        self.pool.metrics()
+    }
 }
@@ -0,0 +1,133 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use super::{emit_description_changed_events, TopologyState};
+use crate::{
+    event::sdam::{
+        SdamEventHandler,
+        ServerClosedEvent,
+        ServerDescriptionChangedEvent,
+        ServerOpeningEvent,
+        TopologyDescriptionChangedEvent,
+    },
+    is_master::{IsMasterCommandResponse, IsMasterReply},
+    options::{ClientOptions, StreamAddress},
+    sdam::{ServerDescription, TopologyDescription},
+    ServerType,
+    TopologyType,
+};
+
+#[derive(Default)]
+struct MembershipHandler {
+    opened: Mutex<Vec<StreamAddress>>,
+    closed: Mutex<Vec<StreamAddress>>,
+}
+
+impl SdamEventHandler for MembershipHandler {
+    fn handle_server_opening_event(&self, event: ServerOpeningEvent) {
+        self.opened.lock().unwrap().push(event.address);
+    }
+
+    fn handle_server_closed_event(&self, event: ServerClosedEvent) {
+        self.closed.lock().unwrap().push(event.address);
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn server_opening_and_closed_events() {
+    let a = StreamAddress::parse("a:27017").unwrap();
+    let b = StreamAddress::parse("b:27017").unwrap();
+
+    let handler = Arc::new(MembershipHandler::default());
+    let options = ClientOptions::builder()
+        .hosts(vec![a.clone()])
+        .sdam_event_handler(handler.clone() as Arc<dyn SdamEventHandler>)
+        .build();
+
+    let mut state = TopologyState {
+        description: TopologyDescription::new(options.clone()).unwrap(),
+        servers: Default::default(),
+        http_client: Default::default(),
+    };
+
+    state.add_new_server(a.clone(), options.clone());
+    assert_eq!(*handler.opened.lock().unwrap(), vec![a.clone()]);
+
+    // Syncing with a newly discovered host only opens that host.
+    let hosts: HashSet<_> = vec![a.clone(), b.clone()].into_iter().collect();
+    state.sync_hosts(&hosts, &options);
+    assert_eq!(*handler.opened.lock().unwrap(), vec![a.clone(), b.clone()]);
+    assert!(handler.closed.lock().unwrap().is_empty());
+
+    let hosts: HashSet<_> = vec![b.clone()].into_iter().collect();
+    state.sync_hosts(&hosts, &options);
+    assert_eq!(*handler.closed.lock().unwrap(), vec![a]);
+    assert_eq!(handler.opened.lock().unwrap().len(), 2);
+    assert!(state.servers.contains_key(&b));
+}
+
+#[derive(Default)]
+struct ChangeHandler {
+    server_changes: Mutex<Vec<ServerDescriptionChangedEvent>>,
+    topology_changes: Mutex<Vec<(TopologyType, TopologyType)>>,
+}
+
+impl SdamEventHandler for ChangeHandler {
+    fn handle_server_description_changed_event(&self, event: ServerDescriptionChangedEvent) {
+        self.server_changes.lock().unwrap().push(event);
+    }
+
+    fn handle_topology_description_changed_event(&self, event: TopologyDescriptionChangedEvent) {
+        self.topology_changes
+            .lock()
+            .unwrap()
+            .push((event.previous_type, event.new_type));
+    }
+}
+
+#[test]
+fn description_changed_events() {
+    let a = StreamAddress::parse("a:27017").unwrap();
+    let options = ClientOptions::builder().hosts(vec![a.clone()]).build();
+    let previous = TopologyDescription::new(options).unwrap();
+
+    let command_response = IsMasterCommandResponse {
+        ok: Some(1.0),
+        msg: Some("isdbgrid".into()),
+        ..Default::default()
+    };
+    let reply = IsMasterReply {
+        command_response,
+        round_trip_time: Some(Duration::from_millis(1)),
+        cluster_time: None,
+    };
+    let mut new = previous.clone();
+    new.update(ServerDescription::new(a.clone(), Some(Ok(reply))))
+        .unwrap();
+
+    let handler = ChangeHandler::default();
+    emit_description_changed_events(&handler, previous, new.clone());
+
+    assert_eq!(
+        *handler.server_changes.lock().unwrap(),
+        vec![ServerDescriptionChangedEvent {
+            address: a,
+            previous_type: ServerType::Unknown,
+            new_type: ServerType::Mongos,
+        }]
+    );
+    assert_eq!(
+        *handler.topology_changes.lock().unwrap(),
+        vec![(TopologyType::Unknown, TopologyType::Sharded)]
+    );
+
+    // Nothing is emitted if neither the topology nor its servers changed type.
+    let handler = ChangeHandler::default();
+    emit_description_changed_events(&handler, new.clone(), new);
+    assert!(handler.server_changes.lock().unwrap().is_empty());
+    assert!(handler.topology_changes.lock().unwrap().is_empty());
+}
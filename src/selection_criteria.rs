@@ -12,6 +12,9 @@ use crate::{
     sdam::public::ServerInfo,
 };
 
+/// The smallest max staleness allowed by the max staleness spec.
+pub(crate) const MIN_MAX_STALENESS: Duration = Duration::from_secs(90);
+
 /// Describes which servers are suitable for a given operation.
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -150,15 +153,19 @@ pub struct ReadPreferenceOptions {
     /// considered for the given operation. Any secondaries lagging behind more than
     /// `max_staleness` will not be considered for the operation.
     ///
-    /// `max_staleness` must be at least 90 seconds. If a `max_staleness` less than 90 seconds is
-    /// specified for an operation, the operation will return an error.
+    /// `max_staleness` must be at least 90 seconds, and at least the client's heartbeat frequency
+    /// plus 10 seconds. If a smaller `max_staleness` is specified for an operation, the operation
+    /// will return an error; [`ReadPreference::with_max_staleness`](enum.ReadPreference.html#method.with_max_staleness)
+    /// can be used to reject a `max_staleness` below 90 seconds up front. A `max_staleness` of zero
+    /// is treated the same as not specifying one.
     #[builder(default)]
     #[serde(rename = "maxStalenessSeconds", deserialize_with = "deserialize_duration_from_u64_seconds")]
     pub max_staleness: Option<Duration>,
 
     /// Specifies hedging behavior for reads. These options only apply to sharded clusters on
-    /// servers that are at least version 4.4. Note that hedged reads are automatically enabled for
-    /// read preference mode "nearest".
+    /// servers that are at least version 4.4, and are only sent to mongos. Note that hedged reads
+    /// are automatically enabled for read preference mode "nearest". As `ReadPreference::Primary`
+    /// has no options, hedging can't be requested for primary reads.
     ///
     /// See the [MongoDB docs](https://docs.mongodb.com/manual/core/read-preference-hedge-option/) for more details.
     #[builder(default)]
@@ -177,6 +184,14 @@ pub struct HedgedReadOptions {
     pub enabled: bool,
 }
 
+impl ReadPreferenceOptions {
+    /// Gets the max staleness, treating a max staleness of zero as unset.
+    pub(crate) fn effective_max_staleness(&self) -> Option<Duration> {
+        self.max_staleness
+            .filter(|max_staleness| *max_staleness > Duration::from_secs(0))
+    }
+}
+
 impl HedgedReadOptions {
     pub fn with_enabled(enabled: bool) -> Self {
         Self { enabled }
@@ -190,7 +205,7 @@ impl ReadPreference {
             ReadPreference::Secondary { ref options }
             | ReadPreference::PrimaryPreferred { ref options }
             | ReadPreference::SecondaryPreferred { ref options }
-            | ReadPreference::Nearest { ref options } => options.max_staleness,
+            | ReadPreference::Nearest { ref options } => options.effective_max_staleness(),
         }
     }
 
@@ -215,7 +230,45 @@ impl ReadPreference {
         Ok(self)
     }
 
-    pub(crate) fn with_max_staleness(mut self, max_staleness: Duration) -> Result<Self> {
+    /// Sets the max staleness of a non-primary read preference.
+    ///
+    /// Returns an error if the read preference is `Primary` or if `max_staleness` is less than the
+    /// minimum of 90 seconds. A `max_staleness` of zero is treated as unset, so it clears any
+    /// previously set max staleness instead.
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use mongodb::options::{ReadPreference, ReadPreferenceOptions};
+    /// let read_pref = ReadPreference::Secondary {
+    ///     options: ReadPreferenceOptions::default(),
+    /// };
+    /// assert!(read_pref.clone().with_max_staleness(Duration::from_secs(120)).is_ok());
+    /// assert!(read_pref.clone().with_max_staleness(Duration::from_secs(30)).is_err());
+    /// assert!(read_pref.with_max_staleness(Duration::from_secs(0)).is_ok());
+    /// ```
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Result<Self> {
+        if max_staleness == Duration::from_secs(0) {
+            match self {
+                ReadPreference::Primary => {}
+                ReadPreference::Secondary { ref mut options }
+                | ReadPreference::PrimaryPreferred { ref mut options }
+                | ReadPreference::SecondaryPreferred { ref mut options }
+                | ReadPreference::Nearest { ref mut options } => options.max_staleness = None,
+            }
+            return Ok(self);
+        }
+
+        if max_staleness < MIN_MAX_STALENESS {
+            return Err(ErrorKind::ArgumentError {
+                message: format!(
+                    "max staleness must be at least {} seconds, got {:?}",
+                    MIN_MAX_STALENESS.as_secs(),
+                    max_staleness
+                ),
+            }
+            .into());
+        }
+
         let options = match self {
             ReadPreference::Primary => {
                 return Err(ErrorKind::ArgumentError {
@@ -236,6 +289,18 @@ impl ReadPreference {
         Ok(self)
     }
 
+    /// Returns this read preference without its hedge options, which only mongos accepts.
+    pub(crate) fn without_hedge(mut self) -> Self {
+        match self {
+            ReadPreference::Primary => {}
+            ReadPreference::Secondary { ref mut options }
+            | ReadPreference::PrimaryPreferred { ref mut options }
+            | ReadPreference::SecondaryPreferred { ref mut options }
+            | ReadPreference::Nearest { ref mut options } => options.hedge = None,
+        }
+        self
+    }
+
     pub(crate) fn into_document(self) -> Document {
         let (mode, tag_sets, max_staleness, hedge) = match self {
             ReadPreference::Primary => ("primary", None, None, None),
@@ -267,7 +332,10 @@ impl ReadPreference {
 
         let mut doc = doc! { "mode": mode };
 
-        if let Some(max_stale) = max_staleness {
+        // A max staleness of zero means no max staleness, so it isn't sent to the server.
+        if let Some(max_stale) =
+            max_staleness.filter(|max_stale| *max_stale > Duration::from_secs(0))
+        {
             doc.insert("maxStalenessSeconds", max_stale.as_secs());
         }
 
@@ -294,6 +362,8 @@ pub type TagSet = HashMap<String, String>;
 
 #[cfg(test)]
 mod test {
+    use std::time::Duration;
+
     use super::{HedgedReadOptions, ReadPreference, ReadPreferenceOptions};
     use crate::bson::doc;
 
@@ -311,4 +381,23 @@ mod test {
             doc! { "mode": "secondary", "hedge": { "enabled": true } }
         );
     }
+    #[test]
+    fn zero_max_staleness_treated_as_unset() {
+        let options = ReadPreferenceOptions::builder()
+            .max_staleness(Duration::from_secs(0))
+            .build();
+
+        let read_pref = ReadPreference::Secondary { options };
+        assert_eq!(read_pref.max_staleness(), None);
+        assert_eq!(read_pref.into_document(), doc! { "mode": "secondary" });
+
+        let read_pref = ReadPreference::Secondary {
+            options: Default::default(),
+        }
+        .with_max_staleness(Duration::from_secs(120))
+        .unwrap()
+        .with_max_staleness(Duration::from_secs(0))
+        .unwrap();
+        assert_eq!(read_pref.max_staleness(), None);
+    }
 }
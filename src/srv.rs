@@ -1,14 +1,18 @@
+use std::{future::Future, time::Duration};
+
 use trust_dns_resolver::{config::ResolverConfig, error::ResolveErrorKind};
 
 use crate::{
     error::{Error, ErrorKind, Result},
     options::StreamAddress,
     runtime::AsyncResolver,
+    RUNTIME,
 };
 
 pub(crate) struct SrvResolver {
     resolver: AsyncResolver,
     min_ttl: Option<u32>,
+    lookup_timeout: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -19,15 +23,35 @@ pub(crate) struct ResolvedConfig {
 }
 
 impl SrvResolver {
-    pub(crate) async fn new(config: Option<ResolverConfig>) -> Result<Self> {
+    pub(crate) async fn new(
+        config: Option<ResolverConfig>,
+        lookup_timeout: Option<Duration>,
+    ) -> Result<Self> {
         let resolver = AsyncResolver::new(config).await?;
 
         Ok(Self {
             resolver,
             min_ttl: None,
+            lookup_timeout,
         })
     }
 
+    /// Awaits the given lookup, returning the error produced by `timeout_error` if the lookup does
+    /// not complete within the configured lookup timeout.
+    async fn with_timeout<T>(
+        &self,
+        lookup: impl Future<Output = Result<T>>,
+        timeout_error: impl FnOnce(Duration) -> ErrorKind,
+    ) -> Result<T> {
+        match self.lookup_timeout {
+            Some(timeout) => RUNTIME
+                .timeout(timeout, lookup)
+                .await
+                .unwrap_or_else(|_| Err(timeout_error(timeout).into())),
+            None => lookup.await,
+        }
+    }
+
     pub(crate) async fn resolve_client_options(
         &mut self,
         hostname: &str,
@@ -64,7 +88,17 @@ impl SrvResolver {
 
         let lookup_hostname = format!("_mongodb._tcp.{}", original_hostname);
 
-        let srv_lookup = self.resolver.srv_lookup(lookup_hostname.as_str()).await?;
+        let srv_lookup = self
+            .with_timeout(
+                self.resolver.srv_lookup(lookup_hostname.as_str()),
+                |timeout| ErrorKind::SrvLookupError {
+                    message: format!(
+                        "SRV lookup for {} timed out after {:?}",
+                        lookup_hostname, timeout
+                    ),
+                },
+            )
+            .await?;
 
         self.min_ttl = srv_lookup
             .as_lookup()
@@ -125,7 +159,15 @@ impl SrvResolver {
         original_hostname: &str,
         config: &mut ResolvedConfig,
     ) -> Result<()> {
-        let txt_records_response = match self.resolver.txt_lookup(original_hostname).await {
+        let txt_lookup = self.with_timeout(self.resolver.txt_lookup(original_hostname), |timeout| {
+            ErrorKind::TxtLookupError {
+                message: format!(
+                    "TXT lookup for {} timed out after {:?}",
+                    original_hostname, timeout
+                ),
+            }
+        });
+        let txt_records_response = match txt_lookup.await {
             Ok(response) => response,
             Err(e) => return ignore_no_records(e),
         };
@@ -153,43 +195,48 @@ impl SrvResolver {
             .map(|bytes| String::from_utf8_lossy(bytes.as_ref()).into_owned())
             .collect();
 
-        let txt_string = txt_data.join("");
+        parse_txt_options(&txt_data.join(""), config)
+    }
+}
 
-        for option_pair in txt_string.split('&') {
-            let parts: Vec<_> = option_pair.split('=').collect();
+/// Parses the options specified by a TXT record into `config`. Per the initial DNS seedlist
+/// discovery spec, only `authSource` and `replicaSet` may be specified this way; any other option,
+/// e.g. `ssl=false`, results in an error rather than being applied.
+fn parse_txt_options(txt_string: &str, config: &mut ResolvedConfig) -> Result<()> {
+    for option_pair in txt_string.split('&') {
+        let parts: Vec<_> = option_pair.split('=').collect();
 
-            if parts.len() != 2 {
+        if parts.len() != 2 {
+            return Err(ErrorKind::TxtLookupError {
+                message: format!(
+                    "TXT record string '{}' is not a value `key=value` option pair",
+                    option_pair
+                ),
+            }
+            .into());
+        }
+
+        match &parts[0].to_lowercase()[..] {
+            "authsource" => {
+                config.auth_source = Some(parts[1].to_string());
+            }
+            "replicaset" => {
+                config.replica_set = Some(parts[1].into());
+            }
+            other => {
                 return Err(ErrorKind::TxtLookupError {
                     message: format!(
-                        "TXT record string '{}' is not a value `key=value` option pair",
-                        option_pair
+                        "TXT record option '{}' was returned, but only 'authSource' and \
+                         'replicaSet' are allowed",
+                        other
                     ),
                 }
-                .into());
+                .into())
             }
-
-            match &parts[0].to_lowercase()[..] {
-                "authsource" => {
-                    config.auth_source = Some(parts[1].to_string());
-                }
-                "replicaset" => {
-                    config.replica_set = Some(parts[1].into());
-                }
-                other => {
-                    return Err(ErrorKind::TxtLookupError {
-                        message: format!(
-                            "TXT record option '{}' was returned, but only 'authSource' and \
-                             'replicaSet' are allowed",
-                            other
-                        ),
-                    }
-                    .into())
-                }
-            };
-        }
-
-        Ok(())
+        };
     }
+
+    Ok(())
 }
 
 fn ignore_no_records(error: Error) -> Result<()> {
@@ -200,3 +247,44 @@ fn ignore_no_records(error: Error) -> Result<()> {
         _ => Err(error),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{parse_txt_options, ResolvedConfig};
+    use crate::error::ErrorKind;
+
+    fn empty_config() -> ResolvedConfig {
+        ResolvedConfig {
+            hosts: Vec::new(),
+            auth_source: None,
+            replica_set: None,
+        }
+    }
+
+    #[test]
+    fn txt_options() {
+        let mut config = empty_config();
+        parse_txt_options("authSource=thisDB&replicaSet=repl0", &mut config).unwrap();
+        assert_eq!(config.auth_source.as_deref(), Some("thisDB"));
+        assert_eq!(config.replica_set.as_deref(), Some("repl0"));
+
+        for disallowed in &[
+            "ssl=false",
+            "tls=false",
+            "appName=app",
+            "replicaSet=repl0&w=0",
+        ] {
+            let mut config = empty_config();
+            match parse_txt_options(disallowed, &mut config).map_err(|e| e.kind) {
+                Err(kind) if matches!(*kind, ErrorKind::TxtLookupError { .. }) => {}
+                other => panic!(
+                    "expected TXT lookup error for {}, got {:?}",
+                    disallowed, other
+                ),
+            }
+        }
+
+        let mut config = empty_config();
+        assert!(parse_txt_options("authSource", &mut config).is_err());
+    }
+}
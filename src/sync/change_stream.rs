@@ -0,0 +1,80 @@
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    bson::Document,
+    change_stream::{
+        event::{ChangeStreamEvent, ResumeToken},
+        ChangeStream as AsyncChangeStream,
+    },
+    error::Result,
+    RUNTIME,
+};
+
+/// A `ChangeStream` streams the ongoing changes of its associated collection, database or
+/// deployment. `ChangeStream` instances should be created with the `watch` method of the relevant
+/// target, such as [`Client::watch`](struct.Client.html#method.watch).
+///
+/// `ChangeStream` is a wrapper around the asynchronous
+/// [`mongodb::change_stream::ChangeStream`](../change_stream/struct.ChangeStream.html), and it can
+/// be iterated like any other `Iterator`:
+///
+/// ```rust
+/// # use mongodb::{error::Result, sync::Client};
+/// #
+/// # fn func() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com")?;
+/// for event in client.watch(None, None)? {
+///     let event = event?;
+///     println!("operation performed: {:?} on {:?}", event.operation_type, event.ns);
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChangeStream<T = ChangeStreamEvent<Document>>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    async_change_stream: AsyncChangeStream<T>,
+}
+
+impl<T> ChangeStream<T>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    pub(crate) fn new(async_change_stream: AsyncChangeStream<T>) -> Self {
+        Self {
+            async_change_stream,
+        }
+    }
+
+    /// Returns the cached resume token that can be used to resume after the most recently returned
+    /// change.
+    pub fn resume_token(&self) -> Option<&ResumeToken> {
+        self.async_change_stream.resume_token()
+    }
+
+    /// Registers a callback that is run with the resume token of each change before the change is
+    /// returned from the stream. See
+    /// [`mongodb::change_stream::ChangeStream::on_resume_token`](../change_stream/struct.ChangeStream.html#method.on_resume_token)
+    /// for more details.
+    pub fn on_resume_token(
+        self,
+        callback: impl FnMut(&ResumeToken) + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(self.async_change_stream.on_resume_token(callback))
+    }
+}
+
+impl<T> Iterator for ChangeStream<T>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        RUNTIME.block_on(self.async_change_stream.next())
+    }
+}
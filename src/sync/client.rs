@@ -1,10 +1,24 @@
-use super::Database;
+use std::time::Duration;
+
+use super::{ChangeStream, Database};
 use crate::{
     bson::Document,
     concern::{ReadConcern, WriteConcern},
     error::Result,
-    options::{ClientOptions, DatabaseOptions, ListDatabasesOptions, SelectionCriteria},
+    options::{
+        ChangeStreamOptions,
+        ClientOptions,
+        DatabaseOptions,
+        ListDatabasesOptions,
+        SelectionCriteria,
+        SessionOptions,
+    },
+    results::DefaultRwConcern,
     Client as AsyncClient,
+    ClientMetrics,
+    ClientSession,
+    ClusterTime,
+    TopologyDescription,
     RUNTIME,
 };
 
@@ -19,7 +33,7 @@ use crate::{
 /// so it can safely be shared across threads. For example:
 ///
 /// ```rust
-/// # use mongodb::{sync::Client, error::Result};
+/// # use mongodb::{bson::Document, sync::Client, error::Result};
 /// #
 /// # fn start_workers() -> Result<()> {
 /// let client = Client::with_uri_str("mongodb://example.com")?;
@@ -28,7 +42,7 @@ use crate::{
 ///     let client_ref = client.clone();
 ///
 ///     std::thread::spawn(move || {
-///         let collection = client_ref.database("items").collection(&format!("coll{}", i));
+///         let collection = client_ref.database("items").collection::<Document>(&format!("coll{}", i));
 ///
 ///         // Do something with the collection
 ///     });
@@ -127,4 +141,87 @@ impl Client {
                 .list_database_names(filter.into(), options.into()),
         )
     }
+
+    /// Gets the cluster-wide default read and write concerns that the server applies to operations
+    /// that don't specify their own, using the `getDefaultRWConcern` command. Note that this
+    /// command is only supported in MongoDB 4.4 or greater.
+    pub fn server_default_rw_concern(&self) -> Result<DefaultRwConcern> {
+        RUNTIME.block_on(self.async_client.server_default_rw_concern())
+    }
+
+    /// Starts a new [`ChangeStream`](struct.ChangeStream.html) that receives events for all
+    /// changes in the cluster. The stream does not observe changes from system collections or the
+    /// "config", "local" or "admin" databases. Note that this method (`watch` on a cluster) is
+    /// only supported in MongoDB 4.0 or greater.
+    ///
+    /// See [`Client::watch`](../struct.Client.html#method.watch) for more details.
+    pub fn watch(
+        &self,
+        pipeline: impl Into<Option<Vec<Document>>>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream> {
+        RUNTIME
+            .block_on(self.async_client.watch(pipeline.into(), options.into()))
+            .map(ChangeStream::new)
+    }
+
+    /// Starts a new [`ClientSession`](../struct.ClientSession.html) that operations can be executed
+    /// with, e.g. using
+    /// [`Collection::find_one_with_session`](struct.Collection.html#method.find_one_with_session).
+    ///
+    /// Sessions are only supported by replica sets and sharded clusters; an error is returned if
+    /// the deployment doesn't support them.
+    pub fn start_session(
+        &self,
+        options: impl Into<Option<SessionOptions>>,
+    ) -> Result<ClientSession> {
+        RUNTIME.block_on(self.async_client.start_session(options.into()))
+    }
+
+    /// Gets a snapshot of the driver's current view of the topology of the cluster, including the
+    /// topology type and information about each known server.
+    ///
+    /// This does not perform any I/O; it only reports the state most recently observed by the
+    /// driver's monitoring threads.
+    pub fn topology_description(&self) -> TopologyDescription {
+        RUNTIME.block_on(self.async_client.topology_description())
+    }
+
+    /// Gets the highest cluster time that this `Client` has seen in a server response.
+    ///
+    /// See [`Client::cluster_time`](../struct.Client.html#method.cluster_time) for more details.
+    pub fn cluster_time(&self) -> Option<ClusterTime> {
+        RUNTIME.block_on(self.async_client.cluster_time())
+    }
+
+    /// Advances the cluster time that this `Client` sends along with its commands to
+    /// `cluster_time`, if it's greater than the current one.
+    ///
+    /// See [`Client::advance_cluster_time`](../struct.Client.html#method.advance_cluster_time) for
+    /// more details.
+    pub fn advance_cluster_time(&self, cluster_time: &ClusterTime) {
+        RUNTIME.block_on(self.async_client.advance_cluster_time(cluster_time))
+    }
+
+    /// Gets the number of operations currently in progress on this `Client`. Operations that are
+    /// waiting for another operation to finish due to `max_concurrent_operations` are not included.
+    pub fn in_flight_operations(&self) -> usize {
+        self.async_client.in_flight_operations()
+    }
+
+    /// Gets a snapshot of the counters this `Client` maintains about the commands it has sent, the
+    /// errors and retries it has encountered, and the connection pools of the servers currently
+    /// in the topology.
+    ///
+    /// This does not perform any I/O.
+    pub fn metrics(&self) -> ClientMetrics {
+        RUNTIME.block_on(self.async_client.metrics())
+    }
+
+    /// Shuts the `Client` down, releasing the resources it holds on the server and locally.
+    ///
+    /// See [`Client::shutdown`](../struct.Client.html#method.shutdown) for more details.
+    pub fn shutdown(self, timeout: impl Into<Option<Duration>>) {
+        RUNTIME.block_on(self.async_client.shutdown(timeout.into()))
+    }
 }
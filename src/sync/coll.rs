@@ -1,21 +1,27 @@
 use std::{
+    borrow::Borrow,
     fmt::Debug,
     marker::{Send, Sync},
 };
 
 use serde::{de::DeserializeOwned, Serialize};
 
-use super::Cursor;
+use super::{ChangeStream, Cursor, SessionCursor};
 use crate::{
     bson::{Bson, Document},
     error::Result,
     options::{
         AggregateOptions,
+        BulkWriteOptions,
+        ChangeStreamOptions,
         CountOptions,
+        CreateIndexOptions,
         DeleteOptions,
         DistinctOptions,
         DropCollectionOptions,
+        DropIndexOptions,
         EstimatedDocumentCountOptions,
+        ExplainVerbosity,
         FindOneAndDeleteOptions,
         FindOneAndReplaceOptions,
         FindOneAndUpdateOptions,
@@ -23,17 +29,28 @@ use crate::{
         FindOptions,
         InsertManyOptions,
         InsertOneOptions,
+        ListIndexesOptions,
         ReadConcern,
         ReplaceOptions,
         SelectionCriteria,
         UpdateModifications,
         UpdateOptions,
+        ValidateOptions,
         WriteConcern,
+        WriteModel,
     },
-    results::{DeleteResult, InsertManyResult, InsertOneResult, UpdateResult},
-    Collection as AsyncCollection,
-    Namespace,
-    RUNTIME,
+    results::{
+        BulkWriteResult,
+        CreateIndexResult,
+        CreateIndexesResult,
+        DeleteResult,
+        InsertManyResult,
+        InsertOneResult,
+        LatencyStats,
+        UpdateResult,
+        ValidationResult,
+    },
+    ClientSession, Collection as AsyncCollection, IndexModel, Namespace, RUNTIME,
 };
 
 /// `Collection` is the client-side abstraction of a MongoDB Collection. It can be used to
@@ -146,7 +163,50 @@ where
             .map(Cursor::new)
     }
 
-    /// Estimates the number of documents in the collection using collection metadata.
+    /// Runs an aggregation operation using the provided `ClientSession`.
+    ///
+    /// The returned [`SessionCursor`](struct.SessionCursor.html) must be iterated using the same
+    /// session. See [`aggregate`](#method.aggregate) for more information on aggregations.
+    pub fn aggregate_with_session(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<SessionCursor> {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME
+            .block_on(self.async_collection.aggregate_with_session(
+                pipeline,
+                options.into(),
+                session,
+            ))
+            .map(SessionCursor::new)
+    }
+
+    /// Performs the writes in `requests`, which may be any mix of inserts, updates, replacements
+    /// and deletes.
+    ///
+    /// If [`ordered`](options/struct.BulkWriteOptions.html#structfield.ordered) is true (the
+    /// default), the operation stops at the first write that fails; otherwise the remaining writes
+    /// are still attempted. Either way, a failure is returned as an `ErrorKind::BulkWriteError`
+    /// whose [`partial_result`](error/struct.BulkWriteFailure.html#structfield.partial_result)
+    /// holds the counts of the writes that succeeded.
+    pub fn bulk_write(
+        &self,
+        requests: impl IntoIterator<Item = WriteModel<T>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+    ) -> Result<BulkWriteResult> {
+        let requests: Vec<WriteModel<T>> = requests.into_iter().collect();
+        RUNTIME.block_on(self.async_collection.bulk_write(requests, options.into()))
+    }
+
+    /// Estimates the number of documents in the collection using collection metadata, which is
+    /// fast regardless of the size of the collection. The result may be inaccurate, e.g. right
+    /// after an unclean shutdown or while bulk operations or orphaned documents in a sharded
+    /// cluster are being processed; use
+    /// [`Collection::count_documents`](#method.count_documents) when an exact count is needed.
+    ///
+    /// The count is retried once as a retryable read if it fails due to a transient error.
     pub fn estimated_document_count(
         &self,
         options: impl Into<Option<EstimatedDocumentCountOptions>>,
@@ -159,8 +219,11 @@ where
 
     /// Gets the number of documents matching `filter`.
     ///
-    /// Note that using [`Collection::estimated_document_count`](#method.estimated_document_count)
-    /// is recommended instead of this method is most cases.
+    /// The count is exact, as it is computed by an aggregation with `$match` and `$group` stages
+    /// that scans the matching documents; for large collections this can be slow. When a fast
+    /// estimate of the total number of documents is enough,
+    /// [`Collection::estimated_document_count`](#method.estimated_document_count) should be used
+    /// instead.
     pub fn count_documents(
         &self,
         filter: impl Into<Option<Document>>,
@@ -172,6 +235,42 @@ where
         )
     }
 
+    /// Gets the number of documents matching `filter` using the provided `ClientSession`.
+    pub fn count_documents_with_session(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<CountOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<i64> {
+        RUNTIME.block_on(self.async_collection.count_documents_with_session(
+            filter.into(),
+            options.into(),
+            session,
+        ))
+    }
+
+    /// Creates the given index on this collection.
+    pub fn create_index(
+        &self,
+        index: IndexModel,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<CreateIndexResult> {
+        RUNTIME.block_on(self.async_collection.create_index(index, options.into()))
+    }
+
+    /// Creates the given indexes on this collection.
+    pub fn create_indexes(
+        &self,
+        indexes: impl IntoIterator<Item = IndexModel>,
+        options: impl Into<Option<CreateIndexOptions>>,
+    ) -> Result<CreateIndexesResult> {
+        let indexes: Vec<IndexModel> = indexes.into_iter().collect();
+        RUNTIME.block_on(
+            self.async_collection
+                .create_indexes(indexes, options.into()),
+        )
+    }
+
     /// Deletes all documents stored in the collection matching `query`.
     pub fn delete_many(
         &self,
@@ -181,6 +280,21 @@ where
         RUNTIME.block_on(self.async_collection.delete_many(query, options.into()))
     }
 
+    /// Deletes all documents stored in the collection matching `query` using the provided
+    /// `ClientSession`.
+    pub fn delete_many_with_session(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<DeleteResult> {
+        RUNTIME.block_on(self.async_collection.delete_many_with_session(
+            query,
+            options.into(),
+            session,
+        ))
+    }
+
     /// Deletes up to one document found matching `query`.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
@@ -195,6 +309,20 @@ where
         RUNTIME.block_on(self.async_collection.delete_one(query, options.into()))
     }
 
+    /// Deletes up to one document found matching `query` using the provided `ClientSession`.
+    pub fn delete_one_with_session(
+        &self,
+        query: Document,
+        options: impl Into<Option<DeleteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<DeleteResult> {
+        RUNTIME.block_on(self.async_collection.delete_one_with_session(
+            query,
+            options.into(),
+            session,
+        ))
+    }
+
     /// Finds the distinct values of the field specified by `field_name` across the collection.
     pub fn distinct(
         &self,
@@ -208,6 +336,83 @@ where
         )
     }
 
+    /// Finds the distinct values of the field specified by `field_name` across the collection using
+    /// the provided `ClientSession`.
+    pub fn distinct_with_session(
+        &self,
+        field_name: &str,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<DistinctOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<Vec<Bson>> {
+        RUNTIME.block_on(self.async_collection.distinct_with_session(
+            field_name,
+            filter.into(),
+            options.into(),
+            session,
+        ))
+    }
+
+    /// Drops the index with the given `name` from this collection. The name of an index created
+    /// without one specified is the one returned by
+    /// [`IndexModel::name`](../index/struct.IndexModel.html#method.name).
+    pub fn drop_index(
+        &self,
+        name: &str,
+        options: impl Into<Option<DropIndexOptions>>,
+    ) -> Result<()> {
+        RUNTIME.block_on(self.async_collection.drop_index(name, options.into()))
+    }
+
+    /// Drops all of the indexes on this collection, except for the one on `_id`.
+    pub fn drop_indexes(&self, options: impl Into<Option<DropIndexOptions>>) -> Result<()> {
+        RUNTIME.block_on(self.async_collection.drop_indexes(options.into()))
+    }
+
+    /// Returns whether any documents in the collection match `filter`.
+    ///
+    /// This is cheaper than `find_one` for existence checks, as only the `_id` of at most one
+    /// matching document is returned by the server, and no documents are deserialized into `T`.
+    pub fn exists(&self, filter: impl Into<Option<Document>>) -> Result<bool> {
+        RUNTIME.block_on(self.async_collection.exists(filter.into()))
+    }
+
+    /// Reports how the server would execute an aggregation with the given `pipeline` and
+    /// `options`, without running it. The raw output of the server's `explain` command is
+    /// returned; its contents are described
+    /// [here](https://docs.mongodb.com/manual/reference/explain-results/).
+    ///
+    /// Any read concern or write concern in `options` is ignored, since they can't be explained.
+    pub fn explain_aggregate(
+        &self,
+        pipeline: impl IntoIterator<Item = Document>,
+        options: impl Into<Option<AggregateOptions>>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<Document> {
+        let pipeline: Vec<Document> = pipeline.into_iter().collect();
+        RUNTIME.block_on(self.async_collection.explain_aggregate(
+            pipeline,
+            options.into(),
+            verbosity,
+        ))
+    }
+
+    /// Reports how the server would execute a find with the given `filter` and `options`, without
+    /// running it. The raw output of the server's `explain` command is returned; its contents are
+    /// described [here](https://docs.mongodb.com/manual/reference/explain-results/).
+    pub fn explain_find(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOptions>>,
+        verbosity: ExplainVerbosity,
+    ) -> Result<Document> {
+        RUNTIME.block_on(self.async_collection.explain_find(
+            filter.into(),
+            options.into(),
+            verbosity,
+        ))
+    }
+
     /// Finds the documents in the collection matching `filter`.
     pub fn find(
         &self,
@@ -219,6 +424,26 @@ where
             .map(Cursor::new)
     }
 
+    /// Finds the documents in the collection matching `filter` using the provided
+    /// `ClientSession`.
+    ///
+    /// The returned [`SessionCursor`](struct.SessionCursor.html) must be iterated using the same
+    /// session.
+    pub fn find_with_session(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<SessionCursor<T>> {
+        RUNTIME
+            .block_on(self.async_collection.find_with_session(
+                filter.into(),
+                options.into(),
+                session,
+            ))
+            .map(SessionCursor::new)
+    }
+
     /// Finds a single document in the collection matching `filter`.
     pub fn find_one(
         &self,
@@ -231,6 +456,21 @@ where
         )
     }
 
+    /// Finds a single document in the collection matching `filter` using the provided
+    /// `ClientSession`.
+    pub fn find_one_with_session(
+        &self,
+        filter: impl Into<Option<Document>>,
+        options: impl Into<Option<FindOneOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<Option<T>> {
+        RUNTIME.block_on(self.async_collection.find_one_with_session(
+            filter.into(),
+            options.into(),
+            session,
+        ))
+    }
+
     /// Atomically finds up to one document in the collection matching `filter` and deletes it.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
@@ -258,12 +498,12 @@ where
     pub fn find_one_and_replace(
         &self,
         filter: Document,
-        replacement: T,
+        replacement: impl Borrow<T>,
         options: impl Into<Option<FindOneAndReplaceOptions>>,
     ) -> Result<Option<T>> {
         RUNTIME.block_on(self.async_collection.find_one_and_replace(
             filter,
-            replacement,
+            replacement.borrow(),
             options.into(),
         ))
     }
@@ -298,13 +538,30 @@ where
     /// retryable writes.
     pub fn insert_many(
         &self,
-        docs: impl IntoIterator<Item = T>,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
         options: impl Into<Option<InsertManyOptions>>,
     ) -> Result<InsertManyResult> {
-        let docs: Vec<T> = docs.into_iter().collect();
+        let docs: Vec<_> = docs.into_iter().collect();
+        let docs: Vec<&T> = docs.iter().map(Borrow::borrow).collect();
         RUNTIME.block_on(self.async_collection.insert_many(docs, options.into()))
     }
 
+    /// Inserts the data in `docs` into the collection using the provided `ClientSession`.
+    pub fn insert_many_with_session(
+        &self,
+        docs: impl IntoIterator<Item = impl Borrow<T>>,
+        options: impl Into<Option<InsertManyOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<InsertManyResult> {
+        let docs: Vec<_> = docs.into_iter().collect();
+        let docs: Vec<&T> = docs.iter().map(Borrow::borrow).collect();
+        RUNTIME.block_on(self.async_collection.insert_many_with_session(
+            docs,
+            options.into(),
+            session,
+        ))
+    }
+
     /// Inserts `doc` into the collection.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
@@ -313,10 +570,38 @@ where
     /// retryable writes.
     pub fn insert_one(
         &self,
-        doc: T,
+        doc: impl Borrow<T>,
         options: impl Into<Option<InsertOneOptions>>,
     ) -> Result<InsertOneResult> {
-        RUNTIME.block_on(self.async_collection.insert_one(doc, options.into()))
+        RUNTIME.block_on(
+            self.async_collection
+                .insert_one(doc.borrow(), options.into()),
+        )
+    }
+
+    /// Inserts `doc` into the collection using the provided `ClientSession`.
+    pub fn insert_one_with_session(
+        &self,
+        doc: impl Borrow<T>,
+        options: impl Into<Option<InsertOneOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<InsertOneResult> {
+        RUNTIME.block_on(self.async_collection.insert_one_with_session(
+            doc.borrow(),
+            options.into(),
+            session,
+        ))
+    }
+
+    /// Lists the indexes on this collection. The returned cursor is empty if the collection
+    /// doesn't exist.
+    pub fn list_indexes(
+        &self,
+        options: impl Into<Option<ListIndexesOptions>>,
+    ) -> Result<Cursor<IndexModel>> {
+        RUNTIME
+            .block_on(self.async_collection.list_indexes(options.into()))
+            .map(Cursor::new)
     }
 
     /// Replaces up to one document matching `query` in the collection with `replacement`.
@@ -328,13 +613,31 @@ where
     pub fn replace_one(
         &self,
         query: Document,
-        replacement: T,
+        replacement: impl Borrow<T>,
         options: impl Into<Option<ReplaceOptions>>,
     ) -> Result<UpdateResult> {
-        RUNTIME.block_on(
-            self.async_collection
-                .replace_one(query, replacement, options.into()),
-        )
+        RUNTIME.block_on(self.async_collection.replace_one(
+            query,
+            replacement.borrow(),
+            options.into(),
+        ))
+    }
+
+    /// Replaces up to one document matching `query` in the collection with `replacement` using the
+    /// provided `ClientSession`.
+    pub fn replace_one_with_session(
+        &self,
+        query: Document,
+        replacement: impl Borrow<T>,
+        options: impl Into<Option<ReplaceOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<UpdateResult> {
+        RUNTIME.block_on(self.async_collection.replace_one_with_session(
+            query,
+            replacement.borrow(),
+            options.into(),
+            session,
+        ))
     }
 
     /// Updates all documents matching `query` in the collection.
@@ -355,6 +658,23 @@ where
         )
     }
 
+    /// Updates all documents matching `query` in the collection using the provided
+    /// `ClientSession`.
+    pub fn update_many_with_session(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<UpdateResult> {
+        RUNTIME.block_on(self.async_collection.update_many_with_session(
+            query,
+            update.into(),
+            options.into(),
+            session,
+        ))
+    }
+
     /// Updates up to one document matching `query` in the collection.
     ///
     /// Both `Document` and `Vec<Document>` implement `Into<UpdateModifications>`, so either can be
@@ -377,4 +697,61 @@ where
                 .update_one(query, update.into(), options.into()),
         )
     }
+
+    /// Updates up to one document matching `query` in the collection using the provided
+    /// `ClientSession`.
+    pub fn update_one_with_session(
+        &self,
+        query: Document,
+        update: impl Into<UpdateModifications>,
+        options: impl Into<Option<UpdateOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<UpdateResult> {
+        RUNTIME.block_on(self.async_collection.update_one_with_session(
+            query,
+            update.into(),
+            options.into(),
+            session,
+        ))
+    }
+
+    /// Checks the collection's data and indexes for correctness using the `validate` command.
+    ///
+    /// Note that an `Ok` result does not mean that the collection is valid; check
+    /// [`ValidationResult::valid`](../results/struct.ValidationResult.html#structfield.valid) for
+    /// that. See the documentation
+    /// [here](https://docs.mongodb.com/manual/reference/command/validate/) for more information on
+    /// validating collections.
+    pub fn validate(
+        &self,
+        options: impl Into<Option<ValidateOptions>>,
+    ) -> Result<ValidationResult> {
+        RUNTIME.block_on(self.async_collection.validate(options.into()))
+    }
+
+    /// Starts a new [`ChangeStream`](struct.ChangeStream.html) that receives events for all
+    /// changes in this collection. Note that change streams are only supported on replica sets and
+    /// sharded clusters.
+    ///
+    /// See [`Collection::watch`](../struct.Collection.html#method.watch) for more details.
+    pub fn watch(
+        &self,
+        pipeline: impl Into<Option<Vec<Document>>>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream> {
+        RUNTIME
+            .block_on(self.async_collection.watch(pipeline.into(), options.into()))
+            .map(ChangeStream::new)
+    }
+
+    /// Gets the latency statistics of the collection, using the `$collStats` aggregation stage with
+    /// `latencyStats` and histograms enabled. For a sharded collection, the statistics reported by
+    /// each shard are combined. Note that `$collStats` is only supported in MongoDB 3.4 or greater.
+    ///
+    /// See the documentation
+    /// [here](https://docs.mongodb.com/manual/reference/operator/aggregation/collStats/) for more
+    /// information on `$collStats`.
+    pub fn latency_stats(&self) -> Result<LatencyStats> {
+        RUNTIME.block_on(self.async_collection.latency_stats())
+    }
 }
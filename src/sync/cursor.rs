@@ -1,7 +1,14 @@
 use futures::StreamExt;
 use serde::de::DeserializeOwned;
 
-use crate::{bson::Document, error::Result, Cursor as AsyncCursor, RUNTIME};
+use crate::{
+    bson::Document,
+    error::Result,
+    ClientSession,
+    Cursor as AsyncCursor,
+    SessionCursor as AsyncSessionCursor,
+    RUNTIME,
+};
 
 /// A `Cursor` streams the result of a query. When a query is made, a `Cursor` will be returned with
 /// the first batch of results from the server; the documents will be returned as the `Cursor` is
@@ -24,11 +31,11 @@ use crate::{bson::Document, error::Result, Cursor as AsyncCursor, RUNTIME};
 /// documents it yields:
 ///
 /// ```rust
-/// # use mongodb::{sync::Client, error::Result};
+/// # use mongodb::{bson::Document, sync::Client, error::Result};
 /// #
 /// # fn do_stuff() -> Result<()> {
 /// # let client = Client::with_uri_str("mongodb://example.com")?;
-/// # let coll = client.database("foo").collection("bar");
+/// # let coll = client.database("foo").collection::<Document>("bar");
 /// # let mut cursor = coll.find(None, None)?;
 /// #
 /// for doc in cursor {
@@ -53,7 +60,7 @@ use crate::{bson::Document, error::Result, Cursor as AsyncCursor, RUNTIME};
 /// #
 /// # fn do_stuff() -> Result<()> {
 /// # let client = Client::with_uri_str("mongodb://example.com")?;
-/// # let coll = client.database("foo").collection("bar");
+/// # let coll = client.database("foo").collection::<Document>("bar");
 /// # let cursor = coll.find(Some(doc! { "x": 1 }), None)?;
 /// #
 /// let results: Vec<Result<Document>> = cursor.collect();
@@ -75,6 +82,22 @@ where
     pub(crate) fn new(async_cursor: AsyncCursor<T>) -> Self {
         Self { async_cursor }
     }
+
+    /// Sets the number of documents the server should return per batch for the `getMore` commands
+    /// issued by this cursor from now on.
+    ///
+    /// Returns an error if `batch_size` is zero, since a batch size of zero is only meaningful for
+    /// the command that creates the cursor, or if it does not fit in a signed 32-bit integer.
+    pub fn set_batch_size(&mut self, batch_size: u32) -> Result<()> {
+        self.async_cursor.set_batch_size(batch_size)
+    }
+
+    /// Returns all of the documents remaining in the batch most recently received from the server,
+    /// first fetching the next batch if the current one has been fully iterated. Returns `None`
+    /// once the cursor is exhausted.
+    pub fn try_next_batch(&mut self) -> Result<Option<Vec<T>>> {
+        RUNTIME.block_on(self.async_cursor.try_next_batch())
+    }
 }
 
 impl<T> Iterator for Cursor<T>
@@ -87,3 +110,82 @@ where
         RUNTIME.block_on(self.async_cursor.next())
     }
 }
+
+/// A `SessionCursor` streams the result of a query that was run in a
+/// [`ClientSession`](../struct.ClientSession.html), such as one returned by
+/// [`Collection::find_with_session`](struct.Collection.html#method.find_with_session). Its
+/// `getMore`s are run in the same session, so the session has to be passed in each time the
+/// cursor is iterated:
+///
+/// ```rust
+/// # use mongodb::{bson::Document, sync::Client, error::Result};
+/// #
+/// # fn do_stuff() -> Result<()> {
+/// # let client = Client::with_uri_str("mongodb://example.com")?;
+/// # let coll = client.database("foo").collection::<Document>("bar");
+/// let mut session = client.start_session(None)?;
+/// let mut cursor = coll.find_with_session(None, None, &mut session)?;
+///
+/// for doc in cursor.iter(&mut session) {
+///   println!("{}", doc?)
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SessionCursor<T = Document>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    async_cursor: AsyncSessionCursor<T>,
+}
+
+impl<T> SessionCursor<T>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    pub(crate) fn new(async_cursor: AsyncSessionCursor<T>) -> Self {
+        Self { async_cursor }
+    }
+
+    /// Returns an iterator over the remaining results of the cursor which runs any `getMore`s in
+    /// `session`. The cursor keeps its position when the iterator is dropped.
+    pub fn iter<'session>(
+        &mut self,
+        session: &'session mut ClientSession,
+    ) -> SessionCursorIter<'_, 'session, T> {
+        SessionCursorIter {
+            session_cursor: self,
+            session,
+        }
+    }
+
+    /// Returns the next result of the cursor, running a `getMore` in `session` if the current
+    /// batch has been fully iterated. Returns `None` once the cursor is exhausted.
+    pub fn next(&mut self, session: &mut ClientSession) -> Option<Result<T>> {
+        RUNTIME.block_on(self.async_cursor.next(session))
+    }
+}
+
+/// An iterator over the results of a [`SessionCursor`](struct.SessionCursor.html) that borrows
+/// the `ClientSession` the cursor was created in. It's returned by
+/// [`SessionCursor::iter`](struct.SessionCursor.html#method.iter).
+pub struct SessionCursorIter<'cursor, 'session, T = Document>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    session_cursor: &'cursor mut SessionCursor<T>,
+    session: &'session mut ClientSession,
+}
+
+impl<'cursor, 'session, T> Iterator for SessionCursorIter<'cursor, 'session, T>
+where
+    T: DeserializeOwned + Unpin + Send,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.session_cursor.next(self.session)
+    }
+}
@@ -1,25 +1,31 @@
 use std::{
+    collections::HashMap,
     fmt::Debug,
     marker::{Send, Sync},
 };
 
 use serde::{de::DeserializeOwned, Serialize};
 
-use super::{Collection, Cursor};
+use super::{ChangeStream, Collection, Cursor};
 use crate::{
     bson::Document,
     error::Result,
     options::{
         AggregateOptions,
+        ChangeStreamOptions,
         CollectionOptions,
         CreateCollectionOptions,
+        CreateIndexesBulkOptions,
         DropDatabaseOptions,
         ListCollectionsOptions,
         ReadConcern,
+        RunCursorCommandOptions,
         SelectionCriteria,
         WriteConcern,
     },
+    results::CreateIndexesResult,
     Database as AsyncDatabase,
+    IndexModel,
     RUNTIME,
 };
 
@@ -33,7 +39,7 @@ use crate::{
 /// so it can safely be shared across threads. For example:
 ///
 /// ```rust
-/// # use mongodb::{sync::Client, error::Result};
+/// # use mongodb::{bson::Document, sync::Client, error::Result};
 ///
 /// # fn start_workers() -> Result<()> {
 /// # let client = Client::with_uri_str("mongodb://example.com")?;
@@ -43,7 +49,7 @@ use crate::{
 ///     let db_ref = db.clone();
 ///
 ///     std::thread::spawn(move || {
-///         let collection = db_ref.collection(&format!("coll{}", i));
+///         let collection = db_ref.collection::<Document>(&format!("coll{}", i));
 ///
 ///         // Do something with the collection
 ///     });
@@ -88,33 +94,18 @@ impl Database {
     /// Gets a handle to a collection specified by `name` of the database. The `Collection` options
     /// (e.g. read preference and write concern) will default to those of the `Database`.
     ///
-    /// This method does not send or receive anything across the wire to the database, so it can be
-    /// used repeatedly without incurring any costs from I/O.
-    pub fn collection(&self, name: &str) -> Collection {
-        Collection::new(self.async_database.collection(name))
-    }
-
-    /// Gets a handle to a collection with type `T` specified by `name` of the database. The
-    /// `Collection` options (e.g. read preference and write concern) will default to those of the
-    /// `Database`.
+    /// The type `T` is the type that the documents in the collection are serialized from and
+    /// deserialized into, e.g. `db.collection::<Document>("coll")` for untyped documents or
+    /// `db.collection::<User>("users")` for a struct `User` implementing `Serialize` and
+    /// `Deserialize`.
     ///
     /// This method does not send or receive anything across the wire to the database, so it can be
     /// used repeatedly without incurring any costs from I/O.
-    pub fn collection_with_type<T>(&self, name: &str) -> Collection<T>
+    pub fn collection<T>(&self, name: &str) -> Collection<T>
     where
         T: Serialize + DeserializeOwned + Unpin + Debug + Send + Sync,
     {
-        Collection::new(self.async_database.collection_with_type(name))
-    }
-
-    /// Gets a handle to a collection specified by `name` in the cluster the `Client` is connected
-    /// to. Operations done with this `Collection` will use the options specified by `options` by
-    /// default and will otherwise default to those of the `Database`.
-    ///
-    /// This method does not send or receive anything across the wire to the database, so it can be
-    /// used repeatedly without incurring any costs from I/O.
-    pub fn collection_with_options(&self, name: &str, options: CollectionOptions) -> Collection {
-        Collection::new(self.async_database.collection_with_options(name, options))
+        Collection::new(self.async_database.collection(name))
     }
 
     /// Gets a handle to a collection with type `T` specified by `name` in the cluster the `Client`
@@ -123,7 +114,7 @@ impl Database {
     ///
     /// This method does not send or receive anything across the wire to the database, so it can be
     /// used repeatedly without incurring any costs from I/O.
-    pub fn collection_with_type_and_options<T>(
+    pub fn collection_with_options<T>(
         &self,
         name: &str,
         options: CollectionOptions,
@@ -131,10 +122,7 @@ impl Database {
     where
         T: Serialize + DeserializeOwned + Unpin + Debug + Send + Sync,
     {
-        Collection::new(
-            self.async_database
-                .collection_with_type_and_options(name, options),
-        )
+        Collection::new(self.async_database.collection_with_options(name, options))
     }
 
     /// Drops the database, deleting all data, collections, users, and indexes stored in it.
@@ -177,6 +165,23 @@ impl Database {
         RUNTIME.block_on(self.async_database.create_collection(name, options.into()))
     }
 
+    /// Creates indexes on multiple collections in the database. `specs` maps the name of each
+    /// collection to the indexes that should be created on it.
+    ///
+    /// See the documentation of
+    /// [`Database::create_indexes_bulk`](../struct.Database.html#method.create_indexes_bulk) for
+    /// more details.
+    pub fn create_indexes_bulk(
+        &self,
+        specs: HashMap<String, Vec<IndexModel>>,
+        options: impl Into<Option<CreateIndexesBulkOptions>>,
+    ) -> HashMap<String, Result<CreateIndexesResult>> {
+        RUNTIME.block_on(
+            self.async_database
+                .create_indexes_bulk(specs, options.into()),
+        )
+    }
+
     /// Runs a database-level command.
     ///
     /// Note that no inspection is done on `doc`, so the command will not use the database's default
@@ -193,6 +198,24 @@ impl Database {
         )
     }
 
+    /// Runs a database-level command that returns a cursor, such as `listCollections` or
+    /// `listIndexes`, and returns a `Cursor` over the results.
+    ///
+    /// See [`Database::run_cursor_command`](../struct.Database.html#method.run_cursor_command) for
+    /// more details.
+    pub fn run_cursor_command(
+        &self,
+        command: Document,
+        options: impl Into<Option<RunCursorCommandOptions>>,
+    ) -> Result<Cursor> {
+        RUNTIME
+            .block_on(
+                self.async_database
+                    .run_cursor_command(command, options.into()),
+            )
+            .map(Cursor::new)
+    }
+
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
@@ -207,4 +230,19 @@ impl Database {
             .block_on(self.async_database.aggregate(pipeline, options.into()))
             .map(Cursor::new)
     }
+
+    /// Starts a new [`ChangeStream`](struct.ChangeStream.html) that receives events for all
+    /// changes in the non-system collections of this database. Note that this method (`watch` on
+    /// a database) is only supported in MongoDB 4.0 or greater.
+    ///
+    /// See [`Database::watch`](../struct.Database.html#method.watch) for more details.
+    pub fn watch(
+        &self,
+        pipeline: impl Into<Option<Vec<Document>>>,
+        options: impl Into<Option<ChangeStreamOptions>>,
+    ) -> Result<ChangeStream> {
+        RUNTIME
+            .block_on(self.async_database.watch(pipeline.into(), options.into()))
+            .map(ChangeStream::new)
+    }
 }
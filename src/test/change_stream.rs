@@ -0,0 +1,392 @@
+use std::sync::{Arc, Mutex};
+
+use futures::stream::StreamExt;
+use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{
+    bson::doc,
+    change_stream::event::{ChangeStreamEventNamespace, OperationType, ResumeToken},
+    error::{CommandError, Error, ErrorKind},
+    options::{ChangeStreamOptions, FullDocumentType},
+    test::{
+        EventClient,
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        TestClient,
+        LOCK,
+    },
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn cluster_change_stream_filters_by_namespace() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let watched = client
+        .init_db_and_coll(function_name!(), "watched")
+        .await;
+    let ignored = client
+        .init_db_and_coll(function_name!(), "ignored")
+        .await;
+
+    let pipeline = vec![doc! {
+        "$match": { "ns.db": function_name!(), "ns.coll": "watched" }
+    }];
+    let mut change_stream = client.watch(pipeline, None).await.unwrap();
+
+    ignored.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    watched.insert_one(doc! { "x": 2 }, None).await.unwrap();
+
+    let event = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(event.operation_type, OperationType::Insert);
+    assert_eq!(
+        event.ns,
+        Some(ChangeStreamEventNamespace {
+            db: function_name!().to_string(),
+            coll: Some("watched".to_string()),
+        })
+    );
+    assert_eq!(
+        event.full_document.and_then(|doc| doc.get_i32("x").ok()),
+        Some(2)
+    );
+    assert_eq!(change_stream.resume_token(), Some(&event.id));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn resume_token_callback() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let pipeline = vec![doc! { "$match": { "ns.db": function_name!() } }];
+    let tokens: Arc<Mutex<Vec<ResumeToken>>> = Default::default();
+    let callback_tokens = tokens.clone();
+    let mut change_stream = client
+        .watch(pipeline, None)
+        .await
+        .unwrap()
+        .on_resume_token(move |token| callback_tokens.lock().unwrap().push(token.clone()));
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.insert_one(doc! { "x": 2 }, None).await.unwrap();
+
+    let first = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(*tokens.lock().unwrap(), vec![first.id.clone()]);
+
+    let second = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(*tokens.lock().unwrap(), vec![first.id, second.id]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn resumes_after_resumable_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+    if !client.supports_fail_command().await {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let pipeline = vec![doc! { "$match": { "ns.db": function_name!() } }];
+    let mut change_stream = client.watch(pipeline, None).await.unwrap();
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    change_stream.next().await.unwrap().unwrap();
+
+    let options = FailCommandOptions::builder().error_code(43).build();
+    let failpoint = FailPoint::fail_command(&["getMore"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    coll.insert_one(doc! { "x": 2 }, None).await.unwrap();
+    let second = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        second.full_document.and_then(|doc| doc.get_i32("x").ok()),
+        Some(2)
+    );
+
+    let aggregates = client.get_command_started_events("aggregate");
+    assert_eq!(aggregates.len(), 2);
+    let resume_stage = aggregates[1].command.get_array("pipeline").unwrap()[0]
+        .as_document()
+        .unwrap()
+        .get_document("$changeStream")
+        .unwrap();
+    assert!(resume_stage.contains_key("resumeAfter"));
+    assert!(!resume_stage.contains_key("startAtOperationTime"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn resumes_once_per_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+    if !client.supports_fail_command().await {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let pipeline = vec![doc! { "$match": { "ns.db": function_name!() } }];
+    let mut change_stream = client.watch(pipeline, None).await.unwrap();
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    change_stream.next().await.unwrap().unwrap();
+
+    // The getMore of the resumed stream fails the same way, so the error is returned rather than
+    // resuming again.
+    let options = FailCommandOptions::builder().error_code(43).build();
+    let failpoint = FailPoint::fail_command(&["getMore"], FailPointMode::Times(2), options);
+    let _fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    let error = change_stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::CommandError(ref err) if err.code == 43));
+    assert_eq!(client.get_command_started_events("aggregate").len(), 2);
+}
+
+#[test]
+fn resumable_errors_by_wire_version() {
+    let error = Error::from(ErrorKind::CommandError(CommandError {
+        code: 43,
+        code_name: "CursorNotFound".to_string(),
+        message: "cursor not found".to_string(),
+        labels: Default::default(),
+    }));
+
+    // Servers before 4.4 don't label resumable errors, so their codes are checked.
+    assert!(error.is_resumable(Some(8)));
+    assert!(error.is_resumable(None));
+
+    // Newer servers label every resumable error, so an unlabeled error isn't resumable.
+    assert!(!error.is_resumable(Some(9)));
+    assert!(error
+        .with_label("ResumableChangeStreamError")
+        .is_resumable(Some(9)));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn resumes_before_any_events_from_initial_response() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+    if !client.supports_fail_command().await {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = FailCommandOptions::builder().error_code(43).build();
+    let failpoint = FailPoint::fail_command(&["getMore"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    let mut change_stream = coll.watch(None, None).await.unwrap();
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    change_stream.next().await.unwrap().unwrap();
+
+    let (_, initial) = client.get_successful_command_execution("aggregate");
+    let aggregates = client.get_command_started_events("aggregate");
+    assert_eq!(aggregates.len(), 2);
+    let resume_stage = aggregates[1].command.get_array("pipeline").unwrap()[0]
+        .as_document()
+        .unwrap()
+        .get_document("$changeStream")
+        .unwrap();
+
+    // Servers from 4.0.7 on report a postBatchResumeToken, which takes precedence over the
+    // operation time of the initial aggregation.
+    match initial
+        .reply
+        .get_document("cursor")
+        .unwrap()
+        .get("postBatchResumeToken")
+    {
+        Some(token) => assert_eq!(resume_stage.get("resumeAfter"), Some(token)),
+        None => assert_eq!(
+            resume_stage.get_timestamp("startAtOperationTime").ok(),
+            initial.reply.get_timestamp("operationTime").ok()
+        ),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn start_after() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 2) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let pipeline = vec![doc! { "$match": { "ns.db": function_name!() } }];
+    let mut change_stream = client.watch(pipeline.clone(), None).await.unwrap();
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.insert_one(doc! { "x": 2 }, None).await.unwrap();
+    let first = change_stream.next().await.unwrap().unwrap();
+    drop(change_stream);
+
+    let options = ChangeStreamOptions::builder()
+        .start_after(Some(first.id))
+        .build();
+    let mut change_stream = client.watch(pipeline, options).await.unwrap();
+    let next = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        next.full_document.and_then(|doc| doc.get_i32("x").ok()),
+        Some(2)
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn database_change_stream() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let first = client.init_db_and_coll(function_name!(), "first").await;
+    let second = client.init_db_and_coll(function_name!(), "second").await;
+    let other_db = client
+        .init_db_and_coll(&format!("{}_other", function_name!()), "first")
+        .await;
+
+    let db = client.database(function_name!());
+    let mut change_stream = db.watch(None, None).await.unwrap();
+
+    other_db.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    first.insert_one(doc! { "x": 2 }, None).await.unwrap();
+    second.insert_one(doc! { "x": 3 }, None).await.unwrap();
+
+    for (coll, x) in &[("first", 2), ("second", 3)] {
+        let event = change_stream.next().await.unwrap().unwrap();
+        assert_eq!(event.operation_type, OperationType::Insert);
+        assert_eq!(
+            event.ns,
+            Some(ChangeStreamEventNamespace {
+                db: function_name!().to_string(),
+                coll: Some(coll.to_string()),
+            })
+        );
+        assert_eq!(
+            event.full_document.and_then(|doc| doc.get_i32("x").ok()),
+            Some(*x)
+        );
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn collection_change_stream() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let watched = client.init_db_and_coll(function_name!(), "watched").await;
+    let ignored = client.init_db_and_coll(function_name!(), "ignored").await;
+
+    let options = ChangeStreamOptions::builder()
+        .full_document(Some(FullDocumentType::UpdateLookup))
+        .build();
+    let mut change_stream = watched.watch(None, options).await.unwrap();
+
+    ignored.insert_one(doc! { "_id": 1 }, None).await.unwrap();
+    watched
+        .insert_one(doc! { "_id": 2, "x": 1 }, None)
+        .await
+        .unwrap();
+    watched
+        .update_one(doc! { "_id": 2 }, doc! { "$inc": { "x": 1 } }, None)
+        .await
+        .unwrap();
+
+    let insert = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(insert.operation_type, OperationType::Insert);
+    assert_eq!(insert.document_key, Some(doc! { "_id": 2 }));
+
+    let update = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(update.operation_type, OperationType::Update);
+    assert_eq!(update.full_document, Some(doc! { "_id": 2, "x": 2 }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn start_at_operation_time() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 0) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let mut session = client.start_session(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "x": 1 }, None, &mut session)
+        .await
+        .unwrap();
+    let operation_time = session.operation_time().unwrap();
+
+    let options = ChangeStreamOptions::builder()
+        .start_at_operation_time(Some(operation_time))
+        .build();
+    let mut change_stream = coll.watch(None, options).await.unwrap();
+    let event = change_stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        event.full_document.and_then(|doc| doc.get_i32("x").ok()),
+        Some(1)
+    );
+}
@@ -1,15 +1,60 @@
-use std::{borrow::Cow, collections::HashMap, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
+use async_trait::async_trait;
+use futures::stream::{StreamExt, TryStreamExt};
 use serde::Deserialize;
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
-    bson::{doc, Bson},
-    error::{CommandError, Error, ErrorKind},
-    options::{AuthMechanism, ClientOptions, Credential, ListDatabasesOptions, StreamAddress},
+    bson::{doc, Bson, Document, Timestamp},
+    bson_util,
+    cursor::CursorInformation,
+    error::{CommandError, Error, ErrorKind, TIMEOUT_ERROR},
+    event::{
+        cmap::{CmapEventHandler, ConnectionClosedEvent, ConnectionClosedReason},
+        command::{
+            CommandEventHandlerAsync,
+            CommandStartedEvent,
+            CommandSucceededEvent,
+            EventBackpressure,
+        },
+    },
+    options::{
+        AuthMechanism,
+        ClientOptions,
+        Compressor,
+        Credential,
+        FindOneOptions,
+        FindOptions,
+        ListDatabasesOptions,
+        ReconnectPolicy,
+        ServerApi,
+        ServerApiVersion,
+        StreamAddress,
+    },
     selection_criteria::{ReadPreference, ReadPreferenceOptions, SelectionCriteria},
-    test::{util::TestClient, CLIENT_OPTIONS, LOCK},
+    test::{
+        util::{
+            CmapEvent,
+            EventClient,
+            FailCommandOptions,
+            FailPoint,
+            FailPointMode,
+            TestClient,
+        },
+        CLIENT_OPTIONS,
+        LOCK,
+    },
     Client,
+    ClusterTime,
+    Namespace,
+    ServerType,
+    TopologyType,
     RUNTIME,
 };
 
@@ -139,6 +184,172 @@ async fn server_selection_timeout_message() {
     }
 }
 
+#[derive(Default)]
+struct ClosedConnectionHandler {
+    reasons: Mutex<Vec<ConnectionClosedReason>>,
+}
+
+impl CmapEventHandler for ClosedConnectionHandler {
+    fn handle_connection_closed_event(&self, event: ConnectionClosedEvent) {
+        self.reasons.lock().unwrap().push(event.reason);
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn command_event_wire_bytes() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let document = doc! { "x": "a".repeat(10_000) };
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.compressors = None;
+    let client = EventClient::with_options(options).await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(document.clone(), None).await.unwrap();
+
+    // An uncompressed message contains the command document along with its framing.
+    let (started, succeeded) = client.get_successful_command_execution("insert");
+    assert!(started.wire_bytes.unwrap() > bson_util::doc_size_bytes(&started.command));
+    assert!(succeeded.wire_bytes.unwrap() > 0);
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.compressors = Some(vec![Compressor::Zlib { level: None }]);
+    let client = EventClient::with_options(options).await;
+    client
+        .database(function_name!())
+        .collection(function_name!())
+        .insert_one(document, None)
+        .await
+        .unwrap();
+
+    let (started, _) = client.get_successful_command_execution("insert");
+    assert!(started.wire_bytes.unwrap() < 10_000);
+}
+
+#[derive(Default)]
+struct SlowCommandHandler {
+    events: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl CommandEventHandlerAsync for SlowCommandHandler {
+    async fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        RUNTIME.delay_for(Duration::from_millis(10)).await;
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("started {}", event.command_name));
+    }
+
+    async fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("succeeded {}", event.command_name));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn async_command_event_handler() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let handler = Arc::new(SlowCommandHandler::default());
+    let mut options = CLIENT_OPTIONS.clone();
+    options.async_command_event_handler =
+        Some(handler.clone() as Arc<dyn CommandEventHandlerAsync>);
+    options.async_command_event_backpressure = Some(EventBackpressure::Wait { capacity: 1 });
+    let client = TestClient::with_options(Some(options)).await;
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    for _ in 0..3 {
+        coll.find_one(None, None).await.unwrap();
+    }
+
+    // The handler runs in the background, so its last events may not have been handled yet.
+    RUNTIME.delay_for(Duration::from_millis(100)).await;
+    let events = handler.events.lock().unwrap();
+    let find_events: Vec<&str> = events
+        .iter()
+        .map(String::as_str)
+        .filter(|event| event.ends_with(" find"))
+        .collect();
+    assert_eq!(
+        find_events,
+        vec!["started find", "succeeded find"].repeat(3)
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn cancelled_operation_closes_connection() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let handler = Arc::new(ClosedConnectionHandler::default());
+    let mut options = CLIENT_OPTIONS.clone();
+    options.cmap_event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    let client = TestClient::with_options(Some(options)).await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let slow = coll.find_one(doc! { "$where": "sleep(1000) || true" }, None);
+    RUNTIME
+        .timeout(Duration::from_millis(200), slow)
+        .await
+        .expect_err("operation should be cancelled by the timeout");
+
+    // The connection is closed in the background once the pool processes its check in.
+    RUNTIME.delay_for(Duration::from_millis(200)).await;
+    assert!(handler
+        .reasons
+        .lock()
+        .unwrap()
+        .contains(&ConnectionClosedReason::Dropped));
+
+    coll.find_one(None, None).await.unwrap();
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn reconnect_policy() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let policy = ReconnectPolicy::builder()
+        .max_attempts(2)
+        .initial_backoff(Duration::from_millis(50))
+        .build();
+    let options = ClientOptions::builder()
+        .hosts(vec![StreamAddress::parse("localhost:1").unwrap()])
+        .server_selection_timeout(Duration::from_millis(100))
+        .reconnect_policy(policy)
+        .build();
+    let client = Client::with_options(options).unwrap();
+
+    let start = Instant::now();
+    let error = client
+        .database("test")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .expect_err("should fail with server selection timeout error");
+
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ServerSelectionError { .. }
+    ));
+    // Three selection attempts of 100ms each, with 50ms and 100ms of backoff between them.
+    assert!(start.elapsed() >= Duration::from_millis(450));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -604,7 +815,7 @@ async fn x509_auth() {
     let client = TestClient::with_options(Some(options)).await;
     client
         .database(function_name!())
-        .collection(function_name!())
+        .collection::<Document>(function_name!())
         .find_one(None, None)
         .await
         .unwrap();
@@ -655,3 +866,661 @@ async fn plain_auth() {
         }
     );
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn topology_description_snapshot() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+
+    // Run a command to ensure that at least one server has been discovered.
+    client
+        .database("admin")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .unwrap();
+
+    let description = client.topology_description().await;
+    let expected_type = if client.is_sharded() {
+        TopologyType::Sharded
+    } else if client.is_replica_set() {
+        TopologyType::ReplicaSetWithPrimary
+    } else {
+        TopologyType::Single
+    };
+    assert_eq!(description.topology_type(), expected_type);
+
+    let servers = description.servers();
+    assert!(!servers.is_empty());
+    assert!(servers
+        .values()
+        .any(|server| server.server_type() != ServerType::Unknown
+            && server.last_update_time().is_some()));
+
+    if client.is_replica_set() {
+        assert_eq!(
+            description.replica_set_name(),
+            client.options.repl_set_name.as_deref()
+        );
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn advance_cluster_time() {
+    let cluster_time = |time: u32| -> ClusterTime {
+        bson::from_document(doc! {
+            "clusterTime": Timestamp { time, increment: 1 },
+            "signature": { "keyId": 0_i64 },
+        })
+        .unwrap()
+    };
+
+    // The address is never connected to in the time the test takes.
+    let options = ClientOptions::builder()
+        .hosts(vec![StreamAddress::parse("localhost:1").unwrap()])
+        .build();
+    let client = Client::with_options(options).unwrap();
+    assert_eq!(client.cluster_time().await, None);
+
+    client.advance_cluster_time(&cluster_time(10)).await;
+    assert_eq!(client.cluster_time().await, Some(cluster_time(10)));
+
+    // The cluster time never moves backwards.
+    client.advance_cluster_time(&cluster_time(5)).await;
+    assert_eq!(client.cluster_time().await, Some(cluster_time(10)));
+
+    client.advance_cluster_time(&cluster_time(20)).await;
+    assert_eq!(
+        client.cluster_time().await.map(|ct| ct.timestamp().time),
+        Some(20)
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn cluster_time_gossiped_between_clients() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    client
+        .database("admin")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .unwrap();
+    let cluster_time = client
+        .cluster_time()
+        .await
+        .expect("should have a cluster time");
+
+    let other_client = EventClient::new().await;
+    other_client.advance_cluster_time(&cluster_time).await;
+    other_client
+        .database("admin")
+        .run_command(doc! { "ping": 1 }, None)
+        .await
+        .unwrap();
+
+    let event = other_client
+        .get_command_started_events("ping")
+        .pop()
+        .unwrap();
+    let sent: ClusterTime =
+        bson::from_bson(event.command.get("$clusterTime").unwrap().clone()).unwrap();
+    assert!(sent >= cluster_time);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn slow_command_events() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.slow_operation_threshold = Some(Duration::from_secs(0));
+    let client = EventClient::with_options(options).await;
+    let coll = client.init_db_and_coll(function_name!(), function_name!()).await;
+    client.slow_command_events.write().unwrap().clear();
+
+    coll.find_one(None, None).await.unwrap();
+
+    {
+        let slow_command_events = client.slow_command_events.read().unwrap();
+        let event = slow_command_events
+            .iter()
+            .find(|event| event.command_name == "find")
+            .expect("expected a slow command event for find");
+        assert_eq!(event.db, function_name!());
+        assert_eq!(event.collection.as_deref(), Some(function_name!()));
+        assert!(event.succeeded);
+    }
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.slow_operation_threshold = Some(Duration::from_secs(60 * 60));
+    let client = EventClient::with_options(options).await;
+    client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find_one(None, None)
+        .await
+        .unwrap();
+    assert!(client.slow_command_events.read().unwrap().is_empty());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn operation_events() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.emit_operation_events = Some(true);
+    let client = EventClient::with_options(options).await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    client.operation_events.write().unwrap().clear();
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.find_one(doc! { "$notAnOperator": 1 }, None)
+        .await
+        .expect_err("find with an unknown operator should fail");
+
+    let operation_events = client.operation_events.read().unwrap();
+    let names: Vec<&str> = operation_events
+        .iter()
+        .map(|event| event.operation_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["insert", "find"]);
+
+    let insert = &operation_events[0];
+    assert!(insert.succeeded);
+    assert!(!insert.retried);
+    assert!(
+        insert.server_selection_duration
+            + insert.connection_checkout_duration
+            + insert.command_duration
+            <= insert.duration
+    );
+    assert!(!operation_events[1].succeeded);
+
+    let find_failed_events = client.get_command_failed_events("find");
+    assert_eq!(find_failed_events.len(), 1);
+    assert!(find_failed_events[0].failure.is_server_error());
+    assert_eq!(
+        client
+            .get_events_matching(|event| event.command_name() == "insert")
+            .len(),
+        2
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn heartbeat_events() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    // Selecting a server for the setup commands requires at least one successful heartbeat.
+    let client = EventClient::new().await;
+
+    let heartbeat_events = client.heartbeat_succeeded_events.read().unwrap();
+    let event = heartbeat_events
+        .front()
+        .expect("a heartbeat should have succeeded");
+    assert!(client.options.hosts.contains(&event.address));
+    assert!(event.reply.contains_key("ok"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn connection_checkout_events() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    client.cmap_events.write().unwrap().clear();
+
+    coll.find_one(None, None).await.unwrap();
+
+    let cmap_events = client.cmap_events.read().unwrap();
+    let checkout_events: Vec<&CmapEvent> = cmap_events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                CmapEvent::ConnectionCheckoutStarted(_)
+                    | CmapEvent::ConnectionCheckedOut(_)
+                    | CmapEvent::ConnectionCheckedIn(_)
+            )
+        })
+        .collect();
+
+    match checkout_events.as_slice() {
+        [CmapEvent::ConnectionCheckoutStarted(started), CmapEvent::ConnectionCheckedOut(checked_out), CmapEvent::ConnectionCheckedIn(checked_in)] =>
+        {
+            assert!(client.options.hosts.contains(&started.address));
+            assert_eq!(checked_out.address, started.address);
+            assert_eq!(checked_in.address, started.address);
+            assert_eq!(checked_in.connection_id, checked_out.connection_id);
+        }
+        other => panic!("unexpected checkout events: {:?}", other),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn comment_provider() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.comment_provider = Some(Arc::new(|| Some(Bson::String("request-id".to_string()))));
+    let client = EventClient::with_options(options).await;
+
+    if client.server_version_lt(4, 4) {
+        return;
+    }
+
+    let coll = client.init_db_and_coll(function_name!(), function_name!()).await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.find_one(None, None).await.unwrap();
+
+    let (insert_started, _) = client.get_successful_command_execution("insert");
+    assert_eq!(
+        insert_started.command.get("comment"),
+        Some(&Bson::String("request-id".to_string()))
+    );
+
+    let (find_started, _) = client.get_successful_command_execution("find");
+    assert_eq!(
+        find_started.command.get("comment"),
+        Some(&Bson::String("request-id".to_string()))
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn comment_provider_before_4_4() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.comment_provider = Some(Arc::new(|| Some(Bson::String("request-id".to_string()))));
+    let client = EventClient::with_options(options).await;
+
+    if !client.server_version_lt(4, 4) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], None)
+        .await
+        .unwrap();
+
+    // Only the find accepts a comment; the server would reject one on the getMore.
+    let options = FindOptions::builder().batch_size(1).build();
+    let docs: Vec<Document> = coll
+        .find(None, options)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs.len(), 2);
+
+    let (find_started, _) = client.get_successful_command_execution("find");
+    assert_eq!(
+        find_started.command.get("comment"),
+        Some(&Bson::String("request-id".to_string()))
+    );
+
+    let (get_more_started, _) = client.get_successful_command_execution("getMore");
+    assert!(!get_more_started.command.contains_key("comment"));
+
+    let (insert_started, _) = client.get_successful_command_execution("insert");
+    assert!(!insert_started.command.contains_key("comment"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn command_event_field_allowlist() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.command_event_field_allowlist = Some(vec!["limit".to_string()]);
+    let client = EventClient::with_options(options).await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    coll.find_one(doc! { "ssn": "123-45-6789" }, None)
+        .await
+        .unwrap();
+
+    let (find_started, _) = client.get_successful_command_execution("find");
+    let keys: Vec<&String> = find_started.command.keys().collect();
+    assert_eq!(keys, vec!["find", "limit"]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn command_event_redaction() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    async fn run_commands(client: &EventClient) {
+        let db = client.database("admin");
+        let speculative_auth = doc! {
+            "isMaster": 1,
+            "speculativeAuthenticate": { "saslStart": 1, "mechanism": "PLAIN", "payload": "" },
+        };
+        // Both of these may fail, but their started events are emitted regardless.
+        let _ = db.run_command(speculative_auth, None).await;
+        let _ = db
+            .run_command(doc! { "saslStart": 1, "mechanism": "PLAIN" }, None)
+            .await;
+        db.run_command(doc! { "isMaster": 1 }, None).await.unwrap();
+    }
+
+    let client = EventClient::new().await;
+    run_commands(&client).await;
+
+    let is_master_events = client.get_command_started_events("isMaster");
+    assert!(is_master_events[0].command.is_empty());
+    assert!(!is_master_events[1].command.is_empty());
+    assert!(client.get_command_started_events("saslStart")[0]
+        .command
+        .is_empty());
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.command_event_redaction = Some(false);
+    let client = EventClient::with_options(options).await;
+    run_commands(&client).await;
+
+    let is_master_events = client.get_command_started_events("isMaster");
+    assert!(is_master_events[0]
+        .command
+        .contains_key("speculativeAuthenticate"));
+    assert!(client.get_command_started_events("saslStart")[0]
+        .command
+        .contains_key("mechanism"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn server_api() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(5, 0) {
+        return;
+    }
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.server_api = Some(
+        ServerApi::builder()
+            .version(ServerApiVersion::V1)
+            .strict(true)
+            .deprecation_errors(true)
+            .build(),
+    );
+    let client = EventClient::with_options(options).await;
+    let coll = client.init_db_and_coll(function_name!(), "coll").await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let insert_started = &client.get_command_started_events("insert")[0];
+    assert_eq!(insert_started.command.get_str("apiVersion"), Ok("1"));
+    assert_eq!(insert_started.command.get_bool("apiStrict"), Ok(true));
+    assert_eq!(
+        insert_started.command.get_bool("apiDeprecationErrors"),
+        Ok(true)
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn max_concurrent_operations() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.max_concurrent_operations = Some(1);
+    options.fail_fast_on_max_concurrent_operations = Some(true);
+    let client = Client::with_options(options).unwrap();
+
+    let coll = client
+        .database(function_name!())
+        .collection(function_name!());
+    coll.drop(None).await.unwrap();
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    assert_eq!(client.in_flight_operations(), 0);
+
+    // Keep the first operation in progress long enough for the second one to be attempted.
+    let slow = coll.find_one(doc! { "$where": "sleep(500) || true" }, None);
+    let fast = async {
+        RUNTIME.delay_for(Duration::from_millis(100)).await;
+        assert_eq!(client.in_flight_operations(), 1);
+        coll.find_one(None, None).await
+    };
+    let (slow_result, fast_result) = futures::join!(slow, fast);
+
+    slow_result.unwrap();
+    match fast_result.map_err(|e| e.kind) {
+        Err(kind) => match kind.as_ref() {
+            ErrorKind::MaxConcurrentOperationsExceeded {
+                max_concurrent_operations,
+            } => assert_eq!(*max_concurrent_operations, 1),
+            other => panic!("expected MaxConcurrentOperationsExceeded, got {:?}", other),
+        },
+        Ok(_) => panic!("expected the second operation to fail"),
+    }
+    assert_eq!(client.in_flight_operations(), 0);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn server_default_rw_concern() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+
+    // getDefaultRWConcern is only supported on replica sets and sharded clusters.
+    if client.server_version_lt(4, 4) || !(client.is_replica_set() || client.is_sharded()) {
+        return;
+    }
+
+    let defaults = client.server_default_rw_concern().await.unwrap();
+    if !defaults.read_concern_explicitly_set && client.server_version_gte(5, 0) {
+        assert!(defaults.read_concern.is_some());
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn metrics() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database("admin");
+
+    db.run_command(doc! { "ping": 1 }, None).await.unwrap();
+    db.run_command(doc! { "ping": 1 }, None).await.unwrap();
+    db.run_command(doc! { "notACommand": 1 }, None)
+        .await
+        .unwrap_err();
+
+    let metrics = client.metrics().await;
+    assert_eq!(metrics.commands.get("ping"), Some(&2));
+    assert_eq!(metrics.commands.get("notACommand"), Some(&1));
+    assert_eq!(metrics.errors.get("CommandError"), Some(&1));
+
+    assert!(!metrics.pools.is_empty());
+    assert!(metrics
+        .pools
+        .values()
+        .all(|pool| pool.connections_created >= u64::from(pool.connections)));
+    assert!(metrics
+        .pools
+        .values()
+        .any(|pool| pool.connections_created > 0));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn operation_timeout() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.timeout = Some(Duration::from_millis(500));
+    let client = TestClient::with_options(Some(options)).await;
+
+    // blockConnection is only available in 4.2.9+.
+    if !client.supports_fail_command().await || client.server_version_lt(4, 4) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many(vec![doc! { "x": 1 }, doc! { "x": 2 }], None)
+        .await
+        .unwrap();
+
+    let failpoint = FailPoint::fail_command(
+        &["find"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder()
+            .block_connection(Duration::from_secs(1))
+            .build(),
+    );
+    let fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    let start = Instant::now();
+    let error = coll
+        .find_one(None, None)
+        .await
+        .expect_err("find_one should time out");
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(matches!(*error.kind, ErrorKind::Timeout { .. }));
+    assert!(error.contains_label(TIMEOUT_ERROR));
+    drop(fp_guard);
+
+    // The timeout of an operation takes precedence over the client's.
+    let failpoint = FailPoint::fail_command(
+        &["find"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder()
+            .block_connection(Duration::from_millis(750))
+            .build(),
+    );
+    let fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+    let options = FindOneOptions::builder()
+        .timeout(Duration::from_secs(5))
+        .build();
+    coll.find_one(None, options).await.unwrap();
+    drop(fp_guard);
+
+    // A cursor's getMores fail once the timeout of the operation that created it has passed.
+    let options = FindOptions::builder()
+        .batch_size(1)
+        .timeout(Duration::from_millis(300))
+        .build();
+    let mut cursor = coll.find(None, options).await.unwrap();
+    cursor.next().await.unwrap().unwrap();
+    RUNTIME.delay_for(Duration::from_millis(400)).await;
+    let error = cursor.next().await.unwrap().unwrap_err();
+    assert!(error.contains_label(TIMEOUT_ERROR));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn shutdown() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many(
+        vec![doc! { "x": 1 }, doc! { "x": 2 }, doc! { "x": 3 }],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let options = FindOptions::builder().batch_size(1).build();
+    let cursor = coll.find(None, options).await.unwrap();
+
+    Client::clone(&client).shutdown(None).await;
+
+    // The cursor is killed on the server even though it's still alive locally.
+    let kill_cursors = client.get_command_started_events("killCursors");
+    assert_eq!(kill_cursors.len(), 1);
+    assert_eq!(
+        kill_cursors[0].command.get_str("killCursors"),
+        Ok(function_name!())
+    );
+
+    if !client.is_standalone() {
+        let end_sessions = client.get_command_started_events("endSessions");
+        assert_eq!(end_sessions.len(), 1);
+        assert!(!end_sessions[0].command.contains_key("lsid"));
+    }
+
+    assert!(!client.pool_cleared_events.read().unwrap().is_empty());
+
+    let error = coll.find_one(None, None).await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ClientShutdown));
+
+    drop(cursor);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn shutdown_with_unreachable_server() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let options = ClientOptions::builder()
+        .hosts(vec![StreamAddress::parse("localhost:1").unwrap()])
+        .server_selection_timeout(Duration::from_secs(30))
+        .build();
+    let client = Client::with_options(options.clone()).unwrap();
+    let cursor = CursorInformation {
+        ns: Namespace::empty(),
+        address: StreamAddress::parse("localhost:1").unwrap(),
+        id: 1,
+        batch_size: None,
+        max_time: None,
+        deadline: None,
+        timeout_deadline: None,
+        pinned_connection: None,
+    };
+
+    // Killing the cursor can't select a server, which is given up on after the timeout...
+    client.track_cursor(&cursor);
+    let start = Instant::now();
+    Client::clone(&client)
+        .shutdown(Duration::from_millis(100))
+        .await;
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // ...or after a short time when there is no timeout.
+    let client = Client::with_options(options).unwrap();
+    client.track_cursor(&cursor);
+    let start = Instant::now();
+    client.shutdown(None).await;
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,155 @@
+use tokio::sync::RwLockReadGuard;
+
+use crate::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson},
+    client_encryption::ClientEncryption,
+    error::ErrorKind,
+    options::{
+        ClientEncryptionOptions,
+        DataKeyOptions,
+        EncryptOptions,
+        EncryptionAlgorithm,
+        KmsProvider,
+        KmsProviders,
+        LocalKmsProviderOptions,
+    },
+    test::{TestClient, LOCK},
+    Namespace,
+};
+
+fn client_encryption(client: &TestClient, key_vault_db: &str) -> ClientEncryption {
+    let options = ClientEncryptionOptions::builder()
+        .key_vault_namespace(Namespace {
+            db: key_vault_db.to_string(),
+            coll: "datakeys".to_string(),
+        })
+        .kms_providers(
+            KmsProviders::builder()
+                .local(LocalKmsProviderOptions::builder().key(vec![3; 96]).build())
+                .build(),
+        )
+        .build();
+    ClientEncryption::new(client, options).unwrap()
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn explicit_encryption() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let client_encryption = client_encryption(&client, function_name!());
+    let key_id = client_encryption
+        .create_data_key(
+            KmsProvider::Local,
+            DataKeyOptions::builder()
+                .key_alt_names(vec!["ssn".to_string()])
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(key_id.subtype, BinarySubtype::Uuid);
+
+    let key = client_encryption
+        .key_vault()
+        .find_one(doc! { "_id": key_id.clone() }, None)
+        .await
+        .unwrap()
+        .expect("data key should be stored");
+    assert_eq!(
+        key.get_document("masterKey"),
+        Ok(&doc! { "provider": "local" })
+    );
+
+    let deterministic = EncryptOptions::builder()
+        .algorithm(EncryptionAlgorithm::Deterministic)
+        .key_id(key_id.clone())
+        .build();
+    let encrypted = client_encryption
+        .encrypt("457-55-5462", deterministic.clone())
+        .await
+        .unwrap();
+    assert_eq!(encrypted.subtype, BinarySubtype::Encrypted);
+    assert_eq!(
+        client_encryption
+            .encrypt("457-55-5462", deterministic)
+            .await
+            .unwrap(),
+        encrypted
+    );
+
+    // Deterministically encrypted values can be queried for equality.
+    let coll = db.collection(function_name!());
+    coll.insert_one(doc! { "ssn": encrypted.clone() }, None)
+        .await
+        .unwrap();
+    let stored = coll
+        .find_one(doc! { "ssn": encrypted.clone() }, None)
+        .await
+        .unwrap()
+        .expect("document should be found");
+    let stored = match stored.get("ssn") {
+        Some(Bson::Binary(binary)) => binary.clone(),
+        other => panic!("expected encrypted value, got {:?}", other),
+    };
+    assert_eq!(
+        client_encryption.decrypt(stored).await.unwrap(),
+        Bson::from("457-55-5462")
+    );
+
+    let random = EncryptOptions::builder()
+        .algorithm(EncryptionAlgorithm::Random)
+        .key_alt_name("ssn".to_string())
+        .build();
+    let value = Bson::Document(doc! { "a": 1.5, "b": [true] });
+    let encrypted = client_encryption
+        .encrypt(value.clone(), random.clone())
+        .await
+        .unwrap();
+    assert_ne!(
+        client_encryption
+            .encrypt(value.clone(), random)
+            .await
+            .unwrap(),
+        encrypted
+    );
+    assert_eq!(client_encryption.decrypt(encrypted).await.unwrap(), value);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn explicit_encryption_errors() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    client.database(function_name!()).drop(None).await.unwrap();
+
+    let client_encryption = client_encryption(&client, function_name!());
+
+    let error = client_encryption
+        .create_data_key(KmsProvider::Aws, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+
+    let options = EncryptOptions::builder()
+        .algorithm(EncryptionAlgorithm::Random)
+        .key_alt_name("missing".to_string())
+        .build();
+    let error = client_encryption.encrypt(1, options).await.unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::EncryptionError { .. }));
+
+    let error = client_encryption
+        .decrypt(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![1, 2, 3],
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::ArgumentError { .. }));
+}
@@ -7,13 +7,18 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
-    bson::{doc, to_document, Bson, Document},
+    bson::{doc, oid::ObjectId, to_document, Bson, Document},
     error::{ErrorKind, Result, WriteFailure},
     event::command::CommandStartedEvent,
     options::{
+        self,
         Acknowledgment,
         AggregateOptions,
+        BulkWriteOptions,
+        CollectionOptions,
+        CreateCollectionOptions,
         DeleteOptions,
+        ExplainVerbosity,
         FindOneAndDeleteOptions,
         FindOneOptions,
         FindOptions,
@@ -22,15 +27,18 @@ use crate::{
         ReadPreference,
         SelectionCriteria,
         UpdateOptions,
+        ValidateOptions,
         WriteConcern,
+        WriteModel,
     },
-    results::DeleteResult,
+    results::{BulkWriteResult, DeleteResult},
     test::{
-        util::{drop_collection, CommandEvent, EventClient, TestClient},
+        util::{drop_collection, CmapEvent, CommandEvent, EventClient, TestClient},
         CLIENT_OPTIONS,
         LOCK,
     },
     Collection,
+    IndexModel,
     RUNTIME,
 };
 
@@ -118,6 +126,111 @@ async fn count() {
     assert_eq!(coll.estimated_document_count(None).await.unwrap(), 4);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn insert_many_return_documents() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let docs: Vec<_> = (0..5).map(|i| doc! { "x": i }).collect();
+    let result = coll.insert_many(docs.clone(), None).await.unwrap();
+    assert!(result.inserted_documents.is_none());
+
+    let options = InsertManyOptions::builder()
+        .return_documents(true)
+        .build();
+    let result = coll.insert_many(docs, options).await.unwrap();
+    let inserted_documents = result.inserted_documents.expect("documents should be returned");
+
+    assert_eq!(inserted_documents.len(), 5);
+    for (index, document) in inserted_documents {
+        assert_eq!(document.get("_id"), result.inserted_ids.get(&index));
+        assert_eq!(document.get_i32("x"), Ok(index as i32));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn insert_many_return_documents_with_session() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.is_standalone() {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let mut session = client.start_session(None).await.unwrap();
+
+    let docs: Vec<_> = (0..5).map(|i| doc! { "x": i }).collect();
+    let options = InsertManyOptions::builder().return_documents(true).build();
+    let result = coll
+        .insert_many_with_session(docs, options, &mut session)
+        .await
+        .unwrap();
+    assert_eq!(result.inserted_documents.map(|docs| docs.len()), Some(5));
+
+    // The documents are read back in the caller's session, after the inserts.
+    let (insert_started, insert_succeeded) = client.get_successful_command_execution("insert");
+    let (find_started, _) = client.get_successful_command_execution("find");
+    assert_eq!(find_started.command.get_document("lsid"), Ok(session.id()));
+    assert_eq!(
+        insert_started.command.get_document("lsid"),
+        Ok(session.id())
+    );
+    let read_concern = find_started.command.get_document("readConcern").unwrap();
+    assert_eq!(
+        read_concern.get_timestamp("afterClusterTime"),
+        insert_succeeded.reply.get_timestamp("operationTime")
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn exists() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Item {
+        x: i32,
+        name: String,
+    }
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_typed_coll::<Item>(function_name!(), function_name!())
+        .await;
+
+    assert!(!coll.exists(None).await.unwrap());
+
+    coll.insert_one(
+        Item {
+            x: 1,
+            name: "one".to_string(),
+        },
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert!(coll.exists(None).await.unwrap());
+    assert!(coll.exists(doc! { "x": 1 }).await.unwrap());
+    assert!(!coll.exists(doc! { "x": 2 }).await.unwrap());
+
+    let (started, _) = client.get_successful_command_execution("find");
+    assert_eq!(started.command.get_document("projection"), Ok(&doc! { "_id": 1 }));
+    assert_eq!(started.command.get_bool("singleBatch"), Ok(true));
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -149,6 +262,54 @@ async fn find() {
     }
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn explain() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0i32..5).map(|i| doc! { "x": i }), None)
+        .await
+        .unwrap();
+
+    let output = coll
+        .explain_find(doc! { "x": 1 }, None, ExplainVerbosity::ExecutionStats)
+        .await
+        .unwrap();
+    assert!(output.contains_key("queryPlanner"));
+    assert!(output.contains_key("executionStats"));
+
+    let output = coll
+        .explain_aggregate(
+            vec![doc! { "$match": { "x": 1 } }],
+            None,
+            ExplainVerbosity::QueryPlanner,
+        )
+        .await
+        .unwrap();
+    assert!(!output.contains_key("executionStats"));
+
+    // Explaining doesn't run the operation, so no documents are written by a $out stage.
+    let out_coll = format!("{}_out", function_name!());
+    coll.explain_aggregate(
+        vec![doc! { "$out": out_coll.as_str() }],
+        None,
+        ExplainVerbosity::QueryPlanner,
+    )
+    .await
+    .unwrap();
+    let written = client
+        .get_coll(function_name!(), &out_coll)
+        .count_documents(None, None)
+        .await
+        .unwrap();
+    assert_eq!(written, 0);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -215,6 +376,52 @@ async fn delete() {
     assert_eq!(coll.count_documents(doc! {"x": 3 }, None).await.unwrap(), 0);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn list_and_drop_indexes() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    assert!(index_names(&coll).await.is_empty());
+
+    coll.create_indexes(
+        vec![
+            IndexModel::builder().keys(doc! { "x": 1 }).build(),
+            IndexModel::builder().keys(doc! { "y": -1 }).build(),
+        ],
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(index_names(&coll).await, vec!["_id_", "x_1", "y_-1"]);
+
+    coll.drop_index("x_1", None).await.unwrap();
+    assert_eq!(index_names(&coll).await, vec!["_id_", "y_-1"]);
+
+    assert!(coll.drop_index("*", None).await.is_err());
+
+    coll.drop_indexes(None).await.unwrap();
+    assert_eq!(index_names(&coll).await, vec!["_id_"]);
+}
+
+async fn index_names(coll: &Collection) -> Vec<String> {
+    let indexes: Vec<IndexModel> = coll
+        .list_indexes(None)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let mut names: Vec<_> = indexes.iter().map(IndexModel::name).collect();
+    names.sort();
+    names
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -233,7 +440,7 @@ async fn aggregate_out() {
         .unwrap();
     assert_eq!(result.inserted_ids.len(), 5);
 
-    let out_coll = db.collection(&format!("{}_1", function_name!()));
+    let out_coll = db.collection::<Document>(&format!("{}_1", function_name!()));
     let pipeline = vec![
         doc! {
             "$match": {
@@ -298,7 +505,7 @@ async fn kill_cursors_on_drop() {
     let event_client = EventClient::new().await;
     let coll = event_client
         .database(function_name!())
-        .collection(function_name!());
+        .collection::<Document>(function_name!());
 
     let cursor = coll
         .find(None, FindOptions::builder().batch_size(1).build())
@@ -336,7 +543,7 @@ async fn no_kill_cursors_on_exhausted() {
     let event_client = EventClient::new().await;
     let coll = event_client
         .database(function_name!())
-        .collection(function_name!());
+        .collection::<Document>(function_name!());
 
     let cursor = coll
         .find(None, FindOptions::builder().build())
@@ -352,6 +559,117 @@ async fn no_kill_cursors_on_exhausted() {
     assert!(!kill_cursors_sent(&event_client));
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn cursor_pinned_when_load_balanced() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    if CLIENT_OPTIONS.load_balanced != Some(true) {
+        println!(
+            "Skipping {}: not connected to a load balancer",
+            function_name!()
+        );
+        return;
+    }
+
+    let client = TestClient::new().await;
+    let coll = client
+        .database(function_name!())
+        .collection(function_name!());
+
+    drop_collection(&coll).await;
+
+    coll.insert_many((0..5).map(|x| doc! { "x": x }), None)
+        .await
+        .unwrap();
+
+    let event_client = EventClient::new().await;
+    let coll = event_client
+        .database(function_name!())
+        .collection::<Document>(function_name!());
+
+    let cursor = coll
+        .find(None, FindOptions::builder().batch_size(2).build())
+        .await
+        .unwrap();
+    let mut cursor = cursor.take(3);
+    while let Some(result) = cursor.next().await {
+        result.unwrap();
+    }
+    std::mem::drop(cursor);
+
+    // wait for the killCursors spawned from `Cursor`'s `Drop`.
+    RUNTIME.delay_for(Duration::from_millis(250)).await;
+
+    let connection_ids: Vec<u32> = event_client
+        .command_events
+        .read()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event {
+            CommandEvent::CommandStartedEvent(event)
+                if ["find", "getMore", "killCursors"].contains(&event.command_name.as_str()) =>
+            {
+                Some(event.connection.id)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(connection_ids.len(), 3);
+    assert!(connection_ids.iter().all(|id| *id == connection_ids[0]));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn session_cursor_unpinned_when_exhausted() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    if CLIENT_OPTIONS.load_balanced != Some(true) {
+        println!(
+            "Skipping {}: not connected to a load balancer",
+            function_name!()
+        );
+        return;
+    }
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..5).map(|x| doc! { "x": x }), None)
+        .await
+        .unwrap();
+    client.cmap_events.write().unwrap().clear();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let mut cursor = coll
+        .find_with_session(
+            None,
+            FindOptions::builder().batch_size(2).build(),
+            &mut session,
+        )
+        .await
+        .unwrap();
+    let docs: Vec<Document> = cursor.stream(&mut session).try_collect().await.unwrap();
+    assert_eq!(docs.len(), 5);
+
+    // The connection the cursor was pinned to is checked back in once the cursor is exhausted,
+    // even though the cursor itself hasn't been dropped yet.
+    RUNTIME.delay_for(Duration::from_millis(250)).await;
+    let (find_started, _) = client.get_successful_command_execution("find");
+    let checked_in = client.cmap_events.read().unwrap().iter().any(|event| {
+        matches!(
+            event,
+            CmapEvent::ConnectionCheckedIn(event)
+                if event.connection_id == find_started.connection.id
+        )
+    });
+    assert!(checked_in);
+    std::mem::drop(cursor);
+}
+
 lazy_static! {
     #[allow(clippy::unreadable_literal)]
     static ref LARGE_DOC: Document = doc! {
@@ -431,6 +749,39 @@ async fn large_insert() {
     );
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn large_insert_return_documents() {
+    if std::env::consts::OS != "linux" {
+        return;
+    }
+
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let docs = vec![LARGE_DOC.clone(); 35000];
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let options = InsertManyOptions::builder().return_documents(true).build();
+    let result = coll.insert_many(docs, options).await.unwrap();
+    assert_eq!(
+        result.inserted_documents.map(|docs| docs.len()),
+        Some(35000)
+    );
+
+    // The documents are read back with one find per insert batch rather than with a single
+    // filter containing every id.
+    let inserts = client.get_command_started_events("insert");
+    assert!(inserts.len() > 1);
+    assert_eq!(
+        client.get_command_started_events("find").len(),
+        inserts.len()
+    );
+}
+
 /// Returns a vector of documents that cannot be sent in one batch (35000 documents).
 /// Includes duplicate _id's across different batches.
 fn multibatch_documents_with_duplicate_keys() -> Vec<Document> {
@@ -490,6 +841,10 @@ async fn large_insert_unordered_with_errors() {
             assert_eq!(write_errors[0].index, 7499);
             assert_eq!(write_errors[1].index, 22499);
             assert_eq!(write_errors[2].index, 32499);
+
+            assert_eq!(failure.inserted_ids.len(), 35000 - 3);
+            assert!(!failure.inserted_ids.contains_key(&22499));
+            assert!(failure.inserted_ids.contains_key(&34999));
         }
         e => panic!("expected bulk write error, got {:?} instead", e),
     }
@@ -533,6 +888,9 @@ async fn large_insert_ordered_with_errors() {
                     .expect("count should succeed"),
                 7499
             );
+
+            assert_eq!(failure.inserted_ids.len(), 7499);
+            assert!(failure.inserted_ids.contains_key(&7498));
         }
         e => panic!("expected bulk write error, got {:?} instead", e),
     }
@@ -547,9 +905,9 @@ async fn empty_insert() {
     let client = TestClient::new().await;
     let coll = client
         .database(function_name!())
-        .collection(function_name!());
+        .collection::<Document>(function_name!());
     match coll
-        .insert_many(Vec::new(), None)
+        .insert_many(Vec::<Document>::new(), None)
         .await
         .expect_err("should get error")
         .kind
@@ -560,6 +918,166 @@ async fn empty_insert() {
     };
 }
 
+/// Writes of every kind; the second insert fails with a duplicate key error.
+fn bulk_writes_with_duplicate_key() -> Vec<WriteModel> {
+    vec![
+        WriteModel::InsertOne {
+            document: doc! { "_id": 1, "x": 1 },
+        },
+        WriteModel::InsertOne {
+            document: doc! { "_id": 1, "x": 1 },
+        },
+        WriteModel::InsertOne {
+            document: doc! { "_id": 2, "x": 2 },
+        },
+        WriteModel::UpdateOne {
+            filter: doc! { "_id": 1 },
+            update: doc! { "$set": { "y": 1 } }.into(),
+            upsert: None,
+        },
+        WriteModel::UpdateMany {
+            filter: doc! { "x": { "$gt": 0 } },
+            update: doc! { "$set": { "z": 1 } }.into(),
+            upsert: None,
+        },
+        WriteModel::ReplaceOne {
+            filter: doc! { "_id": 3 },
+            replacement: doc! { "x": 3 },
+            upsert: Some(true),
+        },
+        WriteModel::DeleteOne {
+            filter: doc! { "_id": 2 },
+        },
+        WriteModel::DeleteMany {
+            filter: doc! { "x": 3 },
+        },
+    ]
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let mut writes = bulk_writes_with_duplicate_key();
+    writes.remove(1);
+    let result = coll.bulk_write(writes, None).await.unwrap();
+
+    let mut expected = BulkWriteResult {
+        inserted_count: 2,
+        matched_count: 3,
+        modified_count: 3,
+        deleted_count: 2,
+        upserted_count: 1,
+        ..Default::default()
+    };
+    expected.upserted_ids = result.upserted_ids.clone();
+    assert_eq!(result, expected);
+    assert_eq!(result.upserted_ids.keys().collect::<Vec<_>>(), vec![&4]);
+
+    let docs: Vec<Document> = coll
+        .find(None, None)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs, vec![doc! { "_id": 1, "x": 1, "y": 1, "z": 1 }]);
+
+    match coll
+        .bulk_write(Vec::new(), None)
+        .await
+        .expect_err("should get error")
+        .kind
+        .as_ref()
+    {
+        ErrorKind::ArgumentError { .. } => {}
+        e => panic!("expected argument error, got {:?}", e),
+    };
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write_ordered_with_errors() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    match coll
+        .bulk_write(bulk_writes_with_duplicate_key(), None)
+        .await
+        .expect_err("should get error")
+        .kind
+        .as_ref()
+    {
+        ErrorKind::BulkWriteError(ref failure) => {
+            let write_errors = failure
+                .write_errors
+                .clone()
+                .expect("should have write errors");
+            assert_eq!(write_errors.len(), 1);
+            assert_eq!(write_errors[0].index, 1);
+            assert_eq!(
+                failure.partial_result,
+                Some(BulkWriteResult {
+                    inserted_count: 1,
+                    ..Default::default()
+                })
+            );
+        }
+        e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write_unordered_with_errors() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = BulkWriteOptions::builder().ordered(false).build();
+    match coll
+        .bulk_write(bulk_writes_with_duplicate_key(), options)
+        .await
+        .expect_err("should get error")
+        .kind
+        .as_ref()
+    {
+        ErrorKind::BulkWriteError(ref failure) => {
+            let write_errors = failure
+                .write_errors
+                .clone()
+                .expect("should have write errors");
+            assert_eq!(write_errors.len(), 1);
+            assert_eq!(write_errors[0].index, 1);
+
+            let result = failure.partial_result.as_ref().expect("should have result");
+            assert_eq!(result.inserted_count, 2);
+            assert_eq!(result.deleted_count, 2);
+            assert_eq!(result.upserted_count, 1);
+            assert!(result.upserted_ids.contains_key(&5));
+        }
+        e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 async fn find_allow_disk_use() {
@@ -591,7 +1109,7 @@ async fn allow_disk_use_test(options: FindOptions, expected_value: Option<bool>)
     }
     let coll = event_client
         .database(function_name!())
-        .collection(function_name!());
+        .collection::<Document>(function_name!());
     coll.find(None, options).await.unwrap();
 
     let events = event_client.get_command_started_events("find");
@@ -617,7 +1135,7 @@ async fn delete_hint_test(options: Option<DeleteOptions>, name: &str) {
     let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
 
     let client = EventClient::new().await;
-    let coll = client.database(name).collection(name);
+    let coll = client.database(name).collection::<Document>(name);
     let _: Result<DeleteResult> = coll.delete_many(doc! {}, options.clone()).await;
 
     let events = client.get_command_started_events("delete");
@@ -710,7 +1228,9 @@ async fn find_one_and_delete_hint_server_version() {
     let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
 
     let client = EventClient::new().await;
-    let coll = client.database(function_name!()).collection("coll");
+    let coll = client
+        .database(function_name!())
+        .collection::<Document>("coll");
 
     let options = FindOneAndDeleteOptions::builder()
         .hint(Hint::Name(String::new()))
@@ -752,7 +1272,7 @@ async fn no_read_preference_to_standalone() {
 
     client
         .database(function_name!())
-        .collection(function_name!())
+        .collection::<Document>(function_name!())
         .find_one(None, options)
         .await
         .unwrap();
@@ -971,3 +1491,267 @@ async fn count_documents_with_wc() {
         .await
         .expect("count_documents should succeed");
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn typed_newtype_id() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(transparent)]
+    struct UserId(ObjectId);
+
+    #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+    struct User {
+        #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+        id: Option<UserId>,
+        name: String,
+    }
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_typed_coll(function_name!(), function_name!())
+        .await;
+
+    let id = UserId(ObjectId::new());
+    let user = User {
+        id: Some(id.clone()),
+        name: "provided".into(),
+    };
+    let result = coll.insert_one(user.clone(), None).await.unwrap();
+    assert_eq!(bson::from_bson::<UserId>(result.inserted_id).unwrap(), id);
+
+    let found = coll
+        .find_one(doc! { "_id": id.0.clone() }, None)
+        .await
+        .unwrap();
+    assert_eq!(found, Some(user));
+
+    let result = coll
+        .insert_one(
+            User {
+                id: None,
+                name: "generated".into(),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let generated_id: UserId = bson::from_bson(result.inserted_id).unwrap();
+
+    let found = coll
+        .find_one(doc! { "_id": generated_id.0.clone() }, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, Some(generated_id));
+    assert_eq!(found.name, "generated");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn typed_borrowed_and_renamed() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+    struct Item {
+        #[serde(rename = "itemName")]
+        name: String,
+        count: i32,
+    }
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_typed_coll::<Item>(function_name!(), function_name!())
+        .await;
+
+    let item = Item {
+        name: "a".into(),
+        count: 1,
+    };
+    coll.insert_one(&item, None).await.unwrap();
+    coll.insert_many(&[item.clone(), item.clone()], None)
+        .await
+        .unwrap();
+
+    let untyped = coll.clone_with_type::<Document>();
+    let raw = untyped
+        .find_one(doc! { "itemName": "a" }, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!raw.contains_key("name"));
+
+    let found = coll.find_one(doc! { "itemName": "a" }, None).await.unwrap();
+    assert_eq!(found, Some(item));
+
+    // Documents that don't match the collection's type are reported as decoding errors.
+    untyped
+        .insert_one(doc! { "itemName": "b", "count": "not a number" }, None)
+        .await
+        .unwrap();
+    let error = coll
+        .find_one(doc! { "itemName": "b" }, None)
+        .await
+        .expect_err("find_one should fail to deserialize");
+    assert!(matches!(error.kind.as_ref(), ErrorKind::BsonDecode(..)));
+
+    let results: Vec<Result<Item>> = coll.find(None, None).await.unwrap().collect().await;
+    assert_eq!(results.len(), 4);
+    assert_eq!(results.iter().filter(|result| result.is_err()).count(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn validate() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..3).map(|i| doc! { "x": i }).collect::<Vec<_>>(), None)
+        .await
+        .unwrap();
+
+    let result = coll.validate(None).await.unwrap();
+    assert!(result.valid);
+    assert!(result.errors.is_empty());
+    assert_eq!(result.n_records, Some(3));
+
+    let options = ValidateOptions::builder().full(true).build();
+    let result = coll.validate(options).await.unwrap();
+    assert!(result.valid);
+    assert_eq!(result.keys_per_index.get("_id_"), Some(&3));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn latency_stats() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(3, 4) {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.find_one(None, None).await.unwrap();
+
+    let stats = coll.latency_stats().await.unwrap();
+    assert!(stats.writes.ops >= 1);
+    assert!(stats.reads.ops >= 1);
+    assert!(!stats.reads.histogram.is_empty());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn ensure_created_with() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    drop_collection(&client.get_coll(function_name!(), function_name!())).await;
+
+    let create_options = CreateCollectionOptions::builder()
+        .validation(doc! { "x": { "$type": "int" } })
+        .build();
+    let options = CollectionOptions::builder()
+        .ensure_created_with(create_options)
+        .build();
+    let coll = client.get_coll_with_options(function_name!(), function_name!(), options.clone());
+
+    // The first write creates the collection with the validator, so invalid documents are
+    // rejected rather than being inserted into an implicitly created collection.
+    let error = coll
+        .insert_one(doc! { "x": "not an int" }, None)
+        .await
+        .expect_err("insert should fail validation");
+    assert!(matches!(error.kind.as_ref(), ErrorKind::WriteError(_)));
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    // A new handle with the same options doesn't fail when the collection already exists.
+    let coll = client.get_coll_with_options(function_name!(), function_name!(), options);
+    coll.update_one(doc! { "x": 1 }, doc! { "$set": { "y": 1 } }, None)
+        .await
+        .unwrap();
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn id_generator() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.id_generator = Some(options::sequential_object_ids(1));
+    let client = TestClient::with_options(Some(options)).await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let expected = options::sequential_object_ids(1);
+    let result = coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    assert_eq!(result.inserted_id, expected());
+
+    let result = coll
+        .insert_many(
+            vec![doc! { "x": 2 }, doc! { "_id": 0, "x": 3 }, doc! { "x": 4 }],
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.inserted_ids[&0], expected());
+    assert_eq!(result.inserted_ids[&1], Bson::Int32(0));
+    assert_eq!(result.inserted_ids[&2], expected());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn error_response() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    drop_collection(&client.get_coll(function_name!(), function_name!())).await;
+
+    let create_options = CreateCollectionOptions::builder()
+        .validation(doc! { "x": { "$type": "int" } })
+        .build();
+    let options = CollectionOptions::builder()
+        .ensure_created_with(create_options)
+        .build();
+    let coll = client.get_coll_with_options(function_name!(), function_name!(), options);
+
+    let error = coll
+        .insert_one(doc! { "x": "not an int" }, None)
+        .await
+        .expect_err("insert should fail validation");
+    let response = error
+        .error_response()
+        .expect("write error should include the server reply");
+    let write_errors = response.get_array("writeErrors").unwrap();
+    assert_eq!(write_errors.len(), 1);
+    if client.server_version_gte(5, 0) {
+        let write_error = write_errors[0].as_document().unwrap();
+        assert!(write_error.get_document("errInfo").is_ok());
+    }
+
+    let error = client
+        .database(function_name!())
+        .run_command(doc! { "notARealCommand": 1 }, None)
+        .await
+        .expect_err("unknown command should fail");
+    let response = error
+        .error_response()
+        .expect("command error should include the server reply");
+    assert!(response.contains_key("errmsg"));
+}
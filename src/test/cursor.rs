@@ -5,8 +5,9 @@ use tokio::sync::RwLockReadGuard;
 
 use crate::{
     bson::doc,
+    error::ErrorKind,
     options::{CreateCollectionOptions, CursorType, FindOptions},
-    test::{TestClient, LOCK},
+    test::{EventClient, TestClient, LOCK},
     RUNTIME,
 };
 
@@ -78,3 +79,65 @@ async fn tailable_cursor() {
         }
     };
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn set_batch_size_and_next_batch() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    coll.insert_many((0..10).map(|i| doc! { "_id": i }), None)
+        .await
+        .unwrap();
+
+    let mut cursor = coll
+        .find(None, FindOptions::builder().batch_size(2).build())
+        .await
+        .unwrap();
+
+    let error = cursor.set_batch_size(0).unwrap_err();
+    assert!(matches!(
+        error.kind.as_ref(),
+        ErrorKind::ArgumentError { .. }
+    ));
+
+    // Half of the first batch is consumed individually; the rest is returned as a whole.
+    assert_eq!(
+        cursor.next().await.transpose().unwrap(),
+        Some(doc! { "_id": 0 })
+    );
+    assert_eq!(
+        cursor.try_next_batch().await.unwrap(),
+        Some(vec![doc! { "_id": 1 }])
+    );
+
+    cursor.set_batch_size(3).unwrap();
+    assert_eq!(
+        cursor.try_next_batch().await.unwrap(),
+        Some((2..5).map(|i| doc! { "_id": i }).collect())
+    );
+
+    cursor.set_batch_size(5).unwrap();
+    assert_eq!(
+        cursor.try_next_batch().await.unwrap(),
+        Some((5..10).map(|i| doc! { "_id": i }).collect())
+    );
+
+    let mut remaining = Vec::new();
+    while let Some(batch) = cursor.try_next_batch().await.unwrap() {
+        remaining.extend(batch);
+    }
+    assert!(remaining.is_empty());
+
+    let get_more_batch_sizes: Vec<_> = client
+        .get_command_started_events("getMore")
+        .into_iter()
+        .map(|event| event.command.get_i32("batchSize").unwrap())
+        .collect();
+    assert_eq!(get_more_batch_sizes[..2], [3, 5]);
+}
@@ -1,4 +1,4 @@
-use std::cmp::Ord;
+use std::{cmp::Ord, collections::HashMap};
 
 use approx::assert_ulps_eq;
 use futures::stream::TryStreamExt;
@@ -8,12 +8,19 @@ use tokio::sync::RwLockReadGuard;
 use crate::{
     bson::{doc, Bson, Document},
     error::Result,
-    options::{AggregateOptions, CreateCollectionOptions, IndexOptionDefaults},
+    options::{
+        AggregateOptions,
+        CreateCollectionOptions,
+        CreateIndexesBulkOptions,
+        IndexOptionDefaults,
+        RunCursorCommandOptions,
+    },
     test::{
         util::{EventClient, TestClient},
         LOCK,
     },
     Database,
+    IndexModel,
 };
 
 #[derive(Debug, Deserialize)]
@@ -115,6 +122,51 @@ async fn list_collections() {
     }
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn run_cursor_command() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    for i in 0..3 {
+        db.collection(&format!("{}{}", function_name!(), i))
+            .insert_one(doc! { "x": 1 }, None)
+            .await
+            .unwrap();
+    }
+
+    let options = RunCursorCommandOptions::builder().batch_size(1).build();
+    let names: Vec<String> = db
+        .run_cursor_command(
+            doc! { "listCollections": 1, "nameOnly": true, "cursor": { "batchSize": 1 } },
+            options,
+        )
+        .await
+        .unwrap()
+        .map_ok(|doc| doc.get_str("name").unwrap().to_string())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(names.len(), 3);
+
+    // The first batch has one collection, and each getMore returns one more.
+    let get_mores = client.get_command_started_events("getMore");
+    assert!(get_mores.len() >= 2);
+    for event in get_mores {
+        assert_eq!(
+            event
+                .command
+                .get("batchSize")
+                .and_then(crate::bson_util::get_int),
+            Some(1)
+        );
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -397,3 +449,62 @@ async fn create_collection_json_schema_validator() {
 	}, None)
 	.await.unwrap();
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn create_indexes_bulk() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let mut specs = HashMap::new();
+    specs.insert(
+        "a".to_string(),
+        vec![
+            IndexModel::builder().keys(doc! { "x": 1 }).build(),
+            IndexModel::builder().keys(doc! { "y": -1 }).build(),
+        ],
+    );
+    specs.insert(
+        "b".to_string(),
+        vec![IndexModel::builder().keys(doc! { "z": 1 }).build()],
+    );
+    specs.insert(
+        "c".to_string(),
+        vec![IndexModel::builder()
+            .keys(doc! { "z": "notAnIndexType" })
+            .build()],
+    );
+
+    let options = CreateIndexesBulkOptions::builder()
+        .max_concurrency(2)
+        .build();
+    let results = db.create_indexes_bulk(specs, options).await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results["a"].as_ref().unwrap().index_names,
+        vec!["x_1", "y_-1"]
+    );
+    assert_eq!(results["b"].as_ref().unwrap().index_names, vec!["z_1"]);
+    assert!(results["c"].is_err());
+
+    let indexes = db
+        .run_command(doc! { "listIndexes": "a" }, None)
+        .await
+        .unwrap();
+    let names: Vec<_> = indexes
+        .get_document("cursor")
+        .unwrap()
+        .get_array("firstBatch")
+        .unwrap()
+        .iter()
+        .filter_map(|index| index.as_document()?.get_str("name").ok())
+        .collect();
+    assert_eq!(names, vec!["_id_", "x_1", "y_-1"]);
+
+    db.drop(None).await.unwrap();
+}
@@ -0,0 +1,339 @@
+use futures::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    stream::StreamExt,
+};
+use tokio::sync::RwLockReadGuard;
+
+use crate::{
+    bson::{doc, Bson},
+    error::ErrorKind,
+    gridfs::{FilesDocument, DEFAULT_CHUNK_SIZE_BYTES},
+    options::{
+        ClientOptions,
+        GridFsBucketOptions,
+        GridFsFindOneOptions,
+        GridFsFindOptions,
+        GridFsUploadOptions,
+        StreamAddress,
+    },
+    test::{TestClient, LOCK},
+    Client,
+};
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn upload_and_download() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = GridFsBucketOptions::builder()
+        .chunk_size_bytes(4)
+        .compute_md5(true)
+        .compute_sha256(true)
+        .build();
+    let bucket = db.gridfs_bucket(options);
+
+    let upload_options = GridFsUploadOptions::builder()
+        .metadata(doc! { "kind": "greeting" })
+        .build();
+    let mut upload_stream = bucket
+        .open_upload_stream("hello.txt", upload_options)
+        .await
+        .unwrap();
+    upload_stream.write_all(b"hello ").await.unwrap();
+    upload_stream.write_all(b"world").await.unwrap();
+    upload_stream.close().await.unwrap();
+    let id = upload_stream.id().clone();
+
+    let file = bucket
+        .files()
+        .find_one(doc! { "_id": id.clone() }, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(file.get_i64("length"), Ok(11));
+    assert_eq!(file.get_i32("chunkSize"), Ok(4));
+    assert_eq!(file.get_str("filename"), Ok("hello.txt"));
+    assert_eq!(
+        file.get_document("metadata"),
+        Ok(&doc! { "kind": "greeting" })
+    );
+    assert_eq!(file.get_str("md5"), Ok("5eb63bbbe01eeed093cb22bb8f5acdc3"));
+    assert_eq!(
+        file.get_str("sha256"),
+        Ok("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+    );
+
+    let chunks = bucket
+        .chunks()
+        .count_documents(doc! { "files_id": id.clone() }, None)
+        .await
+        .unwrap();
+    assert_eq!(chunks, 3);
+
+    let mut download_stream = bucket.open_download_stream(id.clone()).await.unwrap();
+    assert_eq!(download_stream.length(), 11);
+    let mut contents = Vec::new();
+    download_stream.read_to_end(&mut contents).await.unwrap();
+    assert_eq!(contents, b"hello world");
+
+    bucket.delete(id.clone()).await.unwrap();
+    let error = bucket.open_download_stream(id).await.unwrap_err();
+    assert!(matches!(error.kind.as_ref(), ErrorKind::GridFsError { .. }));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn missing_chunk() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = GridFsBucketOptions::builder().chunk_size_bytes(2).build();
+    let bucket = db.gridfs_bucket(options);
+
+    let mut upload_stream = bucket.open_upload_stream("data", None).await.unwrap();
+    upload_stream.write_all(&[1, 2, 3, 4, 5]).await.unwrap();
+    upload_stream.close().await.unwrap();
+    let id = upload_stream.id().clone();
+
+    bucket
+        .chunks()
+        .delete_one(doc! { "files_id": id.clone(), "n": 1 }, None)
+        .await
+        .unwrap();
+
+    let mut download_stream = bucket.open_download_stream(id).await.unwrap();
+    let mut contents = Vec::new();
+    assert!(download_stream.read_to_end(&mut contents).await.is_err());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn download_by_range() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = GridFsBucketOptions::builder().chunk_size_bytes(4).build();
+    let bucket = db.gridfs_bucket(options);
+
+    let data: Vec<u8> = (0..10).collect();
+    let mut upload_stream = bucket.open_upload_stream("ranged", None).await.unwrap();
+    upload_stream.write_all(&data).await.unwrap();
+    upload_stream.close().await.unwrap();
+    let id = upload_stream.id().clone();
+
+    // Ranges within a chunk, spanning chunks, covering whole chunks and covering nothing.
+    for &(start, end) in &[(0, 10), (1, 3), (5, 9), (4, 8), (8, 10), (3, 3), (10, 10)] {
+        let mut download_stream = bucket
+            .open_download_stream_by_range(id.clone(), start, end)
+            .await
+            .unwrap();
+        let mut contents = Vec::new();
+        download_stream.read_to_end(&mut contents).await.unwrap();
+        assert_eq!(contents, &data[start as usize..end as usize]);
+    }
+
+    for &(start, end) in &[(0, 11), (6, 5)] {
+        let error = bucket
+            .open_download_stream_by_range(id.clone(), start, end)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.kind.as_ref(),
+            ErrorKind::ArgumentError { .. }
+        ));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let bucket = db.gridfs_bucket(None);
+    for &(filename, kind, length) in &[("a", "image", 3), ("b", "video", 1), ("c", "image", 2)] {
+        let options = GridFsUploadOptions::builder()
+            .metadata(doc! { "kind": kind })
+            .build();
+        let mut upload_stream = bucket.open_upload_stream(filename, options).await.unwrap();
+        upload_stream.write_all(&vec![0; length]).await.unwrap();
+        upload_stream.close().await.unwrap();
+    }
+
+    let options = GridFsFindOptions::builder()
+        .sort(doc! { "length": 1 })
+        .build();
+    let files: Vec<FilesDocument> = bucket
+        .find(doc! { "metadata.kind": "image" }, options)
+        .await
+        .unwrap()
+        .map(|file| file.unwrap())
+        .collect()
+        .await;
+    let names: Vec<_> = files.iter().map(|file| file.filename.as_str()).collect();
+    assert_eq!(names, vec!["c", "a"]);
+    assert_eq!(files[0].length, 2);
+    assert_eq!(files[0].chunk_size, DEFAULT_CHUNK_SIZE_BYTES as i32);
+    assert_eq!(files[0].metadata, Some(doc! { "kind": "image" }));
+
+    let options = GridFsFindOptions::builder()
+        .sort(doc! { "filename": 1 })
+        .skip(1)
+        .limit(1)
+        .build();
+    let files: Vec<FilesDocument> = bucket
+        .find(None, options)
+        .await
+        .unwrap()
+        .map(|file| file.unwrap())
+        .collect()
+        .await;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].filename, "b");
+
+    let options = GridFsFindOneOptions::builder()
+        .sort(doc! { "length": -1 })
+        .projection(doc! { "metadata": 0 })
+        .build();
+    let file = bucket.find_one(None, options).await.unwrap().unwrap();
+    assert_eq!(file.filename, "a");
+    assert_eq!(file.metadata, None);
+
+    assert!(bucket
+        .find_one(doc! { "filename": "d" }, None)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn abort() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = GridFsBucketOptions::builder().chunk_size_bytes(4).build();
+    let bucket = db.gridfs_bucket(options);
+
+    let mut upload_stream = bucket.open_upload_stream("aborted", None).await.unwrap();
+    upload_stream.write_all(&[0; 10]).await.unwrap();
+    upload_stream.flush().await.unwrap();
+    let id = upload_stream.id().clone();
+
+    let chunks = bucket
+        .chunks()
+        .count_documents(doc! { "files_id": id.clone() }, None)
+        .await
+        .unwrap();
+    assert_eq!(chunks, 2);
+
+    upload_stream.abort().await.unwrap();
+
+    let chunks = bucket
+        .chunks()
+        .count_documents(doc! { "files_id": id.clone() }, None)
+        .await
+        .unwrap();
+    assert_eq!(chunks, 0);
+
+    let files: Vec<_> = bucket
+        .files()
+        .find(doc! { "_id": id }, None)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(files.is_empty());
+
+    // An upload cannot be aborted once it has been closed.
+    let mut upload_stream = bucket.open_upload_stream("kept", None).await.unwrap();
+    upload_stream.write_all(&[0; 10]).await.unwrap();
+    upload_stream.close().await.unwrap();
+    assert!(matches!(upload_stream.id(), Bson::ObjectId(..)));
+    assert!(upload_stream.abort().await.is_err());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn failed_write() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = GridFsBucketOptions::builder().chunk_size_bytes(2).build();
+    let bucket = db.gridfs_bucket(options);
+
+    // The first chunk of the upload conflicts with this one in the unique index on the chunks.
+    let mut upload_stream = bucket.open_upload_stream("failed", None).await.unwrap();
+    let id = upload_stream.id().clone();
+    bucket
+        .chunks()
+        .insert_one(doc! { "files_id": id.clone(), "n": 0 }, None)
+        .await
+        .unwrap();
+
+    upload_stream.write_all(&[1, 2]).await.unwrap();
+    assert!(upload_stream.flush().await.is_err());
+
+    // The failure is reported again by every later write and close, and the file isn't stored.
+    assert!(upload_stream.write_all(&[3]).await.is_err());
+    assert!(upload_stream.close().await.is_err());
+    assert!(upload_stream.close().await.is_err());
+    let files = bucket
+        .files()
+        .count_documents(doc! { "_id": id }, None)
+        .await
+        .unwrap();
+    assert_eq!(files, 0);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn invalid_chunk_size() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    // The chunk size is checked before the indexes are, so no server needs to be reachable.
+    let options = ClientOptions::builder()
+        .hosts(vec![StreamAddress::parse("localhost:1").unwrap()])
+        .build();
+    let client = Client::with_options(options).unwrap();
+    let bucket = client.database("invalid_chunk_size").gridfs_bucket(None);
+
+    for &chunk_size_bytes in &[0, i32::MAX as u32 + 1, u32::MAX] {
+        let options = GridFsUploadOptions::builder()
+            .chunk_size_bytes(chunk_size_bytes)
+            .build();
+        let error = bucket
+            .open_upload_stream("file", options)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.kind.as_ref(),
+            ErrorKind::ArgumentError { .. }
+        ));
+    }
+}
@@ -1,11 +1,15 @@
 #[cfg(not(feature = "sync"))]
 mod atlas_connectivity;
 mod auth_aws;
+mod change_stream;
 mod client;
+#[cfg(feature = "csfle")]
+mod client_encryption;
 mod coll;
 mod cursor;
 mod db;
 mod documentation_examples;
+mod gridfs;
 mod spec;
 mod util;
 
@@ -113,7 +113,7 @@ impl TestOperation for DeleteOne {
 #[derive(Debug, Deserialize, Default)]
 struct FindModifiers {
     #[serde(rename = "$comment", default)]
-    comment: Option<String>,
+    comment: Option<Bson>,
     #[serde(rename = "$hint", default)]
     hint: Option<Hint>,
     #[serde(
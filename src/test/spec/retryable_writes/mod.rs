@@ -11,9 +11,19 @@ use test_file::{Result, TestFile};
 use crate::{
     bson::{doc, Document},
     concern::{Acknowledgment, ReadConcern, WriteConcern},
-    error::ErrorKind,
+    error::{ErrorKind, RETRYABLE_WRITE_ERROR},
     options::{ClientOptions, CollectionOptions, FindOptions, InsertManyOptions},
-    test::{assert_matches, run_spec_test, util::get_db_name, EventClient, TestClient, LOCK},
+    test::{
+        assert_matches,
+        run_spec_test,
+        util::get_db_name,
+        EventClient,
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        TestClient,
+        LOCK,
+    },
 };
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -368,3 +378,56 @@ async fn label_not_added(retry_reads: bool) {
 
     assert!(!err.contains_label("RetryableWriteError"));
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn retry_after_network_error() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+    retry_after_network_error_with(true).await;
+    retry_after_network_error_with(false).await;
+}
+
+#[function_name::named]
+async fn retry_after_network_error_with(retry_writes: bool) {
+    let options = ClientOptions::builder().retry_writes(retry_writes).build();
+    let client = EventClient::with_additional_options(Some(options), None, None).await;
+
+    if !(client.is_replica_set() || client.is_sharded()) {
+        return;
+    }
+    if !client.supports_fail_command().await {
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(&format!("{}{}", function_name!(), retry_writes), "coll")
+        .await;
+
+    let failpoint = FailPoint::fail_command(
+        &["insert"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder().close_connection(true).build(),
+    );
+    let _fp_guard = client.enable_failpoint(failpoint).await.unwrap();
+
+    let result = coll.insert_one(doc! { "x": 1 }, None).await;
+    let started = client.get_command_started_events("insert");
+
+    if retry_writes {
+        result.unwrap();
+        assert_eq!(started.len(), 2);
+
+        // The retry is the same write as the first attempt, so it must use the same txnNumber.
+        let txn_numbers: Vec<_> = started
+            .iter()
+            .map(|event| event.command.get("txnNumber").cloned())
+            .collect();
+        assert!(txn_numbers[0].is_some());
+        assert_eq!(txn_numbers[0], txn_numbers[1]);
+    } else {
+        let error = result.unwrap_err();
+        assert!(error.is_network_error());
+        assert!(!error.contains_label(RETRYABLE_WRITE_ERROR));
+        assert_eq!(started.len(), 1);
+    }
+}
@@ -1,5 +1,8 @@
 use std::{
     collections::VecDeque,
+    convert::TryFrom,
+    fs,
+    path::Path,
     sync::{Arc, RwLock},
     time::Duration,
 };
@@ -8,15 +11,28 @@ use tokio::sync::RwLockReadGuard;
 
 use super::TestClient;
 use crate::{
-    bson::doc,
+    bson::{doc, Bson, Document},
     event::{
-        cmap::{CmapEventHandler, PoolClearedEvent},
+        cmap::{
+            CmapEventHandler,
+            ConnectionCheckedInEvent,
+            ConnectionCheckedOutEvent,
+            ConnectionCheckoutFailedEvent,
+            ConnectionCheckoutStartedEvent,
+            ConnectionClosedEvent,
+            ConnectionCreatedEvent,
+            ConnectionReadyEvent,
+            PoolClearedEvent,
+        },
         command::{
             CommandEventHandler,
             CommandFailedEvent,
             CommandStartedEvent,
             CommandSucceededEvent,
+            OperationEvent,
+            SlowCommandEvent,
         },
+        sdam::{SdamEventHandler, ServerHeartbeatSucceededEvent},
     },
     options::ClientOptions,
     test::{CLIENT_OPTIONS, LOCK},
@@ -24,6 +40,13 @@ use crate::{
 
 pub type EventQueue<T> = Arc<RwLock<VecDeque<T>>>;
 
+/// Command fields whose values differ from run to run and are therefore left out of snapshots.
+const UNSTABLE_COMMAND_FIELDS: &[&str] = &["lsid", "$clusterTime", "txnNumber"];
+
+/// The value that replaces cursor IDs and ObjectIds in snapshots, since those differ from run to
+/// run.
+const UNSTABLE_VALUE_PLACEHOLDER: &str = "<unstable>";
+
 #[derive(Clone, Debug)]
 pub enum CommandEvent {
     CommandStartedEvent(CommandStartedEvent),
@@ -52,6 +75,38 @@ impl CommandEvent {
         }
     }
 
+    /// Converts the event into a document that only contains the parts of the event that are
+    /// stable across runs, for use in snapshots.
+    fn to_snapshot_document(&self) -> Document {
+        match self {
+            CommandEvent::CommandStartedEvent(event) => {
+                let mut command = event.command.clone();
+                for field in UNSTABLE_COMMAND_FIELDS {
+                    command.remove(field);
+                }
+                if event.command_name == "getMore" {
+                    command.insert("getMore", UNSTABLE_VALUE_PLACEHOLDER);
+                }
+                if event.command_name == "killCursors" {
+                    command.insert("cursors", UNSTABLE_VALUE_PLACEHOLDER);
+                }
+                doc! {
+                    "commandStartedEvent": {
+                        "commandName": event.command_name.as_str(),
+                        "databaseName": event.db.as_str(),
+                        "command": replace_object_ids(command),
+                    }
+                }
+            }
+            CommandEvent::CommandSucceededEvent(event) => doc! {
+                "commandSucceededEvent": { "commandName": event.command_name.as_str() }
+            },
+            CommandEvent::CommandFailedEvent(event) => doc! {
+                "commandFailedEvent": { "commandName": event.command_name.as_str() }
+            },
+        }
+    }
+
     fn as_command_started(&self) -> Option<&CommandStartedEvent> {
         match self {
             CommandEvent::CommandStartedEvent(e) => Some(e),
@@ -67,16 +122,75 @@ impl CommandEvent {
     }
 }
 
+/// The connection-level CMAP events, in the order that they were emitted.
+#[derive(Clone, Debug)]
+pub enum CmapEvent {
+    ConnectionCreated(ConnectionCreatedEvent),
+    ConnectionReady(ConnectionReadyEvent),
+    ConnectionClosed(ConnectionClosedEvent),
+    ConnectionCheckoutStarted(ConnectionCheckoutStartedEvent),
+    ConnectionCheckoutFailed(ConnectionCheckoutFailedEvent),
+    ConnectionCheckedOut(ConnectionCheckedOutEvent),
+    ConnectionCheckedIn(ConnectionCheckedInEvent),
+}
+
 #[derive(Default)]
 pub struct EventHandler {
     pub command_events: EventQueue<CommandEvent>,
     pub pool_cleared_events: EventQueue<PoolClearedEvent>,
+    pub cmap_events: EventQueue<CmapEvent>,
+    pub slow_command_events: EventQueue<SlowCommandEvent>,
+    pub operation_events: EventQueue<OperationEvent>,
+    pub heartbeat_succeeded_events: EventQueue<ServerHeartbeatSucceededEvent>,
+}
+
+impl EventHandler {
+    fn handle_cmap_event(&self, event: CmapEvent) {
+        self.cmap_events.write().unwrap().push_back(event)
+    }
 }
 
 impl CmapEventHandler for EventHandler {
     fn handle_pool_cleared_event(&self, event: PoolClearedEvent) {
         self.pool_cleared_events.write().unwrap().push_back(event)
     }
+
+    fn handle_connection_created_event(&self, event: ConnectionCreatedEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionCreated(event))
+    }
+
+    fn handle_connection_ready_event(&self, event: ConnectionReadyEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionReady(event))
+    }
+
+    fn handle_connection_closed_event(&self, event: ConnectionClosedEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionClosed(event))
+    }
+
+    fn handle_connection_checkout_started_event(&self, event: ConnectionCheckoutStartedEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionCheckoutStarted(event))
+    }
+
+    fn handle_connection_checkout_failed_event(&self, event: ConnectionCheckoutFailedEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionCheckoutFailed(event))
+    }
+
+    fn handle_connection_checked_out_event(&self, event: ConnectionCheckedOutEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionCheckedOut(event))
+    }
+
+    fn handle_connection_checked_in_event(&self, event: ConnectionCheckedInEvent) {
+        self.handle_cmap_event(CmapEvent::ConnectionCheckedIn(event))
+    }
+}
+
+impl SdamEventHandler for EventHandler {
+    fn handle_server_heartbeat_succeeded_event(&self, event: ServerHeartbeatSucceededEvent) {
+        self.heartbeat_succeeded_events
+            .write()
+            .unwrap()
+            .push_back(event)
+    }
 }
 
 impl CommandEventHandler for EventHandler {
@@ -100,6 +214,14 @@ impl CommandEventHandler for EventHandler {
             .unwrap()
             .push_back(CommandEvent::CommandSucceededEvent(event))
     }
+
+    fn handle_slow_command_event(&self, event: SlowCommandEvent) {
+        self.slow_command_events.write().unwrap().push_back(event)
+    }
+
+    fn handle_operation_event(&self, event: OperationEvent) {
+        self.operation_events.write().unwrap().push_back(event)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -107,6 +229,10 @@ pub struct EventClient {
     client: TestClient,
     pub command_events: EventQueue<CommandEvent>,
     pub pool_cleared_events: EventQueue<PoolClearedEvent>,
+    pub cmap_events: EventQueue<CmapEvent>,
+    pub slow_command_events: EventQueue<SlowCommandEvent>,
+    pub operation_events: EventQueue<OperationEvent>,
+    pub heartbeat_succeeded_events: EventQueue<ServerHeartbeatSucceededEvent>,
 }
 
 impl std::ops::Deref for EventClient {
@@ -132,15 +258,25 @@ impl EventClient {
         let handler = EventHandler::default();
         let command_events = handler.command_events.clone();
         let pool_cleared_events = handler.pool_cleared_events.clone();
+        let cmap_events = handler.cmap_events.clone();
+        let slow_command_events = handler.slow_command_events.clone();
+        let operation_events = handler.operation_events.clone();
+        let heartbeat_succeeded_events = handler.heartbeat_succeeded_events.clone();
         let client = TestClient::with_handler(Some(handler), options).await;
 
         // clear events from commands used to set up client.
         command_events.write().unwrap().clear();
+        slow_command_events.write().unwrap().clear();
+        operation_events.write().unwrap().clear();
 
         Self {
             client,
             command_events,
             pool_cleared_events,
+            cmap_events,
+            slow_command_events,
+            operation_events,
+            heartbeat_succeeded_events,
         }
     }
 
@@ -251,6 +387,29 @@ impl EventClient {
             .collect()
     }
 
+    /// Gets all of the command failed events for a specified command name.
+    pub fn get_command_failed_events(&self, command_name: &str) -> Vec<CommandFailedEvent> {
+        self.get_events_matching(|event| event.command_name() == command_name)
+            .into_iter()
+            .filter_map(|event| match event {
+                CommandEvent::CommandFailedEvent(event) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Gets all of the command events that match the given predicate, without removing them from
+    /// the queue.
+    pub fn get_events_matching(&self, pred: impl Fn(&CommandEvent) -> bool) -> Vec<CommandEvent> {
+        self.command_events
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|event| pred(event))
+            .cloned()
+            .collect()
+    }
+
     /// Gets a list of all of the events of the requested event types that occurred on this client.
     /// Ignores any event with a name in the ignore list. Also ignores all configureFailPoint
     /// events.
@@ -294,6 +453,69 @@ impl EventClient {
             })
             .collect()
     }
+
+    /// Gets the captured command events as documents with the parts that vary between runs (e.g.
+    /// session IDs, cluster times, cursor IDs and ObjectIds) removed or replaced, in the order
+    /// that the events occurred. configureFailPoint events are ignored.
+    pub fn dump_events(&self) -> Vec<Document> {
+        self.get_filtered_events(&None, &None)
+            .iter()
+            .map(CommandEvent::to_snapshot_document)
+            .collect()
+    }
+
+    /// Asserts that the captured command events match the snapshot stored at `path`, as produced
+    /// by `dump_events`.
+    ///
+    /// If no file exists at `path`, the current events are recorded there instead, so a snapshot
+    /// can be updated by deleting its file and re-running the test.
+    pub fn assert_events_match_snapshot(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let events = self.dump_events();
+
+        if !path.exists() {
+            let json: Vec<serde_json::Value> = events
+                .into_iter()
+                .map(|event| Bson::Document(event).into_canonical_extjson())
+                .collect();
+            fs::write(path, serde_json::to_string_pretty(&json).unwrap()).unwrap_or_else(|e| {
+                panic!("failed to write snapshot to {}: {}", path.display(), e)
+            });
+            return;
+        }
+
+        let contents = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("failed to read snapshot {}: {}", path.display(), e));
+        let json: Vec<serde_json::Value> = serde_json::from_str(&contents).unwrap();
+        let snapshot: Vec<Document> = json
+            .into_iter()
+            .map(|value| match Bson::try_from(value) {
+                Ok(Bson::Document(doc)) => doc,
+                other => panic!("invalid event in snapshot {}: {:?}", path.display(), other),
+            })
+            .collect();
+
+        pretty_assertions::assert_eq!(
+            events,
+            snapshot,
+            "command events do not match snapshot {}",
+            path.display()
+        );
+    }
+}
+
+/// Recursively replaces all ObjectIds in `doc` with a placeholder.
+fn replace_object_ids(doc: Document) -> Document {
+    fn replace(value: Bson) -> Bson {
+        match value {
+            Bson::ObjectId(_) => Bson::String(UNSTABLE_VALUE_PLACEHOLDER.to_string()),
+            Bson::Document(doc) => Bson::Document(replace_object_ids(doc)),
+            Bson::Array(array) => Bson::Array(array.into_iter().map(replace).collect()),
+            other => other,
+        }
+    }
+
+    doc.into_iter().map(|(k, v)| (k, replace(v))).collect()
 }
 
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -319,3 +541,45 @@ async fn command_started_event_count() {
         10
     );
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn command_event_snapshot() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    client.command_events.write().unwrap().clear();
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.find_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let events = client.dump_events();
+    assert_eq!(events.len(), 4);
+    let command = events[0]
+        .get_document("commandStartedEvent")
+        .and_then(|event| event.get_document("command"))
+        .unwrap();
+    assert!(!command.contains_key("lsid"));
+    assert_eq!(
+        command
+            .get_array("documents")
+            .unwrap()
+            .first()
+            .and_then(Bson::as_document)
+            .and_then(|doc| doc.get_str("_id").ok()),
+        Some(UNSTABLE_VALUE_PLACEHOLDER)
+    );
+
+    let path =
+        std::env::temp_dir().join(format!("{}-{}.json", function_name!(), std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    // The first assertion records the snapshot, and the second one checks against it.
+    client.assert_events_match_snapshot(&path);
+    client.assert_events_match_snapshot(&path);
+    fs::remove_file(&path).unwrap();
+}
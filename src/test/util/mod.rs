@@ -4,7 +4,7 @@ mod lock;
 mod matchable;
 
 pub use self::{
-    event::{CommandEvent, EventClient},
+    event::{CmapEvent, CommandEvent, EventClient},
     failpoint::{FailCommandOptions, FailPoint, FailPointGuard, FailPointMode},
     lock::TestLock,
     matchable::{assert_matches, Matchable},
@@ -12,7 +12,7 @@ pub use self::{
 
 use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 
-use crate::bson::{doc, oid::ObjectId, Bson};
+use crate::bson::{doc, oid::ObjectId, Bson, Document};
 use semver::{Version, VersionReq};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
@@ -61,7 +61,8 @@ impl TestClient {
         if let Some(event_handler) = event_handler {
             let handler = Arc::new(event_handler);
             options.command_event_handler = Some(handler.clone());
-            options.cmap_event_handler = Some(handler);
+            options.cmap_event_handler = Some(handler.clone());
+            options.sdam_event_handler = Some(handler);
         }
 
         let client = Client::with_options(options.clone()).unwrap();
@@ -180,7 +181,7 @@ impl TestClient {
     where
         T: Serialize + DeserializeOwned + Unpin + Debug,
     {
-        let coll = self.database(db_name).collection_with_type(coll_name);
+        let coll = self.database(db_name).collection(coll_name);
         drop_collection(&coll).await;
         coll
     }
@@ -286,7 +287,7 @@ impl TestClient {
         if self.is_sharded() {
             let shard_info = self
                 .database("config")
-                .collection("shards")
+                .collection::<Document>("shards")
                 .find_one(None, None)
                 .await
                 .unwrap()
@@ -0,0 +1,171 @@
+//! Integration with the `tracing` crate, enabled by the `tracing` feature flag.
+//!
+//! Each command sent to the server is traced as a span named `mongodb.command` with the fields
+//! `command_name`, `request_id`, `server_address`, `command` and `duration_ms`; an event is
+//! emitted in it at the DEBUG level when the command succeeds and at the WARN level when it fails.
+//! Changes to the topology are emitted as events at the INFO level.
+//!
+//! The `command` field holds the command document, truncated to at most 1000 characters. A
+//! different length can be set with the `MONGODB_TRACING_MAX_DOCUMENT_LENGTH` environment
+//! variable.
+
+use std::{env, sync::Arc, time::Duration};
+
+use lazy_static::lazy_static;
+use tracing::{field, Span};
+
+use crate::{
+    bson::Document,
+    cmap::CommandResponse,
+    error::Result,
+    event::sdam::{
+        SdamEventHandler,
+        ServerClosedEvent,
+        ServerDescriptionChangedEvent,
+        ServerHeartbeatFailedEvent,
+        ServerHeartbeatStartedEvent,
+        ServerHeartbeatSucceededEvent,
+        ServerOpeningEvent,
+        TopologyDescriptionChangedEvent,
+    },
+    options::StreamAddress,
+};
+
+const MAX_DOCUMENT_LENGTH_ENV_VAR: &str = "MONGODB_TRACING_MAX_DOCUMENT_LENGTH";
+
+const DEFAULT_MAX_DOCUMENT_LENGTH: usize = 1000;
+
+lazy_static! {
+    static ref MAX_DOCUMENT_LENGTH: usize = env::var(MAX_DOCUMENT_LENGTH_ENV_VAR)
+        .ok()
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(DEFAULT_MAX_DOCUMENT_LENGTH);
+}
+
+/// Truncates `s` to at most `max_length` characters, marking it with a trailing "..." if it was
+/// truncated.
+fn truncate(mut s: String, max_length: usize) -> String {
+    if let Some((index, _)) = s.char_indices().nth(max_length) {
+        s.truncate(index);
+        s.push_str("...");
+    }
+    s
+}
+
+/// Creates the span for a command. `body` should hold only the fields that may be reported in
+/// command events, and be `None` if the command is sensitive.
+pub(crate) fn command_span(
+    command_name: &str,
+    request_id: i32,
+    address: &StreamAddress,
+    body: Option<&Document>,
+) -> Span {
+    let span = tracing::debug_span!(
+        "mongodb.command",
+        command_name,
+        request_id,
+        server_address = %address,
+        command = field::Empty,
+        duration_ms = field::Empty,
+    );
+
+    // The command is only formatted if a subscriber is interested in the span.
+    if let Some(body) = body.filter(|_| !span.is_disabled()) {
+        let command = truncate(body.to_string(), *MAX_DOCUMENT_LENGTH);
+        span.record("command", command.as_str());
+    }
+
+    span
+}
+
+/// Records the outcome of the command traced by `span`.
+pub(crate) fn command_finished(span: &Span, duration: Duration, result: &Result<CommandResponse>) {
+    // `Span::record` only takes values by reference before tracing 0.1.36.
+    let duration_ms = &(duration.as_millis() as u64);
+    span.record("duration_ms", duration_ms);
+
+    span.in_scope(|| match result {
+        Ok(_) => tracing::debug!(duration_ms, "command succeeded"),
+        Err(error) => tracing::warn!(duration_ms, error = %error, "command failed"),
+    });
+}
+
+/// Wraps the user's SDAM event handler, if any, in one that also traces changes to the topology.
+pub(crate) fn sdam_event_handler(
+    inner: Option<Arc<dyn SdamEventHandler>>,
+) -> Arc<dyn SdamEventHandler> {
+    Arc::new(TracingSdamEventHandler { inner })
+}
+
+struct TracingSdamEventHandler {
+    inner: Option<Arc<dyn SdamEventHandler>>,
+}
+
+impl SdamEventHandler for TracingSdamEventHandler {
+    fn handle_server_opening_event(&self, event: ServerOpeningEvent) {
+        tracing::info!(server_address = %event.address, "server added to topology");
+        if let Some(ref inner) = self.inner {
+            inner.handle_server_opening_event(event);
+        }
+    }
+
+    fn handle_server_closed_event(&self, event: ServerClosedEvent) {
+        tracing::info!(server_address = %event.address, "server removed from topology");
+        if let Some(ref inner) = self.inner {
+            inner.handle_server_closed_event(event);
+        }
+    }
+
+    fn handle_server_description_changed_event(&self, event: ServerDescriptionChangedEvent) {
+        tracing::info!(
+            server_address = %event.address,
+            previous_type = ?event.previous_type,
+            new_type = ?event.new_type,
+            "server type changed"
+        );
+        if let Some(ref inner) = self.inner {
+            inner.handle_server_description_changed_event(event);
+        }
+    }
+
+    fn handle_topology_description_changed_event(&self, event: TopologyDescriptionChangedEvent) {
+        tracing::info!(
+            previous_type = ?event.previous_type,
+            new_type = ?event.new_type,
+            "topology changed"
+        );
+        if let Some(ref inner) = self.inner {
+            inner.handle_topology_description_changed_event(event);
+        }
+    }
+
+    fn handle_server_heartbeat_started_event(&self, event: ServerHeartbeatStartedEvent) {
+        if let Some(ref inner) = self.inner {
+            inner.handle_server_heartbeat_started_event(event);
+        }
+    }
+
+    fn handle_server_heartbeat_succeeded_event(&self, event: ServerHeartbeatSucceededEvent) {
+        if let Some(ref inner) = self.inner {
+            inner.handle_server_heartbeat_succeeded_event(event);
+        }
+    }
+
+    fn handle_server_heartbeat_failed_event(&self, event: ServerHeartbeatFailedEvent) {
+        if let Some(ref inner) = self.inner {
+            inner.handle_server_heartbeat_failed_event(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::truncate;
+
+    #[test]
+    fn truncate_document() {
+        assert_eq!(truncate("abc".to_string(), 3), "abc");
+        assert_eq!(truncate("abcd".to_string(), 3), "abc...");
+        assert_eq!(truncate("ééé".to_string(), 2), "éé...");
+    }
+}